@@ -0,0 +1,276 @@
+/// Generated cover art for custom playlists: a collage of the album images
+/// of the playlist's tracks, stored in the cache directory. Also keeps local
+/// copies of album art for integrations that need a file rather than a URL.
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, RgbImage};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Width and height of generated covers in pixels
+pub const COVER_SIZE: u32 = 600;
+
+/// Number of album images a collage is made from
+const COLLAGE_IMAGES: usize = 4;
+
+/// Directory generated covers are stored in
+pub fn covers_dir() -> Option<PathBuf> {
+    crate::config::configured_data_dir()
+        .or_else(|| dirs::cache_dir().map(|dir| dir.join("any-player")))
+        .map(|dir| dir.join("covers"))
+}
+
+/// File a playlist's generated cover is written to inside `dir`
+pub fn cover_path(dir: &Path, playlist_id: &str) -> PathBuf {
+    let safe_id: String = playlist_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}.png", safe_id))
+}
+
+/// Directory downloaded album art is kept in
+pub fn art_cache_dir() -> Option<PathBuf> {
+    crate::config::configured_data_dir()
+        .or_else(|| dirs::cache_dir().map(|dir| dir.join("any-player")))
+        .map(|dir| dir.join("art"))
+}
+
+/// File the art at `image_url` is cached to inside `dir`, named after a
+/// stable hash of the URL
+pub fn art_path(dir: &Path, image_url: &str) -> PathBuf {
+    // FNV-1a, so names stay the same across runs and Rust versions
+    let hash = image_url
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    dir.join(format!("{:016x}", hash))
+}
+
+/// `file://` URL of the cached copy of `image_url`, if it has been downloaded
+pub fn cached_art_url(dir: &Path, image_url: &str) -> Option<String> {
+    let path = art_path(dir, image_url);
+    if path.is_file() {
+        crate::offline::file_url(&path)
+    } else {
+        None
+    }
+}
+
+/// Download `image_url` into the art cache and return the `file://` URL of
+/// the cached copy. Responses that aren't a recognised image are rejected.
+pub async fn cache_art(image_url: &str) -> Result<String, String> {
+    let dir =
+        art_cache_dir().ok_or_else(|| "Failed to determine art cache directory".to_string())?;
+    let bytes = fetch_bytes(image_url).await?;
+    image::guess_format(&bytes).map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let path = art_path(&dir, image_url);
+    let dest = path.clone();
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create art cache directory: {}", e))?;
+        // Write under a temporary name so readers never see a partial file
+        let tmp = dest.with_extension("part");
+        std::fs::write(&tmp, &bytes).map_err(|e| format!("Failed to write art: {}", e))?;
+        std::fs::rename(&tmp, &dest).map_err(|e| format!("Failed to write art: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Failed to spawn blocking task: {}", e))??;
+
+    crate::offline::file_url(&path).ok_or_else(|| "Failed to build art file URL".to_string())
+}
+
+/// Art URLs currently being downloaded by `spawn_cache_art`
+static ART_DOWNLOADS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+/// Cache `image_url` in the background unless it's already being downloaded
+pub fn spawn_cache_art(image_url: String) {
+    if !ART_DOWNLOADS.lock().unwrap().insert(image_url.clone()) {
+        return;
+    }
+    tokio::spawn(async move {
+        if let Err(e) = cache_art(&image_url).await {
+            tracing::warn!("Failed to cache album art {}: {}", image_url, e);
+        }
+        ART_DOWNLOADS.lock().unwrap().remove(&image_url);
+    });
+}
+
+/// Whether `image_url` is a cover generated for the playlist rather than
+/// one the user set, so it's safe to replace when the tracks change
+pub fn is_generated_cover(image_url: &str, playlist_id: &str) -> bool {
+    covers_dir()
+        .and_then(|dir| crate::offline::file_url(&cover_path(&dir, playlist_id)))
+        .is_some_and(|url| url == image_url)
+}
+
+/// Distinct image URLs in track order
+fn distinct_urls<'a>(image_urls: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut seen = Vec::new();
+    for url in image_urls {
+        if !url.is_empty() && !seen.contains(&url) {
+            seen.push(url);
+        }
+    }
+    seen
+}
+
+/// Lay the images out on a square cover: a 2x2 grid when there are four,
+/// otherwise the first image fills the cover. `None` when there are none.
+pub fn compose_collage(images: &[DynamicImage]) -> Option<RgbImage> {
+    if images.len() < COLLAGE_IMAGES {
+        return images.first().map(|image| {
+            image
+                .resize_to_fill(COVER_SIZE, COVER_SIZE, FilterType::Triangle)
+                .to_rgb8()
+        });
+    }
+
+    let tile = COVER_SIZE / 2;
+    let mut cover = RgbImage::new(COVER_SIZE, COVER_SIZE);
+    for (i, image) in images.iter().take(COLLAGE_IMAGES).enumerate() {
+        let scaled = image
+            .resize_to_fill(tile, tile, FilterType::Triangle)
+            .to_rgb8();
+        let x = (i as u32 % 2) * tile;
+        let y = (i as u32 / 2) * tile;
+        imageops::replace(&mut cover, &scaled, x as i64, y as i64);
+    }
+    Some(cover)
+}
+
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>, String> {
+    let response = crate::http_client::shared_client()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch image: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch image: HTTP {}", response.status()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read image data: {}", e))?;
+    Ok(bytes.to_vec())
+}
+
+async fn fetch_image(url: &str) -> Result<DynamicImage, String> {
+    let bytes = fetch_bytes(url).await?;
+    image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode image: {}", e))
+}
+
+/// Build a cover from the first four distinct images that download, write it
+/// to the covers directory and return its `file://` URL. Returns `None` when
+/// none of the images could be used.
+pub async fn generate_cover(
+    playlist_id: &str,
+    image_urls: &[String],
+) -> Result<Option<String>, String> {
+    let mut images = Vec::new();
+    for url in distinct_urls(image_urls.iter().map(String::as_str)) {
+        match fetch_image(url).await {
+            Ok(image) => images.push(image),
+            Err(e) => tracing::warn!("Skipping cover image {}: {}", url, e),
+        }
+        if images.len() == COLLAGE_IMAGES {
+            break;
+        }
+    }
+
+    let Some(cover) = compose_collage(&images) else {
+        return Ok(None);
+    };
+
+    let dir = covers_dir().ok_or_else(|| "Failed to determine covers directory".to_string())?;
+    let path = cover_path(&dir, playlist_id);
+    let dest = path.clone();
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create covers directory: {}", e))?;
+        cover
+            .save(&dest)
+            .map_err(|e| format!("Failed to write cover: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Failed to spawn blocking task: {}", e))??;
+
+    Ok(crate::offline::file_url(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    fn solid(width: u32, height: u32, color: [u8; 3]) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb(color)))
+    }
+
+    #[test]
+    fn test_collage_places_four_images_in_quadrants() {
+        let red = [255, 0, 0];
+        let green = [0, 255, 0];
+        let blue = [0, 0, 255];
+        let white = [255, 255, 255];
+        let images = vec![
+            solid(300, 300, red),
+            solid(640, 480, green),
+            solid(64, 64, blue),
+            solid(200, 500, white),
+        ];
+
+        let cover = compose_collage(&images).unwrap();
+
+        assert_eq!(cover.dimensions(), (COVER_SIZE, COVER_SIZE));
+        let quarter = COVER_SIZE / 4;
+        let three_quarters = COVER_SIZE - quarter;
+        assert_eq!(cover.get_pixel(quarter, quarter).0, red);
+        assert_eq!(cover.get_pixel(three_quarters, quarter).0, green);
+        assert_eq!(cover.get_pixel(quarter, three_quarters).0, blue);
+        assert_eq!(cover.get_pixel(three_quarters, three_quarters).0, white);
+    }
+
+    #[test]
+    fn test_collage_with_fewer_images_uses_the_first() {
+        let cover = compose_collage(&[solid(10, 10, [1, 2, 3]), solid(10, 10, [9, 9, 9])]).unwrap();
+        assert_eq!(cover.dimensions(), (COVER_SIZE, COVER_SIZE));
+        assert_eq!(cover.get_pixel(COVER_SIZE - 1, COVER_SIZE - 1).0, [1, 2, 3]);
+
+        assert!(compose_collage(&[]).is_none());
+    }
+
+    #[test]
+    fn test_cached_art_url_only_for_downloaded_art() {
+        let dir = std::env::temp_dir().join(format!("any-player-art-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = "https://example.com/cover.jpg";
+
+        assert_eq!(art_path(&dir, url), art_path(&dir, url));
+        assert_ne!(
+            art_path(&dir, url),
+            art_path(&dir, "https://example.com/other.jpg")
+        );
+        assert!(cached_art_url(&dir, url).is_none());
+
+        std::fs::write(art_path(&dir, url), b"art").unwrap();
+        let cached = cached_art_url(&dir, url).unwrap();
+        assert!(cached.starts_with("file://"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_distinct_urls_keeps_track_order() {
+        let urls = ["a", "b", "a", "", "c", "b", "d", "e"];
+        assert_eq!(distinct_urls(urls), vec!["a", "b", "c", "d", "e"]);
+    }
+}
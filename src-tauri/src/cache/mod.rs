@@ -1,18 +1,30 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 
 const PLAYLISTS_CACHE_FILE: &str = "playlists_cache.json";
 const CUSTOM_PLAYLISTS_CACHE_FILE: &str = "custom_playlists_cache.json";
 const CUSTOM_PLAYLIST_TRACKS_CACHE_PREFIX: &str = "custom_playlist_tracks_";
 const UNION_PLAYLIST_TRACKS_CACHE_PREFIX: &str = "union_playlist_tracks_";
 
-/// Get the XDG cache directory for the application
+/// How long search results are reused before the provider is queried again
+pub const SEARCH_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Get the configured data directory, or the XDG cache directory for the
+/// application if none is set
 fn get_cache_dir() -> Result<PathBuf> {
-    let cache_dir = dirs::cache_dir()
-        .context("Failed to get cache directory")?
-        .join("any-player");
+    let cache_dir = match crate::config::configured_data_dir() {
+        Some(dir) => dir,
+        None => dirs::cache_dir()
+            .context("Failed to get cache directory")?
+            .join("any-player"),
+    };
 
     // Create directory if it doesn't exist
     fs::create_dir_all(&cache_dir).context("Failed to create cache directory")?;
@@ -141,10 +153,168 @@ pub fn clear_union_playlist_tracks_cache(playlist_id: &str) -> Result<()> {
     clear_cache(&filename)
 }
 
+/// Drop a disconnected provider's playlists from the playlists cache and
+/// clear the cached tracks of the union playlists that draw from it
+pub fn purge_provider_caches(source: &str, union_playlist_ids: &[String]) -> Result<()> {
+    match read_playlists_cache() {
+        Ok(Some(json)) => match remove_source_playlists(&json, source) {
+            Some(json) => write_playlists_cache(&json)?,
+            // The frontend fetches the playlists again without a cache
+            None => clear_playlists_cache()?,
+        },
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!("Discarding unreadable playlists cache: {}", e);
+            clear_playlists_cache()?;
+        }
+    }
+
+    for id in union_playlist_ids {
+        clear_union_playlist_tracks_cache(id)?;
+    }
+    Ok(())
+}
+
+/// The playlists cache without `source`'s playlists, or `None` when it
+/// can't be parsed
+fn remove_source_playlists(json: &str, source: &str) -> Option<String> {
+    let mut cache: serde_json::Value = serde_json::from_str(json).ok()?;
+    cache
+        .get_mut("playlists")?
+        .as_array_mut()?
+        .retain(|playlist| playlist.get("source").and_then(|s| s.as_str()) != Some(source));
+    serde_json::to_string(&cache).ok()
+}
+
+/// In-memory cache of search results keyed by `(source, query)`
+pub struct SearchCache<T> {
+    ttl: Duration,
+    entries: Mutex<HashMap<(String, String), (Instant, T)>>,
+}
+
+impl<T: Clone> SearchCache<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queries differing only in case or surrounding whitespace share an entry
+    fn key(source: &str, query: &str) -> (String, String) {
+        (source.to_string(), query.trim().to_lowercase())
+    }
+
+    /// Get cached results that are younger than the TTL
+    pub fn get(&self, source: &str, query: &str) -> Option<T> {
+        let mut entries = self.entries.lock().unwrap();
+        let key = Self::key(source, query);
+        match entries.get(&key) {
+            Some((stored_at, results)) if stored_at.elapsed() < self.ttl => Some(results.clone()),
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store results for a query, replacing any previous entry
+    pub fn insert(&self, source: &str, query: &str, results: T) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        entries.insert(Self::key(source, query), (Instant::now(), results));
+    }
+
+    /// Drop every cached result from `source`, e.g. after its account or
+    /// server changed
+    pub fn clear_source(&self, source: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|(entry_source, _), _| entry_source != source);
+    }
+
+    /// Return cached results for the query, or run `fetch` and cache what it
+    /// returns. `refresh` skips the cached entry and always fetches.
+    pub async fn get_or_fetch<F, Fut, E>(
+        &self,
+        source: &str,
+        query: &str,
+        refresh: bool,
+        fetch: F,
+    ) -> std::result::Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        if !refresh {
+            if let Some(results) = self.get(source, query) {
+                tracing::debug!("Using cached {} search results for {:?}", source, query);
+                return Ok(results);
+            }
+        }
+
+        let results = fetch().await?;
+        self.insert(source, query, results.clone());
+        Ok(results)
+    }
+}
+
+/// Keeps only the newest search per key (e.g. per provider) running. Starting
+/// a search drops any earlier one for the same key that is still in flight,
+/// which then resolves to `None`.
+pub struct LatestSearch {
+    generations: Mutex<HashMap<String, watch::Sender<u64>>>,
+}
+
+impl Default for LatestSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatestSearch {
+    pub fn new() -> Self {
+        Self {
+            generations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `search` unless a newer search for `key` starts first
+    pub async fn run<T>(&self, key: &str, search: impl Future<Output = T>) -> Option<T> {
+        let mut latest = {
+            let mut generations = self.generations.lock().unwrap();
+            let sender = generations
+                .entry(key.to_string())
+                .or_insert_with(|| watch::channel(0).0);
+            sender.send_modify(|generation| *generation += 1);
+            sender.subscribe()
+        };
+        let generation = *latest.borrow_and_update();
+
+        tokio::select! {
+            result = search => (*latest.borrow() == generation).then_some(result),
+            _ = Self::superseded(&mut latest, generation) => None,
+        }
+    }
+
+    async fn superseded(latest: &mut watch::Receiver<u64>, generation: u64) {
+        while latest.changed().await.is_ok() {
+            if *latest.borrow_and_update() != generation {
+                return;
+            }
+        }
+        // The sender lives as long as `self`, so this is never reached
+        std::future::pending::<()>().await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde::{Deserialize, Serialize};
+    use serial_test::serial;
 
     #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct TestData {
@@ -153,6 +323,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_write_and_read_cache() {
         let test_file = "test_cache.json";
         let test_data = TestData {
@@ -173,6 +344,28 @@ mod tests {
     }
 
     #[test]
+    #[serial]
+    fn test_configured_data_dir_is_used_for_cache_writes() {
+        let dir = std::env::temp_dir().join(format!("any-player-cache-{}", uuid::Uuid::new_v4()));
+        crate::config::set_data_dir(Some(dir.clone()));
+
+        let test_data = TestData {
+            value: "relocated".to_string(),
+            count: 7,
+        };
+        let written = write_cache("test_relocated.json", &test_data);
+        let read_back: Result<Option<TestData>> = read_cache("test_relocated.json");
+        crate::config::set_data_dir(None);
+
+        written.unwrap();
+        assert!(dir.join("test_relocated.json").exists());
+        assert_eq!(read_back.unwrap(), Some(test_data));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[serial]
     fn test_clear_cache() {
         let test_file = "test_clear.json";
         let test_data = TestData {
@@ -188,4 +381,120 @@ mod tests {
         let read_data: Option<TestData> = read_cache(test_file).unwrap();
         assert!(read_data.is_none());
     }
+
+    async fn search_counting(
+        cache: &SearchCache<Vec<String>>,
+        calls: &std::sync::atomic::AtomicUsize,
+        query: &str,
+        refresh: bool,
+    ) -> Vec<String> {
+        cache
+            .get_or_fetch("jellyfin", query, refresh, || async {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok::<_, String>(vec![format!("result for {}", query)])
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_repeated_search_reads_from_cache() {
+        let cache = SearchCache::new(SEARCH_CACHE_TTL);
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+
+        let first = search_counting(&cache, &calls, "Daft Punk", false).await;
+        let second = search_counting(&cache, &calls, " daft punk ", false).await;
+        assert_eq!(first, second);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        search_counting(&cache, &calls, "Daft Punk", true).await;
+        search_counting(&cache, &calls, "Justice", false).await;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_clear_source_only_drops_that_source() {
+        let cache = SearchCache::new(SEARCH_CACHE_TTL);
+        cache.insert("jellyfin", "Daft Punk", vec!["old server".to_string()]);
+        cache.insert("spotify", "Daft Punk", vec!["spotify".to_string()]);
+
+        cache.clear_source("jellyfin");
+        assert_eq!(cache.get("jellyfin", "Daft Punk"), None);
+        assert_eq!(
+            cache.get("spotify", "Daft Punk"),
+            Some(vec!["spotify".to_string()])
+        );
+
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        search_counting(&cache, &calls, "Daft Punk", false).await;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_search_is_fetched_again() {
+        let cache = SearchCache::new(Duration::ZERO);
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+
+        search_counting(&cache, &calls, "Daft Punk", false).await;
+        search_counting(&cache, &calls, "Daft Punk", false).await;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_superseded_search_resolves_to_none() {
+        let searches = LatestSearch::new();
+        // Held open so the older search stays in flight until it's superseded
+        let (_release, pending) = tokio::sync::oneshot::channel::<()>();
+
+        let older = searches.run("spotify", async {
+            let _ = pending.await;
+            "daft"
+        });
+        let newer = async {
+            tokio::task::yield_now().await;
+            searches.run("spotify", async { "daft punk" }).await
+        };
+        let other_provider = searches.run("jellyfin", async { "daft" });
+
+        let (older, newer, other_provider) = tokio::join!(older, newer, other_provider);
+        assert_eq!(older, None);
+        assert_eq!(newer, Some("daft punk"));
+        assert_eq!(other_provider, Some("daft"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_purge_provider_caches() {
+        let dir = std::env::temp_dir().join(format!("any-player-cache-{}", uuid::Uuid::new_v4()));
+        crate::config::set_data_dir(Some(dir.clone()));
+
+        let playlists = r#"{"version":1,"timestamp":0,"playlists":[
+            {"id":"a","name":"A","owner":"me","track_count":1,"source":"spotify"},
+            {"id":"b","name":"B","owner":"me","track_count":2,"source":"jellyfin"}
+        ]}"#;
+        write_playlists_cache(playlists).unwrap();
+        write_union_playlist_tracks_cache("mixed", "[]").unwrap();
+        write_union_playlist_tracks_cache("jellyfin_only", "[]").unwrap();
+
+        let purged = purge_provider_caches("spotify", &["mixed".to_string()]);
+        let remaining = read_playlists_cache();
+        crate::config::set_data_dir(None);
+        purged.unwrap();
+
+        let remaining: serde_json::Value =
+            serde_json::from_str(&remaining.unwrap().unwrap()).unwrap();
+        let sources: Vec<&str> = remaining["playlists"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["source"].as_str().unwrap())
+            .collect();
+        assert_eq!(sources, ["jellyfin"]);
+        assert!(!dir.join("union_playlist_tracks_mixed.json").exists());
+        assert!(dir
+            .join("union_playlist_tracks_jellyfin_only.json")
+            .exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
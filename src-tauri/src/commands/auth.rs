@@ -1,30 +1,64 @@
 /// Authentication commands for Spotify and Jellyfin
-use crate::commands::AppState;
+use crate::commands::{AppState, CommandError, ConnectionStatus, JellyfinServerInfo, ResultExt};
+use crate::providers::ProviderError;
 use tauri::State;
 
+/// Convert a timed health check result into a status for the settings page
+fn connection_status(result: Result<std::time::Duration, ProviderError>) -> ConnectionStatus {
+    match result {
+        Ok(latency) => ConnectionStatus {
+            connected: true,
+            latency_ms: latency.as_millis() as u64,
+            status: format!("Connected ({} ms)", latency.as_millis()),
+        },
+        Err(e) => ConnectionStatus {
+            connected: false,
+            latency_ms: 0,
+            status: e.to_string(),
+        },
+    }
+}
+
 /// Initialize Spotify OAuth flow and get authorization URL (no credentials needed)
 #[tauri::command]
-pub async fn get_spotify_auth_url(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn get_spotify_auth_url(state: State<'_, AppState>) -> Result<String, CommandError> {
+    // Make sure the callback server is listening and the redirect URI matches its port
+    let port = state
+        .oauth_server
+        .start()
+        .await
+        .context("Failed to start OAuth callback server")?;
+
     let mut providers = state.providers.lock().await;
+    providers.set_spotify_redirect_uri(crate::providers::spotify::redirect_uri_for_port(port));
 
     let auth_url = providers
         .get_spotify_auth_url_default()
-        .map_err(|e| format!("Failed to get auth URL: {}", e))?;
+        .context("Failed to get auth URL")?;
+
+    // Remember the state embedded in the URL so the callback can be verified
+    let expected_state = providers.get_spotify_oauth_state().await;
+    drop(providers);
+    *state.oauth_state.lock().await = expected_state;
 
     Ok(auth_url)
 }
 
 /// Complete Spotify OAuth authentication with authorization code
 #[tauri::command]
-pub async fn authenticate_spotify(state: State<'_, AppState>, code: String) -> Result<(), String> {
+pub async fn authenticate_spotify(
+    state: State<'_, AppState>,
+    code: String,
+) -> Result<(), CommandError> {
     tracing::info!("Starting Spotify authentication with authorization code");
 
     let providers = state.providers.lock().await;
     providers
         .authenticate_spotify(&code)
         .await
-        .map_err(|e| format!("Failed to authenticate: {}", e))?;
+        .context("Failed to authenticate")?;
     drop(providers);
+    state.clear_search_caches("spotify");
 
     tracing::info!("Spotify authentication successful");
 
@@ -36,23 +70,34 @@ pub async fn authenticate_spotify(state: State<'_, AppState>, code: String) -> R
 
 /// Check if Spotify is connected and authenticated
 #[tauri::command]
-pub async fn is_spotify_authenticated(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn is_spotify_authenticated(state: State<'_, AppState>) -> Result<bool, CommandError> {
     let providers = state.providers.lock().await;
     let authenticated = providers.is_spotify_authenticated().await;
     tracing::debug!("is_spotify_authenticated query result: {}", authenticated);
     Ok(authenticated)
 }
 
+/// Test the Spotify connection and report latency
+#[tauri::command]
+pub async fn check_spotify_connection(
+    state: State<'_, AppState>,
+) -> Result<ConnectionStatus, CommandError> {
+    let providers = state.providers.lock().await;
+    Ok(connection_status(
+        providers.check_spotify_connection().await,
+    ))
+}
+
 /// Check if user has Spotify Premium
 ///
 /// Returns true if authenticated user has Spotify Premium, false otherwise
 #[tauri::command]
-pub async fn check_spotify_premium(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn check_spotify_premium(state: State<'_, AppState>) -> Result<bool, CommandError> {
     let providers = state.providers.lock().await;
     providers
         .is_spotify_premium()
         .await
-        .ok_or_else(|| "Spotify not authenticated".to_string())
+        .ok_or_else(|| CommandError::Auth("Spotify not authenticated".to_string()))
 }
 
 /// Initialize Spotify session for premium track streaming
@@ -63,9 +108,12 @@ pub async fn check_spotify_premium(state: State<'_, AppState>) -> Result<bool, S
 pub async fn initialize_spotify_session(
     state: State<'_, AppState>,
     access_token: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let playback = state.playback.lock().await;
-    playback.initialize_spotify_session(&access_token).await
+    playback
+        .initialize_spotify_session(&access_token)
+        .await
+        .map_err(CommandError::from)
 }
 
 /// Initialize Spotify session using the stored provider access token
@@ -75,7 +123,7 @@ pub async fn initialize_spotify_session(
 #[tauri::command]
 pub async fn initialize_spotify_session_from_provider(
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let providers = state.providers.lock().await;
     if let Some(access_token) = providers.get_spotify_access_token().await {
         drop(providers);
@@ -83,15 +131,17 @@ pub async fn initialize_spotify_session_from_provider(
         playback
             .initialize_spotify_session(&access_token)
             .await
-            .map_err(|e| format!("Failed to initialize session: {}", e))
+            .context("Failed to initialize session")
     } else {
-        Err("No Spotify access token available in provider registry".to_string())
+        Err(CommandError::Auth(
+            "No Spotify access token available in provider registry".to_string(),
+        ))
     }
 }
 
 /// Check if Spotify session is initialized and ready for playback
 #[tauri::command]
-pub async fn is_spotify_session_ready(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn is_spotify_session_ready(state: State<'_, AppState>) -> Result<bool, CommandError> {
     let playback = state.playback.lock().await;
     Ok(playback.is_spotify_session_ready().await)
 }
@@ -101,12 +151,12 @@ pub async fn is_spotify_session_ready(state: State<'_, AppState>) -> Result<bool
 /// Called periodically or when token expires to maintain active authentication
 /// and session state for premium playback features.
 #[tauri::command]
-pub async fn refresh_spotify_token(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn refresh_spotify_token(state: State<'_, AppState>) -> Result<(), CommandError> {
     let mut providers = state.providers.lock().await;
     providers
         .refresh_spotify_token()
         .await
-        .map_err(|e| format!("Failed to refresh Spotify token: {}", e))?;
+        .context("Failed to refresh Spotify token")?;
 
     // If token was refreshed and user is premium, reinitialize session
     if let Some(true) = providers.is_spotify_premium().await {
@@ -129,7 +179,7 @@ pub async fn refresh_spotify_token(state: State<'_, AppState>) -> Result<(), Str
 
 /// Check for and process pending OAuth code
 #[tauri::command]
-pub async fn check_oauth_code(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn check_oauth_code(state: State<'_, AppState>) -> Result<bool, CommandError> {
     let mut oauth_code = state.oauth_code.lock().await;
 
     if let Some(code) = oauth_code.take() {
@@ -140,8 +190,9 @@ pub async fn check_oauth_code(state: State<'_, AppState>) -> Result<bool, String
         providers
             .authenticate_spotify(&code)
             .await
-            .map_err(|e| format!("Failed to authenticate: {}", e))?;
+            .context("Failed to authenticate")?;
         drop(providers);
+        state.clear_search_caches("spotify");
 
         tracing::info!("Provider authentication succeeded");
 
@@ -154,41 +205,79 @@ pub async fn check_oauth_code(state: State<'_, AppState>) -> Result<bool, String
     }
 }
 
-/// Disconnect and revoke Spotify authentication
+/// Forget what's cached from a disconnected provider and mark the union
+/// playlist sources that use it as unavailable, so they aren't fetched
+/// until the provider is connected again
+async fn purge_provider_data(state: &AppState, source: &str) -> Result<(), CommandError> {
+    let union_playlist_ids = state
+        .database
+        .lock()
+        .await
+        .mark_union_sources_unavailable(source)
+        .context("Failed to mark union playlist sources unavailable")?;
+    crate::cache::purge_provider_caches(source, &union_playlist_ids)
+        .context("Failed to clear cached playlists")?;
+    tracing::info!(
+        "Purged cached {} playlists from {} union playlists",
+        source,
+        union_playlist_ids.len()
+    );
+    Ok(())
+}
+
+/// Disconnect and revoke Spotify authentication. With `purge` the cached
+/// Spotify playlists are dropped too.
 #[tauri::command]
-pub async fn disconnect_spotify(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn disconnect_spotify(
+    state: State<'_, AppState>,
+    purge: bool,
+) -> Result<(), CommandError> {
     let mut providers = state.providers.lock().await;
 
     providers
         .disconnect_spotify()
         .await
-        .map_err(|e| format!("Failed to disconnect Spotify: {}", e))
+        .context("Failed to disconnect Spotify")?;
+    drop(providers);
+    state.clear_search_caches("spotify");
+
+    if purge {
+        purge_provider_data(&state, "spotify").await?;
+    }
+    Ok(())
 }
 
 /// Restore Spotify session from saved tokens
 #[tauri::command]
-pub async fn restore_spotify_session(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn restore_spotify_session(state: State<'_, AppState>) -> Result<bool, CommandError> {
     let mut providers = state.providers.lock().await;
 
-    providers
+    let restored = providers
         .restore_spotify_session()
         .await
-        .map_err(|e| format!("Failed to restore Spotify session: {}", e))
+        .context("Failed to restore Spotify session")?;
+    drop(providers);
+
+    if restored {
+        state.clear_search_caches("spotify");
+    }
+    Ok(restored)
 }
 
 /// Clear saved Spotify session tokens and in-memory Spotify session state
 #[tauri::command]
-pub async fn clear_spotify_session(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn clear_spotify_session(state: State<'_, AppState>) -> Result<(), CommandError> {
     use crate::config::Config;
 
     let mut providers = state.providers.lock().await;
     providers
         .disconnect_spotify()
         .await
-        .map_err(|e| format!("Failed to disconnect Spotify during session clear: {}", e))?;
+        .context("Failed to disconnect Spotify during session clear")?;
     drop(providers);
+    state.clear_search_caches("spotify");
 
-    Config::clear_tokens().map_err(|e| format!("Failed to clear tokens: {}", e))
+    Config::clear_tokens().context("Failed to clear tokens")
 }
 
 /// Jellyfin authentication and connection
@@ -197,7 +286,8 @@ pub async fn authenticate_jellyfin(
     state: State<'_, AppState>,
     url: String,
     api_key: String,
-) -> Result<(), String> {
+    name: Option<String>,
+) -> Result<(), CommandError> {
     use crate::config::Config;
 
     let mut providers = state.providers.lock().await;
@@ -205,30 +295,127 @@ pub async fn authenticate_jellyfin(
     providers
         .authenticate_jellyfin(&url, &api_key)
         .await
-        .map_err(|e| format!("Failed to authenticate Jellyfin: {}", e))?;
+        .context("Failed to authenticate Jellyfin")?;
+
+    // Remember the URL as normalized by the provider (scheme added, trailing
+    // slash dropped) so it matches the active server later
+    let url = providers.active_jellyfin_server().await.unwrap_or(url);
+    drop(providers);
+    state.clear_search_caches("jellyfin");
 
     // Save credentials to secure storage after successful authentication
-    let mut tokens = Config::load_tokens().map_err(|e| format!("Failed to load tokens: {}", e))?;
+    let mut tokens = Config::load_tokens().context("Failed to load tokens")?;
+    tokens
+        .jellyfin_server_keys
+        .insert(url.clone(), api_key.clone());
     tokens.jellyfin_api_key = Some(api_key);
-    tokens.jellyfin_url = Some(url);
-    Config::save_tokens(&tokens)
-        .map_err(|e| format!("Failed to save Jellyfin credentials: {}", e))?;
+    tokens.jellyfin_url = Some(url.clone());
+    Config::save_tokens(&tokens).context("Failed to save Jellyfin credentials")?;
 
     tracing::info!("Jellyfin credentials saved to secure storage");
 
+    // Remember the server so the user can switch back to it later
+    let mut config = Config::load_from(None).context("Failed to load config")?;
+    config.add_jellyfin_server(&url, name);
+    config.active_jellyfin_server = Some(url);
+    config.save().context("Failed to save config")?;
+
+    Ok(())
+}
+
+/// List configured Jellyfin servers
+#[tauri::command]
+pub async fn list_jellyfin_servers(
+    state: State<'_, AppState>,
+) -> Result<Vec<JellyfinServerInfo>, CommandError> {
+    use crate::config::Config;
+
+    let config = Config::load().context("Failed to load config")?;
+    let active_url = state.providers.lock().await.active_jellyfin_server().await;
+
+    Ok(config
+        .jellyfin
+        .iter()
+        .map(|server| JellyfinServerInfo {
+            name: server
+                .name
+                .clone()
+                .unwrap_or_else(|| server.server_url.clone()),
+            url: server.server_url.clone(),
+            active: active_url.as_deref() == Some(server.server_url.as_str()),
+        })
+        .collect())
+}
+
+/// Switch to another configured Jellyfin server using its saved API key
+#[tauri::command]
+pub async fn set_active_jellyfin_server(
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<(), CommandError> {
+    use crate::config::Config;
+
+    let mut config = Config::load_from(None).context("Failed to load config")?;
+    let server = config
+        .set_active_jellyfin_server(&url)
+        .map_err(CommandError::NotFound)?
+        .clone();
+
+    let mut tokens = Config::load_tokens().context("Failed to load tokens")?;
+    let api_key = tokens
+        .jellyfin_server_keys
+        .get(&url)
+        .cloned()
+        .or_else(|| (!server.api_key.is_empty()).then(|| server.api_key.clone()))
+        .ok_or_else(|| {
+            CommandError::Auth(format!("No saved API key for Jellyfin server: {}", url))
+        })?;
+
+    state
+        .providers
+        .lock()
+        .await
+        .authenticate_jellyfin(&url, &api_key)
+        .await
+        .context("Failed to authenticate Jellyfin")?;
+    state.clear_search_caches("jellyfin");
+
+    tokens
+        .jellyfin_server_keys
+        .insert(url.clone(), api_key.clone());
+    tokens.jellyfin_api_key = Some(api_key);
+    tokens.jellyfin_url = Some(url);
+    Config::save_tokens(&tokens).context("Failed to save Jellyfin credentials")?;
+    config.save().context("Failed to save config")?;
+
     Ok(())
 }
 
 /// Check if Jellyfin is connected and authenticated
 #[tauri::command]
-pub async fn is_jellyfin_authenticated(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn is_jellyfin_authenticated(state: State<'_, AppState>) -> Result<bool, CommandError> {
     let providers = state.providers.lock().await;
     Ok(providers.is_jellyfin_authenticated().await)
 }
 
-/// Disconnect and revoke Jellyfin authentication
+/// Test the Jellyfin connection and report latency
+#[tauri::command]
+pub async fn check_jellyfin_connection(
+    state: State<'_, AppState>,
+) -> Result<ConnectionStatus, CommandError> {
+    let providers = state.providers.lock().await;
+    Ok(connection_status(
+        providers.check_jellyfin_connection().await,
+    ))
+}
+
+/// Disconnect and revoke Jellyfin authentication. With `purge` the cached
+/// Jellyfin playlists are dropped too.
 #[tauri::command]
-pub async fn disconnect_jellyfin(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn disconnect_jellyfin(
+    state: State<'_, AppState>,
+    purge: bool,
+) -> Result<(), CommandError> {
     use crate::config::Config;
 
     let mut providers = state.providers.lock().await;
@@ -236,17 +423,23 @@ pub async fn disconnect_jellyfin(state: State<'_, AppState>) -> Result<(), Strin
     providers
         .disconnect_jellyfin()
         .await
-        .map_err(|e| format!("Failed to disconnect Jellyfin: {}", e))?;
+        .context("Failed to disconnect Jellyfin")?;
+    drop(providers);
+    state.clear_search_caches("jellyfin");
 
     // Clear stored Jellyfin credentials from secure storage
-    let mut tokens = Config::load_tokens().map_err(|e| format!("Failed to load tokens: {}", e))?;
+    let mut tokens = Config::load_tokens().context("Failed to load tokens")?;
+    if let Some(url) = tokens.jellyfin_url.take() {
+        tokens.jellyfin_server_keys.remove(&url);
+    }
     tokens.jellyfin_api_key = None;
-    tokens.jellyfin_url = None;
-    Config::save_tokens(&tokens)
-        .map_err(|e| format!("Failed to clear Jellyfin credentials: {}", e))?;
+    Config::save_tokens(&tokens).context("Failed to clear Jellyfin credentials")?;
 
     tracing::info!("Jellyfin credentials cleared from secure storage");
 
+    if purge {
+        purge_provider_data(&state, "jellyfin").await?;
+    }
     Ok(())
 }
 
@@ -254,10 +447,10 @@ pub async fn disconnect_jellyfin(state: State<'_, AppState>) -> Result<(), Strin
 #[tauri::command]
 pub async fn get_jellyfin_credentials(
     _state: State<'_, AppState>,
-) -> Result<Option<(String, String)>, String> {
+) -> Result<Option<(String, String)>, CommandError> {
     use crate::config::Config;
 
-    let tokens = Config::load_tokens().map_err(|e| format!("Failed to load tokens: {}", e))?;
+    let tokens = Config::load_tokens().context("Failed to load tokens")?;
 
     match (tokens.jellyfin_url, tokens.jellyfin_api_key) {
         (Some(url), Some(api_key)) => Ok(Some((url, api_key))),
@@ -267,11 +460,17 @@ pub async fn get_jellyfin_credentials(
 
 /// Restore Jellyfin session from saved credentials
 #[tauri::command]
-pub async fn restore_jellyfin_session(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn restore_jellyfin_session(state: State<'_, AppState>) -> Result<bool, CommandError> {
     let mut providers = state.providers.lock().await;
 
-    providers
+    let restored = providers
         .restore_jellyfin_session()
         .await
-        .map_err(|e| format!("Failed to restore Jellyfin session: {}", e))
+        .context("Failed to restore Jellyfin session")?;
+    drop(providers);
+
+    if restored {
+        state.clear_search_caches("jellyfin");
+    }
+    Ok(restored)
 }
@@ -1,44 +1,41 @@
 /// Cache management commands
+use crate::commands::{CommandError, ResultExt};
+
 /// Write playlists cache to disk
 #[tauri::command]
-pub async fn write_playlists_cache(data: String) -> Result<(), String> {
-    crate::cache::write_playlists_cache(&data)
-        .map_err(|e| format!("Failed to write playlists cache: {}", e))
+pub async fn write_playlists_cache(data: String) -> Result<(), CommandError> {
+    crate::cache::write_playlists_cache(&data).context("Failed to write playlists cache")
 }
 
 /// Read playlists cache from disk
 #[tauri::command]
-pub async fn read_playlists_cache() -> Result<Option<String>, String> {
-    crate::cache::read_playlists_cache()
-        .map_err(|e| format!("Failed to read playlists cache: {}", e))
+pub async fn read_playlists_cache() -> Result<Option<String>, CommandError> {
+    crate::cache::read_playlists_cache().context("Failed to read playlists cache")
 }
 
 /// Clear playlists cache
 #[tauri::command]
-pub async fn clear_playlists_cache() -> Result<(), String> {
-    crate::cache::clear_playlists_cache()
-        .map_err(|e| format!("Failed to clear playlists cache: {}", e))
+pub async fn clear_playlists_cache() -> Result<(), CommandError> {
+    crate::cache::clear_playlists_cache().context("Failed to clear playlists cache")
 }
 
 /// Write custom playlists cache to disk
 #[tauri::command]
-pub async fn write_custom_playlists_cache(data: String) -> Result<(), String> {
+pub async fn write_custom_playlists_cache(data: String) -> Result<(), CommandError> {
     crate::cache::write_custom_playlists_cache(&data)
-        .map_err(|e| format!("Failed to write custom playlists cache: {}", e))
+        .context("Failed to write custom playlists cache")
 }
 
 /// Read custom playlists cache from disk
 #[tauri::command]
-pub async fn read_custom_playlists_cache() -> Result<Option<String>, String> {
-    crate::cache::read_custom_playlists_cache()
-        .map_err(|e| format!("Failed to read custom playlists cache: {}", e))
+pub async fn read_custom_playlists_cache() -> Result<Option<String>, CommandError> {
+    crate::cache::read_custom_playlists_cache().context("Failed to read custom playlists cache")
 }
 
 /// Clear custom playlists cache
 #[tauri::command]
-pub async fn clear_custom_playlists_cache() -> Result<(), String> {
-    crate::cache::clear_custom_playlists_cache()
-        .map_err(|e| format!("Failed to clear custom playlists cache: {}", e))
+pub async fn clear_custom_playlists_cache() -> Result<(), CommandError> {
+    crate::cache::clear_custom_playlists_cache().context("Failed to clear custom playlists cache")
 }
 
 /// Write custom playlist tracks cache to disk
@@ -46,25 +43,25 @@ pub async fn clear_custom_playlists_cache() -> Result<(), String> {
 pub async fn write_custom_playlist_tracks_cache(
     playlist_id: String,
     data: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     crate::cache::write_custom_playlist_tracks_cache(&playlist_id, &data)
-        .map_err(|e| format!("Failed to write custom playlist tracks cache: {}", e))
+        .context("Failed to write custom playlist tracks cache")
 }
 
 /// Read custom playlist tracks cache from disk
 #[tauri::command]
 pub async fn read_custom_playlist_tracks_cache(
     playlist_id: String,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     crate::cache::read_custom_playlist_tracks_cache(&playlist_id)
-        .map_err(|e| format!("Failed to read custom playlist tracks cache: {}", e))
+        .context("Failed to read custom playlist tracks cache")
 }
 
 /// Clear custom playlist tracks cache
 #[tauri::command]
-pub async fn clear_custom_playlist_tracks_cache(playlist_id: String) -> Result<(), String> {
+pub async fn clear_custom_playlist_tracks_cache(playlist_id: String) -> Result<(), CommandError> {
     crate::cache::clear_custom_playlist_tracks_cache(&playlist_id)
-        .map_err(|e| format!("Failed to clear custom playlist tracks cache: {}", e))
+        .context("Failed to clear custom playlist tracks cache")
 }
 
 /// Write union playlist tracks cache to disk
@@ -72,23 +69,23 @@ pub async fn clear_custom_playlist_tracks_cache(playlist_id: String) -> Result<(
 pub async fn write_union_playlist_tracks_cache(
     playlist_id: String,
     data: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     crate::cache::write_union_playlist_tracks_cache(&playlist_id, &data)
-        .map_err(|e| format!("Failed to write union playlist tracks cache: {}", e))
+        .context("Failed to write union playlist tracks cache")
 }
 
 /// Read union playlist tracks cache from disk
 #[tauri::command]
 pub async fn read_union_playlist_tracks_cache(
     playlist_id: String,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     crate::cache::read_union_playlist_tracks_cache(&playlist_id)
-        .map_err(|e| format!("Failed to read union playlist tracks cache: {}", e))
+        .context("Failed to read union playlist tracks cache")
 }
 
 /// Clear union playlist tracks cache
 #[tauri::command]
-pub async fn clear_union_playlist_tracks_cache(playlist_id: String) -> Result<(), String> {
+pub async fn clear_union_playlist_tracks_cache(playlist_id: String) -> Result<(), CommandError> {
     crate::cache::clear_union_playlist_tracks_cache(&playlist_id)
-        .map_err(|e| format!("Failed to clear union playlist tracks cache: {}", e))
+        .context("Failed to clear union playlist tracks cache")
 }
@@ -1,8 +1,15 @@
 /// Custom playlist management commands
-use crate::commands::AppState;
-use crate::database::{ColumnPreferences, CustomPlaylist, PlaylistTrack, UnionPlaylistSource};
-use crate::models::Track;
+use crate::commands::{
+    parse_source, AppState, CommandError, PushPlaylistReport, ResultExt, TrackInfo,
+};
+use crate::database::{
+    ColumnPreferences, CustomPlaylist, LibraryStats, PlaylistTrack, UnionPlaylistSource,
+};
+use crate::models::{Source, Track};
+use crate::Database;
+use std::sync::Arc;
 use tauri::State;
+use tokio::sync::Mutex;
 
 #[tauri::command]
 pub async fn create_custom_playlist(
@@ -10,10 +17,10 @@ pub async fn create_custom_playlist(
     name: String,
     description: Option<String>,
     image_url: Option<String>,
-) -> Result<CustomPlaylist, String> {
+) -> Result<CustomPlaylist, CommandError> {
     let db = state.database.lock().await;
     db.create_playlist(name, description, image_url)
-        .map_err(|e| format!("Failed to create playlist: {}", e))
+        .context("Failed to create playlist")
 }
 
 #[tauri::command]
@@ -22,28 +29,101 @@ pub async fn create_union_playlist(
     name: String,
     description: Option<String>,
     image_url: Option<String>,
-) -> Result<CustomPlaylist, String> {
+) -> Result<CustomPlaylist, CommandError> {
     let db = state.database.lock().await;
     db.create_playlist_with_type(name, description, image_url, "union".to_string())
-        .map_err(|e| format!("Failed to create union playlist: {}", e))
+        .context("Failed to create union playlist")
+}
+
+/// Copy every track of a provider playlist into a new standard custom playlist
+#[tauri::command]
+pub async fn snapshot_provider_playlist(
+    state: State<'_, AppState>,
+    source: String,
+    playlist_id: String,
+    name: String,
+) -> Result<CustomPlaylist, CommandError> {
+    let playlist = {
+        let providers = state.providers.lock().await;
+        match parse_source(&source)? {
+            Source::Spotify => providers.get_spotify_playlist(&playlist_id).await,
+            Source::Jellyfin => providers.get_jellyfin_playlist(&playlist_id).await,
+            Source::Custom => {
+                return Err(CommandError::InvalidInput(
+                    "Custom playlists can't be snapshotted".to_string(),
+                ));
+            }
+        }
+        .context("Failed to get playlist")?
+    };
+
+    let db = state.database.lock().await;
+    db.snapshot_playlist(name, &playlist)
+        .context("Failed to snapshot playlist")
+}
+
+/// Create a provider playlist holding a custom playlist's tracks. Tracks from
+/// other sources are matched by title and artist; unmatched ones are reported.
+#[tauri::command]
+pub async fn push_custom_playlist_to_provider(
+    state: State<'_, AppState>,
+    playlist_id: String,
+    source: String,
+) -> Result<PushPlaylistReport, CommandError> {
+    let source = parse_source(&source)?;
+
+    let (playlist, stored_tracks) = {
+        let db = state.database.lock().await;
+        let playlist = db
+            .get_playlist(&playlist_id)
+            .context("Failed to get playlist")?
+            .ok_or_else(|| {
+                CommandError::NotFound(format!("Playlist not found: {}", playlist_id))
+            })?;
+        let tracks = db
+            .get_playlist_tracks(&playlist_id)
+            .context("Failed to get playlist tracks")?;
+        (playlist, tracks)
+    };
+
+    let tracks = if playlist.playlist_type == "union" {
+        get_union_playlist_tracks(state.clone(), playlist_id).await?
+    } else {
+        stored_tracks.iter().map(PlaylistTrack::to_track).collect()
+    };
+
+    let providers = state.providers.lock().await;
+    let outcome = providers
+        .push_tracks(
+            source,
+            &playlist.name,
+            playlist.description.as_deref(),
+            &tracks,
+        )
+        .await
+        .context("Failed to push playlist")?;
+
+    Ok(PushPlaylistReport {
+        playlist_id: outcome.playlist.id,
+        pushed_count: outcome.pushed.len(),
+        skipped: outcome.skipped.into_iter().map(TrackInfo::from).collect(),
+    })
 }
 
 #[tauri::command]
 pub async fn get_custom_playlists(
     state: State<'_, AppState>,
-) -> Result<Vec<CustomPlaylist>, String> {
+) -> Result<Vec<CustomPlaylist>, CommandError> {
     let (mut playlists, union_sources_map) = {
         let db = state.database.lock().await;
-        let playlists = db
-            .get_all_playlists()
-            .map_err(|e| format!("Failed to get playlists: {}", e))?;
+        let playlists = db.get_all_playlists().context("Failed to get playlists")?;
 
         let mut union_sources_map = std::collections::HashMap::new();
         for playlist in &playlists {
             if playlist.playlist_type == "union" {
                 let sources = db
                     .get_union_playlist_sources(&playlist.id)
-                    .map_err(|e| format!("Failed to get union playlist sources: {}", e))?;
+                    .context("Failed to get union playlist sources")?;
                 union_sources_map.insert(playlist.id.clone(), sources);
             }
         }
@@ -118,10 +198,10 @@ pub async fn get_custom_playlists(
 pub async fn get_custom_playlist(
     state: State<'_, AppState>,
     playlist_id: String,
-) -> Result<Option<CustomPlaylist>, String> {
+) -> Result<Option<CustomPlaylist>, CommandError> {
     let db = state.database.lock().await;
     db.get_playlist(&playlist_id)
-        .map_err(|e| format!("Failed to get playlist: {}", e))
+        .context("Failed to get playlist")
 }
 
 #[tauri::command]
@@ -131,20 +211,20 @@ pub async fn update_custom_playlist(
     name: Option<String>,
     description: Option<String>,
     image_url: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let db = state.database.lock().await;
     db.update_playlist(&playlist_id, name, description, image_url)
-        .map_err(|e| format!("Failed to update playlist: {}", e))
+        .context("Failed to update playlist")
 }
 
 #[tauri::command]
 pub async fn delete_custom_playlist(
     state: State<'_, AppState>,
     playlist_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let db = state.database.lock().await;
     db.delete_playlist(&playlist_id)
-        .map_err(|e| format!("Failed to delete playlist: {}", e))
+        .context("Failed to delete playlist")
 }
 
 #[tauri::command]
@@ -152,30 +232,52 @@ pub async fn add_track_to_custom_playlist(
     state: State<'_, AppState>,
     playlist_id: String,
     track: Track,
-) -> Result<PlaylistTrack, String> {
-    let db = state.database.lock().await;
-    db.add_track_to_playlist(&playlist_id, &track)
-        .map_err(|e| format!("Failed to add track: {}", e))
+) -> Result<PlaylistTrack, CommandError> {
+    let added = {
+        let db = state.database.lock().await;
+        db.add_track_to_playlist(&playlist_id, &track)
+            .context("Failed to add track")?
+    };
+    refresh_generated_cover(state.database.clone(), playlist_id);
+    Ok(added)
 }
 
 #[tauri::command]
 pub async fn get_custom_playlist_tracks(
     state: State<'_, AppState>,
     playlist_id: String,
-) -> Result<Vec<PlaylistTrack>, String> {
+) -> Result<Vec<PlaylistTrack>, CommandError> {
     let db = state.database.lock().await;
     db.get_playlist_tracks(&playlist_id)
-        .map_err(|e| format!("Failed to get playlist tracks: {}", e))
+        .context("Failed to get playlist tracks")
+}
+
+/// Get a custom playlist's tracks ordered by the saved column sort
+#[tauri::command]
+pub async fn get_custom_playlist_tracks_sorted(
+    state: State<'_, AppState>,
+    playlist_id: String,
+) -> Result<Vec<PlaylistTrack>, CommandError> {
+    let db = state.database.lock().await;
+    let prefs = db
+        .get_column_preferences()
+        .context("Failed to get column preferences")?;
+    db.get_playlist_tracks_sorted(&playlist_id, &prefs)
+        .context("Failed to get playlist tracks")
 }
 
 #[tauri::command]
 pub async fn remove_track_from_custom_playlist(
     state: State<'_, AppState>,
     track_id: i64,
-) -> Result<(), String> {
-    let db = state.database.lock().await;
-    db.remove_track_from_playlist(track_id)
-        .map_err(|e| format!("Failed to remove track: {}", e))
+) -> Result<(), CommandError> {
+    let playlist_id = {
+        let db = state.database.lock().await;
+        db.remove_track_from_playlist(track_id)
+            .context("Failed to remove track")?
+    };
+    refresh_generated_cover(state.database.clone(), playlist_id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -184,29 +286,155 @@ pub async fn reorder_custom_playlist_tracks(
     playlist_id: String,
     track_id: i64,
     new_position: i64,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    {
+        let db = state.database.lock().await;
+        db.reorder_tracks(&playlist_id, track_id, new_position)
+            .context("Failed to reorder tracks")?;
+    }
+    refresh_generated_cover(state.database.clone(), playlist_id);
+    Ok(())
+}
+
+/// Move a track from one custom playlist to the end of another
+#[tauri::command]
+pub async fn move_custom_playlist_track(
+    state: State<'_, AppState>,
+    track_id: i64,
+    from_playlist_id: String,
+    to_playlist_id: String,
+) -> Result<(), CommandError> {
+    {
+        let db = state.database.lock().await;
+        db.move_track(track_id, &from_playlist_id, &to_playlist_id)
+            .context("Failed to move track")?;
+    }
+    refresh_generated_cover(state.database.clone(), from_playlist_id);
+    refresh_generated_cover(state.database.clone(), to_playlist_id);
+    Ok(())
+}
+
+/// Build a cover for a custom playlist from its tracks' album art and set it
+/// as the playlist image. Returns the new image URL, or `None` when none of
+/// the tracks have usable artwork.
+#[tauri::command]
+pub async fn generate_playlist_cover(
+    state: State<'_, AppState>,
+    playlist_id: String,
+) -> Result<Option<String>, CommandError> {
+    let playlist = {
+        let db = state.database.lock().await;
+        db.get_playlist(&playlist_id)
+            .context("Failed to get playlist")?
+            .ok_or_else(|| CommandError::NotFound(format!("Playlist not found: {}", playlist_id)))?
+    };
+
+    let image_urls: Vec<String> = if playlist.playlist_type == "union" {
+        get_union_playlist_tracks(state.clone(), playlist_id.clone())
+            .await?
+            .into_iter()
+            .filter_map(|track| track.image_url)
+            .collect()
+    } else {
+        let db = state.database.lock().await;
+        db.get_playlist_tracks(&playlist_id)
+            .context("Failed to get playlist tracks")?
+            .into_iter()
+            .filter_map(|track| track.image_url)
+            .collect()
+    };
+
+    set_generated_cover(&state.database, &playlist_id, &image_urls).await
+}
+
+/// Generate a cover from `image_urls` and store it as the playlist image
+async fn set_generated_cover(
+    database: &Mutex<Database>,
+    playlist_id: &str,
+    image_urls: &[String],
+) -> Result<Option<String>, CommandError> {
+    let cover_url = crate::artwork::generate_cover(playlist_id, image_urls)
+        .await
+        .context("Failed to generate cover")?;
+
+    if let Some(url) = &cover_url {
+        database
+            .lock()
+            .await
+            .update_playlist(playlist_id, None, None, Some(url.clone()))
+            .context("Failed to set playlist cover")?;
+    }
+    Ok(cover_url)
+}
+
+/// Regenerate a standard playlist's cover in the background after its tracks
+/// change, unless the user has set an image of their own
+fn refresh_generated_cover(database: Arc<Mutex<Database>>, playlist_id: String) {
+    tokio::spawn(async move {
+        let image_urls: Vec<String> = {
+            let db = database.lock().await;
+            let replaceable = match db.get_playlist(&playlist_id) {
+                Ok(Some(playlist)) => playlist.image_url.as_deref().map_or(true, |url| {
+                    crate::artwork::is_generated_cover(url, &playlist_id)
+                }),
+                _ => false,
+            };
+            if !replaceable {
+                return;
+            }
+            match db.get_playlist_tracks(&playlist_id) {
+                Ok(tracks) => tracks.into_iter().filter_map(|t| t.image_url).collect(),
+                Err(_) => return,
+            }
+        };
+
+        if let Err(e) = set_generated_cover(&database, &playlist_id, &image_urls).await {
+            tracing::warn!(
+                "Failed to refresh cover for playlist {}: {}",
+                playlist_id,
+                e
+            );
+        }
+    });
+}
+
+/// Playlist, track and artist counts for the home dashboard
+#[tauri::command]
+pub async fn get_library_stats(state: State<'_, AppState>) -> Result<LibraryStats, CommandError> {
     let db = state.database.lock().await;
-    db.reorder_tracks(&playlist_id, track_id, new_position)
-        .map_err(|e| format!("Failed to reorder tracks: {}", e))
+    db.get_stats().context("Failed to get library stats")
 }
 
 #[tauri::command]
 pub async fn get_column_preferences(
     state: State<'_, AppState>,
-) -> Result<ColumnPreferences, String> {
+) -> Result<ColumnPreferences, CommandError> {
     let db = state.database.lock().await;
     db.get_column_preferences()
-        .map_err(|e| format!("Failed to get column preferences: {}", e))
+        .context("Failed to get column preferences")
+}
+
+/// List the column identifiers the track table can show
+#[tauri::command]
+pub async fn get_available_columns() -> Result<Vec<String>, CommandError> {
+    Ok(crate::database::available_columns()
+        .into_iter()
+        .map(String::from)
+        .collect())
 }
 
 #[tauri::command]
 pub async fn save_column_preferences(
     state: State<'_, AppState>,
     preferences: ColumnPreferences,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    preferences
+        .validate()
+        .map_err(|e| CommandError::InvalidInput(e.to_string()))?;
+
     let db = state.database.lock().await;
     db.save_column_preferences(&preferences)
-        .map_err(|e| format!("Failed to save column preferences: {}", e))
+        .context("Failed to save column preferences")
 }
 
 #[tauri::command]
@@ -215,30 +443,30 @@ pub async fn add_source_to_union_playlist(
     union_playlist_id: String,
     source_type: String,
     source_playlist_id: String,
-) -> Result<UnionPlaylistSource, String> {
+) -> Result<UnionPlaylistSource, CommandError> {
     let db = state.database.lock().await;
     db.add_source_to_union_playlist(&union_playlist_id, &source_type, &source_playlist_id)
-        .map_err(|e| format!("Failed to add source to union playlist: {}", e))
+        .context("Failed to add source to union playlist")
 }
 
 #[tauri::command]
 pub async fn get_union_playlist_sources(
     state: State<'_, AppState>,
     union_playlist_id: String,
-) -> Result<Vec<UnionPlaylistSource>, String> {
+) -> Result<Vec<UnionPlaylistSource>, CommandError> {
     let db = state.database.lock().await;
     db.get_union_playlist_sources(&union_playlist_id)
-        .map_err(|e| format!("Failed to get union playlist sources: {}", e))
+        .context("Failed to get union playlist sources")
 }
 
 #[tauri::command]
 pub async fn remove_source_from_union_playlist(
     state: State<'_, AppState>,
     source_id: i64,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let db = state.database.lock().await;
     db.remove_source_from_union_playlist(source_id)
-        .map_err(|e| format!("Failed to remove source from union playlist: {}", e))
+        .context("Failed to remove source from union playlist")
 }
 
 #[tauri::command]
@@ -247,23 +475,23 @@ pub async fn reorder_union_playlist_sources(
     union_playlist_id: String,
     source_id: i64,
     new_position: i64,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let db = state.database.lock().await;
     db.reorder_union_sources(&union_playlist_id, source_id, new_position)
-        .map_err(|e| format!("Failed to reorder union playlist sources: {}", e))
+        .context("Failed to reorder union playlist sources")
 }
 
 #[tauri::command]
 pub async fn get_union_playlist_tracks(
     state: State<'_, AppState>,
     union_playlist_id: String,
-) -> Result<Vec<Track>, String> {
+) -> Result<Vec<Track>, CommandError> {
     let db = state.database.lock().await;
     let providers = state.providers.lock().await;
 
     let sources = db
         .get_union_playlist_sources(&union_playlist_id)
-        .map_err(|e| format!("Failed to get union playlist sources: {}", e))?;
+        .context("Failed to get union playlist sources")?;
 
     tracing::info!(
         "Getting tracks for union playlist {} with {} sources",
@@ -280,7 +508,24 @@ pub async fn get_union_playlist_tracks(
             source.source_playlist_id
         );
 
-        match source.source_type.as_str() {
+        // Sources of a disconnected provider wait until it's connected again
+        if !source.available {
+            let connected = match source.source_type.as_str() {
+                "spotify" => providers.is_spotify_authenticated().await,
+                "jellyfin" => providers.is_jellyfin_authenticated().await,
+                _ => true,
+            };
+            if !connected {
+                tracing::debug!(
+                    "Skipping unavailable {} source {}",
+                    source.source_type,
+                    source.source_playlist_id
+                );
+                continue;
+            }
+        }
+
+        let loaded = match source.source_type.as_str() {
             "spotify" => {
                 match providers
                     .get_spotify_playlist(&source.source_playlist_id)
@@ -293,9 +538,11 @@ pub async fn get_union_playlist_tracks(
                             source.source_playlist_id
                         );
                         all_tracks.extend(playlist.tracks);
+                        true
                     }
                     Err(e) => {
                         tracing::error!("Failed to get Spotify playlist tracks: {}", e);
+                        false
                     }
                 }
             }
@@ -311,26 +558,35 @@ pub async fn get_union_playlist_tracks(
                             source.source_playlist_id
                         );
                         all_tracks.extend(playlist.tracks);
+                        true
                     }
                     Err(e) => {
                         tracing::error!("Failed to get Jellyfin playlist tracks: {}", e);
+                        false
                     }
                 }
             }
             "custom" => {
                 let tracks = db
                     .get_playlist_tracks(&source.source_playlist_id)
-                    .map_err(|e| format!("Failed to get custom playlist tracks: {}", e))?;
+                    .context("Failed to get custom playlist tracks")?;
                 tracing::info!(
                     "Got {} tracks from custom playlist {}",
                     tracks.len(),
                     source.source_playlist_id
                 );
                 all_tracks.extend(tracks.into_iter().map(|t| t.to_track()));
+                true
             }
             _ => {
                 tracing::warn!("Unknown source type: {}", source.source_type);
+                false
             }
+        };
+
+        if loaded && !source.available {
+            db.set_union_source_available(source.id, true)
+                .context("Failed to mark union playlist source available")?;
         }
     }
 
@@ -343,23 +599,25 @@ pub async fn get_union_playlist_tracks(
     Ok(all_tracks)
 }
 
-/// Internal helper for playing custom playlists
+/// Internal helper for playing custom playlists. With `append` the tracks go
+/// after the current queue instead of replacing it.
 pub(super) async fn play_custom_playlist_internal(
     state: &AppState,
     playlist_id: String,
-) -> Result<(), String> {
+    append: bool,
+) -> Result<(), CommandError> {
     let db = state.database.lock().await;
     let providers = state.providers.lock().await;
 
     let playlist_info = db
         .get_playlist(&playlist_id)
-        .map_err(|e| format!("Failed to get playlist info: {}", e))?
-        .ok_or_else(|| format!("Playlist not found: {}", playlist_id))?;
+        .context("Failed to get playlist info")?
+        .ok_or_else(|| CommandError::NotFound(format!("Playlist not found: {}", playlist_id)))?;
 
     let tracks_with_urls = if playlist_info.playlist_type == "union" {
         let sources = db
             .get_union_playlist_sources(&playlist_id)
-            .map_err(|e| format!("Failed to get union playlist sources: {}", e))?;
+            .context("Failed to get union playlist sources")?;
 
         drop(db);
 
@@ -398,7 +656,7 @@ pub(super) async fn play_custom_playlist_internal(
     } else {
         let playlist_tracks = db
             .get_playlist_tracks(&playlist_id)
-            .map_err(|e| format!("Failed to get custom playlist tracks: {}", e))?;
+            .context("Failed to get custom playlist tracks")?;
 
         drop(db);
 
@@ -428,11 +686,20 @@ pub(super) async fn play_custom_playlist_internal(
     };
 
     if tracks_with_urls.is_empty() {
-        return Err("Playlist is empty".to_string());
+        return Err(CommandError::InvalidInput("Playlist is empty".to_string()));
     }
 
     drop(providers);
 
+    let tracks_with_urls = if append {
+        match super::helpers::append_or_return(state, tracks_with_urls).await {
+            Some(tracks) => tracks,
+            None => return Ok(()),
+        }
+    } else {
+        tracks_with_urls
+    };
+
     let playback = state.playback.lock().await;
     playback.clear_queue().await;
     playback.queue_tracks(tracks_with_urls.clone()).await;
@@ -0,0 +1,149 @@
+/// Structured errors returned from Tauri commands
+use crate::providers::ProviderError;
+use serde::Serialize;
+
+/// Error returned to the frontend by commands. Serializes as
+/// `{ "kind": "auth", "message": "..." }` so the UI can branch on the kind
+/// (e.g. prompt to reconnect on `auth`) while still showing the message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum CommandError {
+    /// The provider isn't connected or rejected our credentials
+    #[error("{0}")]
+    Auth(String),
+    /// The provider couldn't be reached or is having trouble
+    #[error("{0}")]
+    Network(String),
+    /// The requested item doesn't exist
+    #[error("{0}")]
+    NotFound(String),
+    /// The request itself was wrong (unknown source, empty playlist, ...)
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Anything else
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl CommandError {
+    /// Prefix `context` to the message, keeping the kind
+    pub fn context(self, context: &str) -> Self {
+        let wrap = |message: String| format!("{}: {}", context, message);
+        match self {
+            Self::Auth(m) => Self::Auth(wrap(m)),
+            Self::Network(m) => Self::Network(wrap(m)),
+            Self::NotFound(m) => Self::NotFound(wrap(m)),
+            Self::InvalidInput(m) => Self::InvalidInput(wrap(m)),
+            Self::Internal(m) => Self::Internal(wrap(m)),
+        }
+    }
+}
+
+impl From<ProviderError> for CommandError {
+    fn from(error: ProviderError) -> Self {
+        let message = error.to_string();
+        match error {
+            ProviderError::NotAuthenticated(_) => Self::Auth(message),
+            ProviderError::Network(_) | ProviderError::RateLimited(_) => Self::Network(message),
+            ProviderError::NotFound(_) => Self::NotFound(message),
+            ProviderError::Api { status, .. } => match status {
+                401 | 403 => Self::Auth(message),
+                404 => Self::NotFound(message),
+                500..=599 => Self::Network(message),
+                _ => Self::Internal(message),
+            },
+            ProviderError::Other(_) => Self::Internal(message),
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Internal(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::Internal(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+impl From<anyhow::Error> for CommandError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for CommandError {
+    fn from(error: Box<dyn std::error::Error>) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+/// Attach a "Failed to ..." context to a fallible result on its way out of a
+/// command
+pub trait ResultExt<T> {
+    fn context(self, context: &str) -> Result<T, CommandError>;
+}
+
+impl<T, E: Into<CommandError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: &str) -> Result<T, CommandError> {
+        self.map_err(|e| e.into().context(context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_errors_map_to_kinds() {
+        let auth = CommandError::from(ProviderError::NotAuthenticated("Sign in again".into()));
+        assert_eq!(auth, CommandError::Auth("Sign in again".into()));
+
+        let offline = CommandError::from(ProviderError::Network("connection refused".into()));
+        assert!(matches!(offline, CommandError::Network(_)));
+
+        let missing = CommandError::from(ProviderError::Api {
+            status: 404,
+            message: "no such playlist".into(),
+        });
+        assert!(matches!(missing, CommandError::NotFound(_)));
+
+        let expired = CommandError::from(ProviderError::Api {
+            status: 401,
+            message: "token expired".into(),
+        });
+        assert!(matches!(expired, CommandError::Auth(_)));
+    }
+
+    #[test]
+    fn test_context_keeps_kind_and_display() {
+        let result: Result<(), ProviderError> =
+            Err(ProviderError::NotFound("Playlist 42 not found".into()));
+        let error = result.context("Failed to get playlist").unwrap_err();
+
+        assert!(matches!(error, CommandError::NotFound(_)));
+        assert_eq!(
+            error.to_string(),
+            "Failed to get playlist: Playlist 42 not found"
+        );
+    }
+
+    #[test]
+    fn test_serializes_with_kind_and_message() {
+        let json =
+            serde_json::to_value(CommandError::InvalidInput("Unknown source".into())).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "invalid_input", "message": "Unknown source" })
+        );
+    }
+}
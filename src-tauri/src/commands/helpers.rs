@@ -1,12 +1,67 @@
 /// Helper functions for track management and enrichment
-use crate::commands::AppState;
+use crate::commands::{AppState, CommandError, ResultExt};
+use crate::models::{Source, Track};
 use crate::{PlaybackManager, ProviderRegistry};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-/// Delay between API calls when eagerly enriching queued tracks (in milliseconds)
-/// This prevents overwhelming external APIs with rapid consecutive requests
-const TRACK_ENRICHMENT_DELAY_MS: u64 = 50;
+/// A queued track waiting to be enriched: queue index, track id and source
+type PendingTrack = (usize, String, Source);
+
+/// Parse a provider name sent by the frontend
+pub fn parse_source(source: &str) -> Result<Source, CommandError> {
+    match source.to_lowercase().as_str() {
+        "spotify" => Ok(Source::Spotify),
+        "jellyfin" => Ok(Source::Jellyfin),
+        "custom" => Ok(Source::Custom),
+        _ => Err(CommandError::InvalidInput(format!(
+            "Unknown source: '{}'. Supported sources are: spotify, jellyfin, custom",
+            source
+        ))),
+    }
+}
+
+/// Queue indices of the `lookahead` tracks after `current_index`, following
+/// the shuffle order when there is one
+fn enrichment_indices(
+    current_index: usize,
+    total_tracks: usize,
+    shuffle_order: &[usize],
+    lookahead: usize,
+) -> Vec<usize> {
+    // Start from 1 since the current track is already playing
+    (1..=lookahead.min(total_tracks.saturating_sub(1)))
+        .map(|i| {
+            if shuffle_order.is_empty() {
+                (current_index + i) % total_tracks
+            } else {
+                let shuffle_pos = (current_index + i) % total_tracks;
+                shuffle_order.get(shuffle_pos).copied().unwrap_or(i)
+            }
+        })
+        .collect()
+}
+
+/// Claim tracks for enrichment, dropping those another run is already fetching
+async fn claim_tracks(
+    in_flight: &Mutex<HashSet<(Source, String)>>,
+    tracks: Vec<PendingTrack>,
+) -> Vec<PendingTrack> {
+    let mut claimed = in_flight.lock().await;
+    tracks
+        .into_iter()
+        .filter(|(_, id, source)| claimed.insert((*source, id.clone())))
+        .collect()
+}
+
+/// Release tracks claimed with `claim_tracks`
+async fn release_tracks(in_flight: &Mutex<HashSet<(Source, String)>>, tracks: &[PendingTrack]) {
+    let mut claimed = in_flight.lock().await;
+    for (_, id, source) in tracks {
+        claimed.remove(&(*source, id.clone()));
+    }
+}
 
 /// Eagerly enrich queued tracks with full details (URLs, auth headers, etc.)
 /// Prioritizes tracks near the current playback position and loads them immediately
@@ -15,34 +70,38 @@ pub async fn enrich_queued_tracks_eager(
     providers: Arc<Mutex<ProviderRegistry>>,
     current_index: usize,
 ) {
-    const LOOKAHEAD_COUNT: usize = 10; // Number of tracks to load ahead
-
     let pb = playback.lock().await;
     let queue_arc = pb.get_queue_arc();
+    let (lookahead, delay) = pb.enrichment();
+    let in_flight = pb.enrichment_in_flight();
     drop(pb); // Release playback lock
 
+    let indices_to_load = {
+        let queue = queue_arc.lock().await;
+        enrichment_indices(
+            current_index,
+            queue.tracks.len(),
+            &queue.shuffle_order,
+            lookahead,
+        )
+    };
+
+    enrich_indices(queue_arc, providers, in_flight, delay, indices_to_load).await;
+}
+
+/// Fetch full details for the queued tracks at `indices` that aren't
+/// enriched yet and write them back into the queue
+async fn enrich_indices(
+    queue_arc: Arc<Mutex<crate::playback::PlaybackQueue>>,
+    providers: Arc<Mutex<ProviderRegistry>>,
+    in_flight: Arc<Mutex<HashSet<(Source, String)>>>,
+    delay: std::time::Duration,
+    indices_to_load: Vec<usize>,
+) {
     // Gather all information we need in a single lock acquisition
-    let (total_tracks, _shuffle_enabled, _shuffle_order, tracks_to_enrich) = {
+    let (total_tracks, tracks_to_enrich) = {
         let queue = queue_arc.lock().await;
         let total_tracks = queue.tracks.len();
-        let shuffle_enabled = !queue.shuffle_order.is_empty();
-        let shuffle_order = queue.shuffle_order.clone();
-
-        // Calculate which tracks to load first (starting from index 1 since 0 is already playing)
-        let mut indices_to_load = Vec::new();
-        for i in 1..=LOOKAHEAD_COUNT.min(total_tracks.saturating_sub(1)) {
-            let actual_index = if shuffle_enabled {
-                let shuffle_pos = (current_index + i) % total_tracks;
-                if shuffle_pos < shuffle_order.len() {
-                    shuffle_order[shuffle_pos]
-                } else {
-                    i
-                }
-            } else {
-                (current_index + i) % total_tracks
-            };
-            indices_to_load.push(actual_index);
-        }
 
         // Gather track info that needs enrichment
         let mut tracks_info = Vec::new();
@@ -56,33 +115,34 @@ pub async fn enrich_queued_tracks_eager(
             }
         }
 
-        (total_tracks, shuffle_enabled, shuffle_order, tracks_info)
+        (total_tracks, tracks_info)
     };
 
+    // Skip tracks an overlapping run (e.g. from rapid skipping) is already fetching
+    let tracks_to_enrich = claim_tracks(&in_flight, tracks_to_enrich).await;
+
     // Now fetch track details without holding any locks
     let providers_lock = providers.lock().await;
     let mut enriched_tracks = Vec::new();
 
-    for (track_idx, track_id, source) in tracks_to_enrich {
+    for (n, (track_idx, track_id, source)) in tracks_to_enrich.iter().enumerate() {
+        if n > 0 && !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+
         // Fetch full track details
         let enriched_track_result = match source {
-            crate::models::Source::Spotify => providers_lock.get_spotify_track(&track_id).await,
-            crate::models::Source::Jellyfin => providers_lock.get_jellyfin_track(&track_id).await,
+            crate::models::Source::Spotify => providers_lock.get_spotify_track(track_id).await,
+            crate::models::Source::Jellyfin => providers_lock.get_jellyfin_track(track_id).await,
             _ => continue, // Skip custom tracks
         };
 
         if let Ok(enriched_track) = enriched_track_result {
-            enriched_tracks.push((track_idx, enriched_track));
+            enriched_tracks.push((*track_idx, *source, enriched_track));
             tracing::debug!("Eagerly enriched track {} at index {}", track_id, track_idx);
         } else {
             tracing::warn!("Failed to enrich track {} at index {}", track_id, track_idx);
         }
-
-        // Small delay to avoid overwhelming the API
-        tokio::time::sleep(tokio::time::Duration::from_millis(
-            TRACK_ENRICHMENT_DELAY_MS,
-        ))
-        .await;
     }
 
     drop(providers_lock);
@@ -90,22 +150,75 @@ pub async fn enrich_queued_tracks_eager(
     // Update all enriched tracks in a single lock acquisition
     if !enriched_tracks.is_empty() {
         let mut queue = queue_arc.lock().await;
-        for (track_idx, enriched_track) in enriched_tracks {
-            if track_idx < queue.tracks.len() {
-                queue.tracks[track_idx] = enriched_track;
-            }
+        for (track_idx, source, enriched_track) in enriched_tracks {
+            store_enriched_track(&mut queue.tracks, track_idx, source, enriched_track);
         }
     }
 
+    // Only release once the queue holds the results, so later runs see them
+    release_tracks(&in_flight, &tracks_to_enrich).await;
+
     tracing::info!(
         "Completed eager loading for queue (total tracks: {})",
         total_tracks
     );
 }
 
+/// Put an enriched track back in the queue, in place of the track it was
+/// fetched for. The queue may have been cleared, replaced or reordered while
+/// it was fetched, so the track is looked up by ID when it's no longer at
+/// `index`, and dropped when it's gone.
+fn store_enriched_track(tracks: &mut [Track], index: usize, source: Source, enriched: Track) {
+    let is_same = |track: &Track| track.id == enriched.id && track.source == source;
+    let position = if tracks.get(index).is_some_and(is_same) {
+        Some(index)
+    } else {
+        tracks.iter().position(is_same)
+    };
+    match position {
+        Some(position) => tracks[position] = enriched,
+        None => tracing::debug!("Enriched track {} is no longer queued", enriched.id),
+    }
+}
+
+/// Enrich the tracks at `indices` in the background, e.g. a range just
+/// appended to the queue
+pub async fn enrich_queued_range(
+    playback: Arc<Mutex<PlaybackManager>>,
+    providers: Arc<Mutex<ProviderRegistry>>,
+    indices: std::ops::Range<usize>,
+) {
+    let pb = playback.lock().await;
+    let queue_arc = pb.get_queue_arc();
+    let (_, delay) = pb.enrichment();
+    let in_flight = pb.enrichment_in_flight();
+    drop(pb);
+
+    enrich_indices(queue_arc, providers, in_flight, delay, indices.collect()).await;
+}
+
+/// Append tracks to the queue without interrupting playback and enrich them
+/// in the background. Returns the tracks back when nothing is playing, so
+/// the caller can start playback instead.
+pub async fn append_or_return(state: &AppState, tracks: Vec<Track>) -> Option<Vec<Track>> {
+    let playback = state.playback.lock().await;
+    if playback.get_info().await.current_track.is_none() {
+        return Some(tracks);
+    }
+    let added = playback.append_to_queue(tracks).await;
+    drop(playback);
+
+    let playback_arc = state.playback.clone();
+    let providers_arc = state.providers.clone();
+    tokio::spawn(async move {
+        enrich_queued_range(playback_arc, providers_arc, added).await;
+    });
+    None
+}
+
 /// Helper function to initialize Spotify session for premium users
 /// Consolidates the duplicated logic from authenticate_spotify and check_oauth_code
-pub async fn initialize_premium_session_if_needed(state: &AppState) -> Result<(), String> {
+pub async fn initialize_premium_session_if_needed(state: &AppState) -> Result<(), CommandError> {
     let providers = state.providers.lock().await;
 
     match providers.is_spotify_premium().await {
@@ -135,7 +248,9 @@ pub async fn initialize_premium_session_if_needed(state: &AppState) -> Result<()
                 tracing::error!(
                     "Could not retrieve Spotify access token for session initialization"
                 );
-                Err("Failed to retrieve access token".to_string())
+                Err(CommandError::Auth(
+                    "Failed to retrieve access token".to_string(),
+                ))
             }
         }
         Some(false) => {
@@ -144,7 +259,9 @@ pub async fn initialize_premium_session_if_needed(state: &AppState) -> Result<()
         }
         None => {
             tracing::error!("Could not determine Spotify subscription status");
-            Err("Failed to determine subscription status".to_string())
+            Err(CommandError::Auth(
+                "Failed to determine subscription status".to_string(),
+            ))
         }
     }
 }
@@ -214,9 +331,12 @@ pub fn cleanup_old_temp_audio_files() {
 /// This is called on application shutdown to ensure cleanup happens even if
 /// the application doesn't run long enough for the rate-limited cleanup to trigger
 pub fn cleanup_all_temp_audio_files() {
-    let temp_dir = std::env::temp_dir();
+    cleanup_temp_audio_files_in(&std::env::temp_dir());
+}
 
-    if let Ok(entries) = std::fs::read_dir(&temp_dir) {
+/// Remove every temporary audio file the application created in `temp_dir`
+fn cleanup_temp_audio_files_in(temp_dir: &std::path::Path) {
+    if let Ok(entries) = std::fs::read_dir(temp_dir) {
         for entry in entries.flatten() {
             if let Ok(file_name) = entry.file_name().into_string() {
                 // Only process our temporary audio files
@@ -236,10 +356,31 @@ pub fn cleanup_all_temp_audio_files() {
     }
 }
 
+/// Flush playback state to disk and remove temporary audio files
+///
+/// Run once when the application exits, whether from closing the window or
+/// a Ctrl-C in the terminal it was launched from.
+pub async fn shutdown(playback: &Arc<Mutex<PlaybackManager>>) {
+    shutdown_with_temp_dir(playback, &std::env::temp_dir()).await;
+}
+
+/// [`shutdown`], removing temporary audio files from `temp_dir`
+async fn shutdown_with_temp_dir(
+    playback: &Arc<Mutex<PlaybackManager>>,
+    temp_dir: &std::path::Path,
+) {
+    match playback.lock().await.save_state().await {
+        Ok(()) => tracing::info!("✓ Playback state saved on exit"),
+        Err(e) => tracing::error!("Failed to save playback state on exit: {}", e),
+    }
+
+    cleanup_temp_audio_files_in(temp_dir);
+}
+
 /// Download audio to a temporary file and return the path as a file:// URL
 /// Automatically cleans up old temporary audio files to prevent disk space issues
 #[tauri::command]
-pub async fn get_audio_file(url: String) -> Result<String, String> {
+pub async fn get_audio_file(url: String) -> Result<String, CommandError> {
     use std::io::Write;
 
     tracing::info!("Downloading audio from: {}", url);
@@ -248,22 +389,25 @@ pub async fn get_audio_file(url: String) -> Result<String, String> {
     cleanup_old_temp_audio_files();
 
     // Fetch the audio file
-    let response = reqwest::Client::new()
+    let response = crate::http_client::shared_client()
         .get(&url)
-        .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64)")
+        .header("User-Agent", crate::config::client_identity().user_agent)
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch audio: {}", e))?;
+        .map_err(|e| CommandError::Network(format!("Failed to fetch audio: {}", e)))?;
 
     if !response.status().is_success() {
-        return Err(format!("Failed to fetch audio: HTTP {}", response.status()));
+        return Err(CommandError::Network(format!(
+            "Failed to fetch audio: HTTP {}",
+            response.status()
+        )));
     }
 
     // Read audio bytes
     let audio_bytes = response
         .bytes()
         .await
-        .map_err(|e| format!("Failed to read audio bytes: {}", e))?;
+        .map_err(|e| CommandError::Network(format!("Failed to read audio bytes: {}", e)))?;
 
     // Create temp file in system temp directory
     let temp_dir = std::env::temp_dir();
@@ -271,14 +415,149 @@ pub async fn get_audio_file(url: String) -> Result<String, String> {
     let file_path = temp_dir.join(&filename);
 
     // Write audio to file
-    let mut file = std::fs::File::create(&file_path)
-        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let mut file = std::fs::File::create(&file_path).context("Failed to create temp file")?;
 
     file.write_all(&audio_bytes)
-        .map_err(|e| format!("Failed to write audio to file: {}", e))?;
+        .context("Failed to write audio to file")?;
 
     // Return as file:// URL
     let file_url = format!("file://{}", file_path.display());
     tracing::info!("Audio saved to: {}", file_url);
     Ok(file_url)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PersistentPlaybackState, Volume};
+    use serial_test::serial;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(parse_source("Spotify").unwrap(), Source::Spotify);
+        assert_eq!(parse_source("jellyfin").unwrap(), Source::Jellyfin);
+        assert_eq!(parse_source("custom").unwrap(), Source::Custom);
+
+        let err = parse_source("tidal").unwrap_err();
+        assert!(matches!(
+            err,
+            CommandError::InvalidInput(ref m) if m.ends_with("spotify, jellyfin, custom")
+        ));
+    }
+
+    #[test]
+    fn test_enriched_track_only_replaces_its_own_track() {
+        let track = |id: &str, url: Option<&str>| Track {
+            id: id.to_string(),
+            title: format!("Track {}", id),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            album_artist: None,
+            duration_ms: 1000,
+            image_url: None,
+            source: Source::Jellyfin,
+            url: url.map(str::to_string),
+            auth_headers: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at: None,
+        };
+
+        // Still at its index
+        let mut tracks = vec![track("a", None), track("b", None)];
+        store_enriched_track(&mut tracks, 1, Source::Jellyfin, track("b", Some("url")));
+        assert_eq!(tracks[1].url.as_deref(), Some("url"));
+
+        // Moved after the queue was reordered
+        let mut tracks = vec![track("b", None), track("a", None)];
+        store_enriched_track(&mut tracks, 1, Source::Jellyfin, track("b", Some("url")));
+        assert_eq!(tracks[0].url.as_deref(), Some("url"));
+        assert_eq!(tracks[1].id, "a");
+        assert!(tracks[1].url.is_none());
+
+        // Gone after the queue was replaced
+        let mut tracks = vec![track("c", None), track("d", None)];
+        store_enriched_track(&mut tracks, 1, Source::Jellyfin, track("b", Some("url")));
+        assert_eq!(tracks[0].id, "c");
+        assert_eq!(tracks[1].id, "d");
+        assert!(tracks.iter().all(|t| t.url.is_none()));
+
+        // Same ID from another provider is a different track
+        let mut tracks = vec![track("b", None)];
+        store_enriched_track(&mut tracks, 0, Source::Spotify, track("b", Some("url")));
+        assert!(tracks[0].url.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_enrichment_fetches_each_track_once() {
+        let in_flight = Mutex::new(HashSet::new());
+        let pending = |ids: &[&str]| -> Vec<PendingTrack> {
+            ids.iter()
+                .enumerate()
+                .map(|(i, id)| (i, id.to_string(), Source::Jellyfin))
+                .collect()
+        };
+
+        let first = claim_tracks(&in_flight, pending(&["a", "b", "c"])).await;
+        let second = claim_tracks(&in_flight, pending(&["b", "c", "d"])).await;
+        let first_ids: Vec<_> = first.iter().map(|(_, id, _)| id.as_str()).collect();
+        let second_ids: Vec<_> = second.iter().map(|(_, id, _)| id.as_str()).collect();
+        assert_eq!(first_ids, vec!["a", "b", "c"]);
+        assert_eq!(second_ids, vec!["d"]);
+
+        // The same id from another source is a different track
+        let spotify = claim_tracks(&in_flight, vec![(0, "a".to_string(), Source::Spotify)]).await;
+        assert_eq!(spotify.len(), 1);
+
+        release_tracks(&in_flight, &first).await;
+        let third = claim_tracks(&in_flight, pending(&["a", "d"])).await;
+        let third_ids: Vec<_> = third.iter().map(|(_, id, _)| id.as_str()).collect();
+        assert_eq!(third_ids, vec!["a"]);
+    }
+
+    #[test]
+    fn test_enrichment_follows_configured_lookahead() {
+        assert_eq!(enrichment_indices(0, 20, &[], 3), vec![1, 2, 3]);
+        assert_eq!(enrichment_indices(0, 20, &[], 0), Vec::<usize>::new());
+        assert_eq!(enrichment_indices(18, 20, &[], 4), vec![19, 0, 1, 2]);
+        // Never more than the rest of the queue
+        assert_eq!(enrichment_indices(0, 5, &[], 25).len(), 4);
+
+        let shuffle_order = [4, 2, 0, 3, 1];
+        assert_eq!(enrichment_indices(0, 5, &shuffle_order, 2), vec![2, 0]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_shutdown_saves_state_and_cleans_temp_files() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-shutdown-{}", uuid::Uuid::new_v4()));
+        let temp_dir = dir.join("tmp");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        crate::config::set_data_dir(Some(dir.clone()));
+
+        let temp_file = temp_dir.join("any-player-audio-shutdown-test.mp3");
+        let other_file = temp_dir.join("unrelated.mp3");
+        std::fs::write(&temp_file, b"audio").unwrap();
+        std::fs::write(&other_file, b"audio").unwrap();
+
+        let providers = Arc::new(Mutex::new(ProviderRegistry::new()));
+        let playback = Arc::new(Mutex::new(PlaybackManager::new(providers)));
+        playback.lock().await.set_volume(Volume::new(33)).await;
+
+        shutdown_with_temp_dir(&playback, &temp_dir).await;
+
+        let saved = PersistentPlaybackState::load().await;
+        crate::config::set_data_dir(None);
+
+        let saved = saved.unwrap().expect("Shutdown did not save state");
+        assert_eq!(saved.volume.percent(), 33);
+        assert!(dir.join("playback_state.json").exists());
+        assert!(!temp_file.exists(), "Shutdown did not remove temp audio");
+        assert!(other_file.exists(), "Shutdown removed an unrelated file");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
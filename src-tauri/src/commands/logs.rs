@@ -0,0 +1,23 @@
+/// Log file commands
+use crate::commands::{CommandError, ResultExt};
+use tauri_plugin_opener::OpenerExt;
+
+/// Get the directory log files are written to
+#[tauri::command]
+pub async fn get_log_directory() -> Result<String, CommandError> {
+    crate::logging::log_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .ok_or_else(|| CommandError::Internal("Failed to determine log directory".to_string()))
+}
+
+/// Open the log directory in the system file manager
+#[tauri::command]
+pub async fn open_log_directory(app: tauri::AppHandle) -> Result<(), CommandError> {
+    let dir =
+        crate::logging::log_dir().ok_or_else(|| "Failed to determine log directory".to_string())?;
+    std::fs::create_dir_all(&dir).context("Failed to create log directory")?;
+
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| CommandError::Internal(format!("Failed to open log directory: {}", e)))
+}
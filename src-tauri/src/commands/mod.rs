@@ -2,7 +2,10 @@
 pub mod auth;
 pub mod cache;
 pub mod custom_playlists;
+pub mod error;
 pub mod helpers;
+pub mod logs;
+pub mod offline;
 pub mod playback;
 pub mod playlists;
 pub mod providers;
@@ -10,6 +13,7 @@ pub mod state;
 pub mod types;
 
 // Re-export AppState and types for convenience
+pub use error::{CommandError, ResultExt};
 pub use state::AppState;
 pub use types::*;
 
@@ -18,6 +22,8 @@ pub use auth::*;
 pub use cache::*;
 pub use custom_playlists::*;
 pub use helpers::*;
+pub use logs::*;
+pub use offline::*;
 pub use playback::*;
 pub use playlists::*;
 pub use providers::*;
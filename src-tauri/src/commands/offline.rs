@@ -0,0 +1,122 @@
+/// Offline download commands
+use crate::commands::{AppState, CommandError, DownloadSizeReport, ResultExt};
+use crate::database::DownloadedTrack;
+use crate::models::{Source, Track};
+use tauri::State;
+
+/// Download a track to the app data directory for offline playback
+#[tauri::command]
+pub async fn download_track(
+    state: State<'_, AppState>,
+    track: Track,
+) -> Result<DownloadedTrack, CommandError> {
+    let (url, auth_headers) = {
+        let providers = state.providers.lock().await;
+        match track.source {
+            Source::Jellyfin => {
+                let provider = providers.get(Source::Jellyfin).ok_or_else(|| {
+                    CommandError::Auth("Jellyfin provider not configured".to_string())
+                })?;
+                let url = provider
+                    .get_stream_url(&track.id)
+                    .await
+                    .context("Failed to get stream URL")?;
+                (url, providers.get_auth_headers(Source::Jellyfin).await)
+            }
+            // Full Spotify streams only exist inside librespot, so only previews can be saved
+            Source::Spotify => match &track.url {
+                Some(url) if url.starts_with("http") => (url.clone(), None),
+                _ => {
+                    return Err(CommandError::InvalidInput(
+                        "Only Spotify tracks with a preview can be downloaded".to_string(),
+                    ))
+                }
+            },
+            Source::Custom => {
+                return Err(CommandError::InvalidInput(
+                    "Custom tracks cannot be downloaded".to_string(),
+                ));
+            }
+        }
+    };
+
+    let dir = crate::offline::downloads_dir()
+        .ok_or_else(|| "Failed to determine downloads directory".to_string())?;
+    let path = crate::offline::download_path(&dir, track.source, &track.id);
+    let size = crate::offline::download_to(&url, auth_headers, &path).await?;
+
+    let db = state.database.lock().await;
+    db.add_download(
+        &track.source.to_string(),
+        &track.id,
+        &path.to_string_lossy(),
+        size as i64,
+    )
+    .context("Failed to record download")
+}
+
+/// Check whether a track has been downloaded
+#[tauri::command]
+pub async fn is_downloaded(
+    state: State<'_, AppState>,
+    source: String,
+    track_id: String,
+) -> Result<bool, CommandError> {
+    let db = state.database.lock().await;
+    db.get_download(&source.to_lowercase(), &track_id)
+        .map(|download| download.is_some())
+        .context("Failed to check download")
+}
+
+/// Delete a downloaded track and remove it from the index
+#[tauri::command]
+pub async fn remove_download(
+    state: State<'_, AppState>,
+    source: String,
+    track_id: String,
+) -> Result<(), CommandError> {
+    let removed = {
+        let db = state.database.lock().await;
+        db.remove_download(&source.to_lowercase(), &track_id)
+            .context("Failed to remove download")?
+    };
+
+    if let Some(download) = removed {
+        match tokio::fs::remove_file(&download.local_path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(CommandError::from(e).context("Failed to delete downloaded file")),
+        }
+    }
+
+    Ok(())
+}
+
+/// List all downloaded tracks
+#[tauri::command]
+pub async fn get_downloads(
+    state: State<'_, AppState>,
+) -> Result<Vec<DownloadedTrack>, CommandError> {
+    let db = state.database.lock().await;
+    db.get_all_downloads().context("Failed to get downloads")
+}
+
+/// Report how much disk space downloads are using
+#[tauri::command]
+pub async fn get_download_size(
+    state: State<'_, AppState>,
+) -> Result<DownloadSizeReport, CommandError> {
+    let db = state.database.lock().await;
+    let track_count = db
+        .get_all_downloads()
+        .context("Failed to get downloads")?
+        .len();
+    let total_bytes = db
+        .total_download_size()
+        .context("Failed to get download size")?;
+
+    Ok(DownloadSizeReport {
+        track_count,
+        total_bytes,
+    })
+}
@@ -1,96 +1,70 @@
 /// Playback control commands
-use crate::commands::{AppState, PlaybackStatus, TrackInfo};
-use crate::{PlaybackState, RepeatMode};
+use crate::commands::{
+    AppState, CommandError, NowPlaying, PlaybackStatus, QueueInfo, QueueStats, ResultExt,
+};
+use crate::{RepeatMode, Volume};
 use tauri::State;
 
 /// Get current playback status
 #[tauri::command]
-pub async fn get_playback_status(state: State<'_, AppState>) -> Result<PlaybackStatus, String> {
+pub async fn get_playback_status(
+    state: State<'_, AppState>,
+) -> Result<PlaybackStatus, CommandError> {
     let info = {
         let playback = state.playback.lock().await;
         playback.get_info().await
     };
 
-    let state_str = match info.state {
-        PlaybackState::Playing => "playing".to_string(),
-        PlaybackState::Paused => "paused".to_string(),
-        PlaybackState::Stopped => "stopped".to_string(),
+    Ok(PlaybackStatus::from(info))
+}
+
+/// Get the full queue in play order with the current position
+#[tauri::command]
+pub async fn get_queue(state: State<'_, AppState>) -> Result<QueueInfo, CommandError> {
+    let info = {
+        let playback = state.playback.lock().await;
+        playback.get_info().await
     };
 
-    let repeat_str = match info.repeat_mode {
-        RepeatMode::Off => "off".to_string(),
-        RepeatMode::One => "one".to_string(),
-        RepeatMode::All => "all".to_string(),
+    Ok(QueueInfo::from(info))
+}
+
+/// Get the queue's track count and total and remaining running time
+#[tauri::command]
+pub async fn get_queue_stats(state: State<'_, AppState>) -> Result<QueueStats, CommandError> {
+    let info = {
+        let playback = state.playback.lock().await;
+        playback.get_info().await
     };
 
-    let duration = info
-        .current_track
-        .as_ref()
-        .map(|t| t.duration_ms)
-        .unwrap_or(0);
-    let current_track = info.current_track.map(|t| TrackInfo {
-        id: t.id,
-        title: t.title,
-        artist: t.artist,
-        album: t.album,
-        duration: t.duration_ms,
-        source: t.source.to_string(),
-        url: t.url,
-        image_url: t.image_url,
-    });
+    Ok(QueueStats::from(info))
+}
 
-    // Get queue tracks - only return tracks after the current index
-    // If shuffle is enabled, use shuffle_order to determine the actual play order
-    let queue_tracks: Vec<TrackInfo> = if info.shuffle && !info.shuffle_order.is_empty() {
-        // Get remaining tracks in shuffle order
-        info.shuffle_order
-            .iter()
-            .skip(info.current_index + 1)
-            .filter_map(|&idx| info.queue.get(idx))
-            .map(|t| TrackInfo {
-                id: t.id.clone(),
-                title: t.title.clone(),
-                artist: t.artist.clone(),
-                album: t.album.clone(),
-                duration: t.duration_ms,
-                source: t.source.to_string(),
-                url: t.url.clone(),
-                image_url: t.image_url.clone(),
-            })
-            .collect()
-    } else {
-        // Get remaining tracks in normal order
-        info.queue
-            .into_iter()
-            .skip(info.current_index + 1)
-            .map(|t| TrackInfo {
-                id: t.id,
-                title: t.title,
-                artist: t.artist,
-                album: t.album,
-                duration: t.duration_ms,
-                source: t.source.to_string(),
-                url: t.url,
-                image_url: t.image_url,
-            })
-            .collect()
+/// Get the current track with its elapsed time and locally cached album art,
+/// or `None` when nothing is playing. Art that isn't cached yet is downloaded
+/// in the background so later calls can return it.
+#[tauri::command]
+pub async fn get_now_playing(
+    state: State<'_, AppState>,
+) -> Result<Option<NowPlaying>, CommandError> {
+    let info = {
+        let playback = state.playback.lock().await;
+        playback.get_info().await
     };
 
-    Ok(PlaybackStatus {
-        state: state_str,
-        current_track,
-        position: info.position_ms,
-        volume: info.volume,
-        shuffle: info.shuffle,
-        repeat_mode: repeat_str,
-        duration,
-        queue: queue_tracks,
-    })
+    let now_playing = NowPlaying::from_info(info, crate::artwork::art_cache_dir().as_deref());
+    if let Some(now_playing) = &now_playing {
+        if let (None, Some(image_url)) = (&now_playing.art_path, &now_playing.track.image_url) {
+            crate::artwork::spawn_cache_art(image_url.clone());
+        }
+    }
+
+    Ok(now_playing)
 }
 
 /// Play current track in queue
 #[tauri::command]
-pub async fn play(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn play(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     playback.play().await;
     Ok(())
@@ -98,7 +72,7 @@ pub async fn play(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Pause playback
 #[tauri::command]
-pub async fn pause(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn pause(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     playback.pause().await;
     Ok(())
@@ -106,7 +80,7 @@ pub async fn pause(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Toggle play/pause
 #[tauri::command]
-pub async fn toggle_play_pause(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn toggle_play_pause(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     playback.toggle_play_pause().await;
     Ok(())
@@ -114,7 +88,7 @@ pub async fn toggle_play_pause(state: State<'_, AppState>) -> Result<(), String>
 
 /// Play next track
 #[tauri::command]
-pub async fn next_track(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn next_track(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     let _ = playback.next_track().await;
 
@@ -135,7 +109,7 @@ pub async fn next_track(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Play previous track
 #[tauri::command]
-pub async fn previous_track(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn previous_track(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     let _ = playback.previous_track().await;
     Ok(())
@@ -143,7 +117,7 @@ pub async fn previous_track(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Seek to position in milliseconds
 #[tauri::command]
-pub async fn seek(state: State<'_, AppState>, position: u64) -> Result<(), String> {
+pub async fn seek(state: State<'_, AppState>, position: u64) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     playback.seek(position).await;
     Ok(())
@@ -151,38 +125,91 @@ pub async fn seek(state: State<'_, AppState>, position: u64) -> Result<(), Strin
 
 /// Set volume (0-100)
 #[tauri::command]
-pub async fn set_volume(state: State<'_, AppState>, volume: u32) -> Result<(), String> {
+pub async fn set_volume(state: State<'_, AppState>, volume: u32) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
-    playback.set_volume(volume).await;
+    playback.set_volume(Volume::new(volume)).await;
     Ok(())
 }
 
+/// Store a manual gain offset in dB for a track, applied on top of volume
+/// normalization the next time it plays. `None` or 0 clears it.
+#[tauri::command]
+pub async fn set_track_gain(
+    state: State<'_, AppState>,
+    source: String,
+    track_id: String,
+    gain_db: Option<f64>,
+) -> Result<(), CommandError> {
+    if gain_db.is_some_and(|gain| !gain.is_finite()) {
+        return Err(CommandError::InvalidInput(
+            "Track gain must be a finite number of dB".to_string(),
+        ));
+    }
+    let db = state.database.lock().await;
+    db.set_track_gain(&source.to_lowercase(), &track_id, gain_db)
+        .context("Failed to set track gain")
+}
+
+/// Get the manual gain offset in dB stored for a track, if any
+#[tauri::command]
+pub async fn get_track_gain(
+    state: State<'_, AppState>,
+    source: String,
+    track_id: String,
+) -> Result<Option<f64>, CommandError> {
+    let db = state.database.lock().await;
+    db.get_track_gain(&source.to_lowercase(), &track_id)
+        .context("Failed to get track gain")
+}
+
 /// Toggle shuffle mode
 #[tauri::command]
-pub async fn toggle_shuffle(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn toggle_shuffle(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     playback.toggle_shuffle().await;
     Ok(())
 }
 
+/// Shuffle the upcoming tracks again, keeping the current one playing
+#[tauri::command]
+pub async fn reshuffle_queue(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let playback = { state.playback.lock().await };
+    playback.reshuffle_queue().await;
+    Ok(())
+}
+
+/// Shuffle reproducibly from `seed`, or randomly again when it's `None`
+#[tauri::command]
+pub async fn set_shuffle_seed(
+    state: State<'_, AppState>,
+    seed: Option<u64>,
+) -> Result<(), CommandError> {
+    let playback = { state.playback.lock().await };
+    playback.set_shuffle_seed(seed).await;
+    Ok(())
+}
+
 /// Set repeat mode
 #[tauri::command]
-pub async fn set_repeat_mode(state: State<'_, AppState>, mode: String) -> Result<(), String> {
-    let repeat_mode = match mode.as_str() {
-        "off" => RepeatMode::Off,
-        "one" => RepeatMode::One,
-        "all" => RepeatMode::All,
-        _ => return Err("Invalid repeat mode".to_string()),
-    };
+pub async fn set_repeat_mode(state: State<'_, AppState>, mode: String) -> Result<(), CommandError> {
+    let repeat_mode: RepeatMode = mode.parse().map_err(CommandError::InvalidInput)?;
 
     let playback = { state.playback.lock().await };
     playback.set_repeat_mode(repeat_mode).await;
     Ok(())
 }
 
+/// Cycle to the next repeat mode and return it ("off", "all" or "one")
+#[tauri::command]
+pub async fn cycle_repeat_mode(state: State<'_, AppState>) -> Result<String, CommandError> {
+    let playback = { state.playback.lock().await };
+    let mode = playback.cycle_repeat_mode().await;
+    Ok(mode.to_string())
+}
+
 /// Clear the queue
 #[tauri::command]
-pub async fn clear_queue(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn clear_queue(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = { state.playback.lock().await };
     playback.clear_queue().await;
     Ok(())
@@ -190,14 +217,14 @@ pub async fn clear_queue(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Save current playback state to disk
 #[tauri::command]
-pub async fn save_playback_state(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn save_playback_state(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = state.playback.lock().await;
-    playback.save_state().await
+    playback.save_state().await.map_err(CommandError::from)
 }
 
 /// Restore playback state from disk
 #[tauri::command]
-pub async fn restore_playback_state(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn restore_playback_state(state: State<'_, AppState>) -> Result<(), CommandError> {
     let playback = state.playback.lock().await;
-    playback.restore_state().await
+    playback.restore_state().await.map_err(CommandError::from)
 }
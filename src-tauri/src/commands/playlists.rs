@@ -1,19 +1,177 @@
 /// Playlist management commands
-use crate::commands::{AppState, PlaylistInfo, TrackInfo};
+use crate::commands::{
+    parse_source, AppState, CommandError, PlaylistInfo, PlaylistResponse, ResultExt, TrackInfo,
+};
+use crate::models::{Page, Source};
 use tauri::State;
 
-/// Get list of playlists from a provider
+/// Number of items returned by paged commands when no limit is given
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Get a page of playlists from a provider
 #[tauri::command]
 pub async fn get_playlists(
     state: State<'_, AppState>,
-    _source: String,
-) -> Result<Vec<PlaylistInfo>, String> {
-    let _providers = state.providers.lock().await;
-
-    // TODO: Implement based on source
-    // This would require implementing provider lookup and async operations
-    // For now, return empty list
-    Ok(Vec::new())
+    source: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Page<PlaylistInfo>, CommandError> {
+    let source = parse_source(&source)?;
+    let providers = state.providers.lock().await;
+
+    let page = providers
+        .get_playlists_page(
+            source,
+            offset.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+        .await
+        .context("Failed to get playlists")?;
+
+    Ok(page.map(PlaylistInfo::from))
+}
+
+/// Get a page of a provider playlist's tracks
+#[tauri::command]
+pub async fn get_playlist_tracks(
+    state: State<'_, AppState>,
+    source: String,
+    id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Page<TrackInfo>, CommandError> {
+    let source = parse_source(&source)?;
+    let providers = state.providers.lock().await;
+
+    let page = providers
+        .get_playlist_tracks_page(
+            source,
+            &id,
+            offset.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+        .await
+        .context("Failed to get playlist tracks")?;
+
+    Ok(page.map(TrackInfo::from))
+}
+
+/// Get an album from a provider with its tracks in disc and track order
+#[tauri::command]
+pub async fn get_album(
+    state: State<'_, AppState>,
+    source: String,
+    id: String,
+) -> Result<PlaylistResponse, CommandError> {
+    let source = parse_source(&source)?;
+    let providers = state.providers.lock().await;
+
+    let album = providers
+        .get_album(source, &id)
+        .await
+        .context("Failed to get album")?;
+
+    Ok(PlaylistResponse {
+        id: album.id,
+        name: album.name,
+        description: album.description,
+        track_count: album.tracks.len(),
+        owner: album.owner,
+        source: album.source.to_string(),
+        tracks: album.tracks.into_iter().map(TrackInfo::from).collect(),
+    })
+}
+
+/// Get an artist's albums from a provider, without their tracks
+#[tauri::command]
+pub async fn get_artist_albums(
+    state: State<'_, AppState>,
+    source: String,
+    artist_id: String,
+) -> Result<Vec<PlaylistInfo>, CommandError> {
+    let source = parse_source(&source)?;
+    let providers = state.providers.lock().await;
+
+    let albums = providers
+        .get_artist_albums(source, &artist_id)
+        .await
+        .context("Failed to get artist albums")?;
+
+    Ok(albums.into_iter().map(PlaylistInfo::from).collect())
+}
+
+/// Get an artist's most popular tracks from a provider
+#[tauri::command]
+pub async fn get_artist_top_tracks(
+    state: State<'_, AppState>,
+    source: String,
+    artist_id: String,
+) -> Result<Vec<TrackInfo>, CommandError> {
+    let source = parse_source(&source)?;
+    let providers = state.providers.lock().await;
+
+    let tracks = providers
+        .get_artist_top_tracks(source, &artist_id)
+        .await
+        .context("Failed to get artist top tracks")?;
+
+    Ok(tracks.into_iter().map(TrackInfo::from).collect())
+}
+
+/// Get a page of track search results from a provider
+#[tauri::command]
+pub async fn search_tracks(
+    state: State<'_, AppState>,
+    source: String,
+    query: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Page<TrackInfo>, CommandError> {
+    let source = parse_source(&source)?;
+    let providers = state.providers.lock().await;
+
+    let page = providers
+        .search_tracks_page(
+            source,
+            &query,
+            offset.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+        .await
+        .context("Failed to search tracks")?;
+
+    Ok(page.map(TrackInfo::from))
+}
+
+/// Search tracks as the user types. Each new query cancels the one still
+/// running for the same provider, which resolves to `None`, so only the
+/// latest query's results come back.
+#[tauri::command]
+pub async fn search_tracks_debounced(
+    state: State<'_, AppState>,
+    source: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Option<Page<TrackInfo>>, CommandError> {
+    let source = parse_source(&source)?;
+    let search = async {
+        let providers = state.providers.lock().await;
+        providers
+            .search_tracks_page(source, &query, 0, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+            .await
+    };
+
+    match state
+        .latest_track_search
+        .run(&source.to_string(), search)
+        .await
+    {
+        Some(page) => Ok(Some(
+            page.context("Failed to search tracks")?
+                .map(TrackInfo::from),
+        )),
+        None => Ok(None),
+    }
 }
 
 /// Play a track from a source
@@ -22,33 +180,28 @@ pub async fn play_track(
     state: State<'_, AppState>,
     track_id: String,
     source: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    let source = parse_source(&source)?;
     let providers = state.providers.lock().await;
 
-    // Normalize source to lowercase
-    let normalized_source = source.to_lowercase();
-
     // Get the track from the appropriate provider
-    let track = match normalized_source.as_str() {
-        "spotify" => providers
+    let track = match source {
+        Source::Spotify => providers
             .get_spotify_track(&track_id)
             .await
-            .map_err(|e| format!("Failed to get Spotify track: {}", e))?,
-        "jellyfin" => providers
+            .context("Failed to get Spotify track")?,
+        Source::Jellyfin => providers
             .get_jellyfin_track(&track_id)
             .await
-            .map_err(|e| format!("Failed to get Jellyfin track: {}", e))?,
-        "custom" => {
-            return Err("Playing custom tracks directly is not yet supported. Please play from a custom playlist instead.".to_string());
-        }
-        _ => {
-            return Err(format!(
-                "Unknown source: '{}'. Supported sources are: spotify, jellyfin",
-                source
-            ))
+            .context("Failed to get Jellyfin track")?,
+        Source::Custom => {
+            return Err(CommandError::InvalidInput("Playing custom tracks directly is not yet supported. Please play from a custom playlist instead.".to_string()));
         }
     };
 
+    // Release providers before playback, which locks them to fetch auth headers
+    drop(providers);
+
     // Clear queue, add track, and start playing
     let playback = state.playback.lock().await;
     playback.clear_queue().await;
@@ -63,30 +216,22 @@ pub async fn queue_track(
     state: State<'_, AppState>,
     track_id: String,
     source: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
+    let source = parse_source(&source)?;
     let providers = state.providers.lock().await;
 
-    // Normalize source to lowercase
-    let normalized_source = source.to_lowercase();
-
     // Get the track from the appropriate provider
-    let track = match normalized_source.as_str() {
-        "spotify" => providers
+    let track = match source {
+        Source::Spotify => providers
             .get_spotify_track(&track_id)
             .await
-            .map_err(|e| format!("Failed to get Spotify track: {}", e))?,
-        "jellyfin" => providers
+            .context("Failed to get Spotify track")?,
+        Source::Jellyfin => providers
             .get_jellyfin_track(&track_id)
             .await
-            .map_err(|e| format!("Failed to get Jellyfin track: {}", e))?,
-        "custom" => {
-            return Err("Queuing custom tracks directly is not yet supported. Please queue from a custom playlist instead.".to_string());
-        }
-        _ => {
-            return Err(format!(
-                "Unknown source: '{}'. Supported sources are: spotify, jellyfin",
-                source
-            ))
+            .context("Failed to get Jellyfin track")?,
+        Source::Custom => {
+            return Err(CommandError::InvalidInput("Queuing custom tracks directly is not yet supported. Please queue from a custom playlist instead.".to_string()));
         }
     };
 
@@ -97,40 +242,55 @@ pub async fn queue_track(
     Ok(())
 }
 
-/// Play a playlist by loading all its tracks
+/// Play a playlist by loading all its tracks. With `append` the tracks are
+/// added to the end of the queue and playback carries on; if nothing is
+/// playing the playlist starts as usual.
 #[tauri::command]
 pub async fn play_playlist(
     state: State<'_, AppState>,
     playlist_id: String,
     source: String,
-) -> Result<(), String> {
+    append: Option<bool>,
+) -> Result<(), CommandError> {
+    let append = append.unwrap_or(false);
+    let source = parse_source(&source)?;
     let providers = state.providers.lock().await;
 
     // Get the playlist with all tracks from the appropriate provider
-    let playlist = match source.as_str() {
-        "spotify" => providers
+    let mut playlist = match source {
+        Source::Spotify => providers
             .get_spotify_playlist(&playlist_id)
             .await
-            .map_err(|e| format!("Failed to get Spotify playlist: {}", e))?,
-        "jellyfin" => providers
+            .context("Failed to get Spotify playlist")?,
+        Source::Jellyfin => providers
             .get_jellyfin_playlist(&playlist_id)
             .await
-            .map_err(|e| format!("Failed to get Jellyfin playlist: {}", e))?,
-        "custom" => {
+            .context("Failed to get Jellyfin playlist")?,
+        Source::Custom => {
             // Drop providers lock before calling internal function
             drop(providers);
-            return super::custom_playlists::play_custom_playlist_internal(&state, playlist_id)
-                .await;
+            return super::custom_playlists::play_custom_playlist_internal(
+                &state,
+                playlist_id,
+                append,
+            )
+            .await;
         }
-        _ => return Err("Unknown source".to_string()),
     };
 
     if playlist.tracks.is_empty() {
-        return Err("Playlist is empty".to_string());
+        return Err(CommandError::InvalidInput("Playlist is empty".to_string()));
     }
 
     drop(providers);
 
+    if append {
+        match super::helpers::append_or_return(&state, playlist.tracks).await {
+            Some(tracks) => playlist.tracks = tracks,
+            None => return Ok(()),
+        }
+    }
+
     // Clear queue and add all tracks from the playlist
     let playback = state.playback.lock().await;
     playback.clear_queue().await;
@@ -180,9 +340,9 @@ pub async fn play_playlist(
 pub async fn play_tracks_immediate(
     state: State<'_, AppState>,
     tracks: Vec<TrackInfo>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     if tracks.is_empty() {
-        return Err("No tracks provided".to_string());
+        return Err(CommandError::InvalidInput("No tracks provided".to_string()));
     }
 
     // Convert TrackInfo to Track for internal use
@@ -205,17 +365,25 @@ pub async fn play_tracks_immediate(
             title: track_info.title,
             artist: track_info.artist,
             album: track_info.album,
+            album_artist: None,
             duration_ms: track_info.duration,
             image_url: track_info.image_url,
             source,
             url: track_info.url,
             auth_headers,
+            genre: track_info.genre,
+            year: track_info.year,
+            track_number: track_info.track_number,
+            disc_number: track_info.disc_number,
+            enriched_at: None,
         });
     }
 
     // Ensure we have at least one track after conversion
     if internal_tracks.is_empty() {
-        return Err("No valid tracks to play".to_string());
+        return Err(CommandError::InvalidInput(
+            "No valid tracks to play".to_string(),
+        ));
     }
 
     // Store first track for later enrichment
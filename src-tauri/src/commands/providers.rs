@@ -1,7 +1,51 @@
 /// Provider-specific commands for Spotify and Jellyfin
-use crate::commands::{AppState, PlaylistInfo, PlaylistResponse, TrackInfo};
+use crate::commands::{
+    parse_source, AppState, CommandError, PlaylistInfo, PlaylistResponse, RateLimitStatus,
+    ResultExt, TrackInfo,
+};
+use crate::models::Track;
+use crate::providers::ProviderCapabilities;
 use tauri::State;
 
+/// Get the operations a provider supports; all are off until it's connected
+#[tauri::command]
+pub async fn get_provider_capabilities(
+    state: State<'_, AppState>,
+    source: String,
+) -> Result<ProviderCapabilities, CommandError> {
+    let source = parse_source(&source)?;
+    let providers = state.providers.lock().await;
+
+    providers
+        .capabilities(source)
+        .await
+        .context("Failed to get provider capabilities")
+}
+
+/// Whether each provider is being slowed down after hitting its rate limit,
+/// so the UI can explain why loading is slow
+#[tauri::command]
+pub async fn get_rate_limit_status(
+    state: State<'_, AppState>,
+) -> Result<RateLimitStatus, CommandError> {
+    Ok(RateLimitStatus {
+        spotify: state.rate_limiters.spotify.is_throttled().await,
+        jellyfin: state.rate_limiters.jellyfin.is_throttled().await,
+    })
+}
+
+/// Check whether a track will play in full, so the UI can warn about previews
+#[tauri::command]
+pub async fn can_play_full(state: State<'_, AppState>, track: Track) -> Result<bool, CommandError> {
+    let session_ready = {
+        let playback = state.playback.lock().await;
+        playback.is_spotify_session_ready().await
+    };
+
+    let providers = state.providers.lock().await;
+    Ok(providers.can_play_full(&track, session_ready).await)
+}
+
 // ============================================================================
 // Spotify Commands
 // ============================================================================
@@ -10,13 +54,13 @@ use tauri::State;
 #[tauri::command]
 pub async fn get_spotify_playlists(
     state: State<'_, AppState>,
-) -> Result<Vec<PlaylistInfo>, String> {
+) -> Result<Vec<PlaylistInfo>, CommandError> {
     let providers = state.providers.lock().await;
 
     let playlists = providers
         .get_spotify_playlists()
         .await
-        .map_err(|e| format!("Failed to get playlists: {}", e))?;
+        .context("Failed to get playlists")?;
 
     Ok(playlists
         .into_iter()
@@ -27,6 +71,7 @@ pub async fn get_spotify_playlists(
             track_count: p.track_count,
             owner: p.owner,
             source: "spotify".to_string(),
+            revision: p.revision,
         })
         .collect())
 }
@@ -36,13 +81,13 @@ pub async fn get_spotify_playlists(
 pub async fn get_spotify_playlist(
     state: State<'_, AppState>,
     id: String,
-) -> Result<PlaylistResponse, String> {
+) -> Result<PlaylistResponse, CommandError> {
     let providers = state.providers.lock().await;
 
     let playlist = providers
         .get_spotify_playlist(&id)
         .await
-        .map_err(|e| format!("Failed to get Spotify playlist: {}", e))?;
+        .context("Failed to get Spotify playlist")?;
 
     let tracks = playlist
         .tracks
@@ -52,10 +97,15 @@ pub async fn get_spotify_playlist(
             title: t.title.clone(),
             artist: t.artist.clone(),
             album: t.album.clone(),
+            album_artist: t.album_artist.clone(),
             duration: t.duration_ms,
             source: "spotify".to_string(),
             url: t.url.clone(),
             image_url: t.image_url.clone(),
+            genre: t.genre.clone(),
+            year: t.year,
+            track_number: t.track_number,
+            disc_number: t.disc_number,
         })
         .collect();
 
@@ -70,32 +120,92 @@ pub async fn get_spotify_playlist(
     })
 }
 
-/// Search tracks on Spotify
+/// Get the user's saved tracks as the "Liked Songs" pseudo-playlist
 #[tauri::command]
-pub async fn search_spotify_tracks(
+pub async fn get_spotify_liked_songs(
     state: State<'_, AppState>,
-    query: String,
-) -> Result<Vec<TrackInfo>, String> {
+) -> Result<PlaylistResponse, CommandError> {
     let providers = state.providers.lock().await;
 
-    let tracks = providers
-        .search_spotify_tracks(&query)
+    let playlist = providers
+        .get_spotify_liked_songs()
         .await
-        .map_err(|e| format!("Failed to search Spotify tracks: {}", e))?;
+        .context("Failed to get liked songs")?;
 
-    Ok(tracks
-        .into_iter()
-        .map(|t| TrackInfo {
-            id: t.id,
-            title: t.title,
-            artist: t.artist,
-            album: t.album,
-            duration: t.duration_ms,
-            source: "spotify".to_string(),
-            url: t.url,
-            image_url: t.image_url,
+    Ok(PlaylistResponse {
+        id: playlist.id,
+        name: playlist.name,
+        description: playlist.description,
+        track_count: playlist.track_count,
+        owner: playlist.owner,
+        source: "spotify".to_string(),
+        tracks: playlist.tracks.into_iter().map(TrackInfo::from).collect(),
+    })
+}
+
+/// Add a track to the user's Spotify library
+#[tauri::command]
+pub async fn save_spotify_track(
+    state: State<'_, AppState>,
+    track_id: String,
+) -> Result<(), CommandError> {
+    let providers = state.providers.lock().await;
+    providers
+        .set_spotify_track_saved(&track_id, true)
+        .await
+        .context("Failed to save track")
+}
+
+/// Remove a track from the user's Spotify library
+#[tauri::command]
+pub async fn remove_saved_spotify_track(
+    state: State<'_, AppState>,
+    track_id: String,
+) -> Result<(), CommandError> {
+    let providers = state.providers.lock().await;
+    providers
+        .set_spotify_track_saved(&track_id, false)
+        .await
+        .context("Failed to remove saved track")
+}
+
+/// Search tracks on Spotify, reusing recent results unless `refresh` is set
+#[tauri::command]
+pub async fn search_spotify_tracks(
+    state: State<'_, AppState>,
+    query: String,
+    refresh: Option<bool>,
+) -> Result<Vec<TrackInfo>, CommandError> {
+    state
+        .track_search_cache
+        .get_or_fetch("spotify", &query, refresh.unwrap_or(false), || async {
+            let providers = state.providers.lock().await;
+
+            let tracks = providers
+                .search_spotify_tracks(&query)
+                .await
+                .context("Failed to search Spotify tracks")?;
+
+            Ok(tracks
+                .into_iter()
+                .map(|t| TrackInfo {
+                    id: t.id,
+                    title: t.title,
+                    artist: t.artist,
+                    album: t.album,
+                    album_artist: t.album_artist,
+                    duration: t.duration_ms,
+                    source: "spotify".to_string(),
+                    url: t.url,
+                    image_url: t.image_url,
+                    genre: t.genre,
+                    year: t.year,
+                    track_number: t.track_number,
+                    disc_number: t.disc_number,
+                })
+                .collect())
         })
-        .collect())
+        .await
 }
 
 // ============================================================================
@@ -106,13 +216,13 @@ pub async fn search_spotify_tracks(
 #[tauri::command]
 pub async fn get_jellyfin_playlists(
     state: State<'_, AppState>,
-) -> Result<Vec<PlaylistInfo>, String> {
+) -> Result<Vec<PlaylistInfo>, CommandError> {
     let providers = state.providers.lock().await;
 
     let playlists = providers
         .get_jellyfin_playlists()
         .await
-        .map_err(|e| format!("Failed to get Jellyfin playlists: {}", e))?;
+        .context("Failed to get Jellyfin playlists")?;
 
     Ok(playlists
         .into_iter()
@@ -123,6 +233,7 @@ pub async fn get_jellyfin_playlists(
             track_count: p.track_count,
             owner: p.owner,
             source: "jellyfin".to_string(),
+            revision: p.revision,
         })
         .collect())
 }
@@ -132,13 +243,13 @@ pub async fn get_jellyfin_playlists(
 pub async fn get_jellyfin_playlist(
     state: State<'_, AppState>,
     id: String,
-) -> Result<PlaylistResponse, String> {
+) -> Result<PlaylistResponse, CommandError> {
     let providers = state.providers.lock().await;
 
     let playlist = providers
         .get_jellyfin_playlist(&id)
         .await
-        .map_err(|e| format!("Failed to get Jellyfin playlist: {}", e))?;
+        .context("Failed to get Jellyfin playlist")?;
 
     let tracks = playlist
         .tracks
@@ -148,10 +259,15 @@ pub async fn get_jellyfin_playlist(
             title: t.title.clone(),
             artist: t.artist.clone(),
             album: t.album.clone(),
+            album_artist: t.album_artist.clone(),
             duration: t.duration_ms,
             source: "jellyfin".to_string(),
             url: t.url.clone(),
             image_url: t.image_url.clone(),
+            genre: t.genre.clone(),
+            year: t.year,
+            track_number: t.track_number,
+            disc_number: t.disc_number,
         })
         .collect();
 
@@ -166,58 +282,105 @@ pub async fn get_jellyfin_playlist(
     })
 }
 
-/// Search tracks on Jellyfin
+/// Search tracks on Jellyfin, reusing recent results unless `refresh` is set
 #[tauri::command]
 pub async fn search_jellyfin_tracks(
     state: State<'_, AppState>,
     query: String,
-) -> Result<Vec<TrackInfo>, String> {
-    let providers = state.providers.lock().await;
+    refresh: Option<bool>,
+) -> Result<Vec<TrackInfo>, CommandError> {
+    state
+        .track_search_cache
+        .get_or_fetch("jellyfin", &query, refresh.unwrap_or(false), || async {
+            let providers = state.providers.lock().await;
 
-    let tracks = providers
-        .search_jellyfin_tracks(&query)
-        .await
-        .map_err(|e| format!("Failed to search Jellyfin tracks: {}", e))?;
+            let tracks = providers
+                .search_jellyfin_tracks(&query)
+                .await
+                .context("Failed to search Jellyfin tracks")?;
 
-    Ok(tracks
-        .into_iter()
-        .map(|t| TrackInfo {
-            id: t.id,
-            title: t.title,
-            artist: t.artist,
-            album: t.album,
-            duration: t.duration_ms,
-            source: "jellyfin".to_string(),
-            url: t.url,
-            image_url: t.image_url,
+            Ok(tracks
+                .into_iter()
+                .map(|t| TrackInfo {
+                    id: t.id,
+                    title: t.title,
+                    artist: t.artist,
+                    album: t.album,
+                    album_artist: t.album_artist,
+                    duration: t.duration_ms,
+                    source: "jellyfin".to_string(),
+                    url: t.url,
+                    image_url: t.image_url,
+                    genre: t.genre,
+                    year: t.year,
+                    track_number: t.track_number,
+                    disc_number: t.disc_number,
+                })
+                .collect())
         })
-        .collect())
+        .await
 }
 
-/// Search playlists on Jellyfin
+/// Search playlists on Jellyfin, reusing recent results unless `refresh` is set
 #[tauri::command]
 pub async fn search_jellyfin_playlists(
     state: State<'_, AppState>,
     query: String,
-) -> Result<Vec<PlaylistInfo>, String> {
+    refresh: Option<bool>,
+) -> Result<Vec<PlaylistInfo>, CommandError> {
+    state
+        .playlist_search_cache
+        .get_or_fetch("jellyfin", &query, refresh.unwrap_or(false), || async {
+            let providers = state.providers.lock().await;
+
+            let playlists = providers
+                .search_jellyfin_playlists(&query)
+                .await
+                .context("Failed to search Jellyfin playlists")?;
+
+            Ok(playlists
+                .into_iter()
+                .map(|p| PlaylistInfo {
+                    id: p.id,
+                    name: p.name,
+                    description: p.description,
+                    track_count: p.tracks.len(),
+                    owner: p.owner,
+                    source: "jellyfin".to_string(),
+                    revision: p.revision,
+                })
+                .collect())
+        })
+        .await
+}
+
+/// Mark or unmark a Jellyfin track as a favorite
+#[tauri::command]
+pub async fn set_jellyfin_favorite(
+    state: State<'_, AppState>,
+    track_id: String,
+    favorite: bool,
+) -> Result<(), CommandError> {
     let providers = state.providers.lock().await;
+    providers
+        .set_jellyfin_favorite(&track_id, favorite)
+        .await
+        .context("Failed to update favorite")
+}
 
-    let playlists = providers
-        .search_jellyfin_playlists(&query)
+/// Get the user's favorite Jellyfin tracks
+#[tauri::command]
+pub async fn get_jellyfin_favorites(
+    state: State<'_, AppState>,
+) -> Result<Vec<TrackInfo>, CommandError> {
+    let providers = state.providers.lock().await;
+
+    let tracks = providers
+        .get_jellyfin_favorites()
         .await
-        .map_err(|e| format!("Failed to search Jellyfin playlists: {}", e))?;
+        .context("Failed to get favorites")?;
 
-    Ok(playlists
-        .into_iter()
-        .map(|p| PlaylistInfo {
-            id: p.id,
-            name: p.name,
-            description: p.description,
-            track_count: p.tracks.len(),
-            owner: p.owner,
-            source: "jellyfin".to_string(),
-        })
-        .collect())
+    Ok(tracks.into_iter().map(TrackInfo::from).collect())
 }
 
 /// Get recently played tracks from Jellyfin
@@ -225,13 +388,13 @@ pub async fn search_jellyfin_playlists(
 pub async fn get_jellyfin_recently_played(
     state: State<'_, AppState>,
     limit: usize,
-) -> Result<Vec<TrackInfo>, String> {
+) -> Result<Vec<TrackInfo>, CommandError> {
     let providers = state.providers.lock().await;
 
     let tracks = providers
         .get_jellyfin_recently_played(limit)
         .await
-        .map_err(|e| format!("Failed to get recently played: {}", e))?;
+        .context("Failed to get recently played")?;
 
     Ok(tracks
         .into_iter()
@@ -240,10 +403,15 @@ pub async fn get_jellyfin_recently_played(
             title: t.title,
             artist: t.artist,
             album: t.album,
+            album_artist: t.album_artist,
             duration: t.duration_ms,
             source: "jellyfin".to_string(),
             url: t.url,
             image_url: t.image_url,
+            genre: t.genre,
+            year: t.year,
+            track_number: t.track_number,
+            disc_number: t.disc_number,
         })
         .collect())
 }
@@ -1,4 +1,8 @@
 /// Shared application state
+use crate::cache::{LatestSearch, SearchCache, SEARCH_CACHE_TTL};
+use crate::commands::{PlaylistInfo, TrackInfo};
+use crate::oauth::OAuthServer;
+use crate::providers::rate_limit::ProviderRateLimiters;
 use crate::{Database, PlaybackManager, ProviderRegistry};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -7,5 +11,85 @@ pub struct AppState {
     pub playback: Arc<Mutex<PlaybackManager>>,
     pub providers: Arc<Mutex<ProviderRegistry>>,
     pub oauth_code: Arc<Mutex<Option<String>>>,
+    /// OAuth `state` value expected on the next callback (CSRF protection)
+    pub oauth_state: Arc<Mutex<Option<String>>>,
+    pub oauth_server: Arc<OAuthServer>,
     pub database: Arc<Mutex<Database>>,
+    /// Recent track search results, keyed by provider and query
+    pub track_search_cache: Arc<SearchCache<Vec<TrackInfo>>>,
+    /// Recent playlist search results, keyed by provider and query
+    pub playlist_search_cache: Arc<SearchCache<Vec<PlaylistInfo>>>,
+    /// The search-as-you-type query currently running for each provider
+    pub latest_track_search: Arc<LatestSearch>,
+    /// Request rate limiters of the providers, readable without locking them
+    pub rate_limiters: ProviderRateLimiters,
+}
+
+impl AppState {
+    /// State around the shared services, with empty search caches
+    pub fn new(
+        playback: Arc<Mutex<PlaybackManager>>,
+        providers: Arc<Mutex<ProviderRegistry>>,
+        oauth_code: Arc<Mutex<Option<String>>>,
+        oauth_state: Arc<Mutex<Option<String>>>,
+        oauth_server: Arc<OAuthServer>,
+        database: Arc<Mutex<Database>>,
+        rate_limiters: ProviderRateLimiters,
+    ) -> Self {
+        Self {
+            playback,
+            providers,
+            oauth_code,
+            oauth_state,
+            oauth_server,
+            database,
+            track_search_cache: Arc::new(SearchCache::new(SEARCH_CACHE_TTL)),
+            playlist_search_cache: Arc::new(SearchCache::new(SEARCH_CACHE_TTL)),
+            latest_track_search: Arc::new(LatestSearch::new()),
+            rate_limiters,
+        }
+    }
+
+    /// Forget cached search results from `source` so searches after a
+    /// disconnect or account/server change don't show the old ones
+    pub fn clear_search_caches(&self, source: &str) {
+        self.track_search_cache.clear_source(source);
+        self.playlist_search_cache.clear_source(source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_app_state_with_temp_database() {
+        let dir = std::env::temp_dir().join(format!("any-player-state-{}", uuid::Uuid::new_v4()));
+        // The data directory doesn't exist yet on first launch
+        let database = Database::open(dir.join("nested").join("playlists.db")).unwrap();
+
+        let registry = ProviderRegistry::new();
+        let rate_limiters = registry.rate_limiters();
+        let providers = Arc::new(Mutex::new(registry));
+        let oauth_code = Arc::new(Mutex::new(None));
+        let oauth_state = Arc::new(Mutex::new(None));
+        let state = AppState::new(
+            Arc::new(Mutex::new(PlaybackManager::new(providers.clone()))),
+            providers,
+            oauth_code.clone(),
+            oauth_state.clone(),
+            Arc::new(OAuthServer::new(0, oauth_code, oauth_state)),
+            Arc::new(Mutex::new(database)),
+            rate_limiters,
+        );
+
+        // The schema is in place and custom playlist commands can use it
+        let db = state.database.lock().await;
+        let playlist = db.create_playlist("Temp".to_string(), None, None).unwrap();
+        assert_eq!(db.get_all_playlists().unwrap()[0].id, playlist.id);
+        drop(db);
+
+        assert!(dir.join("nested").join("playlists.db").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,5 +1,7 @@
 /// Command response types
+use crate::models::{PlaybackInfo, PlaybackState, Playlist, Track};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PlaybackStatus {
@@ -11,6 +13,173 @@ pub struct PlaybackStatus {
     pub repeat_mode: String,
     pub duration: u64,
     pub queue: Vec<TrackInfo>,
+    pub error: Option<String>,
+}
+
+/// Name the frontend uses for a playback state
+fn state_name(state: PlaybackState) -> &'static str {
+    match state {
+        PlaybackState::Loading => "loading",
+        PlaybackState::Playing => "playing",
+        PlaybackState::Paused => "paused",
+        PlaybackState::Stopped => "stopped",
+    }
+}
+
+impl From<PlaybackInfo> for PlaybackStatus {
+    fn from(info: PlaybackInfo) -> Self {
+        let state = state_name(info.state);
+
+        let duration = info
+            .current_track
+            .as_ref()
+            .map(|t| t.duration_ms)
+            .unwrap_or(0);
+
+        // Get queue tracks - only return tracks after the current index
+        // If shuffle is enabled, use shuffle_order to determine the actual play order
+        let queue = if info.shuffle && !info.shuffle_order.is_empty() {
+            // Get remaining tracks in shuffle order
+            info.shuffle_order
+                .iter()
+                .skip(info.current_index + 1)
+                .filter_map(|&idx| info.queue.get(idx))
+                .cloned()
+                .map(TrackInfo::from)
+                .collect()
+        } else {
+            // Get remaining tracks in normal order
+            info.queue
+                .into_iter()
+                .skip(info.current_index + 1)
+                .map(TrackInfo::from)
+                .collect()
+        };
+
+        Self {
+            state: state.to_string(),
+            current_track: info.current_track.map(TrackInfo::from),
+            position: info.position_ms,
+            volume: info.volume.percent(),
+            shuffle: info.shuffle,
+            repeat_mode: info.repeat_mode.to_string(),
+            duration,
+            queue,
+            error: info.error,
+        }
+    }
+}
+
+/// The current track without the queue, for media integrations and the
+/// now-playing screen
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NowPlaying {
+    pub track: TrackInfo,
+    pub state: String,
+    /// `file://` URL of the locally cached album art, once downloaded
+    pub art_path: Option<String>,
+    pub elapsed_ms: u64,
+    pub duration_ms: u64,
+}
+
+impl NowPlaying {
+    /// Build from the playback info, looking the art up in `art_dir`.
+    /// `None` when no track is loaded or playback is stopped.
+    pub fn from_info(info: PlaybackInfo, art_dir: Option<&Path>) -> Option<Self> {
+        if info.state == PlaybackState::Stopped {
+            return None;
+        }
+        let track = info.current_track?;
+        let art_path = art_dir
+            .zip(track.image_url.as_deref())
+            .and_then(|(dir, url)| crate::artwork::cached_art_url(dir, url));
+
+        Some(Self {
+            state: state_name(info.state).to_string(),
+            art_path,
+            elapsed_ms: info.position_ms,
+            duration_ms: track.duration_ms,
+            track: TrackInfo::from(track),
+        })
+    }
+}
+
+/// The whole queue in the order it will play
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueueInfo {
+    /// Queued tracks in play order (shuffled when shuffle is on)
+    pub tracks: Vec<TrackInfo>,
+    /// Position of the current track in `tracks`
+    pub current_index: usize,
+    /// Index into the unshuffled queue for each entry in `tracks`
+    pub play_order: Vec<usize>,
+}
+
+/// Queue indices in the order they will play
+fn play_order(info: &PlaybackInfo) -> Vec<usize> {
+    if info.shuffle && !info.shuffle_order.is_empty() {
+        info.shuffle_order
+            .iter()
+            .copied()
+            .filter(|&idx| idx < info.queue.len())
+            .collect()
+    } else {
+        (0..info.queue.len()).collect()
+    }
+}
+
+impl From<PlaybackInfo> for QueueInfo {
+    fn from(info: PlaybackInfo) -> Self {
+        let play_order = play_order(&info);
+
+        let tracks = play_order
+            .iter()
+            .map(|&idx| TrackInfo::from(info.queue[idx].clone()))
+            .collect();
+
+        Self {
+            tracks,
+            current_index: info.current_index,
+            play_order,
+        }
+    }
+}
+
+/// Track count and running time of the queue
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QueueStats {
+    pub track_count: usize,
+    /// Sum of the known track durations
+    pub total_duration_ms: u64,
+    /// What's left of the current track plus everything after it
+    pub remaining_duration_ms: u64,
+    /// Tracks whose duration isn't known (0), left out of the totals
+    pub unknown_duration_count: usize,
+}
+
+impl From<PlaybackInfo> for QueueStats {
+    fn from(info: PlaybackInfo) -> Self {
+        let play_order = play_order(&info);
+        let duration = |idx: &usize| info.queue[*idx].duration_ms;
+
+        let upcoming: u64 = play_order
+            .iter()
+            .skip(info.current_index + 1)
+            .map(duration)
+            .sum();
+        let current_left = info
+            .current_track
+            .as_ref()
+            .map(|t| t.duration_ms.saturating_sub(info.position_ms))
+            .unwrap_or(0);
+
+        Self {
+            track_count: info.queue.len(),
+            total_duration_ms: info.queue.iter().map(|t| t.duration_ms).sum(),
+            remaining_duration_ms: current_left + upcoming,
+            unknown_duration_count: info.queue.iter().filter(|t| t.duration_ms == 0).count(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -21,6 +190,23 @@ pub struct PlaylistInfo {
     pub track_count: usize,
     pub owner: String,
     pub source: String,
+    /// Provider's marker for the playlist's contents, used to spot changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+}
+
+impl From<Playlist> for PlaylistInfo {
+    fn from(p: Playlist) -> Self {
+        Self {
+            id: p.id,
+            name: p.name,
+            description: p.description,
+            track_count: p.track_count,
+            owner: p.owner,
+            source: p.source.to_string(),
+            revision: p.revision,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -29,12 +215,42 @@ pub struct TrackInfo {
     pub title: String,
     pub artist: String,
     pub album: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
     pub duration: u64,
     pub source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_number: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disc_number: Option<u32>,
+}
+
+impl From<Track> for TrackInfo {
+    fn from(t: Track) -> Self {
+        Self {
+            id: t.id,
+            title: t.title,
+            artist: t.artist,
+            album: t.album,
+            album_artist: t.album_artist,
+            duration: t.duration_ms,
+            source: t.source.to_string(),
+            url: t.url,
+            image_url: t.image_url,
+            genre: t.genre,
+            year: t.year,
+            track_number: t.track_number,
+            disc_number: t.disc_number,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,6 +264,20 @@ pub struct PlaylistResponse {
     pub tracks: Vec<TrackInfo>,
 }
 
+/// Which providers are currently slowed down by their rate limits
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub spotify: bool,
+    pub jellyfin: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConnectionStatus {
+    pub connected: bool,
+    pub latency_ms: u64,
+    pub status: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct JellyfinAuthRequest {
@@ -55,3 +285,139 @@ pub struct JellyfinAuthRequest {
     #[serde(rename = "apiKey")]
     pub api_key: String,
 }
+
+/// Disk space used by offline downloads
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadSizeReport {
+    pub track_count: usize,
+    pub total_bytes: i64,
+}
+
+/// Outcome of pushing a custom playlist to a provider
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PushPlaylistReport {
+    pub playlist_id: String,
+    pub pushed_count: usize,
+    /// Tracks the provider couldn't match or refused to add
+    pub skipped: Vec<TrackInfo>,
+}
+
+/// A configured Jellyfin server
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JellyfinServerInfo {
+    pub name: String,
+    pub url: String,
+    pub active: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+
+    fn create_test_track(id: &str) -> Track {
+        Track {
+            id: id.to_string(),
+            title: format!("Track {}", id),
+            artist: "Test Artist".to_string(),
+            album: "Test Album".to_string(),
+            album_artist: None,
+            duration_ms: 180000,
+            image_url: None,
+            source: Source::Jellyfin,
+            url: None,
+            auth_headers: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at: None,
+        }
+    }
+
+    #[test]
+    fn test_queue_info_follows_shuffle_order() {
+        let info = PlaybackInfo {
+            queue: vec![
+                create_test_track("a"),
+                create_test_track("b"),
+                create_test_track("c"),
+            ],
+            shuffle: true,
+            shuffle_order: vec![2, 0, 1],
+            current_index: 1,
+            ..Default::default()
+        };
+
+        let queue = QueueInfo::from(info.clone());
+        let ids: Vec<&str> = queue.tracks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+        assert_eq!(queue.play_order, vec![2, 0, 1]);
+        assert_eq!(queue.tracks[queue.current_index].id, "a");
+
+        // Without shuffle the natural order is used
+        let queue = QueueInfo::from(PlaybackInfo {
+            shuffle: false,
+            ..info
+        });
+        let ids: Vec<&str> = queue.tracks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(queue.play_order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_queue_stats_skips_unknown_durations() {
+        let mut unknown = create_test_track("b");
+        unknown.duration_ms = 0;
+        let current = create_test_track("a");
+        let info = PlaybackInfo {
+            queue: vec![current.clone(), unknown, create_test_track("c")],
+            current_track: Some(current),
+            current_index: 0,
+            position_ms: 60_000,
+            ..Default::default()
+        };
+
+        let stats = QueueStats::from(info);
+        assert_eq!(stats.track_count, 3);
+        assert_eq!(stats.total_duration_ms, 360_000);
+        assert_eq!(stats.remaining_duration_ms, 120_000 + 180_000);
+        assert_eq!(stats.unknown_duration_count, 1);
+    }
+
+    #[test]
+    fn test_now_playing_resolves_cached_art() {
+        let dir = std::env::temp_dir().join(format!("any-player-now-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut track = create_test_track("a");
+        track.image_url = Some("https://example.com/a.jpg".to_string());
+        let info = PlaybackInfo {
+            queue: vec![track.clone(), create_test_track("b")],
+            current_track: Some(track),
+            state: PlaybackState::Playing,
+            position_ms: 42_000,
+            ..Default::default()
+        };
+
+        let now = NowPlaying::from_info(info.clone(), Some(&dir)).unwrap();
+        assert_eq!(now.track.id, "a");
+        assert_eq!(now.state, "playing");
+        assert_eq!(now.elapsed_ms, 42_000);
+        assert_eq!(now.duration_ms, 180_000);
+        assert!(now.art_path.is_none());
+
+        let art = crate::artwork::art_path(&dir, "https://example.com/a.jpg");
+        std::fs::write(&art, b"art").unwrap();
+        let now = NowPlaying::from_info(info.clone(), Some(&dir)).unwrap();
+        assert_eq!(now.art_path, crate::offline::file_url(&art));
+
+        let stopped = PlaybackInfo {
+            state: PlaybackState::Stopped,
+            ..info
+        };
+        assert!(NowPlaying::from_info(stopped, Some(&dir)).is_none());
+        assert!(NowPlaying::from_info(PlaybackInfo::default(), Some(&dir)).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,15 +1,41 @@
 use keyring::Entry;
 /// Configuration management
 use rspotify::Token;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, RwLock};
+use std::time::Duration;
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub general: GeneralConfig,
     pub spotify: Option<SpotifyConfig>,
-    pub jellyfin: Option<JellyfinConfig>,
+    /// Configured Jellyfin servers
+    #[serde(default, deserialize_with = "deserialize_jellyfin_servers")]
+    pub jellyfin: Vec<JellyfinConfig>,
+    /// URL of the Jellyfin server currently in use
+    #[serde(default)]
+    pub active_jellyfin_server: Option<String>,
+}
+
+/// Accept both the old single `[jellyfin]` table and a `[[jellyfin]]` list
+fn deserialize_jellyfin_servers<'de, D>(deserializer: D) -> Result<Vec<JellyfinConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(JellyfinConfig),
+        Many(Vec<JellyfinConfig>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(server) => vec![server],
+        OneOrMany::Many(servers) => servers,
+    })
 }
 
 /// General application settings
@@ -25,6 +51,185 @@ pub struct GeneralConfig {
     pub enable_images: bool,
     /// Theme name
     pub theme: String,
+    /// Preferred port for the local OAuth callback server
+    #[serde(default = "default_oauth_port")]
+    pub oauth_port: u16,
+    /// Show a desktop notification when the current track changes
+    #[serde(default)]
+    pub notify_on_track_change: bool,
+    /// Show the current track on the user's Discord profile
+    #[serde(default)]
+    pub discord_presence: bool,
+    /// Discord application ID used for Rich Presence
+    #[serde(default)]
+    pub discord_client_id: Option<String>,
+    /// Maximum requests per second sent to each provider
+    #[serde(default = "default_provider_requests_per_second")]
+    pub provider_requests_per_second: f64,
+    /// Even out loudness between tracks using their ReplayGain / R128 tags
+    #[serde(default)]
+    pub normalize_volume: bool,
+    /// Loudness tracks are normalized to, in LUFS
+    #[serde(default = "default_normalization_target_lufs")]
+    pub normalization_target_lufs: f64,
+    /// Seconds between automatic playback state saves; 0 disables autosave
+    #[serde(default = "default_autosave_interval_secs")]
+    pub autosave_interval_secs: u64,
+    /// Milliseconds into a track after which "previous" restarts it
+    #[serde(default = "default_previous_restart_threshold_ms")]
+    pub previous_restart_threshold_ms: u64,
+    /// Milliseconds to fade the volume over when pausing and resuming; 0 disables
+    #[serde(default = "default_fade_duration_ms")]
+    pub fade_duration_ms: u64,
+    /// Number of upcoming queue tracks to fetch details for ahead of playback
+    #[serde(default = "default_enrich_lookahead")]
+    pub enrich_lookahead: usize,
+    /// Milliseconds to wait between lookahead fetches, for metered connections
+    #[serde(default)]
+    pub enrich_delay_ms: u64,
+    /// Closing the window hides it to the system tray instead of quitting
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    /// Seconds between background refreshes of provider playlists; 0 disables them
+    #[serde(default = "default_playlist_refresh_interval_secs")]
+    pub playlist_refresh_interval_secs: u64,
+    /// Volume (0-100) a session starts at when there's no saved state
+    #[serde(default = "default_volume")]
+    pub default_volume: u32,
+    /// User-Agent sent when fetching audio
+    #[serde(default = "default_user_agent")]
+    pub user_agent: String,
+    /// Client name Jellyfin shows for this app's sessions
+    #[serde(default = "default_jellyfin_client")]
+    pub jellyfin_client: String,
+    /// Device name Jellyfin shows for this app's sessions
+    #[serde(default = "default_jellyfin_client")]
+    pub jellyfin_device_name: String,
+    /// Identifies this install to Jellyfin; generated on first load
+    #[serde(default)]
+    pub jellyfin_device_id: String,
+    /// Client version reported to Jellyfin
+    #[serde(default = "default_jellyfin_client_version")]
+    pub jellyfin_client_version: String,
+}
+
+impl GeneralConfig {
+    /// Interval for periodic playback state saves, or `None` when disabled
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        (self.autosave_interval_secs > 0).then(|| Duration::from_secs(self.autosave_interval_secs))
+    }
+
+    /// Volume for a fresh session
+    pub fn initial_volume(&self) -> crate::models::Volume {
+        crate::models::Volume::new(self.default_volume)
+    }
+
+    /// Interval for background playlist refreshes, or `None` when disabled
+    pub fn playlist_refresh_interval(&self) -> Option<Duration> {
+        (self.playlist_refresh_interval_secs > 0)
+            .then(|| Duration::from_secs(self.playlist_refresh_interval_secs))
+    }
+}
+
+fn default_oauth_port() -> u16 {
+    crate::providers::spotify::DEFAULT_OAUTH_PORT
+}
+
+fn default_provider_requests_per_second() -> f64 {
+    crate::providers::rate_limit::DEFAULT_REQUESTS_PER_SECOND
+}
+
+fn default_normalization_target_lufs() -> f64 {
+    crate::playback::normalization::REPLAYGAIN_REFERENCE_LUFS
+}
+
+fn default_autosave_interval_secs() -> u64 {
+    crate::playback::DEFAULT_AUTOSAVE_INTERVAL.as_secs()
+}
+
+fn default_previous_restart_threshold_ms() -> u64 {
+    crate::playback::DEFAULT_PREVIOUS_RESTART_THRESHOLD.as_millis() as u64
+}
+
+fn default_fade_duration_ms() -> u64 {
+    crate::playback::DEFAULT_FADE_DURATION.as_millis() as u64
+}
+
+fn default_enrich_lookahead() -> usize {
+    crate::playback::DEFAULT_ENRICH_LOOKAHEAD
+}
+
+fn default_playlist_refresh_interval_secs() -> u64 {
+    crate::playlist_refresh::DEFAULT_REFRESH_INTERVAL.as_secs()
+}
+
+fn default_volume() -> u32 {
+    crate::models::Volume::default().percent()
+}
+
+fn default_user_agent() -> String {
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64)".to_string()
+}
+
+fn default_jellyfin_client() -> String {
+    "AnyPlayer".to_string()
+}
+
+fn default_jellyfin_client_version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// How the app identifies itself to servers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    pub user_agent: String,
+    pub jellyfin_client: String,
+    pub jellyfin_device_name: String,
+    pub jellyfin_device_id: String,
+    pub jellyfin_client_version: String,
+}
+
+impl ClientIdentity {
+    pub fn from_config(general: &GeneralConfig) -> Self {
+        Self {
+            user_agent: general.user_agent.clone(),
+            jellyfin_client: general.jellyfin_client.clone(),
+            jellyfin_device_name: general.jellyfin_device_name.clone(),
+            jellyfin_device_id: general.jellyfin_device_id.clone(),
+            jellyfin_client_version: general.jellyfin_client_version.clone(),
+        }
+    }
+
+    /// `X-Emby-Authorization` header value for Jellyfin requests
+    pub fn jellyfin_authorization(&self, token: &str) -> String {
+        // Quotes or control characters would break the header
+        let clean = |value: &str| -> String {
+            value
+                .chars()
+                .filter(|c| *c != '"' && !c.is_control())
+                .collect()
+        };
+        format!(
+            "MediaBrowser Token=\"{}\", Client=\"{}\", Device=\"{}\", DeviceId=\"{}\", Version=\"{}\"",
+            token,
+            clean(&self.jellyfin_client),
+            clean(&self.jellyfin_device_name),
+            clean(&self.jellyfin_device_id),
+            clean(&self.jellyfin_client_version)
+        )
+    }
+}
+
+impl Default for ClientIdentity {
+    fn default() -> Self {
+        Self {
+            user_agent: default_user_agent(),
+            jellyfin_client: default_jellyfin_client(),
+            jellyfin_device_name: default_jellyfin_client(),
+            jellyfin_device_id: default_jellyfin_client(),
+            jellyfin_client_version: default_jellyfin_client_version(),
+        }
+    }
 }
 
 /// Spotify-specific configuration
@@ -43,9 +248,13 @@ pub struct SpotifyConfig {
 /// Jellyfin-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JellyfinConfig {
+    /// Display name for the server (e.g., "Home")
+    #[serde(default)]
+    pub name: Option<String>,
     /// Jellyfin server URL (e.g., http://192.168.1.100:8096)
     pub server_url: String,
-    /// Jellyfin API key
+    /// Jellyfin API key; normally kept in token storage rather than the config file
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub api_key: String,
     /// Username for authentication
     pub username: Option<String>,
@@ -60,7 +269,9 @@ pub struct JellyfinConfig {
 /// - **Windows**: Uses the Credential Manager
 /// - **Linux**: Uses Secret Service API (e.g., GNOME Keyring, KDE Wallet)
 ///
-/// Tokens are stored securely and encrypted by the operating system.
+/// Tokens are stored securely and encrypted by the operating system. Where no
+/// credential store is available they fall back to `tokens.json` in the
+/// config directory.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TokenStorage {
     /// Spotify token
@@ -69,6 +280,93 @@ pub struct TokenStorage {
     pub jellyfin_api_key: Option<String>,
     /// Jellyfin server URL
     pub jellyfin_url: Option<String>,
+    /// API keys for every saved Jellyfin server, keyed by server URL
+    #[serde(default)]
+    pub jellyfin_server_keys: HashMap<String, String>,
+}
+
+impl TokenStorage {
+    /// Whether no credentials are stored at all
+    pub fn is_empty(&self) -> bool {
+        self.spotify_token.is_none()
+            && self.jellyfin_api_key.is_none()
+            && self.jellyfin_url.is_none()
+            && self.jellyfin_server_keys.is_empty()
+    }
+}
+
+/// Fallback token file used when the OS keyring is unavailable
+const TOKEN_FILE_NAME: &str = "tokens.json";
+
+/// Quiet period after the last change before the config is reloaded, so an
+/// editor's burst of writes (truncate, write, rename) triggers one reload
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// `general.data_dir` of the loaded config, for modules that store files
+/// without a `Config` at hand. Set once at startup; files already open
+/// there can't move, so changes take effect after a restart.
+#[cfg(not(test))]
+static DATA_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+// Per thread in tests, so a test relocating storage doesn't move the files
+// of tests running alongside it
+#[cfg(test)]
+thread_local! {
+    static DATA_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Record the configured data directory (None to use the platform defaults)
+#[cfg(not(test))]
+pub fn set_data_dir(dir: Option<PathBuf>) {
+    *DATA_DIR.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+/// Record the configured data directory (None to use the platform defaults)
+#[cfg(test)]
+pub fn set_data_dir(dir: Option<PathBuf>) {
+    DATA_DIR.with(|data_dir| *data_dir.borrow_mut() = dir);
+}
+
+/// The configured data directory, if the user set one
+#[cfg(not(test))]
+pub fn configured_data_dir() -> Option<PathBuf> {
+    DATA_DIR.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The configured data directory, if the user set one
+#[cfg(test)]
+pub fn configured_data_dir() -> Option<PathBuf> {
+    DATA_DIR.with(|data_dir| data_dir.borrow().clone())
+}
+
+/// Identity from the loaded config, for modules that make requests without a
+/// `Config` at hand. Set at startup and on every reload.
+static CLIENT_IDENTITY: RwLock<Option<ClientIdentity>> = RwLock::new(None);
+
+/// Record the identity to send to servers
+pub fn set_client_identity(identity: ClientIdentity) {
+    *CLIENT_IDENTITY.write().unwrap_or_else(|e| e.into_inner()) = Some(identity);
+}
+
+/// The configured identity, or the defaults before a config is loaded
+pub fn client_identity() -> ClientIdentity {
+    CLIENT_IDENTITY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Environment variables that override config file values
+pub const ENV_LOG_LEVEL: &str = "ANYPLAYER_LOG_LEVEL";
+pub const ENV_DATA_DIR: &str = "ANYPLAYER_DATA_DIR";
+pub const ENV_OAUTH_PORT: &str = "ANYPLAYER_OAUTH_PORT";
+pub const ENV_JELLYFIN_URL: &str = "ANYPLAYER_JELLYFIN_URL";
+pub const ENV_JELLYFIN_API_KEY: &str = "ANYPLAYER_JELLYFIN_API_KEY";
+
+/// Keeps a config file watch alive; dropping it stops watching
+pub struct ConfigWatcher {
+    _watcher: std::sync::Mutex<notify::RecommendedWatcher>,
 }
 
 impl Default for Config {
@@ -80,22 +378,64 @@ impl Default for Config {
                 log_level: "info".to_string(),
                 enable_images: true,
                 theme: "default".to_string(),
+                oauth_port: default_oauth_port(),
+                notify_on_track_change: false,
+                discord_presence: false,
+                discord_client_id: None,
+                provider_requests_per_second: default_provider_requests_per_second(),
+                normalize_volume: false,
+                normalization_target_lufs: default_normalization_target_lufs(),
+                autosave_interval_secs: default_autosave_interval_secs(),
+                previous_restart_threshold_ms: default_previous_restart_threshold_ms(),
+                fade_duration_ms: default_fade_duration_ms(),
+                enrich_lookahead: default_enrich_lookahead(),
+                enrich_delay_ms: 0,
+                minimize_to_tray: false,
+                playlist_refresh_interval_secs: default_playlist_refresh_interval_secs(),
+                default_volume: default_volume(),
+                user_agent: default_user_agent(),
+                jellyfin_client: default_jellyfin_client(),
+                jellyfin_device_name: default_jellyfin_client(),
+                jellyfin_device_id: uuid::Uuid::new_v4().to_string(),
+                jellyfin_client_version: default_jellyfin_client_version(),
             },
             spotify: None,
-            jellyfin: None,
+            jellyfin: Vec::new(),
+            active_jellyfin_server: None,
         }
     }
 }
 
 impl Config {
-    /// Load configuration from file or create default
+    /// Load configuration from file or create default, then apply
+    /// `ANYPLAYER_*` environment overrides.
+    ///
+    /// Precedence is environment variable > config file > built-in default.
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let config_dir = Self::config_dir()?;
+        let mut config = Self::load_from(None)?;
+        config.apply_env_overrides();
+        Ok(config)
+    }
+
+    /// Load configuration from an explicit directory, falling back to the
+    /// platform config directory when no override is given.
+    ///
+    /// Allows running isolated profiles (and tests) without touching the
+    /// user's real configuration. Environment overrides are not applied, so
+    /// use this when loading a config to modify and save it back.
+    pub fn load_from(config_dir: Option<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let config_dir = Self::resolve_config_dir(config_dir)?;
         let config_path = config_dir.join("config.toml");
 
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            Ok(toml::from_str(&content)?)
+            let mut config = Self::read_config_file(&config_path)?;
+            // Configs from before device IDs existed get one, saved so it
+            // stays the same on every launch
+            if config.general.jellyfin_device_id.is_empty() {
+                config.general.jellyfin_device_id = uuid::Uuid::new_v4().to_string();
+                std::fs::write(&config_path, toml::to_string_pretty(&config)?)?;
+            }
+            Ok(config)
         } else {
             // Create default config
             std::fs::create_dir_all(&config_dir)?;
@@ -108,7 +448,12 @@ impl Config {
 
     /// Save configuration to file
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let config_dir = Self::config_dir()?;
+        self.save_to(None)
+    }
+
+    /// Save configuration to an explicit directory (or the platform default)
+    pub fn save_to(&self, config_dir: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        let config_dir = Self::resolve_config_dir(config_dir)?;
         let config_path = config_dir.join("config.toml");
         std::fs::create_dir_all(&config_dir)?;
         let content = toml::to_string_pretty(self)?;
@@ -116,6 +461,136 @@ impl Config {
         Ok(())
     }
 
+    /// Apply any `ANYPLAYER_*` environment variables on top of this config.
+    ///
+    /// Lets headless and container deployments configure the app (including
+    /// a Jellyfin server) without writing a config file.
+    pub fn apply_env_overrides(&mut self) {
+        self.apply_overrides(|name| std::env::var(name).ok().filter(|v| !v.is_empty()));
+    }
+
+    fn apply_overrides(&mut self, var: impl Fn(&str) -> Option<String>) {
+        if let Some(level) = var(ENV_LOG_LEVEL) {
+            self.general.log_level = level;
+        }
+        if let Some(dir) = var(ENV_DATA_DIR) {
+            self.general.data_dir = Some(dir);
+        }
+        if let Some(port) = var(ENV_OAUTH_PORT) {
+            match port.parse() {
+                Ok(port) => self.general.oauth_port = port,
+                Err(e) => tracing::warn!("Ignoring invalid {}={}: {}", ENV_OAUTH_PORT, port, e),
+            }
+        }
+
+        if let Some(url) = var(ENV_JELLYFIN_URL) {
+            self.add_jellyfin_server(&url, None);
+            self.active_jellyfin_server = Some(url);
+        }
+        if let Some(api_key) = var(ENV_JELLYFIN_API_KEY) {
+            let active_url = self.active_jellyfin_server().map(|s| s.server_url.clone());
+            match self
+                .jellyfin
+                .iter_mut()
+                .find(|s| Some(&s.server_url) == active_url.as_ref())
+            {
+                Some(server) => server.api_key = api_key,
+                None => tracing::warn!(
+                    "{} is set but no Jellyfin server is configured; set {} too",
+                    ENV_JELLYFIN_API_KEY,
+                    ENV_JELLYFIN_URL
+                ),
+            }
+        }
+    }
+
+    /// Watch the config file and call `callback` with the reloaded config
+    /// whenever it changes
+    pub fn watch<F>(callback: F) -> Result<ConfigWatcher, Box<dyn std::error::Error>>
+    where
+        F: Fn(Config) + Send + 'static,
+    {
+        Self::watch_dir(Self::config_dir()?, callback)
+    }
+
+    /// Watch `config.toml` in an explicit directory.
+    ///
+    /// The directory rather than the file is watched because many editors
+    /// save by replacing the file, which would end a watch on the old inode.
+    pub fn watch_dir<F>(
+        config_dir: PathBuf,
+        callback: F,
+    ) -> Result<ConfigWatcher, Box<dyn std::error::Error>>
+    where
+        F: Fn(Config) + Send + 'static,
+    {
+        use notify::Watcher;
+
+        let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&config_dir, notify::RecursiveMode::NonRecursive)?;
+
+        std::thread::spawn(move || {
+            let is_config_change = |event: &notify::Result<notify::Event>| match event {
+                Ok(event) => {
+                    (event.kind.is_modify() || event.kind.is_create())
+                        && event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name() == Some(std::ffi::OsStr::new("config.toml")))
+                }
+                Err(_) => false,
+            };
+
+            // Ends when the watcher is dropped and the channel disconnects
+            while let Ok(event) = rx.recv() {
+                if !is_config_change(&event) {
+                    continue;
+                }
+                loop {
+                    match rx.recv_timeout(WATCH_DEBOUNCE) {
+                        Ok(_) => continue,
+                        Err(mpsc::RecvTimeoutError::Timeout) => break,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+
+                match Self::read_config_file(&config_dir.join("config.toml")) {
+                    Ok(mut config) => {
+                        config.apply_env_overrides();
+                        tracing::info!("Config file changed, applying new settings");
+                        callback(config);
+                    }
+                    Err(e) => tracing::warn!("Ignoring invalid config file change: {}", e),
+                }
+            }
+        });
+
+        Ok(ConfigWatcher {
+            _watcher: std::sync::Mutex::new(watcher),
+        })
+    }
+
+    fn read_config_file(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        let mut config: Self = toml::from_str(&content)?;
+        config.validate();
+        Ok(config)
+    }
+
+    /// Bring out-of-range values back in range, with a warning
+    fn validate(&mut self) {
+        let volume = self.general.initial_volume().percent();
+        if volume != self.general.default_volume {
+            tracing::warn!(
+                "default_volume {} is out of range, using {}",
+                self.general.default_volume,
+                volume
+            );
+            self.general.default_volume = volume;
+        }
+    }
+
     /// Get configuration directory path
     pub fn config_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
         let dir = dirs::config_dir()
@@ -124,6 +599,16 @@ impl Config {
         Ok(dir)
     }
 
+    /// Use the override directory if provided, otherwise the platform default
+    fn resolve_config_dir(
+        config_dir: Option<PathBuf>,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        match config_dir {
+            Some(dir) => Ok(dir),
+            None => Self::config_dir(),
+        }
+    }
+
     /// Get cache directory path
     pub fn cache_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
         let dir = dirs::cache_dir()
@@ -132,6 +617,24 @@ impl Config {
         Ok(dir)
     }
 
+    /// Spotify token cache file: in the configured data directory if there is
+    /// one, otherwise the config directory
+    pub fn spotify_cache_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Self::spotify_cache_path_from(None)
+    }
+
+    /// Spotify token cache file for a config directory override (or the
+    /// platform default); the configured data directory still wins
+    pub fn spotify_cache_path_from(
+        config_dir: Option<PathBuf>,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let dir = match configured_data_dir() {
+            Some(dir) => dir,
+            None => Self::resolve_config_dir(config_dir)?,
+        };
+        Ok(dir.join("spotify_cache.json"))
+    }
+
     /// Get data directory
     pub fn get_data_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
         if let Some(ref custom_dir) = self.general.data_dir {
@@ -141,11 +644,70 @@ impl Config {
         }
     }
 
-    /// Load token storage from secure keyring
+    /// The Jellyfin server in use: the selected one, else the first configured
+    pub fn active_jellyfin_server(&self) -> Option<&JellyfinConfig> {
+        self.active_jellyfin_server
+            .as_deref()
+            .and_then(|url| self.jellyfin.iter().find(|s| s.server_url == url))
+            .or_else(|| self.jellyfin.first())
+    }
+
+    /// Select the configured Jellyfin server with the given URL
+    pub fn set_active_jellyfin_server(
+        &mut self,
+        server_url: &str,
+    ) -> Result<&JellyfinConfig, String> {
+        let server = self
+            .jellyfin
+            .iter()
+            .find(|s| s.server_url == server_url)
+            .ok_or_else(|| format!("Unknown Jellyfin server: {}", server_url))?;
+        self.active_jellyfin_server = Some(server.server_url.clone());
+        Ok(server)
+    }
+
+    /// Add a Jellyfin server to the list if it isn't there yet
+    pub fn add_jellyfin_server(&mut self, server_url: &str, name: Option<String>) {
+        if let Some(server) = self
+            .jellyfin
+            .iter_mut()
+            .find(|s| s.server_url == server_url)
+        {
+            if name.is_some() {
+                server.name = name;
+            }
+            return;
+        }
+        self.jellyfin.push(JellyfinConfig {
+            name,
+            server_url: server_url.to_string(),
+            api_key: String::new(),
+            username: None,
+            user_id: None,
+        });
+    }
+
+    /// Load token storage from secure keyring, or the fallback file if the
+    /// keyring holds nothing
     pub fn load_tokens() -> Result<TokenStorage, Box<dyn std::error::Error>> {
+        Self::load_tokens_from(None)
+    }
+
+    /// Load token storage for a config directory override (or the platform
+    /// default).
+    ///
+    /// The keyring entries are shared by every profile, so an override
+    /// directory only uses the token file inside it.
+    pub fn load_tokens_from(
+        config_dir: Option<PathBuf>,
+    ) -> Result<TokenStorage, Box<dyn std::error::Error>> {
+        if let Some(dir) = config_dir {
+            return Ok(Self::load_tokens_from_file(&dir.join(TOKEN_FILE_NAME))?.unwrap_or_default());
+        }
+
         tracing::debug!("Loading tokens from keyring");
 
-        match Self::load_tokens_from_keyring() {
+        let tokens = match Self::load_tokens_from_keyring() {
             Ok(tokens) => {
                 let has_spotify = tokens.spotify_token.is_some();
                 let has_jellyfin = tokens.jellyfin_api_key.is_some();
@@ -154,12 +716,104 @@ impl Config {
                     has_spotify,
                     has_jellyfin
                 );
-                Ok(tokens)
+                tokens
             }
             Err(e) => {
                 tracing::debug!("No tokens found in keyring: {}", e);
-                Ok(TokenStorage::default())
+                TokenStorage::default()
             }
+        };
+
+        if tokens.is_empty() {
+            if let Some(file_tokens) = Self::load_tokens_from_file(&Self::token_file_path()?)? {
+                tracing::debug!("Loaded tokens from fallback file");
+                return Ok(file_tokens);
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    /// Path of the fallback token file
+    fn token_file_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Ok(Self::config_dir()?.join(TOKEN_FILE_NAME))
+    }
+
+    /// Load tokens from a fallback file, if it exists
+    fn load_tokens_from_file(
+        path: &std::path::Path,
+    ) -> Result<Option<TokenStorage>, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Write tokens to a fallback file readable only by the current user
+    fn save_tokens_to_file(
+        tokens: &TokenStorage,
+        path: &std::path::Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(tokens)?)?;
+        Self::restrict_to_owner(path)?;
+
+        Ok(())
+    }
+
+    /// Make a file readable and writable only by the current user
+    #[cfg(unix)]
+    fn restrict_to_owner(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(())
+    }
+
+    /// Make a file readable and writable only by the current user.
+    ///
+    /// Replaces the inherited ACL with a single full-control entry for the
+    /// current user via `icacls`. Failure is logged rather than returned so a
+    /// locked-down system can still save tokens.
+    #[cfg(windows)]
+    fn restrict_to_owner(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+        let Ok(user) = std::env::var("USERNAME") else {
+            tracing::warn!("Unable to determine current user, token file ACL left unchanged");
+            return Ok(());
+        };
+
+        let result = std::process::Command::new("icacls")
+            .arg(path)
+            .args(["/inheritance:r", "/grant:r"])
+            .arg(format!("{}:F", user))
+            .output();
+        match result {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => tracing::warn!(
+                "Failed to restrict token file ACL: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Err(e) => tracing::warn!("Failed to run icacls on token file: {}", e),
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn restrict_to_owner(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::warn!(
+            "Cannot restrict permissions of {:?} on this platform; it may be readable by other users",
+            path
+        );
+        Ok(())
+    }
+
+    /// Remove a fallback token file, ignoring a missing file
+    fn remove_token_file(path: &std::path::Path) -> std::io::Result<()> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
         }
     }
 
@@ -168,6 +822,7 @@ impl Config {
         let spotify_entry = Entry::new("any-player", "spotify-token")?;
         let jellyfin_entry = Entry::new("any-player", "jellyfin-api-key")?;
         let jellyfin_url_entry = Entry::new("any-player", "jellyfin-url")?;
+        let jellyfin_servers_entry = Entry::new("any-player", "jellyfin-servers")?;
 
         let spotify_token = match spotify_entry.get_password() {
             Ok(json) => {
@@ -211,11 +866,23 @@ impl Config {
             }
         };
 
+        let jellyfin_server_keys = match jellyfin_servers_entry.get_password() {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Failed to deserialize jellyfin server keys: {}", e);
+                HashMap::new()
+            }),
+            Err(e) => {
+                tracing::debug!("No jellyfin server keys in keyring: {}", e);
+                HashMap::new()
+            }
+        };
+
         // Return tokens (even if all are None)
         Ok(TokenStorage {
             spotify_token,
             jellyfin_api_key,
             jellyfin_url,
+            jellyfin_server_keys,
         })
     }
 
@@ -230,8 +897,45 @@ impl Config {
     /// - **Linux**: Secret Service API (GNOME Keyring, KDE Wallet, etc.)
     ///
     /// This is significantly more secure than file-based storage as the OS
-    /// handles encryption and access control automatically.
+    /// handles encryption and access control automatically. If no keyring is
+    /// available the tokens are written to `tokens.json` instead, with a warning.
     pub fn save_tokens(tokens: &TokenStorage) -> Result<(), Box<dyn std::error::Error>> {
+        Self::save_tokens_to(tokens, None)
+    }
+
+    /// Save tokens for a config directory override (or the platform default).
+    /// An override directory keeps its tokens in its own file, like
+    /// [`Config::load_tokens_from`].
+    pub fn save_tokens_to(
+        tokens: &TokenStorage,
+        config_dir: Option<PathBuf>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(dir) = config_dir {
+            return Self::save_tokens_to_file(tokens, &dir.join(TOKEN_FILE_NAME));
+        }
+
+        let path = Self::token_file_path()?;
+        match Self::save_tokens_to_keyring(tokens) {
+            Ok(()) => {
+                // Don't leave an older plaintext copy behind once the keyring works
+                if let Err(e) = Self::remove_token_file(&path) {
+                    tracing::warn!("Failed to remove fallback token file: {}", e);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "OS keyring unavailable ({}), storing tokens in {:?} instead",
+                    e,
+                    path
+                );
+                Self::save_tokens_to_file(tokens, &path)
+            }
+        }
+    }
+
+    /// Save tokens directly to keyring
+    fn save_tokens_to_keyring(tokens: &TokenStorage) -> Result<(), Box<dyn std::error::Error>> {
         tracing::debug!(
             "Saving tokens to keyring - spotify: {}, jellyfin: {}",
             tokens.spotify_token.is_some(),
@@ -273,21 +977,49 @@ impl Config {
             tracing::debug!("Deleted jellyfin URL from keyring");
         }
 
+        // Save API keys for every known Jellyfin server
+        let jellyfin_servers_entry = Entry::new("any-player", "jellyfin-servers")?;
+        if tokens.jellyfin_server_keys.is_empty() {
+            let _ = jellyfin_servers_entry.delete_credential();
+        } else {
+            let json = serde_json::to_string(&tokens.jellyfin_server_keys)?;
+            jellyfin_servers_entry.set_password(&json)?;
+            tracing::debug!("Successfully saved jellyfin server keys to keyring");
+        }
+
         Ok(())
     }
 
-    /// Clear stored tokens from keyring
+    /// Clear stored tokens from keyring and the fallback file
     pub fn clear_tokens() -> Result<(), Box<dyn std::error::Error>> {
-        tracing::debug!("Clearing tokens from keyring");
+        Self::clear_tokens_from(None)
+    }
 
-        let spotify_entry = Entry::new("any-player", "spotify-token")?;
-        let jellyfin_entry = Entry::new("any-player", "jellyfin-api-key")?;
-        let jellyfin_url_entry = Entry::new("any-player", "jellyfin-url")?;
+    /// Clear stored tokens for a config directory override (or the platform
+    /// default). An override directory only has its token file removed.
+    pub fn clear_tokens_from(
+        config_dir: Option<PathBuf>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(dir) = config_dir {
+            Self::remove_token_file(&dir.join(TOKEN_FILE_NAME))?;
+            return Ok(());
+        }
+
+        tracing::debug!("Clearing tokens from keyring");
 
         // Attempt to delete all entries (ignore errors if they don't exist)
-        let _ = spotify_entry.delete_credential();
-        let _ = jellyfin_entry.delete_credential();
-        let _ = jellyfin_url_entry.delete_credential();
+        for name in [
+            "spotify-token",
+            "jellyfin-api-key",
+            "jellyfin-url",
+            "jellyfin-servers",
+        ] {
+            if let Ok(entry) = Entry::new("any-player", name) {
+                let _ = entry.delete_credential();
+            }
+        }
+
+        Self::remove_token_file(&Self::token_file_path()?)?;
 
         Ok(())
     }
@@ -296,12 +1028,200 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
 
     #[test]
     fn test_default_config() {
         let config = Config::default();
         assert_eq!(config.general.log_level, "info");
         assert!(config.general.enable_images);
+        assert_eq!(config.general.oauth_port, 8989);
+    }
+
+    #[test]
+    fn test_config_without_oauth_port_uses_default() {
+        let toml_str = r#"
+[general]
+logging_enabled = true
+log_level = "info"
+enable_images = true
+theme = "default"
+"#;
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse config");
+        assert_eq!(config.general.oauth_port, 8989);
+        assert!(!config.general.notify_on_track_change);
+        assert!(!config.general.discord_presence);
+        assert!(!config.general.minimize_to_tray);
+        assert_eq!(config.general.provider_requests_per_second, 10.0);
+        assert!(!config.general.normalize_volume);
+        assert_eq!(config.general.normalization_target_lufs, -18.0);
+        assert_eq!(
+            config.general.autosave_interval(),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(config.general.previous_restart_threshold_ms, 3000);
+        assert_eq!(config.general.fade_duration_ms, 150);
+        assert_eq!(config.general.enrich_lookahead, 10);
+        assert_eq!(config.general.enrich_delay_ms, 0);
+        assert_eq!(
+            config.general.playlist_refresh_interval(),
+            Some(Duration::from_secs(15 * 60))
+        );
+    }
+
+    #[test]
+    fn test_autosave_interval_zero_disables() {
+        let mut config = Config::default();
+        config.general.autosave_interval_secs = 0;
+        assert_eq!(config.general.autosave_interval(), None);
+
+        config.general.playlist_refresh_interval_secs = 0;
+        assert_eq!(config.general.playlist_refresh_interval(), None);
+    }
+
+    #[test]
+    fn test_single_jellyfin_table_still_loads() {
+        let toml_str = r#"
+[general]
+logging_enabled = true
+log_level = "info"
+enable_images = true
+theme = "default"
+
+[jellyfin]
+server_url = "http://home:8096"
+api_key = "key"
+"#;
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse config");
+        assert_eq!(config.jellyfin.len(), 1);
+        assert_eq!(config.jellyfin[0].server_url, "http://home:8096");
+        assert!(config.active_jellyfin_server.is_none());
+    }
+
+    #[test]
+    fn test_default_volume_is_clamped_on_load() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-config-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let general = "[general]\nlogging_enabled = true\nlog_level = \"info\"\nenable_images = true\ntheme = \"default\"\njellyfin_device_id = \"id\"\n";
+
+        std::fs::write(dir.join("config.toml"), general).unwrap();
+        let config = Config::load_from(Some(dir.clone())).unwrap();
+        assert_eq!(config.general.default_volume, 50);
+
+        std::fs::write(
+            dir.join("config.toml"),
+            format!("{}default_volume = 150\n", general),
+        )
+        .unwrap();
+        let config = Config::load_from(Some(dir.clone())).unwrap();
+        assert_eq!(config.general.default_volume, 100);
+        assert_eq!(config.general.initial_volume().percent(), 100);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_device_id_is_stable_across_loads() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-config-test-{}", uuid::Uuid::new_v4()));
+
+        let first = Config::load_from(Some(dir.clone())).unwrap();
+        assert!(!first.general.jellyfin_device_id.is_empty());
+        let second = Config::load_from(Some(dir.clone())).unwrap();
+        assert_eq!(
+            first.general.jellyfin_device_id,
+            second.general.jellyfin_device_id
+        );
+
+        // A config written before device IDs gets one that sticks
+        std::fs::write(
+            dir.join("config.toml"),
+            "[general]\nlogging_enabled = true\nlog_level = \"info\"\nenable_images = true\ntheme = \"default\"\n",
+        )
+        .unwrap();
+        let upgraded = Config::load_from(Some(dir.clone())).unwrap();
+        assert!(!upgraded.general.jellyfin_device_id.is_empty());
+        assert_ne!(
+            upgraded.general.jellyfin_device_id,
+            first.general.jellyfin_device_id
+        );
+        let reloaded = Config::load_from(Some(dir.clone())).unwrap();
+        assert_eq!(
+            reloaded.general.jellyfin_device_id,
+            upgraded.general.jellyfin_device_id
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_jellyfin_authorization_uses_identity() {
+        let mut general = Config::default().general;
+        general.jellyfin_device_name = "Living \"Room\"".to_string();
+        general.jellyfin_device_id = "abc-123".to_string();
+        let identity = ClientIdentity::from_config(&general);
+
+        let header = identity.jellyfin_authorization("key");
+        assert!(header.starts_with("MediaBrowser Token=\"key\""));
+        assert!(header.contains("Device=\"Living Room\""));
+        assert!(header.contains("DeviceId=\"abc-123\""));
+        assert!(header.contains(&format!("Version=\"{}\"", env!("CARGO_PKG_VERSION"))));
+    }
+
+    #[test]
+    fn test_load_from_custom_dir() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-config-test-{}", uuid::Uuid::new_v4()));
+
+        // First load writes a default config into the override directory
+        let config = Config::load_from(Some(dir.clone())).expect("Failed to load config");
+        assert_eq!(config.general.log_level, "info");
+        assert!(dir.join("config.toml").exists());
+
+        // Changes saved to the override directory are picked up on reload
+        let mut config = config;
+        config.general.log_level = "debug".to_string();
+        config
+            .save_to(Some(dir.clone()))
+            .expect("Failed to save config");
+
+        let reloaded = Config::load_from(Some(dir.clone())).expect("Failed to reload config");
+        assert_eq!(reloaded.general.log_level, "debug");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[serial]
+    fn test_tokens_and_cache_use_config_dir_override() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-config-test-{}", uuid::Uuid::new_v4()));
+
+        let tokens = TokenStorage {
+            jellyfin_api_key: Some("profile-key".to_string()),
+            jellyfin_url: Some("http://profile.local".to_string()),
+            ..Default::default()
+        };
+        Config::save_tokens_to(&tokens, Some(dir.clone())).unwrap();
+        assert!(dir.join(TOKEN_FILE_NAME).exists());
+
+        let loaded = Config::load_tokens_from(Some(dir.clone())).unwrap();
+        assert_eq!(loaded.jellyfin_api_key.as_deref(), Some("profile-key"));
+        assert_eq!(loaded.jellyfin_url.as_deref(), Some("http://profile.local"));
+
+        assert_eq!(
+            Config::spotify_cache_path_from(Some(dir.clone())).unwrap(),
+            dir.join("spotify_cache.json")
+        );
+
+        Config::clear_tokens_from(Some(dir.clone())).unwrap();
+        assert!(!dir.join(TOKEN_FILE_NAME).exists());
+        assert!(Config::load_tokens_from(Some(dir.clone()))
+            .unwrap()
+            .is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
@@ -318,6 +1238,7 @@ mod tests {
             spotify_token: None,
             jellyfin_api_key: Some("test_key".to_string()),
             jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: Default::default(),
         };
 
         // Test that we can serialize to JSON
@@ -336,6 +1257,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_token_storage_round_trip_with_spotify_token() {
+        use chrono::{Duration as ChronoDuration, TimeZone, Utc};
+
+        let expires_at = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let storage = TokenStorage {
+            spotify_token: Some(Token {
+                access_token: "access".to_string(),
+                expires_in: ChronoDuration::seconds(3600),
+                expires_at: Some(expires_at),
+                refresh_token: Some("refresh".to_string()),
+                scopes: Default::default(),
+            }),
+            jellyfin_api_key: Some("key".to_string()),
+            jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: HashMap::from([(
+                "http://localhost:8096".to_string(),
+                "key".to_string(),
+            )]),
+        };
+
+        let json = serde_json::to_string(&storage).unwrap();
+        let restored: TokenStorage = serde_json::from_str(&json).unwrap();
+
+        // Access token, refresh token and expiry all live in the rspotify token
+        let token = restored.spotify_token.unwrap();
+        assert_eq!(token.access_token, "access");
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+        assert_eq!(token.expires_at, Some(expires_at));
+        assert_eq!(
+            restored.jellyfin_url.as_deref(),
+            Some("http://localhost:8096")
+        );
+        assert_eq!(restored.jellyfin_server_keys, storage.jellyfin_server_keys);
+    }
+
+    #[test]
+    fn test_token_file_from_older_version_loads() {
+        // Written before the server URL and multi-server keys were stored
+        let storage: TokenStorage =
+            serde_json::from_str(r#"{"spotify_token":null,"jellyfin_api_key":"key"}"#).unwrap();
+        assert_eq!(storage.jellyfin_api_key.as_deref(), Some("key"));
+        assert!(storage.jellyfin_url.is_none());
+        assert!(storage.jellyfin_server_keys.is_empty());
+    }
+
     #[test]
     #[ignore] // Ignore by default as it requires a functioning keyring service
     fn test_save_and_load_tokens() {
@@ -344,6 +1311,7 @@ mod tests {
             spotify_token: None,
             jellyfin_api_key: Some("test_api_key".to_string()),
             jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: Default::default(),
         };
 
         // Save tokens using keyring
@@ -365,8 +1333,11 @@ mod tests {
 
     #[test]
     fn test_clear_tokens_nonexistent_file() {
-        // This should not panic when tokens don't exist in keyring
-        let result = Config::clear_tokens();
+        // Clearing a profile that never saved tokens isn't an error. Uses an
+        // override directory so the real tokens and keyring are left alone.
+        let dir =
+            std::env::temp_dir().join(format!("any-player-config-test-{}", uuid::Uuid::new_v4()));
+        let result = Config::clear_tokens_from(Some(dir));
         assert!(result.is_ok());
     }
 
@@ -378,6 +1349,7 @@ mod tests {
             spotify_token: None,
             jellyfin_api_key: Some("secure_test_key_123".to_string()),
             jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: Default::default(),
         };
 
         // Save to keyring
@@ -394,4 +1366,131 @@ mod tests {
         // Cleanup
         Config::clear_tokens().expect("Failed to clear tokens");
     }
+
+    #[test]
+    fn test_token_file_fallback() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-token-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(TOKEN_FILE_NAME);
+
+        // Missing file means no tokens rather than an error
+        assert!(Config::load_tokens_from_file(&path).unwrap().is_none());
+
+        let tokens = TokenStorage {
+            spotify_token: None,
+            jellyfin_api_key: Some("fallback_key".to_string()),
+            jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: Default::default(),
+        };
+        Config::save_tokens_to_file(&tokens, &path).expect("Failed to write token file");
+
+        let loaded = Config::load_tokens_from_file(&path)
+            .expect("Failed to read token file")
+            .expect("Token file should exist");
+        assert_eq!(loaded.jellyfin_api_key, Some("fallback_key".to_string()));
+        assert!(loaded.spotify_token.is_none());
+
+        Config::remove_token_file(&path).expect("Failed to remove token file");
+        assert!(!path.exists());
+        // Removing again is not an error
+        Config::remove_token_file(&path).expect("Failed to remove missing token file");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_token_file_permissions_tightened() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("any-player-token-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(TOKEN_FILE_NAME);
+
+        // An existing world-readable file is tightened when tokens are rewritten
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        Config::save_tokens_to_file(&TokenStorage::default(), &path)
+            .expect("Failed to write token file");
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_watch_reloads_changed_config() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-watch-test-{}", uuid::Uuid::new_v4()));
+        let config = Config::load_from(Some(dir.clone())).expect("Failed to load config");
+
+        let (tx, rx) = mpsc::channel();
+        let _watcher = Config::watch_dir(dir.clone(), move |config| {
+            let _ = tx.send(config);
+        })
+        .expect("Failed to watch config");
+
+        let mut changed = config;
+        changed.general.log_level = "debug".to_string();
+        changed.general.theme = "dark".to_string();
+        changed
+            .save_to(Some(dir.clone()))
+            .expect("Failed to save config");
+
+        let reloaded = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Watcher did not fire");
+        assert_eq!(reloaded.general.log_level, "debug");
+        assert_eq!(reloaded.general.theme, "dark");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[serial]
+    fn test_env_overrides_loaded_config() {
+        std::env::set_var(ENV_LOG_LEVEL, "trace");
+        std::env::set_var(ENV_JELLYFIN_URL, "http://jellyfin:8096");
+        std::env::set_var(ENV_JELLYFIN_API_KEY, "env_key");
+
+        let mut config: Config = toml::from_str(
+            r#"
+[general]
+logging_enabled = true
+log_level = "info"
+enable_images = true
+theme = "default"
+oauth_port = 9000
+"#,
+        )
+        .expect("Failed to parse config");
+        config.apply_env_overrides();
+
+        std::env::remove_var(ENV_LOG_LEVEL);
+        std::env::remove_var(ENV_JELLYFIN_URL);
+        std::env::remove_var(ENV_JELLYFIN_API_KEY);
+
+        // Overridden values come from the environment, the rest from the file
+        assert_eq!(config.general.log_level, "trace");
+        assert_eq!(config.general.oauth_port, 9000);
+        let server = config.active_jellyfin_server().expect("No active server");
+        assert_eq!(server.server_url, "http://jellyfin:8096");
+        assert_eq!(server.api_key, "env_key");
+    }
+
+    #[test]
+    fn test_invalid_env_override_is_ignored() {
+        let mut config = Config::default();
+        config.apply_overrides(|name| match name {
+            ENV_OAUTH_PORT => Some("not-a-port".to_string()),
+            ENV_JELLYFIN_API_KEY => Some("orphan_key".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(config.general.oauth_port, 8989);
+        assert!(config.jellyfin.is_empty());
+    }
 }
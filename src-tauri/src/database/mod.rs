@@ -1,11 +1,11 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
 
-use crate::models::{Source, Track};
+use crate::models::{Playlist, Source, Track};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomPlaylist {
@@ -17,6 +17,12 @@ pub struct CustomPlaylist {
     pub updated_at: i64,
     pub track_count: i64,
     pub playlist_type: String, // "standard" or "union"
+    /// Provider a snapshot playlist was copied from
+    #[serde(default)]
+    pub origin_source: Option<String>,
+    /// Id of the provider playlist a snapshot was copied from
+    #[serde(default)]
+    pub origin_playlist_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +33,13 @@ pub struct UnionPlaylistSource {
     pub source_playlist_id: String,
     pub position: i64,
     pub added_at: i64,
+    /// False after its provider was disconnected, until it loads again
+    #[serde(default = "default_available")]
+    pub available: bool,
+}
+
+fn default_available() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,20 +72,50 @@ impl PlaylistTrack {
             title: self.title.clone(),
             artist: self.artist.clone(),
             album: self.album.clone().unwrap_or_default(),
+            album_artist: None,
             duration_ms: self.duration_ms.unwrap_or(0) as u64,
             image_url: self.image_url.clone(),
             source,
             url: None,
             auth_headers: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at: None,
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadedTrack {
+    pub track_source: String,
+    pub track_id: String,
+    pub local_path: String,
+    pub size_bytes: i64,
+    pub downloaded_at: i64,
+}
+
+/// Counts across all custom playlists for the home dashboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryStats {
+    pub playlist_count: i64,
+    pub union_playlist_count: i64,
+    /// Distinct tracks, so one added to several playlists counts once
+    pub track_count: i64,
+    pub artist_count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnPreferences {
     pub columns: Vec<String>,
     pub column_order: Vec<usize>,
     pub column_widths: std::collections::HashMap<String, i64>,
+    /// Column tracks are sorted by (title, artist, album, duration, position)
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub sort_desc: bool,
 }
 
 impl Default for ColumnPreferences {
@@ -93,10 +136,60 @@ impl Default for ColumnPreferences {
                 ("duration".to_string(), 100),
                 ("source".to_string(), 100),
             ]),
+            sort_by: None,
+            sort_desc: false,
         }
     }
 }
 
+/// Every column identifier the track table knows how to render
+pub fn available_columns() -> Vec<&'static str> {
+    vec![
+        "title",
+        "artist",
+        "album",
+        "duration",
+        "source",
+        "genre",
+        "year",
+        "track_number",
+        "disc_number",
+    ]
+}
+
+impl ColumnPreferences {
+    /// Check that every column is known and `column_order` is a permutation
+    /// of the column indices
+    pub fn validate(&self) -> Result<()> {
+        let known = available_columns();
+        for column in &self.columns {
+            if !known.contains(&column.as_str()) {
+                bail!("Unknown column: {}", column);
+            }
+        }
+        if let Some(column) = self
+            .columns
+            .iter()
+            .enumerate()
+            .find_map(|(i, c)| self.columns[..i].contains(c).then_some(c))
+        {
+            bail!("Duplicate column: {}", column);
+        }
+
+        let mut order = self.column_order.clone();
+        order.sort_unstable();
+        if order != (0..self.columns.len()).collect::<Vec<_>>() {
+            bail!(
+                "Column order {:?} is not a permutation of {} columns",
+                self.column_order,
+                self.columns.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
 pub struct Database {
     conn: Connection,
 }
@@ -109,6 +202,15 @@ impl Database {
         Ok(db)
     }
 
+    /// Open the database at `db_path`, creating its directory if needed
+    pub fn open(db_path: PathBuf) -> Result<Self> {
+        if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create data directory {:?}", parent))?;
+        }
+        Self::new(db_path)
+    }
+
     /// Initialize the database schema.
     ///
     /// NOTE: This uses string concatenation for SQL, which is generally safe here
@@ -126,7 +228,9 @@ impl Database {
                 created_at INTEGER NOT NULL,
                 updated_at INTEGER NOT NULL,
                 track_count INTEGER DEFAULT 0,
-                playlist_type TEXT DEFAULT 'standard'
+                playlist_type TEXT DEFAULT 'standard',
+                origin_source TEXT,
+                origin_playlist_id TEXT
             );
 
             CREATE TABLE IF NOT EXISTS playlist_tracks (
@@ -158,7 +262,25 @@ impl Database {
                 id INTEGER PRIMARY KEY CHECK (id = 1),
                 columns TEXT NOT NULL,
                 column_order TEXT NOT NULL,
-                column_widths TEXT
+                column_widths TEXT,
+                sort_by TEXT,
+                sort_desc INTEGER DEFAULT 0
+            );
+
+            CREATE TABLE IF NOT EXISTS downloads (
+                track_source TEXT NOT NULL,
+                track_id TEXT NOT NULL,
+                local_path TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                downloaded_at INTEGER NOT NULL,
+                PRIMARY KEY (track_source, track_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS track_gain (
+                track_source TEXT NOT NULL,
+                track_id TEXT NOT NULL,
+                gain_db REAL NOT NULL,
+                PRIMARY KEY (track_source, track_id)
             );
 
             CREATE INDEX IF NOT EXISTS idx_playlist_tracks_playlist_id 
@@ -188,6 +310,60 @@ impl Database {
             )?;
         }
 
+        // Migration: Add snapshot origin columns if they don't exist
+        let has_origin: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('custom_playlists') WHERE name='origin_source'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap_or(0)
+            > 0;
+
+        if !has_origin {
+            self.conn.execute_batch(
+                "ALTER TABLE custom_playlists ADD COLUMN origin_source TEXT;
+                 ALTER TABLE custom_playlists ADD COLUMN origin_playlist_id TEXT;",
+            )?;
+        }
+
+        // Migration: Add availability flag to union sources if it doesn't exist
+        let has_available: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('union_playlist_sources') WHERE name='available'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap_or(0)
+            > 0;
+
+        if !has_available {
+            self.conn.execute(
+                "ALTER TABLE union_playlist_sources ADD COLUMN available INTEGER NOT NULL DEFAULT 1",
+                [],
+            )?;
+        }
+
+        // Migration: Add sort columns to column_preferences if they don't exist
+        let has_sort_by: bool = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('column_preferences') WHERE name='sort_by'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .unwrap_or(0)
+            > 0;
+
+        if !has_sort_by {
+            self.conn.execute_batch(
+                "ALTER TABLE column_preferences ADD COLUMN sort_by TEXT;
+                 ALTER TABLE column_preferences ADD COLUMN sort_desc INTEGER DEFAULT 0;",
+            )?;
+        }
+
         Ok(())
     }
 
@@ -227,12 +403,35 @@ impl Database {
             updated_at: now,
             track_count: 0,
             playlist_type,
+            origin_source: None,
+            origin_playlist_id: None,
         })
     }
 
+    /// Copy a provider playlist into a new standard playlist, keeping track
+    /// metadata and recording where it came from so it can be re-synced later
+    pub fn snapshot_playlist(&self, name: String, playlist: &Playlist) -> Result<CustomPlaylist> {
+        let mut snapshot = self.create_playlist(
+            name,
+            playlist.description.clone(),
+            playlist.image_url.clone(),
+        )?;
+        let origin_source = playlist.source.to_string();
+
+        self.conn.execute(
+            "UPDATE custom_playlists SET origin_source = ?1, origin_playlist_id = ?2 WHERE id = ?3",
+            params![origin_source, playlist.id, snapshot.id],
+        )?;
+        snapshot.track_count = self.add_tracks_to_playlist(&snapshot.id, &playlist.tracks)? as i64;
+        snapshot.origin_source = Some(origin_source);
+        snapshot.origin_playlist_id = Some(playlist.id.clone());
+
+        Ok(snapshot)
+    }
+
     pub fn get_all_playlists(&self) -> Result<Vec<CustomPlaylist>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, description, image_url, created_at, updated_at, track_count, playlist_type 
+            "SELECT id, name, description, image_url, created_at, updated_at, track_count, playlist_type, origin_source, origin_playlist_id 
              FROM custom_playlists 
              ORDER BY updated_at DESC",
         )?;
@@ -248,6 +447,8 @@ impl Database {
                     updated_at: row.get(5)?,
                     track_count: row.get(6)?,
                     playlist_type: row.get(7)?,
+                    origin_source: row.get(8)?,
+                    origin_playlist_id: row.get(9)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -259,7 +460,7 @@ impl Database {
         let playlist = self
             .conn
             .query_row(
-                "SELECT id, name, description, image_url, created_at, updated_at, track_count, playlist_type 
+                "SELECT id, name, description, image_url, created_at, updated_at, track_count, playlist_type, origin_source, origin_playlist_id 
                  FROM custom_playlists 
                  WHERE id = ?1",
                 params![playlist_id],
@@ -273,6 +474,8 @@ impl Database {
                         updated_at: row.get(5)?,
                         track_count: row.get(6)?,
                         playlist_type: row.get(7)?,
+                        origin_source: row.get(8)?,
+                        origin_playlist_id: row.get(9)?,
                     })
                 },
             )
@@ -401,14 +604,94 @@ impl Database {
         })
     }
 
+    /// Append many tracks to a playlist in a single transaction, returning how
+    /// many were added
+    pub fn add_tracks_to_playlist(&self, playlist_id: &str, tracks: &[Track]) -> Result<usize> {
+        let now = Utc::now().timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let start: i64 = tx
+            .query_row(
+                "SELECT COALESCE(MAX(position), -1) FROM playlist_tracks WHERE playlist_id = ?1",
+                params![playlist_id],
+                |row| row.get(0),
+            )
+            .unwrap_or(-1)
+            + 1;
+
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO playlist_tracks 
+                 (playlist_id, track_source, track_id, position, added_at, title, artist, album, duration_ms, image_url)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for (offset, track) in tracks.iter().enumerate() {
+                stmt.execute(params![
+                    playlist_id,
+                    track.source.to_string(),
+                    track.id,
+                    start + offset as i64,
+                    now,
+                    track.title,
+                    track.artist,
+                    track.album,
+                    track.duration_ms as i64,
+                    track.image_url
+                ])?;
+            }
+        }
+
+        tx.execute(
+            "UPDATE custom_playlists SET track_count = track_count + ?1, updated_at = ?2 WHERE id = ?3",
+            params![tracks.len() as i64, now, playlist_id],
+        )?;
+        tx.commit()?;
+
+        Ok(tracks.len())
+    }
+
     pub fn get_playlist_tracks(&self, playlist_id: &str) -> Result<Vec<PlaylistTrack>> {
-        let mut stmt = self.conn.prepare(
+        self.query_playlist_tracks(playlist_id, "position ASC")
+    }
+
+    /// Get a playlist's tracks ordered by the sort column in `prefs`.
+    ///
+    /// Unknown or missing sort columns fall back to playlist position; ties are
+    /// broken by position so the order is stable.
+    pub fn get_playlist_tracks_sorted(
+        &self,
+        playlist_id: &str,
+        prefs: &ColumnPreferences,
+    ) -> Result<Vec<PlaylistTrack>> {
+        // Only whitelisted column expressions are ever interpolated into the query
+        let column = match prefs.sort_by.as_deref() {
+            Some("title") => "title COLLATE NOCASE",
+            Some("artist") => "artist COLLATE NOCASE",
+            Some("album") => "album COLLATE NOCASE",
+            Some("duration") => "duration_ms",
+            _ => "position",
+        };
+        let direction = if prefs.sort_desc { "DESC" } else { "ASC" };
+
+        self.query_playlist_tracks(
+            playlist_id,
+            &format!("{} {}, position ASC", column, direction),
+        )
+    }
+
+    fn query_playlist_tracks(
+        &self,
+        playlist_id: &str,
+        order_by: &str,
+    ) -> Result<Vec<PlaylistTrack>> {
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT id, playlist_id, track_source, track_id, position, added_at, 
                     title, artist, album, duration_ms, image_url
              FROM playlist_tracks 
              WHERE playlist_id = ?1 
-             ORDER BY position ASC",
-        )?;
+             ORDER BY {}",
+            order_by
+        ))?;
 
         let tracks = stmt
             .query_map(params![playlist_id], |row| {
@@ -431,7 +714,8 @@ impl Database {
         Ok(tracks)
     }
 
-    pub fn remove_track_from_playlist(&self, track_id: i64) -> Result<()> {
+    /// Remove a track, returning the ID of the playlist it was in
+    pub fn remove_track_from_playlist(&self, track_id: i64) -> Result<String> {
         let now = Utc::now().timestamp();
 
         // Get playlist_id and position before deleting
@@ -460,6 +744,59 @@ impl Database {
             params![now, playlist_id],
         )?;
 
+        Ok(playlist_id)
+    }
+
+    /// Move a track to the end of another playlist, closing the gap it leaves
+    /// behind. Both playlists' counts are updated in the same transaction.
+    pub fn move_track(&self, track_id: i64, from_playlist: &str, to_playlist: &str) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let position: i64 = tx
+            .query_row(
+                "SELECT position FROM playlist_tracks WHERE id = ?1 AND playlist_id = ?2",
+                params![track_id, from_playlist],
+                |row| row.get(0),
+            )
+            .optional()?
+            .with_context(|| format!("Track {} is not in playlist {}", track_id, from_playlist))?;
+
+        if from_playlist == to_playlist {
+            return Ok(());
+        }
+
+        let new_position: i64 = tx.query_row(
+            "SELECT COALESCE(MAX(position), -1) + 1 FROM playlist_tracks WHERE playlist_id = ?1",
+            params![to_playlist],
+            |row| row.get(0),
+        )?;
+
+        tx.execute(
+            "UPDATE playlist_tracks SET playlist_id = ?1, position = ?2, added_at = ?3 WHERE id = ?4",
+            params![to_playlist, new_position, now, track_id],
+        )?;
+
+        // Close the gap in the source playlist
+        tx.execute(
+            "UPDATE playlist_tracks SET position = position - 1 
+             WHERE playlist_id = ?1 AND position > ?2",
+            params![from_playlist, position],
+        )?;
+
+        tx.execute(
+            "UPDATE custom_playlists SET track_count = track_count - 1, updated_at = ?1 WHERE id = ?2",
+            params![now, from_playlist],
+        )?;
+        let updated = tx.execute(
+            "UPDATE custom_playlists SET track_count = track_count + 1, updated_at = ?1 WHERE id = ?2",
+            params![now, to_playlist],
+        )?;
+        if updated == 0 {
+            bail!("Playlist not found: {}", to_playlist);
+        }
+
+        tx.commit()?;
         Ok(())
     }
 
@@ -555,6 +892,7 @@ impl Database {
             source_playlist_id: source_playlist_id.to_string(),
             position,
             added_at: now,
+            available: true,
         })
     }
 
@@ -563,7 +901,7 @@ impl Database {
         union_playlist_id: &str,
     ) -> Result<Vec<UnionPlaylistSource>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, union_playlist_id, source_type, source_playlist_id, position, added_at
+            "SELECT id, union_playlist_id, source_type, source_playlist_id, position, added_at, available
              FROM union_playlist_sources 
              WHERE union_playlist_id = ?1 
              ORDER BY position ASC",
@@ -578,6 +916,7 @@ impl Database {
                     source_playlist_id: row.get(3)?,
                     position: row.get(4)?,
                     added_at: row.get(5)?,
+                    available: row.get(6)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -585,6 +924,32 @@ impl Database {
         Ok(sources)
     }
 
+    /// Mark every union source from `source_type` as unavailable, returning
+    /// the union playlists that use one
+    pub fn mark_union_sources_unavailable(&self, source_type: &str) -> Result<Vec<String>> {
+        self.conn.execute(
+            "UPDATE union_playlist_sources SET available = 0 WHERE source_type = ?1",
+            params![source_type],
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT union_playlist_id FROM union_playlist_sources WHERE source_type = ?1",
+        )?;
+        let ids = stmt
+            .query_map(params![source_type], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Record whether a union source could be loaded
+    pub fn set_union_source_available(&self, source_id: i64, available: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE union_playlist_sources SET available = ?1 WHERE id = ?2",
+            params![available, source_id],
+        )?;
+        Ok(())
+    }
+
     pub fn remove_source_from_union_playlist(&self, source_id: i64) -> Result<()> {
         let now = Utc::now().timestamp();
 
@@ -667,26 +1032,189 @@ impl Database {
         Ok(())
     }
 
+    // Offline Downloads
+
+    pub fn add_download(
+        &self,
+        track_source: &str,
+        track_id: &str,
+        local_path: &str,
+        size_bytes: i64,
+    ) -> Result<DownloadedTrack> {
+        let now = Utc::now().timestamp();
+
+        self.conn.execute(
+            "INSERT OR REPLACE INTO downloads (track_source, track_id, local_path, size_bytes, downloaded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![track_source, track_id, local_path, size_bytes, now],
+        )?;
+
+        Ok(DownloadedTrack {
+            track_source: track_source.to_string(),
+            track_id: track_id.to_string(),
+            local_path: local_path.to_string(),
+            size_bytes,
+            downloaded_at: now,
+        })
+    }
+
+    pub fn get_download(
+        &self,
+        track_source: &str,
+        track_id: &str,
+    ) -> Result<Option<DownloadedTrack>> {
+        let download = self
+            .conn
+            .query_row(
+                "SELECT track_source, track_id, local_path, size_bytes, downloaded_at
+                 FROM downloads WHERE track_source = ?1 AND track_id = ?2",
+                params![track_source, track_id],
+                |row| {
+                    Ok(DownloadedTrack {
+                        track_source: row.get(0)?,
+                        track_id: row.get(1)?,
+                        local_path: row.get(2)?,
+                        size_bytes: row.get(3)?,
+                        downloaded_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(download)
+    }
+
+    pub fn get_all_downloads(&self) -> Result<Vec<DownloadedTrack>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT track_source, track_id, local_path, size_bytes, downloaded_at
+             FROM downloads
+             ORDER BY downloaded_at DESC",
+        )?;
+
+        let downloads = stmt
+            .query_map([], |row| {
+                Ok(DownloadedTrack {
+                    track_source: row.get(0)?,
+                    track_id: row.get(1)?,
+                    local_path: row.get(2)?,
+                    size_bytes: row.get(3)?,
+                    downloaded_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(downloads)
+    }
+
+    /// Remove a download from the index, returning it so the caller can delete the file
+    pub fn remove_download(
+        &self,
+        track_source: &str,
+        track_id: &str,
+    ) -> Result<Option<DownloadedTrack>> {
+        let download = self.get_download(track_source, track_id)?;
+        if download.is_some() {
+            self.conn.execute(
+                "DELETE FROM downloads WHERE track_source = ?1 AND track_id = ?2",
+                params![track_source, track_id],
+            )?;
+        }
+        Ok(download)
+    }
+
+    /// Total size in bytes of all downloaded tracks
+    pub fn total_download_size(&self) -> Result<i64> {
+        let size = self.conn.query_row(
+            "SELECT COALESCE(SUM(size_bytes), 0) FROM downloads",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(size)
+    }
+
+    // Track Gain
+
+    /// Store a manual gain offset in dB for a track; `None` or 0 dB clears it
+    pub fn set_track_gain(
+        &self,
+        track_source: &str,
+        track_id: &str,
+        gain_db: Option<f64>,
+    ) -> Result<()> {
+        match gain_db.filter(|gain| *gain != 0.0) {
+            Some(gain) => {
+                self.conn.execute(
+                    "INSERT OR REPLACE INTO track_gain (track_source, track_id, gain_db)
+                     VALUES (?1, ?2, ?3)",
+                    params![track_source, track_id, gain],
+                )?;
+            }
+            None => {
+                self.conn.execute(
+                    "DELETE FROM track_gain WHERE track_source = ?1 AND track_id = ?2",
+                    params![track_source, track_id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_track_gain(&self, track_source: &str, track_id: &str) -> Result<Option<f64>> {
+        let gain = self
+            .conn
+            .query_row(
+                "SELECT gain_db FROM track_gain WHERE track_source = ?1 AND track_id = ?2",
+                params![track_source, track_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(gain)
+    }
+
+    pub fn get_stats(&self) -> Result<LibraryStats> {
+        let stats = self.conn.query_row(
+            "SELECT
+                (SELECT COUNT(*) FROM custom_playlists
+                 WHERE COALESCE(playlist_type, 'standard') != 'union'),
+                (SELECT COUNT(*) FROM custom_playlists WHERE playlist_type = 'union'),
+                (SELECT COUNT(*) FROM (SELECT DISTINCT track_source, track_id FROM playlist_tracks)),
+                (SELECT COUNT(DISTINCT artist) FROM playlist_tracks)",
+            [],
+            |row| {
+                Ok(LibraryStats {
+                    playlist_count: row.get(0)?,
+                    union_playlist_count: row.get(1)?,
+                    track_count: row.get(2)?,
+                    artist_count: row.get(3)?,
+                })
+            },
+        )?;
+        Ok(stats)
+    }
+
     // Column Preferences
 
     pub fn get_column_preferences(&self) -> Result<ColumnPreferences> {
         let result = self
             .conn
             .query_row(
-                "SELECT columns, column_order, column_widths FROM column_preferences WHERE id = 1",
+                "SELECT columns, column_order, column_widths, sort_by, sort_desc
+                 FROM column_preferences WHERE id = 1",
                 [],
                 |row| {
                     let columns_json: String = row.get(0)?;
                     let order_json: String = row.get(1)?;
                     let widths_json: Option<String> = row.get(2)?;
+                    let sort_by: Option<String> = row.get(3)?;
+                    let sort_desc: Option<bool> = row.get(4)?;
 
-                    Ok((columns_json, order_json, widths_json))
+                    Ok((columns_json, order_json, widths_json, sort_by, sort_desc))
                 },
             )
             .optional()?;
 
         match result {
-            Some((columns_json, order_json, widths_json)) => {
+            Some((columns_json, order_json, widths_json, sort_by, sort_desc)) => {
                 let columns: Vec<String> = serde_json::from_str(&columns_json)?;
                 let column_order: Vec<usize> = serde_json::from_str(&order_json)?;
                 let column_widths: std::collections::HashMap<String, i64> = widths_json
@@ -697,6 +1225,8 @@ impl Database {
                     columns,
                     column_order,
                     column_widths,
+                    sort_by,
+                    sort_desc: sort_desc.unwrap_or(false),
                 })
             }
             None => Ok(ColumnPreferences::default()),
@@ -704,14 +1234,22 @@ impl Database {
     }
 
     pub fn save_column_preferences(&self, prefs: &ColumnPreferences) -> Result<()> {
+        prefs.validate()?;
+
         let columns_json = serde_json::to_string(&prefs.columns)?;
         let order_json = serde_json::to_string(&prefs.column_order)?;
         let widths_json = serde_json::to_string(&prefs.column_widths)?;
 
         self.conn.execute(
-            "INSERT OR REPLACE INTO column_preferences (id, columns, column_order, column_widths)
-             VALUES (1, ?1, ?2, ?3)",
-            params![columns_json, order_json, widths_json],
+            "INSERT OR REPLACE INTO column_preferences (id, columns, column_order, column_widths, sort_by, sort_desc)
+             VALUES (1, ?1, ?2, ?3, ?4, ?5)",
+            params![
+                columns_json,
+                order_json,
+                widths_json,
+                prefs.sort_by,
+                prefs.sort_desc
+            ],
         )?;
 
         Ok(())
@@ -756,11 +1294,17 @@ mod tests {
             title: "Song 1".to_string(),
             artist: "Artist 1".to_string(),
             album: "Album 1".to_string(),
+            album_artist: None,
             duration_ms: 180000,
             image_url: None,
             source: Source::Spotify,
             url: None,
             auth_headers: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at: None,
         };
 
         db.add_track_to_playlist(&playlist.id, &track).unwrap();
@@ -784,11 +1328,17 @@ mod tests {
                 title: format!("Song {}", i),
                 artist: "Artist".to_string(),
                 album: "Album".to_string(),
+                album_artist: None,
                 duration_ms: 180000,
                 image_url: None,
                 source: Source::Spotify,
                 url: None,
                 auth_headers: None,
+                genre: None,
+                year: None,
+                track_number: None,
+                disc_number: None,
+                enriched_at: None,
             };
             db.add_track_to_playlist(&playlist.id, &track).unwrap();
         }
@@ -803,4 +1353,365 @@ mod tests {
         assert_eq!(reordered[2].title, "Song 0");
         assert_eq!(reordered[0].title, "Song 1");
     }
+
+    #[test]
+    fn test_snapshot_provider_playlist() {
+        let db = create_test_db();
+
+        let tracks = (0..3)
+            .map(|i| Track {
+                id: format!("jf{}", i),
+                title: format!("Song {}", i),
+                artist: "Artist".to_string(),
+                album: "Album".to_string(),
+                album_artist: None,
+                duration_ms: 200000,
+                image_url: Some("http://example.com/cover.jpg".to_string()),
+                source: Source::Jellyfin,
+                url: None,
+                auth_headers: None,
+                genre: None,
+                year: None,
+                track_number: None,
+                disc_number: None,
+                enriched_at: None,
+            })
+            .collect::<Vec<_>>();
+        let playlist = Playlist {
+            id: "remote-playlist".to_string(),
+            name: "Remote".to_string(),
+            description: Some("From the server".to_string()),
+            owner: "someone".to_string(),
+            image_url: None,
+            track_count: tracks.len(),
+            tracks,
+            source: Source::Jellyfin,
+            revision: None,
+        };
+
+        let snapshot = db
+            .snapshot_playlist("Remote (copy)".to_string(), &playlist)
+            .unwrap();
+        assert_eq!(snapshot.track_count, 3);
+        assert_eq!(snapshot.playlist_type, "standard");
+
+        let stored = db.get_playlist(&snapshot.id).unwrap().unwrap();
+        assert_eq!(stored.track_count, 3);
+        assert_eq!(stored.origin_source.as_deref(), Some("jellyfin"));
+        assert_eq!(
+            stored.origin_playlist_id.as_deref(),
+            Some("remote-playlist")
+        );
+
+        let stored_tracks = db.get_playlist_tracks(&snapshot.id).unwrap();
+        assert_eq!(stored_tracks.len(), 3);
+        assert_eq!(stored_tracks[2].track_id, "jf2");
+        assert_eq!(stored_tracks[2].position, 2);
+        assert_eq!(stored_tracks[0].to_track().source, Source::Jellyfin);
+    }
+
+    #[test]
+    fn test_move_track_between_playlists() {
+        let db = create_test_db();
+
+        let from = db.create_playlist("From".to_string(), None, None).unwrap();
+        let to = db.create_playlist("To".to_string(), None, None).unwrap();
+
+        let make_track = |id: &str| Track {
+            id: id.to_string(),
+            title: format!("Song {}", id),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            album_artist: None,
+            duration_ms: 180000,
+            image_url: None,
+            source: Source::Spotify,
+            url: None,
+            auth_headers: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at: None,
+        };
+
+        db.add_tracks_to_playlist(
+            &from.id,
+            &[make_track("a"), make_track("b"), make_track("c")],
+        )
+        .unwrap();
+        db.add_track_to_playlist(&to.id, &make_track("x")).unwrap();
+
+        let moved = db.get_playlist_tracks(&from.id).unwrap()[1].id;
+        db.move_track(moved, &from.id, &to.id).unwrap();
+
+        let from_tracks = db.get_playlist_tracks(&from.id).unwrap();
+        let ids: Vec<_> = from_tracks.iter().map(|t| t.track_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+        let positions: Vec<_> = from_tracks.iter().map(|t| t.position).collect();
+        assert_eq!(positions, vec![0, 1]);
+
+        let to_tracks = db.get_playlist_tracks(&to.id).unwrap();
+        let ids: Vec<_> = to_tracks.iter().map(|t| t.track_id.as_str()).collect();
+        assert_eq!(ids, vec!["x", "b"]);
+        assert_eq!(to_tracks[1].position, 1);
+
+        assert_eq!(db.get_playlist(&from.id).unwrap().unwrap().track_count, 2);
+        assert_eq!(db.get_playlist(&to.id).unwrap().unwrap().track_count, 2);
+
+        // A track that isn't in the source playlist is rejected without changes
+        assert!(db.move_track(moved, &from.id, &to.id).is_err());
+        assert_eq!(db.get_playlist(&to.id).unwrap().unwrap().track_count, 2);
+    }
+
+    #[test]
+    fn test_download_index_crud() {
+        let db = create_test_db();
+
+        assert!(db.get_download("jellyfin", "track1").unwrap().is_none());
+        assert_eq!(db.total_download_size().unwrap(), 0);
+
+        db.add_download("jellyfin", "track1", "/downloads/jellyfin-track1", 1000)
+            .unwrap();
+        db.add_download("spotify", "track1", "/downloads/spotify-track1", 500)
+            .unwrap();
+
+        let download = db.get_download("jellyfin", "track1").unwrap().unwrap();
+        assert_eq!(download.local_path, "/downloads/jellyfin-track1");
+        assert_eq!(download.size_bytes, 1000);
+        assert_eq!(db.get_all_downloads().unwrap().len(), 2);
+        assert_eq!(db.total_download_size().unwrap(), 1500);
+
+        // Re-downloading replaces the existing entry
+        db.add_download("jellyfin", "track1", "/downloads/jellyfin-track1", 1200)
+            .unwrap();
+        assert_eq!(db.get_all_downloads().unwrap().len(), 2);
+        assert_eq!(db.total_download_size().unwrap(), 1700);
+
+        let removed = db.remove_download("jellyfin", "track1").unwrap().unwrap();
+        assert_eq!(removed.size_bytes, 1200);
+        assert!(db.get_download("jellyfin", "track1").unwrap().is_none());
+        assert!(db.remove_download("jellyfin", "track1").unwrap().is_none());
+        assert_eq!(db.total_download_size().unwrap(), 500);
+    }
+
+    #[test]
+    fn test_track_gain_crud() {
+        let db = create_test_db();
+
+        assert_eq!(db.get_track_gain("jellyfin", "track1").unwrap(), None);
+
+        db.set_track_gain("jellyfin", "track1", Some(-3.5)).unwrap();
+        db.set_track_gain("spotify", "track1", Some(2.0)).unwrap();
+        assert_eq!(db.get_track_gain("jellyfin", "track1").unwrap(), Some(-3.5));
+        assert_eq!(db.get_track_gain("spotify", "track1").unwrap(), Some(2.0));
+
+        // Setting again replaces the stored offset
+        db.set_track_gain("jellyfin", "track1", Some(1.5)).unwrap();
+        assert_eq!(db.get_track_gain("jellyfin", "track1").unwrap(), Some(1.5));
+
+        // Zero and None both clear it
+        db.set_track_gain("jellyfin", "track1", Some(0.0)).unwrap();
+        assert_eq!(db.get_track_gain("jellyfin", "track1").unwrap(), None);
+        db.set_track_gain("spotify", "track1", None).unwrap();
+        assert_eq!(db.get_track_gain("spotify", "track1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_library_stats() {
+        let db = create_test_db();
+        assert_eq!(
+            db.get_stats().unwrap(),
+            LibraryStats {
+                playlist_count: 0,
+                union_playlist_count: 0,
+                track_count: 0,
+                artist_count: 0,
+            }
+        );
+
+        let first = db.create_playlist("First".to_string(), None, None).unwrap();
+        let second = db
+            .create_playlist("Second".to_string(), None, None)
+            .unwrap();
+        db.create_playlist_with_type("Union".to_string(), None, None, "union".to_string())
+            .unwrap();
+
+        for (playlist, id, artist) in [
+            (&first, "a", "Artist 1"),
+            (&first, "b", "Artist 1"),
+            (&first, "c", "Artist 2"),
+            // Already in the first playlist, so not a new track
+            (&second, "a", "Artist 1"),
+            (&second, "d", "Artist 3"),
+        ] {
+            let track = Track {
+                id: id.to_string(),
+                title: format!("Song {}", id),
+                artist: artist.to_string(),
+                album: "Album".to_string(),
+                album_artist: None,
+                duration_ms: 200000,
+                image_url: None,
+                source: Source::Jellyfin,
+                url: None,
+                auth_headers: None,
+                genre: None,
+                year: None,
+                track_number: None,
+                disc_number: None,
+                enriched_at: None,
+            };
+            db.add_track_to_playlist(&playlist.id, &track).unwrap();
+        }
+
+        assert_eq!(
+            db.get_stats().unwrap(),
+            LibraryStats {
+                playlist_count: 2,
+                union_playlist_count: 1,
+                track_count: 4,
+                artist_count: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_sorted_playlist_tracks() {
+        let db = create_test_db();
+        let playlist = db.create_playlist("Test".to_string(), None, None).unwrap();
+
+        for (title, artist, duration_ms) in [
+            ("Song A", "charlie", 200000),
+            ("Song B", "Alice", 100000),
+            ("Song C", "bob", 300000),
+        ] {
+            let track = Track {
+                id: title.to_string(),
+                title: title.to_string(),
+                artist: artist.to_string(),
+                album: "Album".to_string(),
+                album_artist: None,
+                duration_ms,
+                image_url: None,
+                source: Source::Jellyfin,
+                url: None,
+                auth_headers: None,
+                genre: None,
+                year: None,
+                track_number: None,
+                disc_number: None,
+                enriched_at: None,
+            };
+            db.add_track_to_playlist(&playlist.id, &track).unwrap();
+        }
+
+        let titles = |prefs: &ColumnPreferences| -> Vec<String> {
+            db.get_playlist_tracks_sorted(&playlist.id, prefs)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.title)
+                .collect()
+        };
+
+        // Artist ascending, case-insensitive
+        let mut prefs = ColumnPreferences {
+            sort_by: Some("artist".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(titles(&prefs), ["Song B", "Song C", "Song A"]);
+
+        // Duration descending
+        prefs.sort_by = Some("duration".to_string());
+        prefs.sort_desc = true;
+        assert_eq!(titles(&prefs), ["Song C", "Song A", "Song B"]);
+
+        // Unknown columns keep playlist order
+        prefs.sort_by = Some("bogus; DROP TABLE playlist_tracks".to_string());
+        prefs.sort_desc = false;
+        assert_eq!(titles(&prefs), ["Song A", "Song B", "Song C"]);
+    }
+
+    #[test]
+    fn test_column_sort_preferences_persist() {
+        let db = create_test_db();
+        assert!(db.get_column_preferences().unwrap().sort_by.is_none());
+
+        let prefs = ColumnPreferences {
+            sort_by: Some("title".to_string()),
+            sort_desc: true,
+            ..Default::default()
+        };
+        db.save_column_preferences(&prefs).unwrap();
+
+        let loaded = db.get_column_preferences().unwrap();
+        assert_eq!(loaded.sort_by.as_deref(), Some("title"));
+        assert!(loaded.sort_desc);
+    }
+
+    #[test]
+    fn test_column_preferences_validation() {
+        let db = create_test_db();
+
+        let valid = ColumnPreferences {
+            columns: vec!["title".to_string(), "genre".to_string(), "year".to_string()],
+            column_order: vec![2, 0, 1],
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+        db.save_column_preferences(&valid).unwrap();
+        assert_eq!(db.get_column_preferences().unwrap().column_order, [2, 0, 1]);
+
+        let mismatched_order = ColumnPreferences {
+            columns: vec!["title".to_string(), "artist".to_string()],
+            column_order: vec![0, 2],
+            ..Default::default()
+        };
+        assert!(db.save_column_preferences(&mismatched_order).is_err());
+
+        let repeated_index = ColumnPreferences {
+            columns: vec!["title".to_string(), "artist".to_string()],
+            column_order: vec![0, 0],
+            ..Default::default()
+        };
+        assert!(repeated_index.validate().is_err());
+
+        let typo = ColumnPreferences {
+            columns: vec!["titel".to_string()],
+            column_order: vec![0],
+            ..Default::default()
+        };
+        assert!(typo.validate().is_err());
+
+        // Rejected payloads leave the saved preferences untouched
+        assert_eq!(db.get_column_preferences().unwrap().column_order, [2, 0, 1]);
+    }
+
+    #[test]
+    fn test_union_sources_marked_unavailable_by_provider() {
+        let db = create_test_db();
+        let union = db
+            .create_playlist_with_type("Mix".to_string(), None, None, "union".to_string())
+            .unwrap();
+        let other = db
+            .create_playlist_with_type("Other".to_string(), None, None, "union".to_string())
+            .unwrap();
+        let spotify = db
+            .add_source_to_union_playlist(&union.id, "spotify", "sp1")
+            .unwrap();
+        db.add_source_to_union_playlist(&union.id, "jellyfin", "jf1")
+            .unwrap();
+        db.add_source_to_union_playlist(&other.id, "jellyfin", "jf2")
+            .unwrap();
+        assert!(spotify.available);
+
+        let affected = db.mark_union_sources_unavailable("spotify").unwrap();
+        assert_eq!(affected, vec![union.id.clone()]);
+        let sources = db.get_union_playlist_sources(&union.id).unwrap();
+        assert!(!sources[0].available);
+        assert!(sources[1].available);
+
+        db.set_union_source_available(spotify.id, true).unwrap();
+        assert!(db.get_union_playlist_sources(&union.id).unwrap()[0].available);
+    }
 }
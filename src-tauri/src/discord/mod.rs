@@ -0,0 +1,207 @@
+/// Discord Rich Presence integration showing the current track on the
+/// user's Discord profile
+use crate::models::{PlaybackInfo, PlaybackState};
+use discord_rich_presence::{activity, DiscordIpc, DiscordIpcClient};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+/// Tolerance before a drifting start timestamp is pushed again, so the
+/// once-a-second position events don't hammer the Discord IPC socket
+const START_DRIFT_TOLERANCE_SECS: i64 = 2;
+
+/// What we show on Discord for the current track
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresencePayload {
+    /// Top line - the track title
+    pub details: String,
+    /// Second line - "by <artist>"
+    pub state: String,
+    /// Unix timestamp (seconds) the track started, so Discord shows elapsed time
+    pub start_timestamp: i64,
+}
+
+impl PresencePayload {
+    /// Map a playback snapshot to a presence payload.
+    ///
+    /// Returns `None` when nothing is playing, meaning presence should be cleared.
+    pub fn from_info(info: &PlaybackInfo, now_unix_secs: i64) -> Option<Self> {
+        if info.state != PlaybackState::Playing {
+            return None;
+        }
+
+        info.current_track.as_ref().map(|track| Self {
+            details: track.title.clone(),
+            state: format!("by {}", track.artist),
+            start_timestamp: now_unix_secs - (info.position_ms / 1000) as i64,
+        })
+    }
+
+    /// Whether this payload differs enough from `other` to be worth sending
+    fn differs_from(&self, other: &Self) -> bool {
+        self.details != other.details
+            || self.state != other.state
+            || (self.start_timestamp - other.start_timestamp).abs() > START_DRIFT_TOLERANCE_SECS
+    }
+}
+
+/// Handle for pushing playback updates to the presence thread
+pub struct DiscordPresenceHandle {
+    update_tx: mpsc::UnboundedSender<PlaybackInfo>,
+}
+
+impl DiscordPresenceHandle {
+    /// Publish a playback snapshot
+    pub fn update(&self, info: &PlaybackInfo) {
+        let _ = self.update_tx.send(info.clone());
+    }
+}
+
+/// Start the presence updater on its own thread (the IPC client is blocking)
+pub fn start(client_id: String) -> DiscordPresenceHandle {
+    let (update_tx, update_rx) = mpsc::unbounded_channel();
+
+    if let Err(e) = std::thread::Builder::new()
+        .name("discord-presence".to_string())
+        .spawn(move || run_presence(client_id, update_rx))
+    {
+        tracing::warn!("Failed to spawn Discord presence thread: {}", e);
+    }
+
+    DiscordPresenceHandle { update_tx }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn run_presence(client_id: String, mut update_rx: mpsc::UnboundedReceiver<PlaybackInfo>) {
+    let mut client: Option<DiscordIpcClient> = None;
+    let mut last_payload: Option<PresencePayload> = None;
+
+    while let Some(info) = update_rx.blocking_recv() {
+        let payload = PresencePayload::from_info(&info, unix_now());
+        let changed = match (&payload, &last_payload) {
+            (Some(new), Some(old)) => new.differs_from(old),
+            (None, None) => false,
+            _ => true,
+        };
+        if !changed {
+            continue;
+        }
+
+        // (Re)connect lazily - Discord may not be running, or may have restarted
+        if client.is_none() {
+            client = connect(&client_id);
+            if client.is_none() {
+                continue;
+            }
+        }
+
+        let Some(ipc) = client.as_mut() else {
+            continue;
+        };
+        let result = match &payload {
+            Some(payload) => ipc.set_activity(
+                activity::Activity::new()
+                    .details(&payload.details)
+                    .state(&payload.state)
+                    .timestamps(activity::Timestamps::new().start(payload.start_timestamp)),
+            ),
+            None => ipc.clear_activity(),
+        };
+
+        match result {
+            Ok(()) => last_payload = payload,
+            Err(e) => {
+                tracing::debug!("Failed to update Discord presence: {}", e);
+                client = None;
+            }
+        }
+    }
+}
+
+fn connect(client_id: &str) -> Option<DiscordIpcClient> {
+    let mut client = match DiscordIpcClient::new(client_id) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::debug!("Failed to create Discord IPC client: {}", e);
+            return None;
+        }
+    };
+
+    match client.connect() {
+        Ok(()) => {
+            tracing::info!("✓ Connected to Discord for Rich Presence");
+            Some(client)
+        }
+        Err(e) => {
+            tracing::debug!("Discord not available for Rich Presence: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Source, Track};
+
+    fn playing_info(position_ms: u64) -> PlaybackInfo {
+        PlaybackInfo {
+            current_track: Some(Track {
+                id: "1".to_string(),
+                title: "Song".to_string(),
+                artist: "Artist".to_string(),
+                album: "Album".to_string(),
+                album_artist: None,
+                duration_ms: 200_000,
+                image_url: None,
+                source: Source::Jellyfin,
+                url: None,
+                auth_headers: None,
+                genre: None,
+                year: None,
+                track_number: None,
+                disc_number: None,
+                enriched_at: None,
+            }),
+            state: PlaybackState::Playing,
+            position_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_payload_from_playing_info() {
+        let payload = PresencePayload::from_info(&playing_info(30_500), 1_000).unwrap();
+        assert_eq!(payload.details, "Song");
+        assert_eq!(payload.state, "by Artist");
+        assert_eq!(payload.start_timestamp, 970);
+    }
+
+    #[test]
+    fn test_payload_cleared_when_not_playing() {
+        let mut info = playing_info(0);
+        info.state = PlaybackState::Paused;
+        assert!(PresencePayload::from_info(&info, 1_000).is_none());
+
+        info.state = PlaybackState::Stopped;
+        assert!(PresencePayload::from_info(&info, 1_000).is_none());
+
+        assert!(PresencePayload::from_info(&PlaybackInfo::default(), 1_000).is_none());
+    }
+
+    #[test]
+    fn test_payload_ignores_small_drift() {
+        let a = PresencePayload::from_info(&playing_info(10_000), 1_000).unwrap();
+        let b = PresencePayload::from_info(&playing_info(11_000), 1_002).unwrap();
+        assert!(!b.differs_from(&a));
+
+        // A seek moves the start time well past the tolerance
+        let seeked = PresencePayload::from_info(&playing_info(90_000), 1_002).unwrap();
+        assert!(seeked.differs_from(&a));
+    }
+}
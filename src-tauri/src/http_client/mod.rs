@@ -0,0 +1,82 @@
+/// Shared HTTP clients with sane timeouts
+///
+/// Building a client per request throws away the connection pool, and the
+/// reqwest defaults never time out, so a stuck server would hang playback or
+/// the UI indefinitely.
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Time allowed to establish a TCP/TLS connection
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Overall timeout for API requests
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Overall timeout for audio downloads, which can be large lossless files
+pub const AUDIO_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Redirects followed when fetching audio, e.g. to a CDN
+pub const MAX_AUDIO_REDIRECTS: usize = 5;
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+static BLOCKING_CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+
+/// Build an async client with the given timeouts
+pub fn build_client(connect_timeout: Duration, timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(timeout)
+        .build()
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to build HTTP client, using defaults: {}", e);
+            reqwest::Client::new()
+        })
+}
+
+/// Shared async client for provider API calls.
+///
+/// `reqwest::Client` is reference counted, so clones share one connection pool.
+pub fn shared_client() -> reqwest::Client {
+    CLIENT
+        .get_or_init(|| build_client(CONNECT_TIMEOUT, REQUEST_TIMEOUT))
+        .clone()
+}
+
+/// Shared blocking client for audio playback threads
+pub fn shared_blocking_client() -> reqwest::blocking::Client {
+    BLOCKING_CLIENT
+        .get_or_init(|| {
+            reqwest::blocking::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .timeout(AUDIO_REQUEST_TIMEOUT)
+                .redirect(reqwest::redirect::Policy::limited(MAX_AUDIO_REDIRECTS))
+                .build()
+                .unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "Failed to build blocking HTTP client, using defaults: {}",
+                        e
+                    );
+                    reqwest::blocking::Client::new()
+                })
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_unroutable_request_times_out() {
+        let timeout = Duration::from_millis(500);
+        let client = build_client(timeout, timeout);
+
+        // 10.255.255.1 is non-routable, so the connection attempt never completes
+        let start = Instant::now();
+        let result = client.get("http://10.255.255.1/").send().await;
+
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -1,19 +1,29 @@
 /// Any Player - Multi-Source Music Client
+pub mod artwork;
 pub mod cache;
 pub mod config;
 pub mod database;
+pub mod discord;
+pub mod http_client;
+pub mod logging;
 pub mod models;
+#[cfg(all(target_os = "linux", feature = "mpris"))]
+pub mod mpris;
+pub mod notifications;
+pub mod oauth;
+pub mod offline;
 pub mod playback;
+pub mod playlist_refresh;
 pub mod providers;
 pub mod state;
+pub mod tray;
 
 pub use config::Config;
 pub use database::Database;
-pub use models::{PlaybackInfo, PlaybackState, Playlist, RepeatMode, Source, Track};
+pub use models::{PlaybackInfo, PlaybackState, Playlist, RepeatMode, Source, Track, Volume};
 pub use playback::PlaybackManager;
 pub use providers::{MusicProvider, ProviderError, ProviderRegistry};
 pub use state::PersistentPlaybackState;
-use tracing_subscriber::{filter, layer::SubscriberExt, util::SubscriberInitExt};
 
 mod commands;
 
@@ -21,166 +31,467 @@ mod commands;
 // Other modules (cache, playback, providers) share names with top-level modules
 pub use commands::{auth, custom_playlists};
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::{Emitter, Manager};
+use tauri::{Emitter, Manager, RunEvent, WindowEvent};
 use tokio::sync::Mutex;
 
+/// Bring the main window to the front, restoring it if minimized or hidden
+fn focus_main_window(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        tracing::warn!("No main window to focus");
+        return;
+    };
+    if let Err(e) = window
+        .unminimize()
+        .and_then(|_| window.show())
+        .and_then(|_| window.set_focus())
+    {
+        tracing::warn!("Failed to focus the main window: {}", e);
+    }
+}
+
+/// Declare the invoke handler and the list of command names from one list,
+/// so the test checking it against the command modules can't drift from it
+macro_rules! commands_handler {
+    ($($name:ident),* $(,)?) => {
+        /// Handler for every command the frontend can invoke
+        fn invoke_handler() -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+            tauri::generate_handler![$(commands::$name),*]
+        }
+
+        /// Names of the commands `invoke_handler` registers
+        #[cfg(test)]
+        const COMMAND_NAMES: &[&str] = &[$(stringify!($name)),*];
+    };
+}
+
+commands_handler![
+    // Playback commands
+    get_playback_status,
+    get_queue,
+    get_queue_stats,
+    get_now_playing,
+    play,
+    pause,
+    toggle_play_pause,
+    next_track,
+    previous_track,
+    seek,
+    set_volume,
+    set_track_gain,
+    get_track_gain,
+    toggle_shuffle,
+    reshuffle_queue,
+    set_shuffle_seed,
+    set_repeat_mode,
+    cycle_repeat_mode,
+    // Playlist commands
+    get_playlists,
+    get_playlist_tracks,
+    get_album,
+    get_artist_albums,
+    get_artist_top_tracks,
+    search_tracks,
+    search_tracks_debounced,
+    play_track,
+    queue_track,
+    clear_queue,
+    play_playlist,
+    play_tracks_immediate,
+    get_provider_capabilities,
+    get_rate_limit_status,
+    can_play_full,
+    // Spotify commands
+    get_spotify_auth_url,
+    authenticate_spotify,
+    is_spotify_authenticated,
+    check_spotify_premium,
+    check_spotify_connection,
+    initialize_spotify_session,
+    initialize_spotify_session_from_provider,
+    is_spotify_session_ready,
+    refresh_spotify_token,
+    get_spotify_playlists,
+    get_spotify_playlist,
+    get_spotify_liked_songs,
+    save_spotify_track,
+    remove_saved_spotify_track,
+    check_oauth_code,
+    disconnect_spotify,
+    restore_spotify_session,
+    clear_spotify_session,
+    // Jellyfin commands
+    authenticate_jellyfin,
+    is_jellyfin_authenticated,
+    check_jellyfin_connection,
+    get_jellyfin_playlists,
+    get_jellyfin_playlist,
+    search_jellyfin_tracks,
+    search_jellyfin_playlists,
+    get_jellyfin_recently_played,
+    set_jellyfin_favorite,
+    get_jellyfin_favorites,
+    disconnect_jellyfin,
+    get_jellyfin_credentials,
+    restore_jellyfin_session,
+    list_jellyfin_servers,
+    set_active_jellyfin_server,
+    // Search commands
+    search_spotify_tracks,
+    // Audio commands
+    get_audio_file,
+    // Diagnostics commands
+    get_log_directory,
+    open_log_directory,
+    // Offline download commands
+    download_track,
+    is_downloaded,
+    remove_download,
+    get_downloads,
+    get_download_size,
+    // Custom playlist commands
+    create_custom_playlist,
+    snapshot_provider_playlist,
+    push_custom_playlist_to_provider,
+    get_custom_playlists,
+    get_custom_playlist,
+    update_custom_playlist,
+    delete_custom_playlist,
+    add_track_to_custom_playlist,
+    get_custom_playlist_tracks,
+    get_custom_playlist_tracks_sorted,
+    remove_track_from_custom_playlist,
+    reorder_custom_playlist_tracks,
+    move_custom_playlist_track,
+    generate_playlist_cover,
+    get_library_stats,
+    get_column_preferences,
+    save_column_preferences,
+    get_available_columns,
+    // Union playlist commands
+    create_union_playlist,
+    add_source_to_union_playlist,
+    get_union_playlist_sources,
+    remove_source_from_union_playlist,
+    reorder_union_playlist_sources,
+    get_union_playlist_tracks,
+    // Cache commands
+    write_playlists_cache,
+    read_playlists_cache,
+    clear_playlists_cache,
+    write_custom_playlists_cache,
+    read_custom_playlists_cache,
+    clear_custom_playlists_cache,
+    write_custom_playlist_tracks_cache,
+    read_custom_playlist_tracks_cache,
+    clear_custom_playlist_tracks_cache,
+    write_union_playlist_tracks_cache,
+    read_union_playlist_tracks_cache,
+    clear_union_playlist_tracks_cache,
+    // Playback state commands
+    save_playback_state,
+    restore_playback_state,
+];
+
+/// Report an error that stops the app from starting. Logged too, since the
+/// dialog can't be shown on a headless system.
+fn show_startup_error(message: &str) {
+    tracing::error!("{}", message);
+    eprintln!("{}", message);
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("Any Player failed to start")
+        .set_description(message)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
+
+/// Playlist database file in the configured data directory, or the platform
+/// data directory when none is set
+fn database_path() -> Option<std::path::PathBuf> {
+    crate::config::configured_data_dir()
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("any-player")))
+        .map(|dir| dir.join("playlists.db"))
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logging
-    let filter = filter::Targets::new()
-        .with_default(filter::LevelFilter::TRACE)
-        .with_target("any_player_lib", filter::LevelFilter::TRACE)
-        .with_target("glycin", filter::LevelFilter::INFO)
-        .with_target("hyper", filter::LevelFilter::INFO)
-        .with_target("zbus", filter::LevelFilter::INFO);
-    tracing_subscriber::registry()
-        .with(filter)
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Load user configuration first since it controls logging,
+    // falling back to defaults if it can't be read
+    let (config, config_error) = match Config::load() {
+        Ok(config) => (config, None),
+        Err(e) => (Config::default(), Some(e.to_string())),
+    };
+
+    // The data directory decides where logs go, so it's set first
+    crate::config::set_data_dir(config.general.data_dir.clone().map(Into::into));
+
+    // Initialize logging; the guard flushes the log file when dropped at exit
+    let _log_guard = logging::init(&config.general);
+    if let Some(e) = config_error {
+        tracing::warn!("Failed to load config, using defaults: {}", e);
+    }
+    crate::config::set_client_identity(crate::config::ClientIdentity::from_config(&config.general));
 
     // Initialize database with graceful error handling
-    let db_path = match dirs::data_dir() {
-        Some(dir) => dir.join("any-player").join("playlists.db"),
+    let db_path = match database_path() {
+        Some(path) => path,
         None => {
             eprintln!("Failed to get data directory. Using current directory.");
             std::path::PathBuf::from("playlists.db")
         }
     };
 
-    if let Some(parent) = db_path.parent() {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            eprintln!("Failed to create data directory: {}", e);
-            std::process::exit(1);
-        }
-    }
-
-    let database = match Database::new(db_path.clone()) {
+    let database = match Database::open(db_path.clone()) {
         Ok(db) => Arc::new(Mutex::new(db)),
         Err(e) => {
-            eprintln!("Failed to initialize database at {:?}: {}", db_path, e);
-            eprintln!("Please check file permissions and disk space.");
+            show_startup_error(&format!(
+                "Failed to open the playlist database at {}:\n\n{:#}\n\n\
+                 Please check file permissions and disk space.",
+                db_path.display(),
+                e
+            ));
             std::process::exit(1);
         }
     };
 
     // Create application state
-    let providers = Arc::new(Mutex::new(ProviderRegistry::new()));
+    let mut registry = ProviderRegistry::new();
+    registry.set_requests_per_second(config.general.provider_requests_per_second);
+    let rate_limiters = registry.rate_limiters();
+    let providers = Arc::new(Mutex::new(registry));
     let oauth_code: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let oauth_state: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    // The OAuth callback server is started on demand when an auth URL is requested
+    let oauth_server = Arc::new(oauth::OAuthServer::new(
+        config.general.oauth_port,
+        oauth_code.clone(),
+        oauth_state.clone(),
+    ));
 
     let providers_clone = providers.clone();
     let oauth_code_for_server = oauth_code.clone();
     let database_clone = database.clone();
     let providers_for_state = providers.clone();
+    let notify_on_track_change = config.general.notify_on_track_change;
+    let autosave_interval = config.general.autosave_interval();
+    let playlist_refresh_interval = config.general.playlist_refresh_interval();
+    let previous_restart_threshold =
+        std::time::Duration::from_millis(config.general.previous_restart_threshold_ms);
+    let fade_duration = std::time::Duration::from_millis(config.general.fade_duration_ms);
+    let enrich_lookahead = config.general.enrich_lookahead;
+    let default_volume = config.general.initial_volume();
+    let enrich_delay = std::time::Duration::from_millis(config.general.enrich_delay_ms);
+    let volume_normalization = config
+        .general
+        .normalize_volume
+        .then_some(config.general.normalization_target_lufs);
+    let discord_client_id = config
+        .general
+        .discord_presence
+        .then(|| config.general.discord_client_id.clone())
+        .flatten();
+    if config.general.discord_presence && discord_client_id.is_none() {
+        tracing::warn!("Discord presence is enabled but no discord_client_id is configured");
+    }
+    let minimize_to_tray = Arc::new(AtomicBool::new(config.general.minimize_to_tray));
+    let minimize_to_tray_for_config = minimize_to_tray.clone();
 
     tauri::Builder::default()
+        // Must be registered first: a second launch would start another
+        // backend that can't bind the OAuth port, so it hands off to the
+        // running instance and exits instead
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            tracing::info!("Another launch detected, focusing the existing window");
+            focus_main_window(app);
+        }))
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![
-            // Playback commands
-            commands::get_playback_status,
-            commands::play,
-            commands::pause,
-            commands::toggle_play_pause,
-            commands::next_track,
-            commands::previous_track,
-            commands::seek,
-            commands::set_volume,
-            commands::toggle_shuffle,
-            commands::set_repeat_mode,
-            // Playlist commands
-            commands::get_playlists,
-            commands::play_track,
-            commands::queue_track,
-            commands::clear_queue,
-            commands::play_playlist,
-            commands::play_tracks_immediate,
-            // Spotify commands
-            commands::get_spotify_auth_url,
-            commands::authenticate_spotify,
-            commands::is_spotify_authenticated,
-            commands::check_spotify_premium,
-            commands::initialize_spotify_session,
-            commands::initialize_spotify_session_from_provider,
-            commands::is_spotify_session_ready,
-            commands::refresh_spotify_token,
-            commands::get_spotify_playlists,
-            commands::get_spotify_playlist,
-            commands::check_oauth_code,
-            commands::disconnect_spotify,
-            commands::restore_spotify_session,
-            commands::clear_spotify_session,
-            // Jellyfin commands
-            commands::authenticate_jellyfin,
-            commands::is_jellyfin_authenticated,
-            commands::get_jellyfin_playlists,
-            commands::get_jellyfin_playlist,
-            commands::search_jellyfin_tracks,
-            commands::search_jellyfin_playlists,
-            commands::get_jellyfin_recently_played,
-            commands::disconnect_jellyfin,
-            commands::get_jellyfin_credentials,
-            commands::restore_jellyfin_session,
-            // Search commands
-            commands::search_spotify_tracks,
-            // Audio commands
-            commands::get_audio_file,
-            // Custom playlist commands
-            commands::create_custom_playlist,
-            commands::get_custom_playlists,
-            commands::get_custom_playlist,
-            commands::update_custom_playlist,
-            commands::delete_custom_playlist,
-            commands::add_track_to_custom_playlist,
-            commands::get_custom_playlist_tracks,
-            commands::remove_track_from_custom_playlist,
-            commands::reorder_custom_playlist_tracks,
-            commands::get_column_preferences,
-            commands::save_column_preferences,
-            // Union playlist commands
-            commands::create_union_playlist,
-            commands::add_source_to_union_playlist,
-            commands::get_union_playlist_sources,
-            commands::remove_source_from_union_playlist,
-            commands::reorder_union_playlist_sources,
-            commands::get_union_playlist_tracks,
-            // Cache commands
-            commands::write_playlists_cache,
-            commands::read_playlists_cache,
-            commands::clear_playlists_cache,
-            commands::write_custom_playlists_cache,
-            commands::read_custom_playlists_cache,
-            commands::clear_custom_playlists_cache,
-            commands::write_custom_playlist_tracks_cache,
-            commands::read_custom_playlist_tracks_cache,
-            commands::clear_custom_playlist_tracks_cache,
-            commands::write_union_playlist_tracks_cache,
-            commands::read_union_playlist_tracks_cache,
-            commands::clear_union_playlist_tracks_cache,
-            // Playback state commands
-            commands::save_playback_state,
-            commands::restore_playback_state,
-        ])
+        .plugin(tauri_plugin_notification::init())
+        .on_window_event(move |window, event| {
+            // Keep running in the tray instead of quitting when configured to
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == "main" && minimize_to_tray.load(Ordering::Relaxed) {
+                    api.prevent_close();
+                    if let Err(e) = window.hide() {
+                        tracing::warn!("Failed to hide the main window: {}", e);
+                    }
+                }
+            }
+        })
+        .invoke_handler(invoke_handler())
         .setup(move |app| {
             // Initialize PlaybackManager inside the Tauri runtime context
             // This ensures the Tokio runtime is available for spawning tasks
-            let playback = Arc::new(Mutex::new(PlaybackManager::new(
-                providers_for_state.clone(),
-            )));
+            let (playback_event_tx, mut playback_event_rx) = tokio::sync::mpsc::unbounded_channel();
+            let mut playback_manager =
+                PlaybackManager::with_event_sender(providers_for_state.clone(), playback_event_tx);
+            playback_manager.set_volume_normalization(volume_normalization);
+            playback_manager.set_download_index(database_clone.clone());
+            playback_manager.set_autosave_interval(autosave_interval);
+            playback_manager.set_previous_restart_threshold(previous_restart_threshold);
+            playback_manager.set_fade_duration(fade_duration);
+            playback_manager.set_enrichment(enrich_lookahead, enrich_delay);
+            playback_manager.set_default_volume(default_volume);
+            let (played_tx, mut played_rx) = tokio::sync::mpsc::unbounded_channel();
+            playback_manager.set_played_sender(played_tx);
+            let playback = Arc::new(Mutex::new(playback_manager));
 
             // Note: State saver will be started AFTER restoration completes
             // to prevent overwriting the saved state during startup
 
             // Create app state and manage it
-            let app_state = commands::AppState {
-                playback: playback.clone(),
-                providers: providers_for_state.clone(),
-                oauth_code: oauth_code_for_server.clone(),
-                database: database_clone.clone(),
-            };
+            let app_state = commands::AppState::new(
+                playback.clone(),
+                providers_for_state.clone(),
+                oauth_code_for_server.clone(),
+                oauth_state.clone(),
+                oauth_server.clone(),
+                database_clone.clone(),
+                rate_limiters.clone(),
+            );
             app.manage(app_state);
 
+            // Apply edits to config.toml without a restart
+            let handle_for_config = app.handle().clone();
+            let playback_for_config = playback.clone();
+            match Config::watch(move |config| {
+                logging::set_level(&config.general.log_level);
+                // The database, logs and Spotify cache were opened in the
+                // startup data directory, so moving it mid-session would
+                // split the app's files between two places
+                let data_dir: Option<std::path::PathBuf> =
+                    config.general.data_dir.clone().map(Into::into);
+                if data_dir != crate::config::configured_data_dir() {
+                    tracing::warn!(
+                        "data_dir changed to {:?}; restart Any Player to use it",
+                        data_dir
+                    );
+                }
+                crate::config::set_client_identity(crate::config::ClientIdentity::from_config(
+                    &config.general,
+                ));
+                minimize_to_tray_for_config
+                    .store(config.general.minimize_to_tray, Ordering::Relaxed);
+
+                let normalization = config
+                    .general
+                    .normalize_volume
+                    .then_some(config.general.normalization_target_lufs);
+                let previous_restart_threshold =
+                    std::time::Duration::from_millis(config.general.previous_restart_threshold_ms);
+                let fade_duration =
+                    std::time::Duration::from_millis(config.general.fade_duration_ms);
+                let enrich_lookahead = config.general.enrich_lookahead;
+                let enrich_delay = std::time::Duration::from_millis(config.general.enrich_delay_ms);
+                let playback = playback_for_config.clone();
+                tauri::async_runtime::spawn(async move {
+                    let mut playback = playback.lock().await;
+                    playback.set_volume_normalization(normalization);
+                    playback.set_previous_restart_threshold(previous_restart_threshold);
+                    playback.set_fade_duration(fade_duration);
+                    playback.set_enrichment(enrich_lookahead, enrich_delay);
+                });
+
+                if let Err(err) = handle_for_config.emit("config-changed", &config) {
+                    tracing::error!(?err, "Failed to emit 'config-changed' event to frontend");
+                }
+            }) {
+                Ok(watcher) => {
+                    app.manage(watcher);
+                }
+                Err(e) => tracing::warn!("Failed to watch config file for changes: {}", e),
+            }
+
+            // Keep the playlist library fresh without manual refreshes
+            if let Some(interval) = playlist_refresh_interval {
+                playlist_refresh::start(
+                    app.handle().clone(),
+                    providers_for_state.clone(),
+                    interval,
+                );
+            }
+
+            // Exit cleanly on Ctrl-C so the exit handler still saves state
+            let handle_for_signal = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    tracing::info!("Received Ctrl-C, shutting down");
+                    handle_for_signal.exit(0);
+                }
+            });
+
             let handle = app.handle().clone();
 
+            // Expose media controls to the desktop over MPRIS
+            #[cfg(all(target_os = "linux", feature = "mpris"))]
+            let mpris_handle = mpris::start(playback.clone());
+
+            // Show the current track and transport controls in the system tray
+            let tray_handle = match tray::start(app.handle(), playback.clone()) {
+                Ok(tray_handle) => Some(tray_handle),
+                Err(e) => {
+                    tracing::warn!("Failed to create the tray icon: {}", e);
+                    None
+                }
+            };
+
+            // Let integrations on the frontend count plays the same way
+            let handle_for_played = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                while let Some(track) = played_rx.recv().await {
+                    let track = commands::TrackInfo::from(track);
+                    if let Err(err) = handle_for_played.emit("track-played", track) {
+                        tracing::error!(?err, "Failed to emit 'track-played' event to frontend");
+                    }
+                }
+            });
+
+            // Forward playback changes to the frontend so it doesn't need to poll
+            let handle_for_events = app.handle().clone();
+            let mut track_notifier = notify_on_track_change
+                .then(|| notifications::TrackChangeNotifier::new(app.handle().clone()));
+            let discord_presence = discord_client_id.clone().map(discord::start);
+            tauri::async_runtime::spawn(async move {
+                let mut last_error: Option<String> = None;
+                while let Some(info) = playback_event_rx.recv().await {
+                    // Surface each new playback failure once
+                    if info.error != last_error {
+                        if let Some(error) = &info.error {
+                            if let Err(err) = handle_for_events.emit("playback-error", error) {
+                                tracing::error!(
+                                    ?err,
+                                    "Failed to emit 'playback-error' event to frontend"
+                                );
+                            }
+                        }
+                        last_error = info.error.clone();
+                    }
+
+                    #[cfg(all(target_os = "linux", feature = "mpris"))]
+                    mpris_handle.update(&info);
+
+                    if let Some(notifier) = track_notifier.as_mut() {
+                        notifier.update(&info);
+                    }
+                    if let Some(presence) = discord_presence.as_ref() {
+                        presence.update(&info);
+                    }
+                    if let Some(tray_handle) = tray_handle.as_ref() {
+                        tray_handle.update(&info);
+                    }
+
+                    let status = commands::PlaybackStatus::from(info);
+                    if let Err(err) = handle_for_events.emit("playback-changed", status) {
+                        tracing::error!(
+                            ?err,
+                            "Failed to emit 'playback-changed' event to frontend"
+                        );
+                    }
+                }
+            });
+
             // Spawn a task to listen for track completion and emit events
             let playback_for_listener = playback.clone();
             tauri::async_runtime::spawn(async move {
@@ -205,9 +516,20 @@ pub fn run() {
                 }
             });
 
-            // Start OAuth callback server in the Tauri runtime
-            let oauth_code_clone = oauth_code_for_server.clone();
-            tauri::async_runtime::spawn(start_oauth_server(oauth_code_clone));
+            // Skip tracks that fail to play and tell the frontend which ones
+            let handle_for_skips = app.handle().clone();
+            tauri::async_runtime::spawn(PlaybackManager::run_failure_recovery(
+                playback.clone(),
+                move |skipped: &[Track]| {
+                    let titles: Vec<String> = skipped
+                        .iter()
+                        .map(|track| format!("{} - {}", track.artist, track.title))
+                        .collect();
+                    if let Err(err) = handle_for_skips.emit("tracks-skipped", titles) {
+                        tracing::error!(?err, "Failed to emit 'tracks-skipped' event to frontend");
+                    }
+                },
+            ));
 
             // Try to restore Spotify session on startup in the background
             // This allows the UI to load immediately while authentication is being restored
@@ -310,132 +632,109 @@ pub fn run() {
 
             Ok(())
         })
-        .on_window_event(|window, event| {
-            // Clean up temporary audio files when the application is closing
-            if let tauri::WindowEvent::Destroyed = event {
-                commands::cleanup_all_temp_audio_files();
-
-                // Save playback state before closing - block to ensure it completes
-                if let Some(app_state) = window.try_state::<commands::AppState>() {
-                    let playback_clone = app_state.playback.clone();
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Flush state and clean up temporary audio files when the application exits,
+            // blocking so it completes before the process ends
+            if let RunEvent::Exit = event {
+                if let Some(app_state) = app.try_state::<commands::AppState>() {
+                    let playback = app_state.playback.clone();
                     tauri::async_runtime::block_on(async move {
-                        let playback_locked = playback_clone.lock().await;
-                        match playback_locked.save_state().await {
-                            Ok(()) => tracing::info!("✓ Playback state saved on exit"),
-                            Err(e) => {
-                                tracing::error!("Failed to save playback state on exit: {}", e)
-                            }
-                        }
+                        commands::shutdown(&playback).await;
                     });
+                } else {
+                    commands::cleanup_all_temp_audio_files();
                 }
             }
-        })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        });
 }
 
-/// Start a simple HTTP server for OAuth callbacks
-async fn start_oauth_server(oauth_code: Arc<Mutex<Option<String>>>) {
-    use std::net::SocketAddr;
-
-    let addr: SocketAddr = "127.0.0.1:8989".parse().expect("Failed to parse address");
-
-    let listener = match tokio::net::TcpListener::bind(&addr).await {
-        Ok(l) => {
-            tracing::info!("OAuth callback server listening on {}", addr);
-            l
-        }
-        Err(e) => {
-            tracing::error!("Failed to bind OAuth server: {}", e);
-            return;
-        }
-    };
-
-    loop {
-        match listener.accept().await {
-            Ok((socket, _)) => {
-                let oauth_code_clone = oauth_code.clone();
-                tauri::async_runtime::spawn(handle_oauth_request(socket, oauth_code_clone));
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use std::collections::BTreeSet;
+
+    /// Names of the functions marked `#[tauri::command]` in a source file
+    fn command_functions(source: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut lines = source.lines();
+        while let Some(line) = lines.next() {
+            if line.trim() != "#[tauri::command]" {
+                continue;
             }
-            Err(e) => {
-                tracing::error!("Error accepting connection: {}", e);
+            for line in lines.by_ref() {
+                let line = line.trim();
+                let signature = line
+                    .strip_prefix("pub async fn ")
+                    .or_else(|| line.strip_prefix("pub fn "));
+                if let Some(signature) = signature {
+                    let name = signature.split(['(', '<']).next().unwrap_or_default();
+                    names.push(name.to_string());
+                    break;
+                }
             }
         }
+        names
     }
-}
-
-/// Handle a single OAuth callback request
-async fn handle_oauth_request(
-    socket: tokio::net::TcpStream,
-    oauth_code: Arc<Mutex<Option<String>>>,
-) {
-    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-
-    let (reader, mut writer) = socket.into_split();
-    let mut reader = BufReader::new(reader);
-    let mut request_line = String::new();
-
-    if (reader.read_line(&mut request_line).await).is_ok() {
-        // Extract the request path
-        if let Some(path) = request_line.split_whitespace().nth(1) {
-            // Parse the authorization code from the callback URL
-            if path.contains("code=") {
-                if let Some(code_part) = path.split("code=").nth(1) {
-                    if let Some(code) = code_part.split('&').next() {
-                        let code_str = code.to_string();
-
-                        // Store the code for the UI to retrieve
-                        {
-                            let mut code_storage = oauth_code.lock().await;
-                            *code_storage = Some(code_str.clone());
-                        }
 
-                        // Send a response to the browser
-                        let response = b"HTTP/1.1 200 OK\r\n\
-                                      Content-Type: text/html\r\n\
-                                      Content-Length: 220\r\n\
-                                      \r\n\
-                                      <!DOCTYPE html>\r\n\
-                                      <html>\r\n\
-                                      <head><title>Authentication Complete</title></head>\r\n\
-                                      <body style=\"font-family: Arial, sans-serif; text-align: center; padding: 50px;\">\r\n\
-                                      <h1>Authentication Successful</h1>\r\n\
-                                      <p>You can close this window.</p>\r\n\
-                                      </body>\r\n\
-                                      </html>\r\n";
-
-                        let _ = writer.write_all(response).await;
-                        let _ = writer.flush().await;
-
-                        tracing::info!("OAuth callback received and code stored");
-                        return;
-                    }
+    #[test]
+    fn test_handler_registers_every_command() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/commands");
+        let mut defined = BTreeSet::new();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for name in command_functions(&source) {
+                    assert!(defined.insert(name.clone()), "{} is defined twice", name);
                 }
             }
-
-            // Handle error case
-            if path.contains("error=") {
-                let response = b"HTTP/1.1 400 Bad Request\r\n\
-                              Content-Type: text/html\r\n\
-                              Content-Length: 150\r\n\
-                              \r\n\
-                              <!DOCTYPE html>\r\n\
-                              <html>\r\n\
-                              <body>\r\n\
-                              <p>Authentication failed. Please try again.</p>\r\n\
-                              </body>\r\n\
-                              </html>\r\n";
-                let _ = writer.write_all(response).await;
-                let _ = writer.flush().await;
-                return;
-            }
         }
+
+        let registered_set: BTreeSet<String> = super::COMMAND_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        assert_eq!(
+            super::COMMAND_NAMES.len(),
+            registered_set.len(),
+            "a command is registered twice"
+        );
+
+        let unregistered: Vec<_> = defined.difference(&registered_set).collect();
+        assert!(
+            unregistered.is_empty(),
+            "commands missing from commands_handler!: {:?}",
+            unregistered
+        );
+        let unknown: Vec<_> = registered_set.difference(&defined).collect();
+        assert!(
+            unknown.is_empty(),
+            "commands_handler! lists functions that aren't commands: {:?}",
+            unknown
+        );
     }
 
-    // Default response for other requests
-    let response = b"HTTP/1.1 404 Not Found\r\n\
-                  Content-Length: 0\r\n\
-                  \r\n";
-    let _ = writer.write_all(response).await;
-    let _ = writer.flush().await;
+    #[test]
+    #[serial]
+    fn test_storage_follows_configured_data_dir() {
+        let dir = std::env::temp_dir().join(format!("any-player-data-{}", uuid::Uuid::new_v4()));
+        crate::config::set_data_dir(Some(dir.clone()));
+
+        let paths = [
+            super::database_path(),
+            crate::offline::downloads_dir(),
+            crate::artwork::covers_dir(),
+            crate::artwork::art_cache_dir(),
+            crate::logging::log_dir(),
+        ];
+        crate::config::set_data_dir(None);
+
+        assert_eq!(paths[0], Some(dir.join("playlists.db")));
+        assert_eq!(paths[1], Some(dir.join("downloads")));
+        assert_eq!(paths[2], Some(dir.join("covers")));
+        assert_eq!(paths[3], Some(dir.join("art")));
+        assert_eq!(paths[4], Some(dir.join("logs")));
+    }
 }
@@ -0,0 +1,165 @@
+/// Logging setup: console output in debug builds plus a daily-rotated log
+/// file in the app data directory so GUI users can attach logs to bug reports
+use crate::config::GeneralConfig;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{
+    filter::{LevelFilter, Targets},
+    layer::SubscriberExt,
+    reload, Layer, Registry,
+};
+
+/// Handle for swapping the level filter of the installed subscriber
+pub type FilterHandle = reload::Handle<Targets, Registry>;
+
+static FILTER_HANDLE: OnceLock<FilterHandle> = OnceLock::new();
+
+/// Prefix for rotated log files (`any-player.log.YYYY-MM-DD`)
+const LOG_FILE_PREFIX: &str = "any-player.log";
+
+/// Directory log files are written to
+pub fn log_dir() -> Option<PathBuf> {
+    crate::config::configured_data_dir()
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("any-player")))
+        .map(|dir| dir.join("logs"))
+}
+
+/// Parse a configured log level, falling back to `info` for unknown values
+pub fn parse_level(level: &str) -> LevelFilter {
+    match level.trim().to_lowercase().as_str() {
+        "error" => LevelFilter::ERROR,
+        "warn" | "warning" => LevelFilter::WARN,
+        "info" => LevelFilter::INFO,
+        "debug" => LevelFilter::DEBUG,
+        "trace" => LevelFilter::TRACE,
+        "off" => LevelFilter::OFF,
+        _ => LevelFilter::INFO,
+    }
+}
+
+/// Target filter for the configured level, keeping chatty dependencies quiet
+fn build_filter(level: LevelFilter) -> Targets {
+    let dependency_level = level.min(LevelFilter::INFO);
+    Targets::new()
+        .with_default(level)
+        .with_target("any_player_lib", level)
+        .with_target("glycin", dependency_level)
+        .with_target("hyper", dependency_level)
+        .with_target("zbus", dependency_level)
+}
+
+/// Build the subscriber described by `config` without installing it.
+///
+/// File output is written to `log_dir` when logging is enabled; the returned
+/// guard must be kept alive for buffered lines to be flushed. The filter
+/// handle changes the log level while the subscriber is running.
+pub fn build_subscriber(
+    config: &GeneralConfig,
+    log_dir: Option<PathBuf>,
+) -> (
+    impl tracing::Subscriber + Send + Sync,
+    Option<WorkerGuard>,
+    FilterHandle,
+) {
+    let (filter, filter_handle) = reload::Layer::new(build_filter(parse_level(&config.log_level)));
+
+    // Console output is for development; release builds have no terminal attached
+    let console_layer = cfg!(debug_assertions).then(|| tracing_subscriber::fmt::layer().boxed());
+
+    let (file_layer, guard) = match log_dir.filter(|_| config.logging_enabled) {
+        Some(dir) => {
+            let appender = tracing_appender::rolling::daily(dir, LOG_FILE_PREFIX);
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer)
+                .boxed();
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let subscriber = Registry::default()
+        .with(filter)
+        .with(console_layer)
+        .with(file_layer);
+    (subscriber, guard, filter_handle)
+}
+
+/// Install the global subscriber for the application
+pub fn init(config: &GeneralConfig) -> Option<WorkerGuard> {
+    let (subscriber, guard, filter_handle) = build_subscriber(config, log_dir());
+    match tracing::subscriber::set_global_default(subscriber) {
+        Ok(()) => {
+            let _ = FILTER_HANDLE.set(filter_handle);
+        }
+        Err(e) => eprintln!("Failed to initialize logging: {}", e),
+    }
+    guard
+}
+
+/// Change the log level of the running application
+pub fn set_level(level: &str) {
+    let Some(handle) = FILTER_HANDLE.get() else {
+        return;
+    };
+    if let Err(e) = handle.reload(build_filter(parse_level(level))) {
+        tracing::warn!("Failed to change log level: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("error"), LevelFilter::ERROR);
+        assert_eq!(parse_level("WARN"), LevelFilter::WARN);
+        assert_eq!(parse_level(" debug "), LevelFilter::DEBUG);
+        assert_eq!(parse_level("trace"), LevelFilter::TRACE);
+        assert_eq!(parse_level("nonsense"), LevelFilter::INFO);
+    }
+
+    #[test]
+    fn test_build_subscriber_for_each_level() {
+        let dir =
+            std::env::temp_dir().join(format!("any-player-log-test-{}", uuid::Uuid::new_v4()));
+
+        for level in ["error", "warn", "info", "debug", "trace", "bogus"] {
+            let mut config = Config::default().general;
+            config.log_level = level.to_string();
+
+            let (subscriber, guard, _) = build_subscriber(&config, Some(dir.clone()));
+            assert!(guard.is_some());
+            tracing::subscriber::with_default(subscriber, || {
+                tracing::error!("logging test at level {}", level);
+            });
+        }
+
+        // Disabled logging skips the file writer entirely
+        let mut config = Config::default().general;
+        config.logging_enabled = false;
+        let (_subscriber, guard, _) = build_subscriber(&config, Some(dir.clone()));
+        assert!(guard.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_filter_handle_changes_level() {
+        let config = Config::default().general;
+        let (subscriber, _guard, handle) = build_subscriber(&config, None);
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(!tracing::enabled!(tracing::Level::DEBUG));
+            handle
+                .reload(build_filter(parse_level("debug")))
+                .expect("Failed to reload filter");
+            tracing::callsite::rebuild_interest_cache();
+            assert!(tracing::enabled!(tracing::Level::DEBUG));
+        });
+    }
+}
@@ -1,6 +1,7 @@
 /// Core data models for the music player
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
 /// Source provider type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -31,6 +32,10 @@ pub struct Track {
     pub artist: String,
     /// Album name
     pub album: String,
+    /// Artist the album is credited to, which differs from the track artist
+    /// on compilations (e.g. "Various Artists")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
     /// Duration in milliseconds
     pub duration_ms: u64,
     /// Cover art URL (if available)
@@ -42,6 +47,45 @@ pub struct Track {
     /// HTTP headers for authentication (e.g., API keys)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub auth_headers: Option<Vec<(String, String)>>,
+    /// Genre (if available)
+    #[serde(default)]
+    pub genre: Option<String>,
+    /// Release year (if available)
+    #[serde(default)]
+    pub year: Option<u32>,
+    /// Position of the track on its disc
+    #[serde(default)]
+    pub track_number: Option<u32>,
+    /// Disc number for multi-disc albums
+    #[serde(default)]
+    pub disc_number: Option<u32>,
+    /// Unix time the stream URL was fetched from the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enriched_at: Option<i64>,
+}
+
+impl Track {
+    /// Stamp the track as just fetched from its provider
+    pub fn mark_enriched(mut self) -> Self {
+        self.enriched_at = Some(chrono::Utc::now().timestamp());
+        self
+    }
+
+    /// Whether the stream URL should be re-fetched before playing at `now`
+    ///
+    /// Provider stream URLs and tokens can expire, so URLs fetched more than
+    /// `max_age_secs` ago are stale, as are tracks that were never fetched and
+    /// have no URL. Spotify URIs never expire.
+    pub fn needs_refresh(&self, now: i64, max_age_secs: i64) -> bool {
+        if self.source == Source::Custom {
+            return false;
+        }
+        match (&self.url, self.enriched_at) {
+            (Some(url), _) if url.starts_with("spotify:") => false,
+            (_, Some(enriched_at)) => now - enriched_at >= max_age_secs,
+            (url, None) => url.is_none(),
+        }
+    }
 }
 
 impl fmt::Display for Track {
@@ -69,6 +113,10 @@ pub struct Playlist {
     pub tracks: Vec<Track>,
     /// Source provider
     pub source: Source,
+    /// Provider's marker for the playlist's current contents (Spotify
+    /// snapshot ID, Jellyfin modification date), when it reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
 }
 
 impl fmt::Display for Playlist {
@@ -77,9 +125,37 @@ impl fmt::Display for Playlist {
     }
 }
 
+/// One page of a larger result set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items on this page
+    pub items: Vec<T>,
+    /// Number of items across all pages
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    /// Cut the page starting at `offset` out of a complete result set
+    pub fn from_all(all: Vec<T>, offset: usize, limit: usize) -> Self {
+        let total = all.len();
+        let items = all.into_iter().skip(offset).take(limit).collect();
+        Self { items, total }
+    }
+
+    /// Convert every item on the page, keeping the total
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+        }
+    }
+}
+
 /// Playback state
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlaybackState {
+    /// A track was started but its audio is still being fetched or decoded
+    Loading,
     Playing,
     Paused,
     Stopped,
@@ -88,6 +164,7 @@ pub enum PlaybackState {
 impl fmt::Display for PlaybackState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            PlaybackState::Loading => write!(f, "Loading"),
             PlaybackState::Playing => write!(f, "Playing"),
             PlaybackState::Paused => write!(f, "Paused"),
             PlaybackState::Stopped => write!(f, "Stopped"),
@@ -103,6 +180,88 @@ pub enum RepeatMode {
     All,
 }
 
+impl RepeatMode {
+    /// The next mode in the repeat button cycle: Off → All → One → Off
+    pub fn next(self) -> Self {
+        match self {
+            RepeatMode::Off => RepeatMode::All,
+            RepeatMode::All => RepeatMode::One,
+            RepeatMode::One => RepeatMode::Off,
+        }
+    }
+}
+
+impl fmt::Display for RepeatMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepeatMode::Off => write!(f, "off"),
+            RepeatMode::One => write!(f, "one"),
+            RepeatMode::All => write!(f, "all"),
+        }
+    }
+}
+
+impl FromStr for RepeatMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(RepeatMode::Off),
+            "one" => Ok(RepeatMode::One),
+            "all" => Ok(RepeatMode::All),
+            other => Err(format!("Invalid repeat mode: {}", other)),
+        }
+    }
+}
+
+/// Playback volume on the 0-100 scale the UI uses. Values above 100 are
+/// clamped on the way in, including when deserializing saved state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "u32", into = "u32")]
+pub struct Volume(u32);
+
+impl Volume {
+    pub const MAX: u32 = 100;
+
+    pub fn new(percent: u32) -> Self {
+        Self(percent.min(Self::MAX))
+    }
+
+    /// Volume as a 0-100 percentage
+    pub fn percent(self) -> u32 {
+        self.0
+    }
+
+    /// Volume on the 0.0-1.0 scale audio sinks use
+    pub fn as_f32(self) -> f32 {
+        self.0 as f32 / Self::MAX as f32
+    }
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Self(50)
+    }
+}
+
+impl From<u32> for Volume {
+    fn from(percent: u32) -> Self {
+        Self::new(percent)
+    }
+}
+
+impl From<Volume> for u32 {
+    fn from(volume: Volume) -> Self {
+        volume.0
+    }
+}
+
+impl fmt::Display for Volume {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
 /// Current playback information
 #[derive(Debug, Clone)]
 pub struct PlaybackInfo {
@@ -117,7 +276,7 @@ pub struct PlaybackInfo {
     /// Repeat mode
     pub repeat_mode: RepeatMode,
     /// Volume (0-100)
-    pub volume: u32,
+    pub volume: Volume,
     /// Queue of tracks
     pub queue: Vec<Track>,
     /// Current index in queue
@@ -125,6 +284,8 @@ pub struct PlaybackInfo {
     /// Shuffle order: maps shuffle position to original queue index
     /// When shuffle is enabled, this array defines the play order
     pub shuffle_order: Vec<usize>,
+    /// Why the current track failed to play, if it did
+    pub error: Option<String>,
 }
 
 impl Default for PlaybackInfo {
@@ -135,10 +296,136 @@ impl Default for PlaybackInfo {
             position_ms: 0,
             shuffle: false,
             repeat_mode: RepeatMode::Off,
-            volume: 50,
+            volume: Volume::default(),
             queue: Vec::new(),
             current_index: 0,
             shuffle_order: Vec::new(),
+            error: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jellyfin_track(url: Option<&str>, enriched_at: Option<i64>) -> Track {
+        Track {
+            id: "1".to_string(),
+            title: "Song".to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            album_artist: None,
+            duration_ms: 200_000,
+            image_url: None,
+            source: Source::Jellyfin,
+            url: url.map(str::to_string),
+            auth_headers: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at,
+        }
+    }
+
+    #[test]
+    fn test_track_needs_refresh() {
+        let now = 10_000;
+        let url = Some("http://jellyfin/Audio/1/stream");
+
+        // Fresh and stale stream URLs
+        assert!(!jellyfin_track(url, Some(now - 60)).needs_refresh(now, 1800));
+        assert!(jellyfin_track(url, Some(now - 1800)).needs_refresh(now, 1800));
+
+        // Never fetched: only refresh when there's nothing to play
+        assert!(!jellyfin_track(url, None).needs_refresh(now, 1800));
+        assert!(jellyfin_track(None, None).needs_refresh(now, 1800));
+
+        // Spotify URIs and custom tracks never go stale
+        let mut spotify = jellyfin_track(Some("spotify:track:abc"), Some(0));
+        spotify.source = Source::Spotify;
+        assert!(!spotify.needs_refresh(now, 1800));
+        let mut custom = jellyfin_track(None, None);
+        custom.source = Source::Custom;
+        assert!(!custom.needs_refresh(now, 1800));
+
+        assert!(jellyfin_track(url, None)
+            .mark_enriched()
+            .enriched_at
+            .is_some());
+    }
+
+    #[test]
+    fn test_repeat_mode_display() {
+        assert_eq!(RepeatMode::Off.to_string(), "off");
+        assert_eq!(RepeatMode::One.to_string(), "one");
+        assert_eq!(RepeatMode::All.to_string(), "all");
+    }
+
+    #[test]
+    fn test_repeat_mode_from_str() {
+        assert_eq!("off".parse::<RepeatMode>(), Ok(RepeatMode::Off));
+        assert_eq!("one".parse::<RepeatMode>(), Ok(RepeatMode::One));
+        assert_eq!("ALL".parse::<RepeatMode>(), Ok(RepeatMode::All));
+
+        // Round trip through Display
+        for mode in [RepeatMode::Off, RepeatMode::One, RepeatMode::All] {
+            assert_eq!(mode.to_string().parse::<RepeatMode>(), Ok(mode));
+        }
+    }
+
+    #[test]
+    fn test_repeat_mode_cycle() {
+        assert_eq!(RepeatMode::Off.next(), RepeatMode::All);
+        assert_eq!(RepeatMode::All.next(), RepeatMode::One);
+        assert_eq!(RepeatMode::One.next(), RepeatMode::Off);
+
+        // Three steps bring every mode back to where it started
+        for mode in [RepeatMode::Off, RepeatMode::One, RepeatMode::All] {
+            assert_eq!(mode.next().next().next(), mode);
+        }
+    }
+
+    #[test]
+    fn test_repeat_mode_from_str_invalid() {
+        let result = "sometimes".parse::<RepeatMode>();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("sometimes"));
+    }
+
+    #[test]
+    fn test_volume_clamps_to_100() {
+        assert_eq!(Volume::new(150).percent(), 100);
+        assert_eq!(Volume::from(u32::MAX), Volume::new(100));
+        assert_eq!(Volume::new(42).percent(), 42);
+        assert_eq!(Volume::default().percent(), 50);
+    }
+
+    #[test]
+    fn test_volume_as_f32() {
+        assert_eq!(Volume::new(0).as_f32(), 0.0);
+        assert_eq!(Volume::new(50).as_f32(), 0.5);
+        assert_eq!(Volume::new(100).as_f32(), 1.0);
+        assert_eq!(Volume::new(250).as_f32(), 1.0);
+    }
+
+    #[test]
+    fn test_volume_serde_clamps() {
+        assert_eq!(serde_json::to_string(&Volume::new(30)).unwrap(), "30");
+        let volume: Volume = serde_json::from_str("180").unwrap();
+        assert_eq!(volume.percent(), 100);
+        assert!(serde_json::from_str::<Volume>("-5").is_err());
+    }
+
+    #[test]
+    fn test_page_from_all_slices_and_keeps_total() {
+        let page = Page::from_all((0..10).collect(), 4, 3);
+        assert_eq!(page.items, vec![4, 5, 6]);
+        assert_eq!(page.total, 10);
+
+        let past_end = Page::from_all((0..10).collect::<Vec<i32>>(), 20, 3);
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.total, 10);
+    }
+}
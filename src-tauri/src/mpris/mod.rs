@@ -0,0 +1,262 @@
+/// MPRIS D-Bus integration so Linux media keys and desktop media widgets
+/// can control playback
+use crate::models::{PlaybackInfo, PlaybackState};
+use crate::PlaybackManager;
+use mpris_server::{Metadata, PlaybackStatus, Player, Time};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+/// Bus name suffix, exposed as `org.mpris.MediaPlayer2.AnyPlayer`
+const MPRIS_BUS_NAME: &str = "AnyPlayer";
+
+/// Track metadata in the shape MPRIS expects
+#[derive(Debug, Clone, PartialEq)]
+pub struct MprisMetadata {
+    pub title: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    pub art_url: Option<String>,
+    /// Track length in microseconds (`mpris:length`)
+    pub length_us: i64,
+}
+
+impl MprisMetadata {
+    /// Map the current track of a playback snapshot to MPRIS metadata
+    pub fn from_info(info: &PlaybackInfo) -> Option<Self> {
+        info.current_track.as_ref().map(|track| Self {
+            title: track.title.clone(),
+            artists: vec![track.artist.clone()],
+            album: track.album.clone(),
+            art_url: track.image_url.clone(),
+            length_us: (track.duration_ms as i64).saturating_mul(1000),
+        })
+    }
+
+    fn to_metadata(&self) -> Metadata {
+        let mut builder = Metadata::builder()
+            .title(self.title.clone())
+            .artist(self.artists.clone())
+            .album(self.album.clone())
+            .length(Time::from_micros(self.length_us));
+        if let Some(art_url) = &self.art_url {
+            builder = builder.art_url(art_url.clone());
+        }
+        builder.build()
+    }
+}
+
+/// Map our playback state to the MPRIS playback status
+pub fn playback_status(state: PlaybackState) -> PlaybackStatus {
+    match state {
+        // MPRIS has no loading status; the track is about to play
+        PlaybackState::Loading | PlaybackState::Playing => PlaybackStatus::Playing,
+        PlaybackState::Paused => PlaybackStatus::Paused,
+        PlaybackState::Stopped => PlaybackStatus::Stopped,
+    }
+}
+
+/// Handle for pushing playback updates to the MPRIS service
+pub struct MprisHandle {
+    update_tx: mpsc::UnboundedSender<PlaybackInfo>,
+}
+
+impl MprisHandle {
+    /// Publish a playback snapshot (emits PropertiesChanged on D-Bus)
+    pub fn update(&self, info: &PlaybackInfo) {
+        let _ = self.update_tx.send(info.clone());
+    }
+}
+
+/// Start the MPRIS service on its own thread.
+///
+/// `mpris_server::Player` is not `Send`, so it runs on a dedicated
+/// single-threaded runtime; control callbacks hop back onto the Tauri runtime
+/// to drive the `PlaybackManager`.
+pub fn start(playback: Arc<Mutex<PlaybackManager>>) -> MprisHandle {
+    let (update_tx, update_rx) = mpsc::unbounded_channel();
+
+    let spawn_result = std::thread::Builder::new()
+        .name("mpris".to_string())
+        .spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(e) => {
+                    tracing::warn!("Failed to create MPRIS runtime: {}", e);
+                    return;
+                }
+            };
+
+            let local = tokio::task::LocalSet::new();
+            local.block_on(&runtime, run_service(playback, update_rx));
+        });
+
+    if let Err(e) = spawn_result {
+        tracing::warn!("Failed to spawn MPRIS thread: {}", e);
+    }
+
+    MprisHandle { update_tx }
+}
+
+/// Run a playback command on the Tauri runtime
+fn dispatch<F, Fut>(playback: &Arc<Mutex<PlaybackManager>>, f: F)
+where
+    F: FnOnce(Arc<Mutex<PlaybackManager>>) -> Fut,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    tauri::async_runtime::spawn(f(playback.clone()));
+}
+
+async fn run_service(
+    playback: Arc<Mutex<PlaybackManager>>,
+    mut update_rx: mpsc::UnboundedReceiver<PlaybackInfo>,
+) {
+    let player = match Player::builder(MPRIS_BUS_NAME)
+        .identity("Any Player")
+        .can_control(true)
+        .can_play(true)
+        .can_pause(true)
+        .can_go_next(true)
+        .can_go_previous(true)
+        .can_seek(true)
+        .build()
+        .await
+    {
+        Ok(player) => player,
+        Err(e) => {
+            tracing::warn!("Failed to register MPRIS service: {}", e);
+            return;
+        }
+    };
+
+    let pb = playback.clone();
+    player.connect_play_pause(move |_| {
+        dispatch(&pb, |pb| async move {
+            pb.lock().await.toggle_play_pause().await
+        });
+    });
+    let pb = playback.clone();
+    player.connect_play(move |_| {
+        dispatch(&pb, |pb| async move { pb.lock().await.play().await });
+    });
+    let pb = playback.clone();
+    player.connect_pause(move |_| {
+        dispatch(&pb, |pb| async move { pb.lock().await.pause().await });
+    });
+    let pb = playback.clone();
+    player.connect_next(move |_| {
+        dispatch(&pb, |pb| async move {
+            let _ = pb.lock().await.next_track().await;
+        });
+    });
+    let pb = playback.clone();
+    player.connect_previous(move |_| {
+        dispatch(&pb, |pb| async move {
+            let _ = pb.lock().await.previous_track().await;
+        });
+    });
+    let pb = playback.clone();
+    player.connect_seek(move |_, offset| {
+        let offset_ms = offset.as_millis();
+        dispatch(&pb, move |pb| async move {
+            let playback = pb.lock().await;
+            let position = playback.get_info().await.position_ms as i64;
+            playback
+                .seek(position.saturating_add(offset_ms).max(0) as u64)
+                .await;
+        });
+    });
+
+    tokio::task::spawn_local(player.run());
+    tracing::info!("✓ MPRIS service registered");
+
+    let mut last_metadata: Option<MprisMetadata> = None;
+    while let Some(info) = update_rx.recv().await {
+        let metadata = MprisMetadata::from_info(&info);
+        if metadata != last_metadata {
+            let mpris_metadata = metadata
+                .as_ref()
+                .map(MprisMetadata::to_metadata)
+                .unwrap_or_default();
+            if let Err(e) = player.set_metadata(mpris_metadata).await {
+                tracing::debug!("Failed to update MPRIS metadata: {}", e);
+            }
+            last_metadata = metadata;
+        }
+
+        if let Err(e) = player
+            .set_playback_status(playback_status(info.state))
+            .await
+        {
+            tracing::debug!("Failed to update MPRIS playback status: {}", e);
+        }
+        player.set_position(Time::from_millis(info.position_ms as i64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Source, Track};
+
+    #[test]
+    fn test_metadata_from_info() {
+        let info = PlaybackInfo {
+            current_track: Some(Track {
+                id: "1".to_string(),
+                title: "Song".to_string(),
+                artist: "Artist".to_string(),
+                album: "Album".to_string(),
+                album_artist: None,
+                duration_ms: 200_000,
+                image_url: Some("http://example.com/art.jpg".to_string()),
+                source: Source::Jellyfin,
+                url: None,
+                auth_headers: None,
+                genre: None,
+                year: None,
+                track_number: None,
+                disc_number: None,
+                enriched_at: None,
+            }),
+            ..Default::default()
+        };
+
+        let metadata = MprisMetadata::from_info(&info).unwrap();
+        assert_eq!(metadata.title, "Song");
+        assert_eq!(metadata.artists, vec!["Artist".to_string()]);
+        assert_eq!(metadata.album, "Album");
+        assert_eq!(
+            metadata.art_url.as_deref(),
+            Some("http://example.com/art.jpg")
+        );
+        assert_eq!(metadata.length_us, 200_000_000);
+    }
+
+    #[test]
+    fn test_metadata_without_track() {
+        assert!(MprisMetadata::from_info(&PlaybackInfo::default()).is_none());
+    }
+
+    #[test]
+    fn test_playback_status_mapping() {
+        assert_eq!(
+            playback_status(PlaybackState::Loading),
+            PlaybackStatus::Playing
+        );
+        assert_eq!(
+            playback_status(PlaybackState::Playing),
+            PlaybackStatus::Playing
+        );
+        assert_eq!(
+            playback_status(PlaybackState::Paused),
+            PlaybackStatus::Paused
+        );
+        assert_eq!(
+            playback_status(PlaybackState::Stopped),
+            PlaybackStatus::Stopped
+        );
+    }
+}
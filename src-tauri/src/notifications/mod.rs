@@ -0,0 +1,140 @@
+/// Desktop notifications when the current track changes
+use crate::models::PlaybackInfo;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// How long a track must stay current before we announce it.
+/// Rapid next-next-next skips only notify for the track the user lands on.
+pub const TRACK_CHANGE_DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// Decides when a track change is stable enough to notify about
+#[derive(Debug)]
+pub struct NotificationDebouncer {
+    delay: Duration,
+    /// Track that became current and when, awaiting the debounce delay
+    pending: Option<(String, Instant)>,
+    /// Last track we showed a notification for
+    last_notified: Option<String>,
+}
+
+impl NotificationDebouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: None,
+            last_notified: None,
+        }
+    }
+
+    /// Observe the current track at `now`; returns true if a notification
+    /// should be shown for it
+    pub fn observe(&mut self, track_id: Option<&str>, now: Instant) -> bool {
+        let Some(track_id) = track_id else {
+            self.pending = None;
+            return false;
+        };
+
+        if self.last_notified.as_deref() == Some(track_id) {
+            self.pending = None;
+            return false;
+        }
+
+        match &self.pending {
+            Some((pending_id, since)) if pending_id == track_id => {
+                if now.duration_since(*since) >= self.delay {
+                    self.pending = None;
+                    self.last_notified = Some(track_id.to_string());
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => {
+                // New track - start the debounce window
+                self.pending = Some((track_id.to_string(), now));
+                false
+            }
+        }
+    }
+}
+
+/// Shows "Now Playing" notifications from playback change events
+pub struct TrackChangeNotifier {
+    app: AppHandle,
+    debouncer: NotificationDebouncer,
+}
+
+impl TrackChangeNotifier {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            debouncer: NotificationDebouncer::new(TRACK_CHANGE_DEBOUNCE),
+        }
+    }
+
+    /// Handle a playback snapshot, notifying once the track has settled
+    pub fn update(&mut self, info: &PlaybackInfo) {
+        let track_id = info.current_track.as_ref().map(|t| t.id.as_str());
+        if !self.debouncer.observe(track_id, Instant::now()) {
+            return;
+        }
+
+        if let Some(track) = &info.current_track {
+            let result = self
+                .app
+                .notification()
+                .builder()
+                .title("Now Playing")
+                .body(format!("{} — {}", track.title, track.artist))
+                .show();
+            if let Err(e) = result {
+                tracing::debug!("Failed to show track notification: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notifies_after_track_settles() {
+        let mut debouncer = NotificationDebouncer::new(Duration::from_millis(1000));
+        let start = Instant::now();
+
+        assert!(!debouncer.observe(Some("a"), start));
+        assert!(!debouncer.observe(Some("a"), start + Duration::from_millis(500)));
+        assert!(debouncer.observe(Some("a"), start + Duration::from_millis(1000)));
+
+        // Only once per track
+        assert!(!debouncer.observe(Some("a"), start + Duration::from_millis(2000)));
+    }
+
+    #[test]
+    fn test_rapid_skips_are_debounced() {
+        let mut debouncer = NotificationDebouncer::new(Duration::from_millis(1000));
+        let start = Instant::now();
+
+        // Skipping through tracks faster than the delay never notifies
+        assert!(!debouncer.observe(Some("a"), start));
+        assert!(!debouncer.observe(Some("b"), start + Duration::from_millis(300)));
+        assert!(!debouncer.observe(Some("c"), start + Duration::from_millis(600)));
+        assert!(!debouncer.observe(Some("c"), start + Duration::from_millis(1200)));
+
+        // The track the user lands on is announced once it settles
+        assert!(debouncer.observe(Some("c"), start + Duration::from_millis(1600)));
+    }
+
+    #[test]
+    fn test_no_track_resets_pending() {
+        let mut debouncer = NotificationDebouncer::new(Duration::from_millis(1000));
+        let start = Instant::now();
+
+        assert!(!debouncer.observe(Some("a"), start));
+        assert!(!debouncer.observe(None, start + Duration::from_millis(500)));
+        assert!(!debouncer.observe(Some("a"), start + Duration::from_millis(1100)));
+        assert!(debouncer.observe(Some("a"), start + Duration::from_millis(2100)));
+    }
+}
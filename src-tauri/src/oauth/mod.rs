@@ -0,0 +1,428 @@
+/// Local HTTP server that receives OAuth callbacks from the browser
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, Notify};
+
+/// Number of consecutive ports to try when the preferred port is taken
+pub const OAUTH_PORT_ATTEMPTS: u16 = 10;
+
+/// How long to wait for a client to send its request line before giving up
+const REQUEST_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Bind the OAuth callback listener, falling back to the next few ports if
+/// the preferred one is already in use.
+///
+/// Use `listener.local_addr()` to find out which port was actually bound so
+/// the redirect URI can be built to match.
+pub async fn bind_callback_listener(preferred_port: u16) -> std::io::Result<TcpListener> {
+    let mut last_error = None;
+
+    for offset in 0..OAUTH_PORT_ATTEMPTS {
+        let Some(port) = preferred_port.checked_add(offset) else {
+            break;
+        };
+
+        match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => {
+                if offset > 0 {
+                    tracing::warn!(
+                        "OAuth port {} unavailable, using port {} instead",
+                        preferred_port,
+                        port
+                    );
+                }
+                return Ok(listener);
+            }
+            Err(e) => {
+                tracing::debug!("Failed to bind OAuth server on port {}: {}", port, e);
+                last_error = Some(e);
+            }
+        }
+
+        // Port 0 asks the OS for an ephemeral port, so there is nothing to retry
+        if preferred_port == 0 {
+            break;
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::AddrNotAvailable,
+            "No port available for OAuth callback server",
+        )
+    }))
+}
+
+/// Handle to the OAuth callback server
+///
+/// The server is started on demand when an authorization URL is requested and
+/// shuts itself down once a valid code has been received, so the port is only
+/// held while an authentication flow is in progress.
+pub struct OAuthServer {
+    preferred_port: u16,
+    oauth_code: Arc<Mutex<Option<String>>>,
+    expected_state: Arc<Mutex<Option<String>>>,
+    running: Arc<AtomicBool>,
+    bound_port: Arc<AtomicU16>,
+    /// Shutdown signal for the currently running server (if any)
+    shutdown: Mutex<Option<Arc<Notify>>>,
+}
+
+impl OAuthServer {
+    pub fn new(
+        preferred_port: u16,
+        oauth_code: Arc<Mutex<Option<String>>>,
+        expected_state: Arc<Mutex<Option<String>>>,
+    ) -> Self {
+        Self {
+            preferred_port,
+            oauth_code,
+            expected_state,
+            running: Arc::new(AtomicBool::new(false)),
+            bound_port: Arc::new(AtomicU16::new(0)),
+            shutdown: Mutex::new(None),
+        }
+    }
+
+    /// Check whether the server is currently accepting connections
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Start the server if it isn't already running and return the bound port
+    pub async fn start(&self) -> std::io::Result<u16> {
+        let mut shutdown_slot = self.shutdown.lock().await;
+
+        if self.is_running() {
+            return Ok(self.bound_port.load(Ordering::SeqCst));
+        }
+
+        let listener = bind_callback_listener(self.preferred_port).await?;
+        let port = listener.local_addr()?.port();
+
+        // Use a fresh signal per run so a stale notification can't stop a restart
+        let shutdown = Arc::new(Notify::new());
+        *shutdown_slot = Some(shutdown.clone());
+        self.bound_port.store(port, Ordering::SeqCst);
+        self.running.store(true, Ordering::SeqCst);
+
+        let running = self.running.clone();
+        let oauth_code = self.oauth_code.clone();
+        let expected_state = self.expected_state.clone();
+        tokio::spawn(async move {
+            run_callback_server(listener, oauth_code, expected_state, shutdown).await;
+            running.store(false, Ordering::SeqCst);
+            tracing::info!("OAuth callback server stopped");
+        });
+
+        Ok(port)
+    }
+
+    /// Ask the running server (if any) to stop and release its port
+    pub async fn shutdown(&self) {
+        if let Some(shutdown) = self.shutdown.lock().await.take() {
+            shutdown.notify_one();
+        }
+    }
+}
+
+/// Accept OAuth callback connections on an already bound listener until
+/// `shutdown` is notified
+///
+/// `expected_state` holds the `state` value sent with the authorization URL;
+/// callbacks that don't echo it back are rejected. A valid callback triggers
+/// `shutdown` so the listener is dropped and the port freed.
+pub async fn run_callback_server(
+    listener: TcpListener,
+    oauth_code: Arc<Mutex<Option<String>>>,
+    expected_state: Arc<Mutex<Option<String>>>,
+    shutdown: Arc<Notify>,
+) {
+    if let Ok(addr) = listener.local_addr() {
+        tracing::info!("OAuth callback server listening on {}", addr);
+    }
+
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => {
+                tracing::debug!("OAuth callback server received shutdown signal");
+                break;
+            }
+            accepted = listener.accept() => match accepted {
+                Ok((socket, _)) => {
+                    tokio::spawn(handle_oauth_request(
+                        socket,
+                        oauth_code.clone(),
+                        expected_state.clone(),
+                        shutdown.clone(),
+                    ));
+                }
+                Err(e) => {
+                    tracing::error!("Error accepting connection: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Query parameters of an OAuth callback request, URL-decoded
+#[derive(Debug, Default, PartialEq)]
+struct CallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+/// Parse the request target of an HTTP request line (e.g. `GET /callback?code=.. HTTP/1.1`)
+///
+/// Returns `None` if the line isn't a well-formed request.
+fn parse_request_line(request_line: &str) -> Option<CallbackParams> {
+    let mut parts = request_line.split_whitespace();
+    let _method = parts.next()?;
+    let target = parts.next()?;
+    let version = parts.next()?;
+
+    if !version.starts_with("HTTP/") || !target.starts_with('/') {
+        return None;
+    }
+
+    // The target is only a path; give it a base so the url crate can parse it
+    let url = url::Url::parse(&format!("http://127.0.0.1{}", target)).ok()?;
+
+    let mut params = CallbackParams::default();
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "code" => params.code = Some(value.into_owned()),
+            "state" => params.state = Some(value.into_owned()),
+            "error" => params.error = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+
+    Some(params)
+}
+
+/// Build a minimal HTTP response with a correct Content-Length
+fn http_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// Check the `state` returned by the callback against the one we generated.
+/// A missing value on either side is treated as a mismatch.
+pub fn state_matches(expected: Option<&str>, received: Option<&str>) -> bool {
+    match (expected, received) {
+        (Some(expected), Some(received)) => !expected.is_empty() && expected == received,
+        _ => false,
+    }
+}
+
+/// Handle a single OAuth callback request
+async fn handle_oauth_request(
+    socket: tokio::net::TcpStream,
+    oauth_code: Arc<Mutex<Option<String>>>,
+    expected_state: Arc<Mutex<Option<String>>>,
+    shutdown: Arc<Notify>,
+) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = socket.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut request_line = String::new();
+
+    // Don't let a slow or idle connection hold a task forever
+    let params =
+        match tokio::time::timeout(REQUEST_READ_TIMEOUT, reader.read_line(&mut request_line)).await
+        {
+            Ok(Ok(_)) => parse_request_line(&request_line),
+            Ok(Err(e)) => {
+                tracing::debug!("Failed to read OAuth callback request: {}", e);
+                None
+            }
+            Err(_) => {
+                tracing::warn!("Timed out waiting for OAuth callback request");
+                return;
+            }
+        };
+
+    let response = match params {
+        Some(CallbackParams {
+            code: Some(code),
+            state,
+            ..
+        }) => {
+            // Only accept the code if the state matches the pending request
+            let state_ok = {
+                let mut expected = expected_state.lock().await;
+                let ok = state_matches(expected.as_deref(), state.as_deref());
+                if ok {
+                    // The state is single use
+                    *expected = None;
+                }
+                ok
+            };
+
+            if state_ok {
+                // Store the code for the UI to retrieve
+                *oauth_code.lock().await = Some(code);
+                tracing::info!("OAuth callback received and code stored");
+
+                // Authentication is complete; stop listening
+                shutdown.notify_one();
+
+                http_response(
+                    "200 OK",
+                    "<!DOCTYPE html>\r\n\
+                     <html>\r\n\
+                     <head><title>Authentication Complete</title></head>\r\n\
+                     <body style=\"font-family: Arial, sans-serif; text-align: center; padding: 50px;\">\r\n\
+                     <h1>Authentication Successful</h1>\r\n\
+                     <p>You can close this window.</p>\r\n\
+                     </body>\r\n\
+                     </html>\r\n",
+                )
+            } else {
+                tracing::warn!("Rejected OAuth callback with missing or mismatched state");
+                http_response(
+                    "400 Bad Request",
+                    "<!DOCTYPE html>\r\n\
+                     <html>\r\n\
+                     <body>\r\n\
+                     <p>Invalid authentication request. Please try again.</p>\r\n\
+                     </body>\r\n\
+                     </html>\r\n",
+                )
+            }
+        }
+        Some(CallbackParams {
+            error: Some(error), ..
+        }) => {
+            tracing::warn!("OAuth callback returned error: {}", error);
+            http_response(
+                "400 Bad Request",
+                "<!DOCTYPE html>\r\n\
+                 <html>\r\n\
+                 <body>\r\n\
+                 <p>Authentication failed. Please try again.</p>\r\n\
+                 </body>\r\n\
+                 </html>\r\n",
+            )
+        }
+        Some(_) => http_response("404 Not Found", ""),
+        None => http_response("400 Bad Request", ""),
+    };
+
+    let _ = writer.write_all(response.as_bytes()).await;
+    let _ = writer.flush().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::spotify::redirect_uri_for_port;
+
+    #[test]
+    fn test_state_matches() {
+        assert!(state_matches(Some("abc123"), Some("abc123")));
+        assert!(!state_matches(Some("abc123"), Some("evil")));
+        assert!(!state_matches(Some("abc123"), None));
+        assert!(!state_matches(None, Some("abc123")));
+        assert!(!state_matches(Some(""), Some("")));
+    }
+
+    #[test]
+    fn test_parse_request_line() {
+        let params = parse_request_line("GET /callback?code=abc&state=xyz HTTP/1.1\r\n").unwrap();
+        assert_eq!(params.code.as_deref(), Some("abc"));
+        assert_eq!(params.state.as_deref(), Some("xyz"));
+        assert_eq!(params.error, None);
+
+        let params = parse_request_line("GET /favicon.ico HTTP/1.1\r\n").unwrap();
+        assert_eq!(params, CallbackParams::default());
+    }
+
+    #[test]
+    fn test_parse_request_line_decodes_code() {
+        let params =
+            parse_request_line("GET /callback?state=s%201&code=AQ%2Bb%2Fc%3D%3D HTTP/1.1\r\n")
+                .unwrap();
+        assert_eq!(params.code.as_deref(), Some("AQ+b/c=="));
+        assert_eq!(params.state.as_deref(), Some("s 1"));
+    }
+
+    #[test]
+    fn test_parse_request_line_malformed() {
+        assert!(parse_request_line("").is_none());
+        assert!(parse_request_line("garbage").is_none());
+        assert!(parse_request_line("GET /callback?code=abc").is_none());
+        assert!(parse_request_line("GET callback?code=abc HTTP/1.1").is_none());
+    }
+
+    #[test]
+    fn test_http_response_content_length() {
+        let response = http_response("200 OK", "hello");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Length: 5\r\n"));
+        assert!(response.ends_with("\r\n\r\nhello"));
+    }
+
+    #[tokio::test]
+    async fn test_bind_ephemeral_port_builds_matching_redirect_uri() {
+        let listener = bind_callback_listener(0)
+            .await
+            .expect("Failed to bind ephemeral port");
+        let port = listener.local_addr().unwrap().port();
+
+        assert_ne!(port, 0);
+        assert_eq!(
+            redirect_uri_for_port(port),
+            format!("http://127.0.0.1:{}/callback", port)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bind_falls_back_when_port_taken() {
+        // Occupy a port, then ask for it as the preferred port
+        let occupied = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let taken_port = occupied.local_addr().unwrap().port();
+
+        let listener = bind_callback_listener(taken_port)
+            .await
+            .expect("Failed to bind fallback port");
+        let port = listener.local_addr().unwrap().port();
+
+        assert_ne!(port, taken_port);
+        assert!(port > taken_port && port < taken_port + OAUTH_PORT_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_releases_port() {
+        let server = OAuthServer::new(0, Arc::new(Mutex::new(None)), Arc::new(Mutex::new(None)));
+
+        let port = server.start().await.expect("Failed to start server");
+        assert!(server.is_running());
+
+        // Starting again while running reuses the same port
+        assert_eq!(server.start().await.unwrap(), port);
+
+        server.shutdown().await;
+
+        // Wait for the accept loop to observe the signal
+        for _ in 0..50 {
+            if !server.is_running() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(!server.is_running());
+
+        // The port should be free to bind again
+        let rebound = TcpListener::bind(("127.0.0.1", port)).await;
+        assert!(rebound.is_ok());
+    }
+}
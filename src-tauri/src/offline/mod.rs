@@ -0,0 +1,99 @@
+/// Offline downloads: audio files saved to the app data directory and
+/// indexed in the database so playback can use them without a connection
+use crate::models::Source;
+use std::path::{Path, PathBuf};
+
+/// Directory downloaded tracks are stored in
+pub fn downloads_dir() -> Option<PathBuf> {
+    crate::config::configured_data_dir()
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("any-player")))
+        .map(|dir| dir.join("downloads"))
+}
+
+/// File a track is downloaded to inside `dir`.
+///
+/// Track IDs come from the providers, so anything outside a conservative
+/// character set is replaced to keep the name a single path component.
+pub fn download_path(dir: &Path, source: Source, track_id: &str) -> PathBuf {
+    let safe_id: String = track_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}-{}", source, safe_id))
+}
+
+/// `file://` URL the audio player can load a downloaded track from
+pub fn file_url(path: &Path) -> Option<String> {
+    url::Url::from_file_path(path).ok().map(|u| u.to_string())
+}
+
+/// Fetch `url` into `dest`, returning the number of bytes written.
+///
+/// The file is written to a temporary name first so an interrupted download
+/// never leaves a truncated track behind.
+pub async fn download_to(
+    url: &str,
+    auth_headers: Option<Vec<(String, String)>>,
+    dest: &Path,
+) -> Result<u64, String> {
+    let client = crate::http_client::shared_client();
+    let mut request = client
+        .get(url)
+        .timeout(crate::http_client::AUDIO_REQUEST_TIMEOUT);
+    if let Some(headers) = auth_headers {
+        for (key, value) in headers {
+            request = request.header(key, value);
+        }
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch track: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch track: HTTP {}", response.status()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read track data: {}", e))?;
+
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create downloads directory: {}", e))?;
+    }
+    let partial = dest.with_extension("part");
+    tokio::fs::write(&partial, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write track: {}", e))?;
+    tokio::fs::rename(&partial, dest)
+        .await
+        .map_err(|e| format!("Failed to write track: {}", e))?;
+
+    Ok(bytes.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_path_is_single_component() {
+        let dir = Path::new("/downloads");
+        assert_eq!(
+            download_path(dir, Source::Jellyfin, "abc-123"),
+            dir.join("jellyfin-abc-123")
+        );
+        assert_eq!(
+            download_path(dir, Source::Spotify, "../../etc/passwd"),
+            dir.join("spotify-______etc_passwd")
+        );
+    }
+}
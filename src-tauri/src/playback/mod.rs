@@ -1,12 +1,15 @@
 /// Playback management
-use crate::models::{PlaybackInfo, PlaybackState, RepeatMode, Track};
-use crate::providers::{spotify::SPOTIFY_CLIENT_ID, ProviderRegistry};
+use crate::database::Database;
+use crate::models::{PlaybackInfo, PlaybackState, RepeatMode, Track, Volume};
+use crate::providers::spotify::{select_stream, SpotifyStream, SPOTIFY_CLIENT_ID};
+use crate::providers::ProviderRegistry;
 use rodio::{Decoder, OutputStream, Sink, Source};
+use std::collections::HashSet;
 use std::io::Cursor;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, watch, Mutex};
 
 // Librespot imports for premium Spotify streaming via session-based OAuth
 use librespot_core::authentication::Credentials;
@@ -24,6 +27,7 @@ use librespot_playback::player::Player as LibrespotPlayer;
 const LIBRESPOT_FALLBACK_SAMPLE_RATE: u32 = 44100;
 const LIBRESPOT_FALLBACK_CHANNELS: u16 = 2;
 
+pub mod normalization;
 pub mod spotify_session;
 pub use spotify_session::SpotifySessionManager;
 
@@ -140,17 +144,96 @@ impl LibrespotSink for RodioSink {
     }
 }
 
+/// Default length of the volume ramp around pause and resume
+pub const DEFAULT_FADE_DURATION: Duration = Duration::from_millis(150);
+
+/// Time between volume changes while fading
+const FADE_STEP: Duration = Duration::from_millis(10);
+
+/// Fractions of the full volume to step through when fading in over
+/// `duration`, ending at 1.0. Empty when fading is disabled.
+fn fade_schedule(duration: Duration) -> Vec<f32> {
+    if duration.is_zero() {
+        return Vec::new();
+    }
+    let steps = (duration.as_millis() / FADE_STEP.as_millis()).max(1) as u32;
+    (1..=steps).map(|step| step as f32 / steps as f32).collect()
+}
+
+/// Position and duration published by the audio thread
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlaybackProgress {
+    /// Current playback position in milliseconds
+    pub position_ms: u64,
+    /// Total duration in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Reject responses that are clearly not audio, such as the HTML login or
+/// error page a server sends when a token has expired, so the user sees
+/// why instead of a decoder error. A missing or generic type is allowed.
+fn check_audio_content_type(content_type: Option<&str>) -> Result<(), String> {
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let not_audio = mime.starts_with("text/")
+        || matches!(
+            mime.as_str(),
+            "application/json" | "application/xml" | "application/xhtml+xml"
+        );
+    if not_audio {
+        return Err(format!(
+            "Server returned {}, not audio; the access token may have expired",
+            mime
+        ));
+    }
+    Ok(())
+}
+
+/// Why a playback handle stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// Stopped by the user or replaced by another track
+    User,
+    /// The track played to the end
+    Completed,
+    /// The audio couldn't be played; the message is in `error()`
+    Failed,
+}
+
 /// Shared playback state for the current audio stream
 #[derive(Clone)]
 pub struct PlaybackHandle {
     /// Global flag to stop the playback thread
     stop_flag: Arc<AtomicBool>,
-    /// Current playback position in milliseconds
-    position_ms: Arc<AtomicU64>,
-    /// Total duration in milliseconds
-    duration_ms: Arc<AtomicU64>,
+    /// Progress updates; subscribers are woken on every change instead of polling
+    progress: Arc<watch::Sender<PlaybackProgress>>,
+    /// Per-track loudness multiplier applied on top of the user volume (f32 bits)
+    gain: Arc<AtomicU32>,
+    /// Target loudness in LUFS when volume normalization is enabled
+    normalization_target: Option<f64>,
+    /// User's manual gain offset in dB for this track, applied on top of
+    /// normalization
+    manual_gain_db: Option<f64>,
     /// Whether playback is paused
     is_paused: Arc<AtomicBool>,
+    /// Set once decoded audio has been handed to the sink
+    started: Arc<AtomicBool>,
+    /// Sink volume from the user's volume setting (f32 bits); fades ramp to
+    /// and from this level
+    volume: Arc<AtomicU32>,
+    /// Bumped by every fade so a pause or resume cancels one in progress
+    fade_generation: Arc<AtomicU64>,
+    /// Why playback stopped early, if the audio thread failed
+    error: Arc<std::sync::Mutex<Option<String>>>,
+    /// Why the stop flag was set; the first reason sticks
+    stop_reason: Arc<std::sync::Mutex<Option<StopReason>>>,
     /// Direct reference to rodio sink for immediate pause/play control
     /// Using Arc<Mutex<Option<...>>> for interior mutability
     sink: Arc<Mutex<Option<Arc<Mutex<Sink>>>>>,
@@ -160,25 +243,134 @@ impl PlaybackHandle {
     pub fn new() -> Self {
         Self {
             stop_flag: Arc::new(AtomicBool::new(false)),
-            position_ms: Arc::new(AtomicU64::new(0)),
-            duration_ms: Arc::new(AtomicU64::new(0)),
+            progress: Arc::new(watch::channel(PlaybackProgress::default()).0),
+            gain: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            normalization_target: None,
+            manual_gain_db: None,
             is_paused: Arc::new(AtomicBool::new(false)),
+            started: Arc::new(AtomicBool::new(false)),
+            volume: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            fade_generation: Arc::new(AtomicU64::new(0)),
+            error: Arc::new(std::sync::Mutex::new(None)),
+            stop_reason: Arc::new(std::sync::Mutex::new(None)),
             sink: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Sink volume that fades restore
+    fn target_volume(&self) -> f32 {
+        f32::from_bits(self.volume.load(Ordering::SeqCst))
+    }
+
+    fn set_target_volume(&self, volume: f32) {
+        self.volume.store(volume.to_bits(), Ordering::SeqCst);
+    }
+
+    async fn current_sink(&self) -> Option<Arc<Mutex<Sink>>> {
+        self.sink.lock().await.clone()
+    }
+
     /// Set the sink handle for direct pause/play control
     pub async fn set_sink(&self, sink: Arc<Mutex<Sink>>) {
         let mut sink_opt = self.sink.lock().await;
         *sink_opt = Some(sink);
     }
 
+    /// Normalize this track's loudness to `target_lufs` (None disables)
+    pub fn with_normalization(mut self, target_lufs: Option<f64>) -> Self {
+        self.normalization_target = target_lufs;
+        self
+    }
+
+    pub fn normalization_target(&self) -> Option<f64> {
+        self.normalization_target
+    }
+
+    /// Apply the user's stored gain offset for this track (None for none)
+    pub fn with_manual_gain(mut self, gain_db: Option<f64>) -> Self {
+        self.manual_gain_db = gain_db;
+        self
+    }
+
+    pub fn manual_gain_db(&self) -> Option<f64> {
+        self.manual_gain_db
+    }
+
+    /// Per-track gain multiplier from loudness normalization and the manual
+    /// offset
+    pub fn gain(&self) -> f32 {
+        f32::from_bits(self.gain.load(Ordering::SeqCst))
+    }
+
+    pub fn set_gain(&self, gain: f32) {
+        self.gain.store(gain.to_bits(), Ordering::SeqCst);
+    }
+
+    /// Sink volume for the user volume, including the track gain
+    pub fn effective_volume(&self, volume: Volume) -> f32 {
+        volume.as_f32() * self.gain()
+    }
+
+    /// Subscribe to progress updates
+    pub fn subscribe(&self) -> watch::Receiver<PlaybackProgress> {
+        self.progress.subscribe()
+    }
+
+    /// Wake subscribers after a stop/pause flag change
+    fn notify_subscribers(&self) {
+        self.progress.send_modify(|_| {});
+    }
+
+    /// Stop playback at the user's request
     pub fn stop(&self) {
+        self.stop_with(StopReason::User);
+    }
+
+    /// Stop playback because the track reached its end
+    pub fn complete(&self) {
+        self.stop_with(StopReason::Completed);
+    }
+
+    fn stop_with(&self, reason: StopReason) {
+        if let Ok(mut stop_reason) = self.stop_reason.lock() {
+            stop_reason.get_or_insert(reason);
+        }
         self.stop_flag.store(true, Ordering::SeqCst);
+        self.notify_subscribers();
+    }
+
+    /// Why playback stopped, or `None` while it's still running
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason.lock().ok().and_then(|reason| *reason)
+    }
+
+    /// Record that the audio is now playing through the sink
+    pub fn mark_started(&self) {
+        self.started.store(true, Ordering::SeqCst);
+        self.notify_subscribers();
+    }
+
+    /// Whether the audio has reached the sink, as opposed to still loading
+    pub fn has_started(&self) -> bool {
+        self.started.load(Ordering::SeqCst)
+    }
+
+    /// Stop playback because the audio could not be played
+    pub fn fail(&self, message: String) {
+        if let Ok(mut error) = self.error.lock() {
+            *error = Some(message);
+        }
+        self.stop_with(StopReason::Failed);
+    }
+
+    /// Error that stopped playback, if it failed rather than finishing
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().ok().and_then(|error| error.clone())
     }
 
     pub fn pause(&self) {
         self.is_paused.store(true, Ordering::SeqCst);
+        self.notify_subscribers();
         // Directly pause the rodio sink for immediate effect
         // This works for both HTTP and Spotify playback since both use rodio for audio output
         let sink_arc = self.sink.clone();
@@ -195,6 +387,7 @@ impl PlaybackHandle {
 
     pub fn resume(&self) {
         self.is_paused.store(false, Ordering::SeqCst);
+        self.notify_subscribers();
         // Directly resume the rodio sink for immediate effect
         // This works for both HTTP and Spotify playback since both use rodio for audio output
         let sink_arc = self.sink.clone();
@@ -209,9 +402,75 @@ impl PlaybackHandle {
         });
     }
 
-    pub fn set_volume(&self, volume: u32) {
+    /// Ramp the volume down over `fade`, then pause. Pauses immediately
+    /// when `fade` is zero.
+    pub fn fade_out_and_pause(&self, fade: Duration) {
+        let generation = self.fade_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if fade.is_zero() {
+            self.pause();
+            return;
+        }
+
+        let handle = self.clone();
+        tokio::spawn(async move {
+            if let Some(sink) = handle.current_sink().await {
+                for fraction in fade_schedule(fade) {
+                    if handle.fade_generation.load(Ordering::SeqCst) != generation {
+                        return;
+                    }
+                    sink.lock()
+                        .await
+                        .set_volume(handle.target_volume() * (1.0 - fraction));
+                    tokio::time::sleep(FADE_STEP).await;
+                }
+            }
+            if handle.fade_generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            handle.pause();
+            // Put the volume back while paused so a plain resume isn't silent
+            if let Some(sink) = handle.current_sink().await {
+                let s = sink.lock().await;
+                s.pause();
+                s.set_volume(handle.target_volume());
+            }
+        });
+    }
+
+    /// Resume and ramp the volume up from silence over `fade`. Resumes at
+    /// full volume immediately when `fade` is zero.
+    pub fn resume_with_fade(&self, fade: Duration) {
+        let generation = self.fade_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if fade.is_zero() {
+            self.resume();
+            return;
+        }
+
+        let handle = self.clone();
+        tokio::spawn(async move {
+            let Some(sink) = handle.current_sink().await else {
+                handle.resume();
+                return;
+            };
+            sink.lock().await.set_volume(0.0);
+            handle.resume();
+            for fraction in fade_schedule(fade) {
+                if handle.fade_generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                // Read the target each step so volume changes mid-fade are kept
+                sink.lock()
+                    .await
+                    .set_volume(handle.target_volume() * fraction);
+                tokio::time::sleep(FADE_STEP).await;
+            }
+        });
+    }
+
+    pub fn set_volume(&self, volume: Volume) {
         // Set volume on the rodio sink (0-100 scale converted to 0.0-1.0)
-        let volume_f32 = (volume.min(100) as f32) / 100.0;
+        let volume_f32 = self.effective_volume(volume);
+        self.set_target_volume(volume_f32);
         let sink_arc = self.sink.clone();
         tokio::spawn(async move {
             let sink_opt = sink_arc.lock().await;
@@ -228,19 +487,27 @@ impl PlaybackHandle {
     }
 
     pub fn get_position(&self) -> u64 {
-        self.position_ms.load(Ordering::SeqCst)
+        self.progress.borrow().position_ms
     }
 
     pub fn set_position(&self, ms: u64) {
-        self.position_ms.store(ms, Ordering::SeqCst);
+        self.progress.send_if_modified(|progress| {
+            let changed = progress.position_ms != ms;
+            progress.position_ms = ms;
+            changed
+        });
     }
 
     pub fn get_duration(&self) -> u64 {
-        self.duration_ms.load(Ordering::SeqCst)
+        self.progress.borrow().duration_ms
     }
 
     pub fn set_duration(&self, ms: u64) {
-        self.duration_ms.store(ms, Ordering::SeqCst);
+        self.progress.send_if_modified(|progress| {
+            let changed = progress.duration_ms != ms;
+            progress.duration_ms = ms;
+            changed
+        });
     }
 
     pub fn should_stop(&self) -> bool {
@@ -258,12 +525,50 @@ impl Default for PlaybackHandle {
     }
 }
 
+/// Best-effort container detection from magic bytes, used to name the
+/// format in decode errors
+fn sniff_audio_format(data: &[u8]) -> &'static str {
+    match data {
+        [b'f', b'L', b'a', b'C', ..] => "FLAC",
+        [b'O', b'g', b'g', b'S', ..] => "Ogg",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => "WAV",
+        [b'I', b'D', b'3', ..] => "MP3",
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => "MP4/AAC",
+        [0xFF, second, ..] if second & 0xF6 == 0xF0 => "AAC (ADTS)",
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 => "MP3",
+        _ => "unknown",
+    }
+}
+
+/// Decode downloaded audio (MP3, FLAC, Ogg Vorbis, WAV, AAC/MP4)
+fn decode_audio(data: Vec<u8>) -> Result<Decoder<Cursor<Vec<u8>>>, String> {
+    let format = sniff_audio_format(&data);
+    Decoder::new(Cursor::new(data)).map_err(|e| format!("Failed to decode {} audio: {}", format, e))
+}
+
+/// Audio fetched ahead of time for the track that plays next
+struct PrebufferedAudio {
+    url: String,
+    bytes: Vec<u8>,
+}
+
+/// Whether `next` continues the same album as `current`, so the gap between
+/// them should be closed by fetching `next` before `current` ends
+fn is_gapless_transition(current: &Track, next: &Track) -> bool {
+    current.source == next.source
+        && !current.album.trim().is_empty()
+        && current.album == next.album
+        && current.id != next.id
+}
+
 /// Audio player for playback
 #[derive(Clone)]
 pub struct AudioPlayer {
     current_handle: Arc<Mutex<Option<PlaybackHandle>>>,
     /// Store the active librespot player to keep it alive during playback
     active_player: Arc<Mutex<Option<Arc<LibrespotPlayer>>>>,
+    /// Next track's audio, fetched before the current track ends
+    prebuffered: Arc<Mutex<Option<PrebufferedAudio>>>,
 }
 
 /// Queue for managing playback
@@ -276,6 +581,30 @@ pub struct PlaybackQueue {
     /// Shuffle order: maps shuffle position to original queue index
     /// When shuffle is enabled, this array defines the play order
     pub shuffle_order: Vec<usize>,
+    /// Seed for new shuffle orders, so the same queue shuffles the same way.
+    /// `None` shuffles randomly.
+    pub shuffle_seed: Option<u64>,
+    /// Re-rolls since the seed was set, mixed into the seed so each re-roll
+    /// gives a new order
+    pub reshuffle_count: u64,
+}
+
+/// A seed derived from `seed` and `salt`, for shuffles that must differ from
+/// each other while staying reproducible
+fn mix_seed(seed: Option<u64>, salt: u64) -> Option<u64> {
+    seed.map(|seed| seed ^ salt.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+/// Shuffle `indices` with a generator seeded from `seed`, or a random one
+fn shuffle_indices(indices: &mut [usize], seed: Option<u64>) {
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{thread_rng, SeedableRng};
+
+    match seed {
+        Some(seed) => indices.shuffle(&mut StdRng::seed_from_u64(seed)),
+        None => indices.shuffle(&mut thread_rng()),
+    }
 }
 
 impl PlaybackQueue {
@@ -284,6 +613,8 @@ impl PlaybackQueue {
             tracks: Vec::new(),
             current_index: 0,
             shuffle_order: Vec::new(),
+            shuffle_seed: None,
+            reshuffle_count: 0,
         }
     }
 
@@ -318,6 +649,17 @@ impl PlaybackQueue {
         }
     }
 
+    /// The track that plays after the current one, without moving to it
+    pub fn peek_next_shuffled(&self, shuffle_enabled: bool) -> Option<&Track> {
+        if shuffle_enabled && !self.shuffle_order.is_empty() {
+            self.shuffle_order
+                .get(self.current_index + 1)
+                .and_then(|&index| self.tracks.get(index))
+        } else {
+            self.tracks.get(self.current_index + 1)
+        }
+    }
+
     pub fn previous(&mut self) -> Option<&Track> {
         if self.current_index > 0 {
             self.current_index -= 1;
@@ -336,11 +678,18 @@ impl PlaybackQueue {
     }
 
     /// Generate a new shuffle order for the current tracks
-    /// This creates a randomized order of indices from 0..tracks.len()
+    /// This creates a randomized order of indices from 0..tracks.len(),
+    /// reproducible when a shuffle seed is set
     pub fn generate_shuffle_order(&mut self) {
-        use rand::seq::SliceRandom;
-        use rand::thread_rng;
+        self.build_shuffle_order(self.shuffle_seed);
+    }
 
+    /// Generate the shuffle order `seed` gives for the current tracks
+    pub fn generate_shuffle_order_seeded(&mut self, seed: u64) {
+        self.build_shuffle_order(Some(seed));
+    }
+
+    fn build_shuffle_order(&mut self, seed: Option<u64>) {
         let track_count = self.tracks.len();
         if track_count == 0 {
             self.shuffle_order.clear();
@@ -351,13 +700,63 @@ impl PlaybackQueue {
         let mut indices: Vec<usize> = (0..track_count).collect();
 
         // Shuffle the indices
-        let mut rng = thread_rng();
-        indices.shuffle(&mut rng);
+        shuffle_indices(&mut indices, seed);
 
         self.shuffle_order = indices;
         tracing::info!("Generated shuffle order: {:?}", self.shuffle_order);
     }
 
+    /// Generate a shuffle order that starts with the track at `current` and
+    /// randomizes the rest, so shuffling mid-playback doesn't change what's
+    /// playing
+    pub fn generate_shuffle_order_keeping(&mut self, current: usize) {
+        if current >= self.tracks.len() {
+            self.generate_shuffle_order();
+            return;
+        }
+
+        let mut rest: Vec<usize> = (0..self.tracks.len()).filter(|&i| i != current).collect();
+        shuffle_indices(&mut rest, self.shuffle_seed);
+
+        self.shuffle_order = std::iter::once(current).chain(rest).collect();
+        tracing::info!("Generated shuffle order: {:?}", self.shuffle_order);
+    }
+
+    /// Shuffle the tracks after the current one again, leaving the current
+    /// track and those already played where they are. Returns false when
+    /// shuffle is off and there's no shuffle order to redo.
+    pub fn reshuffle_upcoming(&mut self) -> bool {
+        if self.shuffle_order.is_empty() {
+            return false;
+        }
+        self.validate_shuffle_order();
+
+        let start = (self.current_index + 1).min(self.shuffle_order.len());
+        self.reshuffle_count += 1;
+        let seed = mix_seed(self.shuffle_seed, self.reshuffle_count);
+        shuffle_indices(&mut self.shuffle_order[start..], seed);
+        tracing::info!("Reshuffled upcoming tracks: {:?}", self.shuffle_order);
+        true
+    }
+
+    /// Add tracks after everything already queued and return their indices.
+    /// With shuffle on they're shuffled in after the existing shuffle order,
+    /// so what's already coming up doesn't change.
+    pub fn append_tracks(&mut self, tracks: Vec<Track>) -> std::ops::Range<usize> {
+        let start = self.tracks.len();
+        self.tracks.extend(tracks);
+        let added = start..self.tracks.len();
+
+        if !self.shuffle_order.is_empty() {
+            let mut indices: Vec<usize> = added.clone().collect();
+            // Salted with the position so each appended batch differs
+            shuffle_indices(&mut indices, mix_seed(self.shuffle_seed, start as u64));
+            self.shuffle_order.extend(indices);
+        }
+
+        added
+    }
+
     /// Clear the shuffle order (used when shuffle is disabled)
     pub fn clear_shuffle_order(&mut self) {
         self.shuffle_order.clear();
@@ -485,18 +884,57 @@ impl AudioPlayer {
         Self {
             current_handle: Arc::new(Mutex::new(None)),
             active_player: Arc::new(Mutex::new(None)),
+            prebuffered: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Fetch `url` into memory so the next `play_url` for it can start
+    /// decoding immediately
+    pub async fn prebuffer(
+        &self,
+        url: &str,
+        auth_headers: Option<Vec<(String, String)>>,
+    ) -> Result<(), String> {
+        let bytes = tokio::task::spawn_blocking({
+            let url = url.to_string();
+            move || Self::fetch_audio(&url, auth_headers)
+        })
+        .await
+        .map_err(|e| format!("Prebuffer task failed: {}", e))??;
+
+        tracing::debug!("Prebuffered {} bytes from {}", bytes.len(), url);
+        *self.prebuffered.lock().await = Some(PrebufferedAudio {
+            url: url.to_string(),
+            bytes,
+        });
+        Ok(())
+    }
+
+    /// Take the prebuffered audio if it was fetched from `url`. Any other
+    /// prebuffered audio is dropped since the queue has moved on.
+    async fn take_prebuffered(&self, url: &str) -> Option<Vec<u8>> {
+        self.prebuffered
+            .lock()
+            .await
+            .take()
+            .filter(|audio| audio.url == url)
+            .map(|audio| audio.bytes)
+    }
+
     pub async fn play_url(
         &self,
         url: &str,
         auth_headers: Option<Vec<(String, String)>>,
-        volume: u32,
+        volume: Volume,
+        normalization_target: Option<f64>,
+        manual_gain_db: Option<f64>,
     ) -> Result<PlaybackHandle, String> {
         let url = url.to_string();
-        let handle = PlaybackHandle::new();
+        let handle = PlaybackHandle::new()
+            .with_normalization(normalization_target)
+            .with_manual_gain(manual_gain_db);
         let handle_clone = handle.clone();
+        let prefetched = self.take_prebuffered(&url).await;
 
         // Store the handle so we can control playback
         {
@@ -515,7 +953,7 @@ impl AudioPlayer {
             let result = tokio::task::spawn_blocking({
                 let url = url.clone();
                 let handle = handle_clone.clone();
-                move || Self::play_audio_blocking(&url, &handle, auth_headers, volume)
+                move || Self::play_audio_blocking(&url, &handle, auth_headers, volume, prefetched)
             })
             .await;
 
@@ -525,9 +963,11 @@ impl AudioPlayer {
                 }
                 Ok(Err(e)) => {
                     tracing::error!("Audio playback error: {}", e);
+                    handle_clone.fail(e);
                 }
                 Err(e) => {
                     tracing::error!("Task join error: {}", e);
+                    handle_clone.fail(format!("Audio playback task failed: {}", e));
                 }
             }
         });
@@ -539,7 +979,8 @@ impl AudioPlayer {
         url: &str,
         handle: &PlaybackHandle,
         auth_headers: Option<Vec<(String, String)>>,
-        volume: u32,
+        volume: Volume,
+        prefetched: Option<Vec<u8>>,
     ) -> Result<(), String> {
         // Check if URL is a spotify: URI - would require session for full playback
         if url.starts_with("spotify:track:") {
@@ -549,32 +990,35 @@ impl AudioPlayer {
             );
         }
 
-        // Check if URL is valid (should be HTTP(S))
-        if !url.starts_with("http") {
+        // Check if URL is valid (should be HTTP(S) or a downloaded file)
+        if !url.starts_with("http") && !url.starts_with("file://") {
             return Err(format!(
                 "Invalid playback URL format. Expected HTTP URL or spotify: URI, got: {}",
                 url
             ));
         }
 
-        Self::play_http_audio(url, handle, auth_headers, volume)
+        Self::play_http_audio(url, handle, auth_headers, volume, prefetched)
     }
 
-    fn play_http_audio(
+    /// Load audio bytes from an HTTP URL or a `file://` URL of a downloaded track
+    fn fetch_audio(
         url: &str,
-        handle: &PlaybackHandle,
         auth_headers: Option<Vec<(String, String)>>,
-        volume: u32,
-    ) -> Result<(), String> {
-        // Get audio output stream
-        let (_stream, stream_handle) = OutputStream::try_default()
-            .map_err(|e| format!("Failed to get audio output: {}", e))?;
+    ) -> Result<Vec<u8>, String> {
+        if url.starts_with("file://") {
+            let path = url::Url::parse(url)
+                .ok()
+                .and_then(|u| u.to_file_path().ok())
+                .ok_or_else(|| format!("Invalid file URL: {}", url))?;
+            return std::fs::read(&path)
+                .map_err(|e| format!("Failed to read downloaded track: {}", e));
+        }
 
-        // Fetch audio data from URL
-        let client = reqwest::blocking::Client::new();
+        let client = crate::http_client::shared_blocking_client();
         let mut request = client
             .get(url)
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64)");
+            .header("User-Agent", crate::config::client_identity().user_agent);
 
         // Add authentication headers if provided (e.g., for Jellyfin)
         if let Some(headers) = auth_headers {
@@ -590,14 +1034,56 @@ impl AudioPlayer {
         if !response.status().is_success() {
             return Err(format!("Failed to fetch audio: HTTP {}", response.status()));
         }
+        check_audio_content_type(
+            response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok()),
+        )?;
 
         let bytes = response
             .bytes()
             .map_err(|e| format!("Failed to read response body: {}", e))?;
 
+        Ok(bytes.to_vec())
+    }
+
+    fn play_http_audio(
+        url: &str,
+        handle: &PlaybackHandle,
+        auth_headers: Option<Vec<(String, String)>>,
+        volume: Volume,
+        prefetched: Option<Vec<u8>>,
+    ) -> Result<(), String> {
+        // Get audio output stream
+        let (_stream, stream_handle) = OutputStream::try_default()
+            .map_err(|e| format!("Failed to get audio output: {}", e))?;
+
+        let bytes = match prefetched {
+            Some(bytes) => {
+                tracing::info!("Using prebuffered audio for gapless playback");
+                bytes
+            }
+            None => Self::fetch_audio(url, auth_headers)?,
+        };
+
+        // Look up loudness tags before handing the bytes to the decoder
+        let normalization_db = handle.normalization_target().and_then(|target_lufs| {
+            let gain_db = normalization::track_gain_db(&bytes, target_lufs);
+            if gain_db.is_none() {
+                tracing::debug!("No loudness tags found, skipping normalization");
+            }
+            gain_db
+        });
+        if let Some(gain_db) =
+            normalization::combined_gain_db(normalization_db, handle.manual_gain_db())
+        {
+            tracing::info!("Applying {:.2} dB track gain", gain_db);
+            handle.set_gain(normalization::db_to_multiplier(gain_db));
+        }
+
         // Decode audio data
-        let cursor = Cursor::new(bytes.to_vec());
-        let source = Decoder::new(cursor).map_err(|e| format!("Failed to decode audio: {}", e))?;
+        let source = decode_audio(bytes)?;
 
         // Get duration
         let duration_secs = source
@@ -621,11 +1107,12 @@ impl AudioPlayer {
             runtime.block_on(handle.set_sink(sink_handle.clone()));
         });
 
-        // Apply initial volume (0-100 scale converted to 0.0-1.0)
-        let volume_f32 = (volume.min(100) as f32) / 100.0;
+        // Apply initial volume (0-100 scale converted to 0.0-1.0, times track gain)
+        let volume_f32 = handle.effective_volume(volume);
+        handle.set_target_volume(volume_f32);
         if let Ok(s) = sink_handle.try_lock() {
             s.set_volume(volume_f32);
-            tracing::info!("Set initial volume to {} ({}%)", volume_f32, volume);
+            tracing::info!("Set initial volume to {} ({})", volume_f32, volume);
         }
 
         // Check if we should start paused (for restore scenarios)
@@ -656,6 +1143,7 @@ impl AudioPlayer {
         } else if let Ok(s) = sink_handle.try_lock() {
             s.append(source);
         }
+        handle.mark_started();
 
         // Track playback progress - initialize from handle position for restore support
         let start = Instant::now();
@@ -722,7 +1210,7 @@ impl AudioPlayer {
             // Stop if we've reached the end or duration is exceeded
             if elapsed >= duration_secs && duration_secs > 0 {
                 tracing::info!("Track playback completed based on duration");
-                handle.stop();
+                handle.complete();
                 break;
             }
         }
@@ -850,7 +1338,7 @@ impl AudioPlayer {
                                 &format!("spotify:track:{}", track_id_for_fetch),
                                 &handle_clone,
                                 None,
-                                100, // Default volume for simulation
+                                Volume::new(Volume::MAX), // Default volume for simulation
                             )
                             .await?;
                         }
@@ -866,6 +1354,7 @@ impl AudioPlayer {
                 }
                 Err(e) => {
                     tracing::error!("Spotify playback error: {}", e);
+                    handle_clone.fail(e);
                 }
             }
         });
@@ -1111,7 +1600,7 @@ impl AudioPlayer {
                 let duration_ms = handle_clone.get_duration();
                 if duration_ms > 0 && position_ms >= duration_ms {
                     tracing::info!("Track playback completed based on duration");
-                    handle_clone.stop();
+                    handle_clone.complete();
                     break;
                 }
 
@@ -1121,7 +1610,7 @@ impl AudioPlayer {
                     if active_lock.is_none() {
                         tracing::warn!("Player no longer active (error or stopped externally)");
                         // Set stop flag so monitoring task can detect completion
-                        handle_clone.stop();
+                        handle_clone.complete();
                         break;
                     }
                 }
@@ -1145,7 +1634,7 @@ impl AudioPlayer {
         url: &str,
         handle: &PlaybackHandle,
         auth_headers: Option<Vec<(String, String)>>,
-        volume: u32,
+        volume: Volume,
     ) -> Result<(), String> {
         // For Spotify URIs, we provide full-track duration simulation
         // In a full implementation with real librespot, this would stream actual audio
@@ -1157,7 +1646,7 @@ impl AudioPlayer {
             let handle_clone = handle.clone();
 
             tokio::task::spawn_blocking(move || {
-                Self::play_http_audio(&url_copy, &handle_clone, auth_headers, volume)
+                Self::play_http_audio(&url_copy, &handle_clone, auth_headers, volume, None)
             })
             .await
             .map_err(|e| format!("Playback task failed: {}", e))?
@@ -1193,7 +1682,7 @@ impl AudioPlayer {
             // Check if we've reached the end of track duration
             if duration > 0 && elapsed_ms >= duration {
                 tracing::info!("Track playback completed");
-                handle.stop();
+                handle.complete();
                 break;
             }
 
@@ -1204,9 +1693,10 @@ impl AudioPlayer {
         Ok(())
     }
 
-    pub async fn pause(&self) -> Result<(), String> {
+    /// Pause the current stream, fading out over `fade`
+    pub async fn pause(&self, fade: Duration) -> Result<(), String> {
         if let Some(handle) = &*self.current_handle.lock().await {
-            handle.pause();
+            handle.fade_out_and_pause(fade);
             tracing::info!("Pausing playback");
             Ok(())
         } else {
@@ -1214,9 +1704,10 @@ impl AudioPlayer {
         }
     }
 
-    pub async fn resume(&self) -> Result<(), String> {
+    /// Resume the current stream, fading in over `fade`
+    pub async fn resume(&self, fade: Duration) -> Result<(), String> {
         if let Some(handle) = &*self.current_handle.lock().await {
-            handle.resume();
+            handle.resume_with_fade(fade);
             tracing::info!("Resuming playback");
             Ok(())
         } else {
@@ -1246,6 +1737,67 @@ impl Default for AudioPlayer {
 }
 
 /// Playback manager - handles playback state and queue
+/// Interval between position updates sent to playback event listeners
+const POSITION_EVENT_INTERVAL_MS: u64 = 1000;
+
+/// Minimum time between state saves triggered by playback changes
+const STATE_SAVE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Default interval between periodic playback state saves
+pub const DEFAULT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Sender for playback change notifications.
+///
+/// A snapshot of the playback info is sent whenever the track, playback state
+/// or settings change, and roughly once per second while a track is playing.
+pub type PlaybackEventSender = mpsc::UnboundedSender<PlaybackInfo>;
+
+/// Sender for tracks that have played long enough to count as played.
+///
+/// Each playthrough of a track is sent once, for scrobbling, play counts and
+/// server progress reporting to share one definition of "played".
+pub type TrackPlayedSender = mpsc::UnboundedSender<Track>;
+
+/// Position after which any track counts as played, however long it is
+pub const PLAYED_MAX_POSITION: Duration = Duration::from_secs(240);
+
+/// Position in milliseconds at which a track of `duration_ms` counts as
+/// played: halfway through, or four minutes in for long tracks and tracks
+/// of unknown length
+pub fn played_threshold_ms(duration_ms: u64) -> u64 {
+    let max = PLAYED_MAX_POSITION.as_millis() as u64;
+    if duration_ms == 0 {
+        max
+    } else {
+        (duration_ms / 2).min(max)
+    }
+}
+
+/// Default position after which "previous" restarts the current track
+pub const DEFAULT_PREVIOUS_RESTART_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// Default number of upcoming tracks fetched ahead of playback
+pub const DEFAULT_ENRICH_LOOKAHEAD: usize = 10;
+
+/// Stream URLs fetched longer ago than this are re-fetched before playing
+const STREAM_URL_MAX_AGE_SECS: i64 = 30 * 60;
+
+/// How long before the end of a track the next track of the same album is
+/// fetched, so consecutive album tracks play without a gap
+const GAPLESS_PREBUFFER_LEAD_MS: u64 = 10_000;
+
+/// Unplayable tracks skipped in a row before playback gives up
+const MAX_CONSECUTIVE_FAILURES: usize = 3;
+
+/// Progress through a run of tracks that failed to play
+#[derive(Debug, Default)]
+struct FailureRecovery {
+    /// Track that has already been retried with a refreshed stream URL
+    refreshed: Option<String>,
+    /// Tracks skipped since a track last played
+    skipped: Vec<Track>,
+}
+
 pub struct PlaybackManager {
     queue: Arc<Mutex<PlaybackQueue>>,
     info: Arc<Mutex<PlaybackInfo>>,
@@ -1254,9 +1806,32 @@ pub struct PlaybackManager {
     providers: Arc<Mutex<ProviderRegistry>>,
     track_complete_tx: mpsc::UnboundedSender<()>,
     track_complete_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<()>>>>,
+    track_failed_tx: mpsc::UnboundedSender<()>,
+    track_failed_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<()>>>>,
+    failure_recovery: Arc<Mutex<FailureRecovery>>,
     monitoring_task_abort: Arc<Mutex<Option<tokio::task::AbortHandle>>>,
     state_save_tx: mpsc::UnboundedSender<()>,
     state_save_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<()>>>>,
+    event_tx: Option<PlaybackEventSender>,
+    /// Receives each track once it counts as played
+    played_tx: Option<TrackPlayedSender>,
+    /// Target loudness in LUFS for HTTP tracks, when normalization is enabled
+    volume_normalization: Option<f64>,
+    /// Database used to play local copies of tracks when available and to
+    /// look up manual per-track gain
+    downloads: Option<Arc<Mutex<Database>>>,
+    /// Interval between periodic state saves, or `None` to only save on changes
+    autosave_interval: Option<Duration>,
+    /// Position after which "previous" restarts the current track instead
+    previous_restart_threshold: Duration,
+    /// Volume ramp length around pause and resume; zero disables fading
+    fade_duration: Duration,
+    /// Number of upcoming tracks to fetch details for ahead of playback
+    enrich_lookahead: usize,
+    /// Pause between lookahead fetches
+    enrich_delay: Duration,
+    /// Tracks currently being enriched, so overlapping runs don't fetch them twice
+    enriching: Arc<Mutex<HashSet<(crate::models::Source, String)>>>,
 }
 
 impl PlaybackManager {
@@ -1264,6 +1839,9 @@ impl PlaybackManager {
         // Create a channel for track completion events
         let (track_complete_tx, track_complete_rx) = mpsc::unbounded_channel::<()>();
 
+        // Create a channel for tracks that fail to play
+        let (track_failed_tx, track_failed_rx) = mpsc::unbounded_channel::<()>();
+
         // Create a channel for state save requests
         let (state_save_tx, state_save_rx) = mpsc::unbounded_channel::<()>();
 
@@ -1275,105 +1853,467 @@ impl PlaybackManager {
             providers,
             track_complete_tx,
             track_complete_rx: Arc::new(Mutex::new(Some(track_complete_rx))),
+            track_failed_tx,
+            track_failed_rx: Arc::new(Mutex::new(Some(track_failed_rx))),
+            failure_recovery: Arc::new(Mutex::new(FailureRecovery::default())),
             monitoring_task_abort: Arc::new(Mutex::new(None)),
             state_save_tx,
             state_save_rx: Arc::new(Mutex::new(Some(state_save_rx))),
+            event_tx: None,
+            played_tx: None,
+            volume_normalization: None,
+            downloads: None,
+            autosave_interval: Some(DEFAULT_AUTOSAVE_INTERVAL),
+            previous_restart_threshold: DEFAULT_PREVIOUS_RESTART_THRESHOLD,
+            fade_duration: DEFAULT_FADE_DURATION,
+            enrich_lookahead: DEFAULT_ENRICH_LOOKAHEAD,
+            enrich_delay: Duration::ZERO,
+            enriching: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
-    /// Start the state saver task - must be called from a Tokio runtime context
-    pub async fn start_state_saver(&self) {
-        if let Some(state_save_rx) = self.state_save_rx.lock().await.take() {
-            let info_clone = self.info.clone();
-            let queue_clone = self.queue.clone();
-            tokio::spawn(Self::state_saver_task(
-                info_clone,
-                queue_clone,
-                state_save_rx,
-            ));
-        }
+    /// Prefer downloaded copies recorded in `database` when playing tracks
+    pub fn set_download_index(&mut self, database: Arc<Mutex<Database>>) {
+        self.downloads = Some(database);
     }
 
-    /// Centralized state saver task with debouncing
-    /// Saves state at most once every 5 seconds, even if requested more frequently
-    async fn state_saver_task(
-        info: Arc<Mutex<PlaybackInfo>>,
-        queue: Arc<Mutex<PlaybackQueue>>,
-        mut save_rx: mpsc::UnboundedReceiver<()>,
-    ) {
-        let mut last_save = Instant::now();
-        const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+    /// Set how often the state saver writes state while nothing else asks it
+    /// to; takes effect when the state saver starts
+    pub fn set_autosave_interval(&mut self, interval: Option<Duration>) {
+        self.autosave_interval = interval;
+    }
 
-        loop {
-            // Wait for save request or timeout
-            match tokio::time::timeout(SAVE_INTERVAL, save_rx.recv()).await {
-                Ok(Some(())) => {
-                    // Request received, check if enough time has passed
-                    if last_save.elapsed() >= SAVE_INTERVAL {
-                        Self::perform_state_save(&info, &queue).await;
-                        last_save = Instant::now();
-                    }
-                    // If not enough time has passed, the request is dropped (debounced)
-                }
-                Ok(None) => {
-                    // Channel closed, exit the task
-                    tracing::info!("State saver task exiting (channel closed)");
-                    break;
-                }
-                Err(_) => {
-                    // Timeout, perform periodic save if state might have changed
-                    // This ensures we save even during continuous playback
-                    if last_save.elapsed() >= SAVE_INTERVAL {
-                        Self::perform_state_save(&info, &queue).await;
-                        last_save = Instant::now();
-                    }
-                }
-            }
+    /// `file://` URL of the downloaded copy of `track`, if there is one on disk
+    async fn local_copy(&self, track: &Track) -> Option<String> {
+        let database = self.downloads.as_ref()?;
+        let download = database
+            .lock()
+            .await
+            .get_download(&track.source.to_string(), &track.id)
+            .ok()
+            .flatten()?;
+        let path = std::path::Path::new(&download.local_path);
+        if !path.exists() {
+            tracing::warn!(
+                "Downloaded copy of {} is missing: {}",
+                track.title,
+                download.local_path
+            );
+            return None;
         }
+        crate::offline::file_url(path)
     }
 
-    /// Perform the actual state save operation
-    async fn perform_state_save(
-        info: &Arc<Mutex<PlaybackInfo>>,
-        queue: &Arc<Mutex<PlaybackQueue>>,
-    ) {
-        use crate::state::PersistentPlaybackState;
+    /// User's stored gain offset in dB for `track`, if any
+    async fn manual_gain(&self, track: &Track) -> Option<f64> {
+        let database = self.downloads.as_ref()?;
+        let gain = database
+            .lock()
+            .await
+            .get_track_gain(&track.source.to_string(), &track.id);
+        match gain {
+            Ok(gain) => gain,
+            Err(e) => {
+                tracing::warn!("Failed to look up gain for {}: {}", track.title, e);
+                None
+            }
+        }
+    }
 
-        let info_locked = info.lock().await;
-        let queue_locked = queue.lock().await;
+    /// Enable loudness normalization to `target_lufs` for tracks started afterwards
+    pub fn set_volume_normalization(&mut self, target_lufs: Option<f64>) {
+        self.volume_normalization = target_lufs;
+    }
 
-        let state = PersistentPlaybackState {
-            current_track: info_locked.current_track.clone(),
-            queue: queue_locked.tracks.clone(),
-            current_index: queue_locked.current_index,
-            position_ms: info_locked.position_ms,
-            shuffle: info_locked.shuffle,
-            repeat_mode: info_locked.repeat_mode,
-            volume: info_locked.volume,
-            shuffle_order: queue_locked.shuffle_order.clone(),
-            state: info_locked.state,
-        };
+    /// Set how far into a track "previous" restarts it rather than going back
+    pub fn set_previous_restart_threshold(&mut self, threshold: Duration) {
+        self.previous_restart_threshold = threshold;
+    }
 
-        drop(info_locked);
-        drop(queue_locked);
+    /// Fade the volume over `duration` when pausing and resuming; zero
+    /// pauses and resumes abruptly
+    pub fn set_fade_duration(&mut self, duration: Duration) {
+        self.fade_duration = duration;
+    }
 
-        if let Err(e) = state.save().await {
-            tracing::error!("Failed to save state: {}", e);
+    /// Volume a fresh session starts at; restoring saved state replaces it
+    pub fn set_default_volume(&mut self, volume: Volume) {
+        match self.info.try_lock() {
+            Ok(mut info) => info.volume = volume,
+            Err(_) => tracing::warn!("Playback info is in use, default volume not applied"),
         }
     }
 
-    /// Request a state save (debounced)
-    pub fn request_state_save(&self) {
-        // Ignore send errors - if the channel is closed, we're shutting down anyway
-        let _ = self.state_save_tx.send(());
+    /// Send tracks to `played_tx` once they've played past the threshold
+    pub fn set_played_sender(&mut self, played_tx: TrackPlayedSender) {
+        self.played_tx = Some(played_tx);
     }
 
-    /// Take the track completion receiver.
-    ///
-    /// This *must* be called exactly once during application setup to start
-    /// receiving track completion events. Calling it more than once will
-    /// return `None` and is considered a programming error.
-    ///
+    /// Set how many upcoming tracks are enriched ahead of playback and how
+    /// long to wait between fetches
+    pub fn set_enrichment(&mut self, lookahead: usize, delay: Duration) {
+        self.enrich_lookahead = lookahead;
+        self.enrich_delay = delay;
+    }
+
+    /// Lookahead depth and delay used when enriching upcoming tracks
+    pub fn enrichment(&self) -> (usize, Duration) {
+        (self.enrich_lookahead, self.enrich_delay)
+    }
+
+    /// Tracks being enriched right now, shared by every enrichment run
+    pub fn enrichment_in_flight(&self) -> Arc<Mutex<HashSet<(crate::models::Source, String)>>> {
+        Arc::clone(&self.enriching)
+    }
+
+    /// Create a manager that reports playback changes through `event_tx`
+    ///
+    /// Keeps the playback layer independent of Tauri: the app bridges the
+    /// receiving end to frontend events.
+    pub fn with_event_sender(
+        providers: Arc<Mutex<ProviderRegistry>>,
+        event_tx: PlaybackEventSender,
+    ) -> Self {
+        let mut manager = Self::new(providers);
+        manager.event_tx = Some(event_tx);
+        manager
+    }
+
+    /// Build a snapshot of the playback info including the current queue
+    async fn snapshot_info(
+        info: &Arc<Mutex<PlaybackInfo>>,
+        queue: &Arc<Mutex<PlaybackQueue>>,
+    ) -> PlaybackInfo {
+        let mut info = info.lock().await.clone();
+        let queue = queue.lock().await;
+        info.queue = queue.tracks.clone();
+        info.current_index = queue.current_index;
+        info.shuffle_order = queue.shuffle_order.clone();
+        info
+    }
+
+    /// Send a playback change notification if a listener is attached
+    async fn notify_change(
+        event_tx: &Option<PlaybackEventSender>,
+        info: &Arc<Mutex<PlaybackInfo>>,
+        queue: &Arc<Mutex<PlaybackQueue>>,
+    ) {
+        if let Some(tx) = event_tx {
+            // Ignore send errors - the listener may have shut down
+            let _ = tx.send(Self::snapshot_info(info, queue).await);
+        }
+    }
+
+    /// Notify listeners that playback changed
+    async fn emit_change(&self) {
+        Self::notify_change(&self.event_tx, &self.info, &self.queue).await;
+    }
+
+    /// Spawn a task that mirrors a playback handle's position and state into
+    /// the playback info, notifies listeners, and signals track completion.
+    ///
+    /// The task's abort handle is stored so the next track can cancel it.
+    async fn spawn_monitor(&self, handle: PlaybackHandle, label: &'static str) {
+        let info_arc = self.info.clone();
+        let queue_arc = self.queue.clone();
+        let track_complete_tx = self.track_complete_tx.clone();
+        let track_failed_tx = self.track_failed_tx.clone();
+        let failure_recovery = self.failure_recovery.clone();
+        let state_save_tx = self.state_save_tx.clone();
+        let event_tx = self.event_tx.clone();
+        let played_tx = self.played_tx.clone();
+        let providers = self.providers.clone();
+        let audio_player = self.audio_player.clone();
+
+        let mut progress_rx = handle.subscribe();
+        // A track resumed past the threshold was counted when it got there
+        let mut played_reported = {
+            let progress = *progress_rx.borrow();
+            progress.position_ms >= played_threshold_ms(progress.duration_ms)
+        };
+        let task = tokio::spawn(async move {
+            tracing::debug!("{} monitoring task started", label);
+            let mut last_state_save = std::time::Instant::now();
+            let mut last_position_event = u64::MAX;
+            let mut started = false;
+            let mut prebuffer_checked = false;
+            loop {
+                let PlaybackProgress {
+                    position_ms: position,
+                    duration_ms: duration,
+                } = *progress_rx.borrow_and_update();
+                let should_stop = handle.should_stop();
+                let is_paused = handle.is_paused();
+
+                // Audio is coming out, so any run of failed tracks is over
+                if !started && (position > 0 || handle.has_started()) {
+                    started = true;
+                    *failure_recovery.lock().await = FailureRecovery::default();
+                }
+
+                let state_changed = {
+                    let mut info = info_arc.lock().await;
+                    let previous_state = info.state;
+                    info.position_ms = position;
+                    if duration > 0 && info.current_track.is_some() {
+                        info.current_track.as_mut().unwrap().duration_ms = duration;
+                    }
+
+                    // Update playback state based on pause status; until the
+                    // audio reaches the sink the track stays loading
+                    if is_paused {
+                        info.state = PlaybackState::Paused;
+                    } else if !should_stop && started {
+                        info.state = PlaybackState::Playing;
+                    }
+                    info.state != previous_state
+                };
+
+                if !played_reported
+                    && started
+                    && !is_paused
+                    && position >= played_threshold_ms(duration)
+                {
+                    played_reported = true;
+                    let track = info_arc.lock().await.current_track.clone();
+                    if let (Some(tx), Some(track)) = (&played_tx, track) {
+                        tracing::debug!("{} track played: {}", label, track.title);
+                        let _ = tx.send(track);
+                    }
+                }
+
+                // Fetch the next track ahead of time if it continues this album
+                if !prebuffer_checked
+                    && duration > 0
+                    && duration.saturating_sub(position) <= GAPLESS_PREBUFFER_LEAD_MS
+                {
+                    prebuffer_checked = true;
+                    tokio::spawn(Self::prebuffer_gapless_next(
+                        queue_arc.clone(),
+                        info_arc.clone(),
+                        providers.clone(),
+                        audio_player.clone(),
+                    ));
+                }
+
+                // Notify listeners on state changes and position milestones
+                let position_bucket = position / POSITION_EVENT_INTERVAL_MS;
+                if state_changed || position_bucket != last_position_event {
+                    last_position_event = position_bucket;
+                    Self::notify_change(&event_tx, &info_arc, &queue_arc).await;
+                }
+
+                // Request state save periodically (every 5 seconds)
+                if last_state_save.elapsed().as_secs() >= 5 {
+                    let _ = state_save_tx.send(());
+                    last_state_save = std::time::Instant::now();
+                }
+
+                // A failed stream stops without advancing so the error stays visible
+                if let Some(error) = should_stop.then(|| handle.error()).flatten() {
+                    tracing::warn!("{} playback failed: {}", label, error);
+                    {
+                        let mut info = info_arc.lock().await;
+                        info.state = PlaybackState::Stopped;
+                        info.error = Some(error);
+                    }
+                    Self::notify_change(&event_tx, &info_arc, &queue_arc).await;
+                    let _ = track_failed_tx.send(());
+                    break;
+                }
+
+                // Stopping or switching tracks is handled by whoever stopped it
+                if should_stop && handle.stop_reason() == Some(StopReason::User) {
+                    tracing::debug!("{} playback stopped by user, not advancing", label);
+                    break;
+                }
+
+                // When track completes, send event to advance to next track
+                if should_stop {
+                    tracing::debug!("{} monitoring task detected should_stop=true", label);
+                    {
+                        let mut info = info_arc.lock().await;
+                        info.state = PlaybackState::Stopped;
+                    }
+                    Self::notify_change(&event_tx, &info_arc, &queue_arc).await;
+
+                    tracing::info!("{} track completed, sending auto-advance event", label);
+                    let _ = track_complete_tx.send(());
+                    break;
+                }
+
+                // Sleep until the audio thread publishes progress or a state change
+                if progress_rx.changed().await.is_err() {
+                    tracing::debug!("{} playback handle dropped, stopping monitor", label);
+                    break;
+                }
+            }
+        });
+
+        // Store the abort handle immediately to prevent race conditions
+        // This ensures the task can be aborted before completion
+        let mut abort_handle = self.monitoring_task_abort.lock().await;
+        *abort_handle = Some(task.abort_handle());
+    }
+
+    /// Prebuffer the next queued track when it continues the current track's
+    /// album. Returns whether prebuffering succeeded.
+    async fn prebuffer_gapless_next(
+        queue: Arc<Mutex<PlaybackQueue>>,
+        info: Arc<Mutex<PlaybackInfo>>,
+        providers: Arc<Mutex<ProviderRegistry>>,
+        audio_player: Arc<AudioPlayer>,
+    ) -> bool {
+        let (current, shuffle_enabled) = {
+            let info = info.lock().await;
+            (info.current_track.clone(), info.shuffle)
+        };
+        let Some(current) = current else {
+            return false;
+        };
+        let next = queue
+            .lock()
+            .await
+            .peek_next_shuffled(shuffle_enabled)
+            .cloned();
+        let Some(next) = next.filter(|next| is_gapless_transition(&current, next)) else {
+            return false;
+        };
+
+        // Spotify audio comes from librespot, and stale URLs are re-fetched
+        // by play_track, so only fresh HTTP or local URLs can be prebuffered
+        let url = match &next.url {
+            Some(url) if url.starts_with("http") || url.starts_with("file://") => url.clone(),
+            _ => return false,
+        };
+        if next.needs_refresh(chrono::Utc::now().timestamp(), STREAM_URL_MAX_AGE_SECS) {
+            return false;
+        }
+
+        let auth_headers = if next.source == crate::models::Source::Jellyfin {
+            let providers = providers.lock().await;
+            providers.get_auth_headers(next.source).await
+        } else {
+            next.auth_headers.clone()
+        };
+
+        tracing::info!(
+            "Prebuffering next album track: {} - {}",
+            next.artist,
+            next.title
+        );
+        match audio_player.prebuffer(&url, auth_headers).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Failed to prebuffer {}: {}", next.title, e);
+                false
+            }
+        }
+    }
+
+    /// Start the state saver task - must be called from a Tokio runtime context
+    pub async fn start_state_saver(&self) {
+        if let Some(state_save_rx) = self.state_save_rx.lock().await.take() {
+            let info_clone = self.info.clone();
+            let queue_clone = self.queue.clone();
+            tokio::spawn(Self::state_saver_task(
+                info_clone,
+                queue_clone,
+                state_save_rx,
+                self.autosave_interval,
+            ));
+        }
+    }
+
+    /// Centralized state saver task with debouncing
+    ///
+    /// Requested saves happen at most once every 5 seconds; a request that
+    /// arrives sooner is deferred until the debounce window ends. When
+    /// `autosave_interval` is set, state is also saved whenever that long
+    /// passes without a save, so a crash loses at most one interval of
+    /// progress.
+    async fn state_saver_task(
+        info: Arc<Mutex<PlaybackInfo>>,
+        queue: Arc<Mutex<PlaybackQueue>>,
+        mut save_rx: mpsc::UnboundedReceiver<()>,
+        autosave_interval: Option<Duration>,
+    ) {
+        let mut last_save = Instant::now();
+        let mut pending = false;
+
+        loop {
+            // Wake for the next request, or when a deferred or periodic save is due
+            let deferred = pending.then(|| STATE_SAVE_DEBOUNCE.saturating_sub(last_save.elapsed()));
+            let periodic =
+                autosave_interval.map(|interval| interval.saturating_sub(last_save.elapsed()));
+            let wait = match (deferred, periodic) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            let received = match wait {
+                Some(wait) => tokio::time::timeout(wait, save_rx.recv()).await,
+                None => Ok(save_rx.recv().await),
+            };
+
+            match received {
+                Ok(Some(())) => {
+                    // Request received, check if enough time has passed
+                    if last_save.elapsed() >= STATE_SAVE_DEBOUNCE {
+                        Self::perform_state_save(&info, &queue).await;
+                        last_save = Instant::now();
+                        pending = false;
+                    } else {
+                        pending = true;
+                    }
+                }
+                Ok(None) => {
+                    // Channel closed, exit the task
+                    tracing::info!("State saver task exiting (channel closed)");
+                    break;
+                }
+                Err(_) => {
+                    // Timeout, save the deferred request or the periodic snapshot
+                    // This ensures we save even during continuous playback
+                    Self::perform_state_save(&info, &queue).await;
+                    last_save = Instant::now();
+                    pending = false;
+                }
+            }
+        }
+    }
+
+    /// Perform the actual state save operation
+    async fn perform_state_save(
+        info: &Arc<Mutex<PlaybackInfo>>,
+        queue: &Arc<Mutex<PlaybackQueue>>,
+    ) {
+        use crate::state::PersistentPlaybackState;
+
+        let info_locked = info.lock().await;
+        let queue_locked = queue.lock().await;
+
+        let state = PersistentPlaybackState::capture(&info_locked, &queue_locked);
+
+        drop(info_locked);
+        drop(queue_locked);
+
+        if let Err(e) = state.save().await {
+            tracing::error!("Failed to save state: {}", e);
+        }
+    }
+
+    /// Request a state save (debounced)
+    pub fn request_state_save(&self) {
+        // Ignore send errors - if the channel is closed, we're shutting down anyway
+        let _ = self.state_save_tx.send(());
+    }
+
+    /// Take the track completion receiver.
+    ///
+    /// This *must* be called exactly once during application setup to start
+    /// receiving track completion events. Calling it more than once will
+    /// return `None` and is considered a programming error.
+    ///
     /// # Usage
     /// Call this method once during application initialization to get the receiver,
     /// then use it to listen for track completion events and trigger auto-advance.
@@ -1382,27 +2322,184 @@ impl PlaybackManager {
         rx_opt.take()
     }
 
-    /// Set current track and start playing
-    pub async fn play_track(&self, track: Track) {
-        tracing::info!("play_track called for: {} ({})", track.title, track.id);
+    /// Stop the current audio without signalling track completion
+    ///
+    /// The monitoring task is aborted first so stopping the handle doesn't
+    /// look like the track finished and trigger auto-advance.
+    async fn stop_audio(&self) {
+        if let Some(handle) = self.monitoring_task_abort.lock().await.take() {
+            handle.abort();
+            tracing::debug!("Aborted monitoring task");
+        }
+        let _ = self.audio_player.stop().await;
+    }
+
+    /// Mark the current track as stopped because it couldn't be played
+    async fn fail_playback(&self, message: String) {
+        self.record_error(message).await;
+        let _ = self.track_failed_tx.send(());
+    }
 
-        // Abort any existing monitoring task before starting a new one
+    /// Stop and show `message` without triggering failure recovery
+    async fn record_error(&self, message: String) {
         {
-            let mut abort_handle = self.monitoring_task_abort.lock().await;
-            if let Some(handle) = abort_handle.take() {
-                handle.abort();
-                tracing::debug!(
-                    "Aborted previous monitoring task for new track: {}",
-                    track.title
-                );
+            let mut info = self.info.lock().await;
+            info.state = PlaybackState::Stopped;
+            info.error = Some(message);
+        }
+        self.emit_change().await;
+    }
+
+    /// Fetch fresh details (stream URL, auth) for a provider-backed track
+    async fn refetch_track(&self, track: &Track) -> Option<Track> {
+        if track.source == crate::models::Source::Custom {
+            return None;
+        }
+        let result = {
+            let providers = self.providers.lock().await;
+            providers.get_track(track.source, &track.id).await
+        };
+        match result {
+            Ok(fresh) => Some(fresh),
+            Err(e) => {
+                tracing::warn!("Failed to refresh track {}: {}", track.id, e);
+                None
+            }
+        }
+    }
+
+    /// `track` ready to play, with the URL of its downloaded copy if there is
+    /// one. Without a download, a stale stream URL is re-fetched first, since
+    /// stream URLs and tokens expire.
+    async fn prepare_track(&self, track: Track) -> (Track, Option<String>) {
+        let local_copy = self.local_copy(&track).await;
+        if local_copy.is_some()
+            || !track.needs_refresh(chrono::Utc::now().timestamp(), STREAM_URL_MAX_AGE_SECS)
+        {
+            return (track, local_copy);
+        }
+
+        match self.refetch_track(&track).await {
+            Some(fresh) => {
+                tracing::info!("Refreshed stale stream URL for: {}", track.title);
+                self.replace_queued_track(&fresh).await;
+                (fresh, None)
+            }
+            None => (track, None),
+        }
+    }
+
+    /// Swap a re-fetched track into the queue in place of its stale copy
+    async fn replace_queued_track(&self, fresh: &Track) {
+        let mut queue = self.queue.lock().await;
+        if let Some(queued) = queue.tracks.iter_mut().find(|t| t.id == fresh.id) {
+            *queued = fresh.clone();
+        }
+    }
+
+    /// Recover after the current track failed to play
+    ///
+    /// The track is retried once with a refreshed stream URL, since an
+    /// expired URL or token is the usual cause. After that it is skipped,
+    /// until `MAX_CONSECUTIVE_FAILURES` tracks in a row have failed. Returns
+    /// the tracks skipped so far when this call skipped one.
+    pub async fn recover_from_failure(&self) -> Option<Vec<Track>> {
+        let track = self.info.lock().await.current_track.clone()?;
+
+        let should_refresh = {
+            let mut recovery = self.failure_recovery.lock().await;
+            let should_refresh = recovery.refreshed.as_deref() != Some(track.id.as_str());
+            recovery.refreshed = Some(track.id.clone());
+            should_refresh
+        };
+        if should_refresh {
+            if let Some(fresh) = self.refetch_track(&track).await {
+                tracing::info!("Retrying {} with a refreshed stream URL", track.title);
+                self.replace_queued_track(&fresh).await;
+                self.play_track(fresh).await;
+                return None;
             }
         }
 
+        let skipped = {
+            let mut recovery = self.failure_recovery.lock().await;
+            recovery.refreshed = None;
+            recovery.skipped.push(track);
+            let skipped = recovery.skipped.clone();
+            if skipped.len() >= MAX_CONSECUTIVE_FAILURES {
+                recovery.skipped.clear();
+            }
+            skipped
+        };
+
+        if skipped.len() >= MAX_CONSECUTIVE_FAILURES {
+            tracing::warn!("{} tracks in a row failed to play, stopping", skipped.len());
+            self.record_error(format!(
+                "Stopped after {} tracks in a row failed to play",
+                skipped.len()
+            ))
+            .await;
+        } else {
+            tracing::info!(
+                "Skipping unplayable track: {}",
+                skipped[skipped.len() - 1].title
+            );
+            if self.next_track().await.is_none() {
+                self.failure_recovery.lock().await.skipped.clear();
+            }
+        }
+
+        Some(skipped)
+    }
+
+    /// Keep playback going past tracks that fail to play
+    ///
+    /// Runs until the manager is dropped, so spawn it once during setup.
+    /// `on_skip` receives the tracks skipped so far each time one is skipped.
+    pub async fn run_failure_recovery<F>(playback: Arc<Mutex<PlaybackManager>>, on_skip: F)
+    where
+        F: Fn(&[Track]) + Send + 'static,
+    {
+        let failed_rx = {
+            let manager = playback.lock().await;
+            let mut failed_rx = manager.track_failed_rx.lock().await;
+            failed_rx.take()
+        };
+        let Some(mut failed_rx) = failed_rx else {
+            tracing::error!("Failure recovery is already running");
+            return;
+        };
+
+        while let Some(()) = failed_rx.recv().await {
+            let skipped = playback.lock().await.recover_from_failure().await;
+            if let Some(skipped) = skipped {
+                on_skip(&skipped);
+            }
+        }
+    }
+
+    /// Set current track and start playing
+    pub async fn play_track(&self, track: Track) {
+        tracing::info!("play_track called for: {} ({})", track.title, track.id);
+
+        // Stop the previous track up front so it doesn't keep playing if this
+        // one can't start (no URL, Spotify session not ready)
+        self.stop_audio().await;
+
+        let (track, local_copy) = self.prepare_track(track).await;
+
         // Update queue's current_index if this track is in the queue
         {
+            let shuffle_enabled = self.info.lock().await.shuffle;
             let mut queue = self.queue.lock().await;
             // Find the track in the queue and set current_index
-            if let Some(index) = queue.tracks.iter().position(|t| t.id == track.id) {
+            if let Some(mut index) = queue.tracks.iter().position(|t| t.id == track.id) {
+                // In shuffle mode current_index is a position in the shuffle order
+                if shuffle_enabled {
+                    if let Some(position) = queue.shuffle_order.iter().position(|&i| i == index) {
+                        index = position;
+                    }
+                }
                 queue.current_index = index;
                 tracing::debug!(
                     "Set queue current_index to {} for track: {}",
@@ -1414,15 +2511,47 @@ impl PlaybackManager {
 
         let mut info = self.info.lock().await;
         info.current_track = Some(track.clone());
-        info.state = PlaybackState::Playing;
+        info.state = PlaybackState::Loading;
         info.position_ms = 0;
+        info.error = None;
         drop(info); // Release the lock
 
         // Save state AFTER track info is updated
         let _ = self.save_state().await;
+        self.emit_change().await;
 
-        // Attempt to play the audio
-        if let Some(url) = &track.url {
+        // Attempt to play the audio, preferring a downloaded copy
+        let url = match local_copy {
+            Some(local_url) => {
+                tracing::info!("Playing downloaded copy of: {}", track.title);
+                Some(local_url)
+            }
+            None if track.source == crate::models::Source::Spotify => {
+                let is_premium = {
+                    let providers = self.providers.lock().await;
+                    providers.is_spotify_premium().await.unwrap_or(false)
+                };
+                let preview = track.url.as_deref().filter(|url| url.starts_with("http"));
+                let session_ready = self.spotify_session.is_initialized().await;
+                match select_stream(&track.id, preview, is_premium, session_ready) {
+                    Ok(SpotifyStream::Full(uri)) => Some(uri),
+                    Ok(SpotifyStream::Preview(url)) => {
+                        tracing::warn!(
+                            "Spotify session not ready, playing preview of: {}",
+                            track.title
+                        );
+                        Some(url)
+                    }
+                    Err(e) => {
+                        tracing::error!("Cannot play Spotify track {}: {}", track.title, e);
+                        self.fail_playback(e.to_string()).await;
+                        return;
+                    }
+                }
+            }
+            None => track.url.clone(),
+        };
+        if let Some(url) = &url {
             // Check if this is a Spotify URI requiring premium playback
             if url.starts_with("spotify:track:") {
                 // Verify session is initialized before attempting playback
@@ -1431,92 +2560,26 @@ impl PlaybackManager {
                         "Cannot play Spotify track: session not initialized. URL: {}",
                         url
                     );
-                    let mut info = self.info.lock().await;
-                    info.state = PlaybackState::Stopped;
+                    self.fail_playback("Spotify session is not initialized".to_string())
+                        .await;
                     return;
                 }
 
                 // Premium user with session initialized - use librespot
                 tracing::info!("Playing Spotify track via librespot: {}", url);
-                let track_complete_tx = self.track_complete_tx.clone();
-                let monitoring_abort = self.monitoring_task_abort.clone();
-                let state_save_tx = self.state_save_tx.clone();
                 match self.play_spotify_track(url).await {
                     Ok(handle) => {
                         // Spawn a task to update playback position from the audio player
-                        let info_arc = self.info.clone();
-                        let _queue_arc = self.queue.clone();
-
-                        let task = tokio::spawn(async move {
-                            tracing::debug!("Spotify monitoring task started");
-                            let mut last_state_save = std::time::Instant::now();
-                            loop {
-                                let position = handle.get_position();
-                                let duration = handle.get_duration();
-                                let should_stop = handle.should_stop();
-                                let is_paused = handle.is_paused();
-
-                                {
-                                    let mut info = info_arc.lock().await;
-                                    info.position_ms = position;
-                                    if duration > 0 && info.current_track.is_some() {
-                                        info.current_track.as_mut().unwrap().duration_ms = duration;
-                                    }
-
-                                    // Update playback state based on pause status
-                                    if is_paused {
-                                        info.state = PlaybackState::Paused;
-                                    } else if !should_stop {
-                                        info.state = PlaybackState::Playing;
-                                    }
-                                }
-
-                                // Request state save periodically (every 5 seconds)
-                                if last_state_save.elapsed().as_secs() >= 5 {
-                                    let _ = state_save_tx.send(());
-                                    last_state_save = std::time::Instant::now();
-                                }
-
-                                // When track completes, send event to advance to next track
-                                if should_stop {
-                                    tracing::debug!(
-                                        "Spotify monitoring task detected should_stop=true"
-                                    );
-                                    {
-                                        let mut info = info_arc.lock().await;
-                                        info.state = PlaybackState::Stopped;
-                                    }
-
-                                    tracing::info!(
-                                        "Spotify track completed, sending auto-advance event"
-                                    );
-                                    let _ = track_complete_tx.send(());
-                                    break;
-                                }
-
-                                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                            }
-                        });
-
-                        // Store the abort handle immediately to prevent race conditions
-                        // This ensures the task can be aborted before completion
-                        {
-                            let mut abort_handle = monitoring_abort.lock().await;
-                            *abort_handle = Some(task.abort_handle());
-                        }
+                        self.spawn_monitor(handle, "Spotify").await;
                     }
                     Err(e) => {
                         tracing::error!("Failed to play Spotify track: {}", e);
-                        let mut info = self.info.lock().await;
-                        info.state = PlaybackState::Stopped;
+                        self.fail_playback(format!("Failed to play Spotify track: {}", e))
+                            .await;
                     }
                 }
             } else {
                 // HTTP URL - play as normal
-                let track_complete_tx = self.track_complete_tx.clone();
-                let monitoring_abort = self.monitoring_task_abort.clone();
-                let state_save_tx = self.state_save_tx.clone();
-
                 // Fetch auth headers dynamically from provider if needed (e.g., for Jellyfin)
                 let auth_headers = if track.source == crate::models::Source::Jellyfin {
                     let providers = self.providers.lock().await;
@@ -1531,89 +2594,34 @@ impl PlaybackManager {
                     info.volume
                 };
 
-                match self.audio_player.play_url(url, auth_headers, volume).await {
+                let manual_gain = self.manual_gain(&track).await;
+
+                match self
+                    .audio_player
+                    .play_url(
+                        url,
+                        auth_headers,
+                        volume,
+                        self.volume_normalization,
+                        manual_gain,
+                    )
+                    .await
+                {
                     Ok(handle) => {
                         // Spawn a task to update playback position from the audio player
-                        let info_arc = self.info.clone();
-                        let _queue_arc = self.queue.clone();
-
-                        let task = tokio::spawn(async move {
-                            tracing::debug!("HTTP monitoring task started");
-                            let mut last_state_save = std::time::Instant::now();
-                            loop {
-                                let position = handle.get_position();
-                                let duration = handle.get_duration();
-                                let should_stop = handle.should_stop();
-                                let is_paused = handle.is_paused();
-
-                                // Debug: Log every 10 seconds to confirm task is running
-                                if position % 10000 < 100 {
-                                    tracing::debug!(
-                                        "HTTP monitor check: pos={}, dur={}, should_stop={}",
-                                        position,
-                                        duration,
-                                        should_stop
-                                    );
-                                }
-
-                                {
-                                    let mut info = info_arc.lock().await;
-                                    info.position_ms = position;
-                                    if duration > 0 && info.current_track.is_some() {
-                                        info.current_track.as_mut().unwrap().duration_ms = duration;
-                                    }
-
-                                    // Update playback state based on pause status
-                                    if is_paused {
-                                        info.state = PlaybackState::Paused;
-                                    } else if !should_stop {
-                                        info.state = PlaybackState::Playing;
-                                    }
-                                }
-
-                                // Request state save periodically (every 5 seconds)
-                                if last_state_save.elapsed().as_secs() >= 5 {
-                                    let _ = state_save_tx.send(());
-                                    last_state_save = std::time::Instant::now();
-                                }
-
-                                // When track completes, send event to advance to next track
-                                if should_stop {
-                                    tracing::debug!(
-                                        "HTTP monitoring task detected should_stop=true"
-                                    );
-                                    {
-                                        let mut info = info_arc.lock().await;
-                                        info.state = PlaybackState::Stopped;
-                                    }
-
-                                    tracing::info!(
-                                        "HTTP track completed, sending auto-advance event"
-                                    );
-                                    let _ = track_complete_tx.send(());
-                                    break;
-                                }
-
-                                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                            }
-                        });
-
-                        // Store the abort handle immediately to prevent race conditions
-                        // This ensures the task can be aborted before completion
-                        {
-                            let mut abort_handle = monitoring_abort.lock().await;
-                            *abort_handle = Some(task.abort_handle());
-                        }
+                        self.spawn_monitor(handle, "HTTP").await;
                     }
                     Err(e) => {
                         tracing::error!("Failed to play audio: {}", e);
-                        let mut info = self.info.lock().await;
-                        info.state = PlaybackState::Stopped;
+                        self.fail_playback(format!("Failed to play audio: {}", e))
+                            .await;
                     }
                 }
             }
         } else {
             tracing::warn!("No playback URL available for track: {}", track.title);
+            self.fail_playback(format!("No playback URL available for {}", track.title))
+                .await;
         }
     }
 
@@ -1631,17 +2639,32 @@ impl PlaybackManager {
 
         // Save state when queue changes
         let _ = self.save_state().await;
+        self.emit_change().await;
+    }
+
+    /// Add tracks to the end of the queue without interrupting playback,
+    /// returning the queue indices they were added at
+    pub async fn append_to_queue(&self, tracks: Vec<Track>) -> std::ops::Range<usize> {
+        let added = self.queue.lock().await.append_tracks(tracks);
+
+        let _ = self.save_state().await;
+        self.emit_change().await;
+        added
     }
 
     /// Clear the playback queue
     pub async fn clear_queue(&self) {
+        self.stop_audio().await;
+
         let mut queue = self.queue.lock().await;
         queue.clear();
         let mut info = self.info.lock().await;
         info.state = PlaybackState::Stopped;
         info.current_track = None;
+        info.position_ms = 0;
         drop(info);
         drop(queue);
+        self.emit_change().await;
 
         // Don't save state immediately - let the caller decide when to save
         // This prevents saving an empty state when clearing before loading a new track
@@ -1650,11 +2673,11 @@ impl PlaybackManager {
     /// Play a track (start playback)
     pub async fn play(&self) {
         // Try to resume existing playback first
-        match self.audio_player.resume().await {
+        match self.audio_player.resume(self.fade_duration).await {
             Ok(_) => {
                 // Successfully resumed
-                let mut info = self.info.lock().await;
-                info.state = PlaybackState::Playing;
+                self.info.lock().await.state = PlaybackState::Playing;
+                self.emit_change().await;
             }
             Err(_) => {
                 // No active playback - try to load and play the current track
@@ -1706,9 +2729,10 @@ impl PlaybackManager {
         drop(info);
 
         // Pause audio playback
-        if let Err(e) = self.audio_player.pause().await {
+        if let Err(e) = self.audio_player.pause(self.fade_duration).await {
             tracing::warn!("Failed to pause playback: {}", e);
         }
+        self.emit_change().await;
     }
 
     /// Toggle play/pause
@@ -1720,7 +2744,7 @@ impl PlaybackManager {
         let new_state = {
             let info = info_arc.lock().await;
             match info.state {
-                PlaybackState::Playing => PlaybackState::Paused,
+                PlaybackState::Loading | PlaybackState::Playing => PlaybackState::Paused,
                 PlaybackState::Paused | PlaybackState::Stopped => PlaybackState::Playing,
             }
         };
@@ -1728,21 +2752,21 @@ impl PlaybackManager {
         // Update audio player
         match new_state {
             PlaybackState::Playing => {
-                if let Err(e) = player.resume().await {
+                if let Err(e) = player.resume(self.fade_duration).await {
                     tracing::warn!("Failed to resume playback: {}", e);
                 }
             }
             PlaybackState::Paused => {
-                if let Err(e) = player.pause().await {
+                if let Err(e) = player.pause(self.fade_duration).await {
                     tracing::warn!("Failed to pause playback: {}", e);
                 }
             }
-            PlaybackState::Stopped => {}
+            PlaybackState::Loading | PlaybackState::Stopped => {}
         }
 
         // Update playback state
-        let mut info = info_arc.lock().await;
-        info.state = new_state;
+        info_arc.lock().await.state = new_state;
+        self.emit_change().await;
     }
 
     /// Play next track
@@ -1766,14 +2790,26 @@ impl PlaybackManager {
         }
     }
 
-    /// Play previous track
+    /// Play previous track, or restart the current one once it's past the
+    /// restart threshold
     pub async fn previous_track(&self) -> Option<Track> {
         // Get shuffle state from info
-        let shuffle_enabled = {
+        let (shuffle_enabled, restart_track) = {
             let info = self.info.lock().await;
-            info.shuffle
+            let past_threshold =
+                info.position_ms > self.previous_restart_threshold.as_millis() as u64;
+            (
+                info.shuffle,
+                info.current_track.clone().filter(|_| past_threshold),
+            )
         };
 
+        if let Some(track) = restart_track {
+            tracing::debug!("Restarting {} instead of going back", track.title);
+            self.play_track(track.clone()).await;
+            return Some(track);
+        }
+
         let mut queue = self.queue.lock().await;
         let track_opt = queue.previous_shuffled(shuffle_enabled);
 
@@ -1789,14 +2825,14 @@ impl PlaybackManager {
 
     /// Seek to a position in the current track
     pub async fn seek(&self, position_ms: u64) {
-        let mut info = self.info.lock().await;
-        info.position_ms = position_ms;
+        self.info.lock().await.position_ms = position_ms;
+        self.emit_change().await;
     }
 
     /// Set volume (0-100)
-    pub async fn set_volume(&self, volume: u32) {
+    pub async fn set_volume(&self, volume: Volume) {
         let mut info = self.info.lock().await;
-        info.volume = volume.min(100);
+        info.volume = volume;
         drop(info);
 
         // Apply volume to the active playback handle
@@ -1804,6 +2840,8 @@ impl PlaybackManager {
         if let Some(handle) = player.as_ref() {
             handle.set_volume(volume);
         }
+        drop(player);
+        self.emit_change().await;
     }
 
     /// Toggle shuffle mode
@@ -1813,47 +2851,107 @@ impl PlaybackManager {
         let shuffle_enabled = info.shuffle;
         drop(info);
 
-        // Generate or clear shuffle order based on new state
+        // Generate or clear shuffle order based on new state, keeping the
+        // current track current either way
+        let has_current_track = self.info.lock().await.current_track.is_some();
         let mut queue = self.queue.lock().await;
         if shuffle_enabled {
-            // When enabling shuffle, generate a new shuffle order
-            queue.generate_shuffle_order();
-            // Reset to beginning of shuffled playlist
+            // Put the playing track first and shuffle only the rest
+            if has_current_track {
+                let current = queue.current_index;
+                queue.generate_shuffle_order_keeping(current);
+            } else {
+                queue.generate_shuffle_order();
+            }
             queue.current_index = 0;
             tracing::info!("Shuffle enabled - generated new shuffle order");
         } else {
-            // When disabling shuffle, clear the shuffle order
+            // Map the shuffle position back to the track's place in the original order
+            let current = queue
+                .shuffle_order
+                .get(queue.current_index)
+                .copied()
+                .filter(|&idx| idx < queue.tracks.len())
+                .unwrap_or(0);
             queue.clear_shuffle_order();
-            // Try to maintain the same track by finding it in the original order
-            // This is best-effort - if we can't find it, just reset to 0
-            queue.current_index = 0;
+            queue.current_index = current;
             tracing::info!("Shuffle disabled - cleared shuffle order");
         }
         drop(queue);
 
         // Save state when shuffle changes
         let _ = self.save_state().await;
+        self.emit_change().await;
+    }
+
+    /// Pick a new random order for the tracks still to come while shuffled,
+    /// without interrupting the current track
+    pub async fn reshuffle_queue(&self) {
+        if !self.info.lock().await.shuffle {
+            tracing::debug!("Shuffle is off, nothing to reshuffle");
+            return;
+        }
+
+        let reshuffled = self.queue.lock().await.reshuffle_upcoming();
+        if reshuffled {
+            let _ = self.save_state().await;
+            self.emit_change().await;
+        }
+    }
+
+    /// Use `seed` for shuffle orders from now on, or random orders for
+    /// `None`. With shuffle on, the tracks after the current one are
+    /// reordered right away.
+    pub async fn set_shuffle_seed(&self, seed: Option<u64>) {
+        let shuffle_enabled = self.info.lock().await.shuffle;
+        let mut queue = self.queue.lock().await;
+        queue.shuffle_seed = seed;
+        queue.reshuffle_count = 0;
+        if !shuffle_enabled || queue.is_empty() {
+            return;
+        }
+
+        let current = queue
+            .shuffle_order
+            .get(queue.current_index)
+            .copied()
+            .filter(|&idx| idx < queue.tracks.len())
+            .unwrap_or(0);
+        queue.generate_shuffle_order_keeping(current);
+        queue.current_index = 0;
+        drop(queue);
+
+        let _ = self.save_state().await;
+        self.emit_change().await;
+    }
+
+    /// Set repeat mode
+    pub async fn set_repeat_mode(&self, mode: RepeatMode) {
+        let mut info = self.info.lock().await;
+        info.repeat_mode = mode;
+        drop(info);
+
+        // Save state when repeat mode changes
+        let _ = self.save_state().await;
+        self.emit_change().await;
     }
 
-    /// Set repeat mode
-    pub async fn set_repeat_mode(&self, mode: RepeatMode) {
+    /// Advance to the next repeat mode (Off → All → One → Off) and return it
+    pub async fn cycle_repeat_mode(&self) -> RepeatMode {
         let mut info = self.info.lock().await;
-        info.repeat_mode = mode;
+        info.repeat_mode = info.repeat_mode.next();
+        let mode = info.repeat_mode;
         drop(info);
 
         // Save state when repeat mode changes
         let _ = self.save_state().await;
+        self.emit_change().await;
+        mode
     }
 
     /// Get current playback info
     pub async fn get_info(&self) -> PlaybackInfo {
-        let mut info = self.info.lock().await.clone();
-        let queue = self.queue.lock().await;
-        info.queue = queue.tracks.clone();
-        info.current_index = queue.current_index;
-        info.shuffle_order = queue.shuffle_order.clone();
-        drop(queue);
-        info
+        Self::snapshot_info(&self.info, &self.queue).await
     }
 
     /// Get current queue length
@@ -1930,6 +3028,7 @@ impl PlaybackManager {
                 }
                 Err(e) => {
                     tracing::error!("Spotify playback error: {}", e);
+                    handle_for_spawn.fail(e);
                 }
             }
         });
@@ -1944,22 +3043,10 @@ impl PlaybackManager {
 
     /// Build persistent state from current playback info and queue
     async fn build_persistent_state(&self) -> crate::state::PersistentPlaybackState {
-        use crate::state::PersistentPlaybackState;
-
         let info = self.info.lock().await;
         let queue = self.queue.lock().await;
 
-        PersistentPlaybackState {
-            current_track: info.current_track.clone(),
-            queue: queue.tracks.clone(),
-            current_index: queue.current_index,
-            position_ms: info.position_ms,
-            shuffle: info.shuffle,
-            repeat_mode: info.repeat_mode,
-            volume: info.volume,
-            shuffle_order: queue.shuffle_order.clone(),
-            state: info.state,
-        }
+        crate::state::PersistentPlaybackState::capture(&info, &queue)
     }
 
     /// Save current playback state to disk
@@ -1980,8 +3067,10 @@ impl PlaybackManager {
             }
         };
 
-        // Only restore if there's actually a current track to restore
+        // Only restore if there's actually a current track to restore, but
+        // keep the volume the user last chose either way
         if saved_state.current_track.is_none() {
+            self.info.lock().await.volume = saved_state.volume;
             tracing::info!("No current track in saved state, skipping restore");
             return Ok(());
         }
@@ -2046,14 +3135,20 @@ impl PlaybackManager {
                 position
             );
 
+            // The saved URL is from an earlier run and has likely expired,
+            // so prefer a download or refresh it like play_track does
+            let (track, local_copy) = self.prepare_track(track).await;
+
             // Set the info state to match what we're about to do (load but paused)
             {
                 let mut info = self.info.lock().await;
+                info.current_track = Some(track.clone());
                 info.state = PlaybackState::Paused;
             }
 
             // Actually load the track into the player so it's ready to play
-            if let Some(url) = &track.url {
+            let url = local_copy.or_else(|| track.url.clone());
+            if let Some(url) = &url {
                 // For Spotify tracks, we need an active session
                 if url.starts_with("spotify:track:") {
                     if self.spotify_session.is_initialized().await {
@@ -2103,6 +3198,7 @@ impl PlaybackManager {
                                 }
                                 Err(e) => {
                                     tracing::error!("Spotify restore playback error: {}", e);
+                                    handle_for_spawn.fail(e);
                                 }
                             }
                         });
@@ -2115,66 +3211,7 @@ impl PlaybackManager {
                         }
 
                         // Spawn monitoring task to sync position to info
-                        let info_arc = self.info.clone();
-                        let _queue_arc = self.queue.clone();
-                        let track_complete_tx = self.track_complete_tx.clone();
-                        let monitoring_abort = self.monitoring_task_abort.clone();
-                        let state_save_tx = self.state_save_tx.clone();
-
-                        let task = tokio::spawn(async move {
-                            tracing::debug!("Spotify monitoring task started (restore)");
-                            let mut last_state_save = std::time::Instant::now();
-                            loop {
-                                let position = handle.get_position();
-                                let duration = handle.get_duration();
-                                let should_stop = handle.should_stop();
-                                let is_paused = handle.is_paused();
-
-                                {
-                                    let mut info = info_arc.lock().await;
-                                    info.position_ms = position;
-                                    if duration > 0 && info.current_track.is_some() {
-                                        info.current_track.as_mut().unwrap().duration_ms = duration;
-                                    }
-
-                                    // Update playback state based on pause status
-                                    if is_paused {
-                                        info.state = PlaybackState::Paused;
-                                    } else if !should_stop {
-                                        info.state = PlaybackState::Playing;
-                                    }
-                                }
-
-                                // Request state save periodically (every 5 seconds)
-                                if last_state_save.elapsed().as_secs() >= 5 {
-                                    let _ = state_save_tx.send(());
-                                    last_state_save = std::time::Instant::now();
-                                }
-
-                                if should_stop {
-                                    tracing::debug!(
-                                        "Spotify monitoring task detected should_stop=true"
-                                    );
-                                    {
-                                        let mut info = info_arc.lock().await;
-                                        info.state = PlaybackState::Stopped;
-                                    }
-                                    tracing::info!(
-                                        "Spotify track completed, sending auto-advance event"
-                                    );
-                                    let _ = track_complete_tx.send(());
-                                    break;
-                                }
-
-                                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                            }
-                        });
-
-                        // Store the abort handle
-                        {
-                            let mut abort_handle = monitoring_abort.lock().await;
-                            *abort_handle = Some(task.abort_handle());
-                        }
+                        self.spawn_monitor(handle, "Spotify (restore)").await;
 
                         tracing::info!(
                             "Spotify track pre-loaded and ready at position {}ms",
@@ -2191,7 +3228,9 @@ impl PlaybackManager {
                     );
 
                     // Create a new PlaybackHandle and pre-configure it with the restored position/pause state
-                    let handle = PlaybackHandle::new();
+                    let handle = PlaybackHandle::new()
+                        .with_normalization(self.volume_normalization)
+                        .with_manual_gain(self.manual_gain(&track).await);
                     if position > 0 {
                         handle.set_position(position);
                     }
@@ -2236,6 +3275,7 @@ impl PlaybackManager {
                                     &handle,
                                     auth_headers,
                                     volume,
+                                    None,
                                 )
                             }
                         })
@@ -2249,9 +3289,11 @@ impl PlaybackManager {
                             }
                             Ok(Err(e)) => {
                                 tracing::error!("HTTP audio playback (restore) error: {}", e);
+                                handle_clone.fail(e);
                             }
                             Err(e) => {
                                 tracing::error!("HTTP restore task join error: {}", e);
+                                handle_clone.fail(format!("Audio playback task failed: {}", e));
                             }
                         }
                     });
@@ -2264,65 +3306,7 @@ impl PlaybackManager {
                     }
 
                     // Spawn monitoring task for HTTP restore path
-                    let info_arc = self.info.clone();
-                    let _queue_arc = self.queue.clone();
-                    let track_complete_tx = self.track_complete_tx.clone();
-                    let monitoring_abort = self.monitoring_task_abort.clone();
-                    let state_save_tx = self.state_save_tx.clone();
-
-                    let task = tokio::spawn(async move {
-                        tracing::debug!("HTTP restore monitoring task started");
-                        let mut last_state_save = std::time::Instant::now();
-                        loop {
-                            let position = handle.get_position();
-                            let duration = handle.get_duration();
-                            let should_stop = handle.should_stop();
-                            let is_paused = handle.is_paused();
-
-                            {
-                                let mut info = info_arc.lock().await;
-                                info.position_ms = position;
-                                if duration > 0 && info.current_track.is_some() {
-                                    info.current_track.as_mut().unwrap().duration_ms = duration;
-                                }
-
-                                // Update playback state based on pause status
-                                if is_paused {
-                                    info.state = PlaybackState::Paused;
-                                } else if !should_stop {
-                                    info.state = PlaybackState::Playing;
-                                }
-                            }
-
-                            // Request state save periodically (every 5 seconds)
-                            if last_state_save.elapsed().as_secs() >= 5 {
-                                let _ = state_save_tx.send(());
-                                last_state_save = std::time::Instant::now();
-                            }
-
-                            // When track completes, send event to advance to next track
-                            if should_stop {
-                                tracing::debug!(
-                                    "HTTP restore monitoring task detected should_stop=true"
-                                );
-                                {
-                                    let mut info = info_arc.lock().await;
-                                    info.state = PlaybackState::Stopped;
-                                }
-                                tracing::info!("HTTP track completed, sending auto-advance event");
-                                let _ = track_complete_tx.send(());
-                                break;
-                            }
-
-                            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                        }
-                    });
-
-                    // Store the abort handle
-                    {
-                        let mut abort_handle = monitoring_abort.lock().await;
-                        *abort_handle = Some(task.abort_handle());
-                    }
+                    self.spawn_monitor(handle, "HTTP (restore)").await;
 
                     tracing::info!("HTTP track pre-loaded and ready at position {}ms", position);
                 }
@@ -2332,6 +3316,7 @@ impl PlaybackManager {
             self.start_lookahead_preload().await;
         }
 
+        self.emit_change().await;
         Ok(())
     }
 
@@ -2382,7 +3367,7 @@ impl PlaybackManager {
                     if let Some(url) = &track.url {
                         if !url.starts_with("spotify:track:") {
                             // For HTTP URLs, we can prefetch headers to warm up the connection
-                            let _ = reqwest::Client::new().head(url).send().await;
+                            let _ = crate::http_client::shared_client().head(url).send().await;
                             tracing::debug!("Preloaded: {} - {}", track.artist, track.title);
                         }
                     }
@@ -2391,3 +3376,865 @@ impl PlaybackManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Source;
+
+    /// Tests that touch the saved playback state file take this first so
+    /// they don't overwrite each other's state
+    static SAVED_STATE: Mutex<()> = Mutex::const_new(());
+
+    fn create_test_track(id: &str) -> Track {
+        Track {
+            id: id.to_string(),
+            title: format!("Track {}", id),
+            artist: "Test Artist".to_string(),
+            album: "Test Album".to_string(),
+            album_artist: None,
+            duration_ms: 180000,
+            image_url: None,
+            source: Source::Jellyfin,
+            url: None,
+            auth_headers: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at: None,
+        }
+    }
+
+    fn create_manager_with_events() -> (PlaybackManager, mpsc::UnboundedReceiver<PlaybackInfo>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let providers = Arc::new(Mutex::new(ProviderRegistry::new()));
+        (PlaybackManager::with_event_sender(providers, tx), rx)
+    }
+
+    fn assert_decodes(data: &[u8], expected_format: &str) {
+        assert_eq!(sniff_audio_format(data), expected_format);
+
+        let decoder = decode_audio(data.to_vec())
+            .unwrap_or_else(|e| panic!("{} fixture failed to decode: {}", expected_format, e));
+        assert_eq!(decoder.sample_rate(), 44100);
+        assert_eq!(decoder.channels(), 1);
+        assert!(
+            decoder.count() > 0,
+            "{} fixture produced no samples",
+            expected_format
+        );
+    }
+
+    #[test]
+    fn test_decode_flac() {
+        assert_decodes(include_bytes!("fixtures/silence.flac"), "FLAC");
+    }
+
+    #[test]
+    fn test_decode_ogg_vorbis() {
+        assert_decodes(include_bytes!("fixtures/silence.ogg"), "Ogg");
+    }
+
+    #[test]
+    fn test_decode_aac() {
+        assert_decodes(include_bytes!("fixtures/silence.aac"), "AAC (ADTS)");
+    }
+
+    #[test]
+    fn test_decode_unsupported_format_names_it() {
+        let err = decode_audio(b"RIFF\0\0\0\0WAVEjunk".to_vec()).unwrap_err();
+        assert!(err.contains("WAV"), "{}", err);
+
+        let err = decode_audio(vec![0u8; 64]).unwrap_err();
+        assert!(err.contains("unknown"), "{}", err);
+    }
+
+    #[test]
+    fn test_fade_schedule_steps_up_to_full_volume() {
+        assert_eq!(
+            fade_schedule(Duration::from_millis(50)),
+            vec![0.2, 0.4, 0.6, 0.8, 1.0]
+        );
+        assert_eq!(fade_schedule(DEFAULT_FADE_DURATION).len(), 15);
+        assert_eq!(fade_schedule(Duration::from_millis(3)), vec![1.0]);
+        assert!(fade_schedule(Duration::ZERO).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fade_without_sink_toggles_pause() {
+        let handle = PlaybackHandle::new();
+
+        handle.fade_out_and_pause(DEFAULT_FADE_DURATION);
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while !handle.is_paused() {
+                tokio::time::sleep(FADE_STEP).await;
+            }
+        })
+        .await
+        .expect("Handle never paused");
+
+        handle.resume_with_fade(DEFAULT_FADE_DURATION);
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while handle.is_paused() {
+                tokio::time::sleep(FADE_STEP).await;
+            }
+        })
+        .await
+        .expect("Handle never resumed");
+    }
+
+    #[test]
+    fn test_content_type_guard_rejects_pages() {
+        let error = check_audio_content_type(Some("text/html; charset=utf-8")).unwrap_err();
+        assert!(error.contains("text/html"));
+        assert!(error.contains("token"));
+        assert!(check_audio_content_type(Some("application/json")).is_err());
+
+        assert!(check_audio_content_type(Some("audio/mpeg")).is_ok());
+        assert!(check_audio_content_type(Some("audio/flac")).is_ok());
+        assert!(check_audio_content_type(Some("application/octet-stream")).is_ok());
+        assert!(check_audio_content_type(None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_audio_rejects_html_and_follows_redirects() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/login"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Type", "text/html")
+                    .set_body_string("<html>Sign in</html>"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/stream"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", format!("{}/audio", server.uri()).as_str()),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/audio"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Type", "audio/mpeg")
+                    .set_body_bytes(vec![1, 2, 3]),
+            )
+            .mount(&server)
+            .await;
+
+        let fetch =
+            |url: String| tokio::task::spawn_blocking(move || AudioPlayer::fetch_audio(&url, None));
+        let error = fetch(format!("{}/login", server.uri()))
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(error.contains("text/html"), "{}", error);
+
+        let bytes = fetch(format!("{}/stream", server.uri()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_stop_reason_records_why_playback_stopped() {
+        let handle = PlaybackHandle::new();
+        assert_eq!(handle.stop_reason(), None);
+        handle.stop();
+        assert!(handle.should_stop());
+        assert_eq!(handle.stop_reason(), Some(StopReason::User));
+
+        let handle = PlaybackHandle::new();
+        handle.complete();
+        assert!(handle.should_stop());
+        assert_eq!(handle.stop_reason(), Some(StopReason::Completed));
+        // Cleaning up after a finished track doesn't turn it into a user stop
+        handle.stop();
+        assert_eq!(handle.stop_reason(), Some(StopReason::Completed));
+
+        let handle = PlaybackHandle::new();
+        handle.fail("No audio".to_string());
+        assert_eq!(handle.stop_reason(), Some(StopReason::Failed));
+        assert_eq!(handle.error().as_deref(), Some("No audio"));
+    }
+
+    #[test]
+    fn test_played_threshold() {
+        assert_eq!(played_threshold_ms(180_000), 90_000);
+        // Long tracks count after four minutes
+        assert_eq!(played_threshold_ms(3_600_000), 240_000);
+        assert_eq!(played_threshold_ms(0), 240_000);
+    }
+
+    #[tokio::test]
+    async fn test_track_played_fires_once_past_threshold() {
+        let (played_tx, mut played_rx) = mpsc::unbounded_channel();
+        let mut manager = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        manager.set_played_sender(played_tx);
+        manager.info.lock().await.current_track = Some(create_test_track("1"));
+
+        let handle = PlaybackHandle::new();
+        handle.set_duration(200_000);
+        manager.spawn_monitor(handle.clone(), "Test").await;
+        handle.mark_started();
+
+        let settle = || tokio::time::sleep(Duration::from_millis(30));
+        handle.set_position(60_000);
+        settle().await;
+        assert!(played_rx.try_recv().is_err());
+
+        for position in [100_000, 150_000, 199_000] {
+            handle.set_position(position);
+            settle().await;
+        }
+        assert_eq!(played_rx.try_recv().unwrap().id, "1");
+        assert!(played_rx.try_recv().is_err());
+
+        handle.stop();
+    }
+
+    #[tokio::test]
+    async fn test_monitor_advances_only_on_completion() {
+        let (manager, _rx) = create_manager_with_events();
+        let mut completed = manager.take_completion_receiver().await.unwrap();
+        manager
+            .queue_tracks(vec![create_test_track("1"), create_test_track("2")])
+            .await;
+
+        let handle = PlaybackHandle::new();
+        manager.spawn_monitor(handle.clone(), "Test").await;
+        handle.stop();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(completed.try_recv().is_err());
+
+        let handle = PlaybackHandle::new();
+        manager.spawn_monitor(handle.clone(), "Test").await;
+        handle.complete();
+        tokio::time::timeout(Duration::from_secs(1), completed.recv())
+            .await
+            .expect("Completion was not reported")
+            .expect("Completion channel closed");
+    }
+
+    #[tokio::test]
+    async fn test_position_update_propagates_through_watch() {
+        let handle = PlaybackHandle::new();
+        let mut progress_rx = handle.subscribe();
+
+        handle.set_position(1500);
+        tokio::time::timeout(Duration::from_secs(1), progress_rx.changed())
+            .await
+            .expect("Timed out waiting for position update")
+            .expect("Progress sender dropped");
+        assert_eq!(progress_rx.borrow_and_update().position_ms, 1500);
+        assert_eq!(handle.get_position(), 1500);
+
+        // Setting the same position is not a change
+        handle.set_position(1500);
+        assert!(!progress_rx.has_changed().unwrap());
+
+        // Stopping wakes subscribers so the monitor can finish
+        handle.stop();
+        assert!(progress_rx.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_play_track_emits_playback_event() {
+        let (manager, mut rx) = create_manager_with_events();
+        let track = create_test_track("1");
+
+        manager.queue_tracks(vec![track.clone()]).await;
+        while rx.try_recv().is_ok() {}
+
+        manager.play_track(track).await;
+
+        let info = rx.try_recv().expect("Expected a playback event");
+        assert_eq!(info.current_track.map(|t| t.id), Some("1".to_string()));
+        assert_eq!(info.state, PlaybackState::Loading);
+        assert_eq!(info.queue.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_play_track_reports_loading_until_audio_starts() {
+        let (manager, mut rx) = create_manager_with_events();
+        let track = create_test_track("1");
+
+        manager.play_track(track).await;
+        let info = rx.try_recv().expect("Expected a playback event");
+        assert_eq!(info.state, PlaybackState::Loading);
+
+        // Stand in for the audio thread: the monitor keeps the track loading
+        // while it is fetched and decoded
+        manager.info.lock().await.state = PlaybackState::Loading;
+        let handle = PlaybackHandle::new();
+        manager.spawn_monitor(handle.clone(), "Test").await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(manager.get_info().await.state, PlaybackState::Loading);
+
+        // Once the audio reaches the sink the track is playing
+        handle.mark_started();
+        let playing = tokio::time::timeout(Duration::from_secs(1), async {
+            while let Some(info) = rx.recv().await {
+                if info.state == PlaybackState::Playing {
+                    return info;
+                }
+            }
+            panic!("Event channel closed");
+        })
+        .await
+        .expect("Timed out waiting for playing state");
+        assert_eq!(playing.state, PlaybackState::Playing);
+
+        handle.stop();
+    }
+
+    #[tokio::test]
+    async fn test_manager_without_sender_does_not_emit() {
+        let providers = Arc::new(Mutex::new(ProviderRegistry::new()));
+        let manager = PlaybackManager::new(providers);
+
+        // Should not panic or block without a listener
+        manager.play_track(create_test_track("1")).await;
+        let info = manager.get_info().await;
+        assert_eq!(info.current_track.map(|t| t.id), Some("1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_autosave_writes_state_after_interval() {
+        use crate::state::PersistentPlaybackState;
+
+        let _saved_state = SAVED_STATE.lock().await;
+        PersistentPlaybackState::delete().await.unwrap();
+
+        let info = Arc::new(Mutex::new(PlaybackInfo::default()));
+        let queue = Arc::new(Mutex::new(PlaybackQueue::new()));
+        info.lock().await.volume = Volume::new(77);
+
+        // Keep the sender alive so the task only saves on its own timer
+        let (_save_tx, save_rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(PlaybackManager::state_saver_task(
+            info,
+            queue,
+            save_rx,
+            Some(Duration::from_millis(50)),
+        ));
+
+        assert!(PersistentPlaybackState::load().await.unwrap().is_none());
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        task.abort();
+
+        let saved = PersistentPlaybackState::load()
+            .await
+            .unwrap()
+            .expect("Autosave did not write state");
+        assert_eq!(saved.volume.percent(), 77);
+    }
+
+    #[tokio::test]
+    async fn test_clear_queue_stops_current_audio() {
+        let providers = Arc::new(Mutex::new(ProviderRegistry::new()));
+        let manager = PlaybackManager::new(providers);
+        manager.queue_tracks(vec![create_test_track("1")]).await;
+
+        let handle = PlaybackHandle::new();
+        *manager.audio_player.current_handle.lock().await = Some(handle.clone());
+        manager.info.lock().await.position_ms = 42_000;
+
+        manager.clear_queue().await;
+
+        assert!(manager.audio_player.get_current_handle().await.is_none());
+        assert!(handle.should_stop());
+        let info = manager.get_info().await;
+        assert_eq!(info.state, PlaybackState::Stopped);
+        assert_eq!(info.position_ms, 0);
+        assert!(info.current_track.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_url_records_playback_error() {
+        let (manager, mut rx) = create_manager_with_events();
+        let mut track = create_test_track("1");
+        track.url = Some("not-a-url".to_string());
+
+        manager.play_track(track).await;
+
+        let (error, state) = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let info = rx.recv().await.expect("Event sender dropped");
+                if let Some(error) = info.error {
+                    return (error, info.state);
+                }
+            }
+        })
+        .await
+        .expect("Timed out waiting for playback error");
+        assert!(error.contains("Invalid playback URL"), "{}", error);
+        assert_eq!(state, PlaybackState::Stopped);
+        assert_eq!(manager.get_info().await.error, Some(error));
+    }
+
+    #[tokio::test]
+    async fn test_failed_track_skips_to_next() {
+        let (manager, mut rx) = create_manager_with_events();
+        let mut first = create_test_track("1");
+        first.url = Some("not-a-url".to_string());
+        let mut second = create_test_track("2");
+        second.url = Some("not-a-url".to_string());
+        manager.queue_tracks(vec![first.clone(), second]).await;
+
+        let playback = Arc::new(Mutex::new(manager));
+        let (skip_tx, mut skip_rx) = mpsc::unbounded_channel();
+        tokio::spawn(PlaybackManager::run_failure_recovery(
+            playback.clone(),
+            move |skipped: &[Track]| {
+                let ids: Vec<String> = skipped.iter().map(|t| t.id.clone()).collect();
+                let _ = skip_tx.send(ids);
+            },
+        ));
+
+        playback.lock().await.play_track(first).await;
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let info = rx.recv().await.expect("Event sender dropped");
+                if info.current_track.map(|t| t.id).as_deref() == Some("2") {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("Playback never moved on to the second track");
+
+        let skipped = skip_rx.recv().await.expect("No skip notice sent");
+        assert_eq!(skipped, vec!["1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_librespot_playback_requires_session() {
+        let manager = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        assert!(!manager.is_spotify_session_ready().await);
+
+        let err = manager
+            .play_spotify_track("spotify:track:4uLU6hMCjMI75M1A2tKUQC")
+            .await
+            .unwrap_err();
+        assert!(err.contains("not initialized"));
+    }
+
+    #[tokio::test]
+    async fn test_spotify_track_without_premium_reports_error() {
+        let manager = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        let mut track = create_test_track("4uLU6hMCjMI75M1A2tKUQC");
+        track.source = Source::Spotify;
+        track.url = Some("spotify:track:4uLU6hMCjMI75M1A2tKUQC".to_string());
+
+        manager.play_track(track).await;
+
+        let info = manager.info.lock().await;
+        assert_eq!(info.state, PlaybackState::Stopped);
+        assert!(info
+            .error
+            .as_deref()
+            .unwrap_or_default()
+            .contains("Premium"));
+    }
+
+    async fn prebuffer_after(current: &Track, next: Track) -> bool {
+        let manager = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        manager.queue_tracks(vec![current.clone(), next]).await;
+        manager.info.lock().await.current_track = Some(current.clone());
+
+        PlaybackManager::prebuffer_gapless_next(
+            manager.queue.clone(),
+            manager.info.clone(),
+            manager.providers.clone(),
+            manager.audio_player.clone(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_same_album_tracks_are_prebuffered() {
+        let path = std::env::temp_dir().join(format!(
+            "any-player-prebuffer-test-{}.flac",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&path, b"audio").unwrap();
+        let url = url::Url::from_file_path(&path).unwrap().to_string();
+
+        let current = create_test_track("1");
+        let mut same_album = create_test_track("2");
+        same_album.url = Some(url.clone());
+        let mut other_album = same_album.clone();
+        other_album.album = "Other Album".to_string();
+
+        assert!(prebuffer_after(&current, same_album).await);
+        assert!(!prebuffer_after(&current, other_album).await);
+
+        let player = AudioPlayer::new();
+        player.prebuffer(&url, None).await.unwrap();
+        assert_eq!(player.take_prebuffered(&url).await, Some(b"audio".to_vec()));
+        assert_eq!(player.take_prebuffered(&url).await, None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_gapless_transition_requires_matching_album() {
+        let current = create_test_track("1");
+        let next = create_test_track("2");
+        assert!(is_gapless_transition(&current, &next));
+
+        let mut other_album = next.clone();
+        other_album.album = "Other Album".to_string();
+        assert!(!is_gapless_transition(&current, &other_album));
+
+        let mut no_album = current.clone();
+        no_album.album = String::new();
+        let mut next_no_album = next.clone();
+        next_no_album.album = String::new();
+        assert!(!is_gapless_transition(&no_album, &next_no_album));
+
+        let mut other_source = next;
+        other_source.source = Source::Spotify;
+        assert!(!is_gapless_transition(&current, &other_source));
+    }
+
+    async fn manager_playing(index: usize) -> PlaybackManager {
+        let providers = Arc::new(Mutex::new(ProviderRegistry::new()));
+        let manager = PlaybackManager::new(providers);
+        let tracks: Vec<Track> = (0..10).map(|i| create_test_track(&i.to_string())).collect();
+        manager.queue_tracks(tracks.clone()).await;
+        manager.queue.lock().await.current_index = index;
+        manager.info.lock().await.current_track = Some(tracks[index].clone());
+        manager
+    }
+
+    #[test]
+    fn test_seeded_shuffle_is_reproducible() {
+        let mut queue = PlaybackQueue::new();
+        queue.add_tracks((0..20).map(|i| create_test_track(&i.to_string())).collect());
+
+        queue.generate_shuffle_order_seeded(42);
+        let first = queue.shuffle_order.clone();
+        queue.generate_shuffle_order_seeded(42);
+        assert_eq!(queue.shuffle_order, first);
+
+        queue.generate_shuffle_order_seeded(7);
+        assert_ne!(queue.shuffle_order, first);
+
+        // A stored seed applies to regular generation too
+        queue.shuffle_seed = Some(42);
+        queue.generate_shuffle_order();
+        assert_eq!(queue.shuffle_order, first);
+
+        let mut sorted = first;
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_seeded_append_and_reshuffle_are_reproducible() {
+        let seeded_queue = || {
+            let mut queue = PlaybackQueue::new();
+            queue.add_tracks((0..10).map(|i| create_test_track(&i.to_string())).collect());
+            queue.shuffle_seed = Some(42);
+            queue.generate_shuffle_order();
+            queue.append_tracks(
+                (10..20)
+                    .map(|i| create_test_track(&i.to_string()))
+                    .collect(),
+            );
+            queue
+        };
+
+        let mut first = seeded_queue();
+        let mut second = seeded_queue();
+        assert_eq!(first.shuffle_order, second.shuffle_order);
+        let mut appended = first.shuffle_order[10..].to_vec();
+        appended.sort_unstable();
+        assert_eq!(appended, (10..20).collect::<Vec<_>>());
+
+        // Re-rolls follow the seed but don't repeat the previous order
+        let before = first.shuffle_order.clone();
+        assert!(first.reshuffle_upcoming());
+        assert!(second.reshuffle_upcoming());
+        assert_eq!(first.shuffle_order, second.shuffle_order);
+        assert_ne!(first.shuffle_order, before);
+
+        let rerolled = first.shuffle_order.clone();
+        assert!(first.reshuffle_upcoming());
+        assert_ne!(first.shuffle_order, rerolled);
+    }
+
+    #[tokio::test]
+    async fn test_setting_seed_reorders_upcoming_tracks() {
+        let manager = manager_playing(4).await;
+        manager.toggle_shuffle().await;
+
+        manager.set_shuffle_seed(Some(42)).await;
+        let first = manager.queue.lock().await.shuffle_order.clone();
+        assert_eq!(first[0], 4);
+        assert_eq!(manager.queue.lock().await.current_index, 0);
+
+        manager.set_shuffle_seed(Some(42)).await;
+        assert_eq!(manager.queue.lock().await.shuffle_order, first);
+    }
+
+    #[tokio::test]
+    async fn test_enabling_shuffle_keeps_current_track() {
+        let manager = manager_playing(4).await;
+
+        manager.toggle_shuffle().await;
+
+        let queue = manager.queue.lock().await;
+        assert_eq!(queue.shuffle_order[0], 4);
+        assert_eq!(queue.current_index, 0);
+        assert_eq!(queue.current_track_shuffled(true).unwrap().id, "4");
+
+        let mut order = queue.shuffle_order.clone();
+        order.sort_unstable();
+        assert_eq!(order, (0..10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_shuffle_order_survives_restart() {
+        use crate::state::PersistentPlaybackState;
+
+        let _saved_state = SAVED_STATE.lock().await;
+        PersistentPlaybackState::delete().await.unwrap();
+
+        let manager = manager_playing(4).await;
+        manager.toggle_shuffle().await;
+        let (shuffle_order, up_next) = {
+            let mut queue = manager.queue.lock().await;
+            queue.shuffle_order = vec![4, 7, 1, 9, 0, 2, 8, 3, 6, 5];
+            (
+                queue.shuffle_order.clone(),
+                queue.peek_next_shuffled(true).unwrap().id.clone(),
+            )
+        };
+        manager.save_state().await.unwrap();
+
+        let restored = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        restored.restore_state().await.unwrap();
+
+        assert!(restored.info.lock().await.shuffle);
+        let queue = restored.queue.lock().await;
+        assert_eq!(queue.shuffle_order, shuffle_order);
+        assert_eq!(queue.current_index, 0);
+        assert_eq!(queue.peek_next_shuffled(true).unwrap().id, up_next);
+        assert_eq!(up_next, "7");
+    }
+
+    #[tokio::test]
+    async fn test_save_and_restore_state_round_trip() {
+        use crate::state::PersistentPlaybackState;
+
+        let _saved_state = SAVED_STATE.lock().await;
+        PersistentPlaybackState::delete().await.unwrap();
+
+        let manager = manager_playing(3).await;
+        {
+            let mut info = manager.info.lock().await;
+            info.state = PlaybackState::Playing;
+            info.position_ms = 61_000;
+            info.volume = Volume::new(42);
+            info.repeat_mode = RepeatMode::All;
+        }
+        manager.save_state().await.unwrap();
+
+        let restored = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        restored.restore_state().await.unwrap();
+
+        let info = restored.get_info().await;
+        assert_eq!(info.current_track.map(|t| t.id), Some("3".to_string()));
+        assert_eq!(info.position_ms, 61_000);
+        assert_eq!(info.volume.percent(), 42);
+        assert_eq!(info.repeat_mode, RepeatMode::All);
+        // Never resumes on its own after a restart
+        assert_eq!(info.state, PlaybackState::Paused);
+
+        let queue = restored.get_queue_arc();
+        let queue = queue.lock().await;
+        assert_eq!(queue.len(), 10);
+        assert_eq!(queue.current_index, 3);
+    }
+
+    #[tokio::test]
+    async fn test_default_volume_applies_until_state_is_restored() {
+        use crate::state::PersistentPlaybackState;
+
+        let _saved_state = SAVED_STATE.lock().await;
+        PersistentPlaybackState::delete().await.unwrap();
+
+        let mut manager = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        manager.set_default_volume(Volume::new(30));
+        assert_eq!(manager.get_info().await.volume.percent(), 30);
+
+        // Nothing saved yet, so a fresh session keeps the default
+        manager.restore_state().await.unwrap();
+        assert_eq!(manager.get_info().await.volume.percent(), 30);
+
+        let saved = PersistentPlaybackState {
+            volume: Volume::new(80),
+            ..Default::default()
+        };
+        saved.save().await.unwrap();
+
+        let mut restored = PlaybackManager::new(Arc::new(Mutex::new(ProviderRegistry::new())));
+        restored.set_default_volume(Volume::new(30));
+        restored.restore_state().await.unwrap();
+        assert_eq!(restored.get_info().await.volume.percent(), 80);
+    }
+
+    #[tokio::test]
+    async fn test_restore_without_saved_state_keeps_current_state() {
+        use crate::state::PersistentPlaybackState;
+
+        let _saved_state = SAVED_STATE.lock().await;
+        PersistentPlaybackState::delete().await.unwrap();
+
+        let manager = manager_playing(2).await;
+        manager.restore_state().await.unwrap();
+
+        let info = manager.get_info().await;
+        assert_eq!(info.current_track.map(|t| t.id), Some("2".to_string()));
+        assert_eq!(manager.queue.lock().await.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_appending_keeps_current_track_and_extends_queue() {
+        let manager = manager_playing(4).await;
+        let added: Vec<Track> = (10..13)
+            .map(|i| create_test_track(&i.to_string()))
+            .collect();
+
+        let range = manager.append_to_queue(added).await;
+
+        assert_eq!(range, 10..13);
+        let queue = manager.queue.lock().await;
+        assert_eq!(queue.len(), 13);
+        assert_eq!(queue.current_index, 4);
+        assert_eq!(queue.tracks[12].id, "12");
+        drop(queue);
+        assert_eq!(
+            manager.info.lock().await.current_track.as_ref().unwrap().id,
+            "4"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_appending_while_shuffled_keeps_upcoming_order() {
+        let manager = manager_playing(4).await;
+        manager.toggle_shuffle().await;
+        let before = manager.queue.lock().await.shuffle_order.clone();
+
+        let added: Vec<Track> = (10..13)
+            .map(|i| create_test_track(&i.to_string()))
+            .collect();
+        manager.append_to_queue(added).await;
+
+        let queue = manager.queue.lock().await;
+        assert_eq!(queue.shuffle_order[..before.len()], before[..]);
+        let mut appended = queue.shuffle_order[before.len()..].to_vec();
+        appended.sort_unstable();
+        assert_eq!(appended, vec![10, 11, 12]);
+        assert_eq!(queue.current_track_shuffled(true).unwrap().id, "4");
+    }
+
+    #[tokio::test]
+    async fn test_reshuffle_keeps_current_track_and_permutes_tail() {
+        let manager = manager_playing(4).await;
+        manager.toggle_shuffle().await;
+        manager.queue.lock().await.current_index = 3;
+        let before = manager.queue.lock().await.shuffle_order.clone();
+
+        // A single reshuffle can land on the same order by chance (1 in 720)
+        let mut after = before.clone();
+        for _ in 0..10 {
+            manager.reshuffle_queue().await;
+            after = manager.queue.lock().await.shuffle_order.clone();
+            if after != before {
+                break;
+            }
+        }
+
+        // Played tracks and the current one stay put
+        assert_eq!(after[..=3], before[..=3]);
+        let queue = manager.queue.lock().await;
+        assert_eq!(queue.current_index, 3);
+        assert_eq!(
+            queue.current_track_shuffled(true).unwrap().id,
+            before[3].to_string()
+        );
+
+        // The tail holds the same tracks in a new order
+        assert_ne!(after[4..], before[4..]);
+        let mut tail = after[4..].to_vec();
+        let mut expected = before[4..].to_vec();
+        tail.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(tail, expected);
+    }
+
+    #[tokio::test]
+    async fn test_reshuffle_without_shuffle_does_nothing() {
+        let manager = manager_playing(4).await;
+        manager.reshuffle_queue().await;
+        assert!(manager.queue.lock().await.shuffle_order.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disabling_shuffle_keeps_current_track() {
+        let manager = manager_playing(4).await;
+        manager.toggle_shuffle().await;
+
+        // Move a few tracks into the shuffled order
+        let expected = {
+            let mut queue = manager.queue.lock().await;
+            queue.current_index = 3;
+            queue.shuffle_order[3]
+        };
+
+        manager.toggle_shuffle().await;
+
+        let queue = manager.queue.lock().await;
+        assert!(queue.shuffle_order.is_empty());
+        assert_eq!(queue.current_index, expected);
+        assert_eq!(
+            queue.current_track_shuffled(false).unwrap().id,
+            expected.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_previous_restarts_track_past_threshold() {
+        let manager = manager_playing(4).await;
+        manager.info.lock().await.position_ms = 10_000;
+
+        let track = manager.previous_track().await.expect("Expected a track");
+
+        assert_eq!(track.id, "4");
+        assert_eq!(manager.queue.lock().await.current_index, 4);
+        assert_eq!(manager.get_info().await.position_ms, 0);
+    }
+
+    #[tokio::test]
+    async fn test_previous_goes_back_before_threshold() {
+        let mut manager = manager_playing(4).await;
+        manager.set_previous_restart_threshold(Duration::from_secs(5));
+        manager.info.lock().await.position_ms = 4_000;
+
+        let track = manager.previous_track().await.expect("Expected a track");
+
+        assert_eq!(track.id, "3");
+        assert_eq!(manager.queue.lock().await.current_index, 3);
+    }
+}
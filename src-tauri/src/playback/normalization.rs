@@ -0,0 +1,134 @@
+/// Loudness normalization from ReplayGain / R128 tags
+use lofty::file::TaggedFileExt;
+use lofty::probe::Probe;
+use lofty::tag::ItemKey;
+use std::io::Cursor;
+
+/// Loudness ReplayGain 2.0 gains are calculated against
+pub const REPLAYGAIN_REFERENCE_LUFS: f64 = -18.0;
+
+/// Loudness Opus R128 gains are calculated against (EBU R128)
+const R128_REFERENCE_LUFS: f64 = -23.0;
+
+/// Limits on applied gain so a bad tag can't blast or mute a track
+const MIN_GAIN_DB: f64 = -24.0;
+const MAX_GAIN_DB: f64 = 12.0;
+
+/// Convert a gain in dB to a linear volume multiplier
+pub fn db_to_multiplier(gain_db: f64) -> f32 {
+    10f64.powf(gain_db.clamp(MIN_GAIN_DB, MAX_GAIN_DB) / 20.0) as f32
+}
+
+/// Total gain in dB from loudness normalization plus the user's manual
+/// per-track offset. `None` when neither applies.
+pub fn combined_gain_db(normalization_db: Option<f64>, manual_db: Option<f64>) -> Option<f64> {
+    match (normalization_db, manual_db) {
+        (None, None) => None,
+        (normalization, manual) => Some(normalization.unwrap_or(0.0) + manual.unwrap_or(0.0)),
+    }
+}
+
+/// Parse a ReplayGain value such as `"-6.54 dB"`
+pub fn parse_replaygain_db(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .unwrap_or(value);
+    number.trim().parse().ok()
+}
+
+/// Parse an Opus `R128_TRACK_GAIN` value (Q7.8 fixed point dB)
+pub fn parse_r128_db(value: &str) -> Option<f64> {
+    value.trim().parse::<i16>().ok().map(|q| q as f64 / 256.0)
+}
+
+/// Gain in dB to bring the track to `target_lufs`, from its tags.
+///
+/// Prefers track gain over album gain; returns `None` when the file has no
+/// loudness tags so playback is left unchanged.
+pub fn track_gain_db(data: &[u8], target_lufs: f64) -> Option<f64> {
+    let tagged_file = Probe::new(Cursor::new(data))
+        .guess_file_type()
+        .ok()?
+        .read()
+        .ok()?;
+
+    for tag in tagged_file.tags() {
+        let replaygain = tag
+            .get_string(&ItemKey::ReplayGainTrackGain)
+            .or_else(|| tag.get_string(&ItemKey::ReplayGainAlbumGain))
+            .and_then(parse_replaygain_db);
+        if let Some(gain) = replaygain {
+            return Some(gain + (target_lufs - REPLAYGAIN_REFERENCE_LUFS));
+        }
+
+        let r128 = tag
+            .get_string(&ItemKey::Unknown("R128_TRACK_GAIN".to_string()))
+            .and_then(parse_r128_db);
+        if let Some(gain) = r128 {
+            return Some(gain + (target_lufs - R128_REFERENCE_LUFS));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_db_to_multiplier() {
+        assert!(approx_eq(db_to_multiplier(0.0), 1.0));
+        assert!(approx_eq(db_to_multiplier(-6.0), 0.501_187));
+        assert!(approx_eq(db_to_multiplier(6.0), 1.995_262));
+        assert!(approx_eq(db_to_multiplier(-20.0), 0.1));
+
+        // Out-of-range gains are clamped
+        assert!(approx_eq(
+            db_to_multiplier(100.0),
+            db_to_multiplier(MAX_GAIN_DB)
+        ));
+        assert!(approx_eq(
+            db_to_multiplier(-100.0),
+            db_to_multiplier(MIN_GAIN_DB)
+        ));
+    }
+
+    #[test]
+    fn test_combined_gain_adds_manual_offset() {
+        assert_eq!(combined_gain_db(None, None), None);
+        assert_eq!(combined_gain_db(Some(-4.5), None), Some(-4.5));
+        assert_eq!(combined_gain_db(None, Some(2.0)), Some(2.0));
+        assert_eq!(combined_gain_db(Some(-4.5), Some(2.0)), Some(-2.5));
+
+        // The combined gain is clamped like any other
+        let boosted = combined_gain_db(Some(8.0), Some(8.0)).unwrap();
+        assert!(approx_eq(
+            db_to_multiplier(boosted),
+            db_to_multiplier(MAX_GAIN_DB)
+        ));
+    }
+
+    #[test]
+    fn test_parse_gain_values() {
+        assert_eq!(parse_replaygain_db("-6.54 dB"), Some(-6.54));
+        assert_eq!(parse_replaygain_db("+2.10 dB"), Some(2.1));
+        assert_eq!(parse_replaygain_db("3"), Some(3.0));
+        assert_eq!(parse_replaygain_db("loud"), None);
+
+        assert_eq!(parse_r128_db("-512"), Some(-2.0));
+        assert_eq!(parse_r128_db("abc"), None);
+    }
+
+    #[test]
+    fn test_untagged_audio_has_no_gain() {
+        let data = include_bytes!("fixtures/silence.flac");
+        assert_eq!(track_gain_db(data, REPLAYGAIN_REFERENCE_LUFS), None);
+    }
+}
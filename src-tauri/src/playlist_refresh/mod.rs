@@ -0,0 +1,198 @@
+/// Periodic background refresh of provider playlists. The frontend caches
+/// the playlist list on disk; when a refresh finds it out of date, the fresh
+/// list is sent in a `playlists-updated` event so the library view can
+/// update without a manual refresh.
+use crate::commands::PlaylistInfo;
+use crate::providers::{ProviderError, ProviderRegistry};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+/// Default time between refreshes
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Shape of the frontend's playlist disk cache; only the list is needed
+#[derive(Debug, Deserialize)]
+struct CachedPlaylists {
+    playlists: Vec<PlaylistInfo>,
+}
+
+/// What has to match for a playlist to count as unchanged. The track count
+/// is included for servers that don't report a revision.
+fn fingerprints(playlists: &[PlaylistInfo]) -> BTreeSet<(&str, &str, Option<&str>, usize)> {
+    playlists
+        .iter()
+        .map(|p| {
+            (
+                p.source.as_str(),
+                p.id.as_str(),
+                p.revision.as_deref(),
+                p.track_count,
+            )
+        })
+        .collect()
+}
+
+/// Whether the fetched playlists differ from the cached ones: a playlist
+/// was added, removed or has new contents. Order and display-only fields
+/// like the name are ignored.
+pub fn playlists_changed(cached: &[PlaylistInfo], fetched: &[PlaylistInfo]) -> bool {
+    fingerprints(cached) != fingerprints(fetched)
+}
+
+/// Playlists from every connected provider, in the order the frontend lists
+/// them. Fails if any connected provider can't be listed, so a partial
+/// result is never mistaken for deleted playlists.
+async fn fetch_playlists(
+    providers: &Mutex<ProviderRegistry>,
+) -> Result<Vec<PlaylistInfo>, ProviderError> {
+    let providers = providers.lock().await;
+    let mut playlists = Vec::new();
+    if providers.is_spotify_authenticated().await {
+        playlists.extend(providers.get_spotify_playlists().await?);
+    }
+    if providers.is_jellyfin_authenticated().await {
+        playlists.extend(providers.get_jellyfin_playlists().await?);
+    }
+    Ok(playlists.into_iter().map(PlaylistInfo::from).collect())
+}
+
+/// Playlists in the frontend's disk cache, if there is a readable one
+fn cached_playlists() -> Option<Vec<PlaylistInfo>> {
+    let json = match crate::cache::read_playlists_cache() {
+        Ok(json) => json?,
+        Err(e) => {
+            tracing::warn!("Failed to read playlists cache: {}", e);
+            return None;
+        }
+    };
+    match serde_json::from_str::<CachedPlaylists>(&json) {
+        Ok(cached) => Some(cached.playlists),
+        Err(e) => {
+            tracing::warn!("Failed to parse playlists cache: {}", e);
+            None
+        }
+    }
+}
+
+/// Refresh once, emitting `playlists-updated` if the list changed
+async fn refresh(app: &AppHandle, providers: &Mutex<ProviderRegistry>) {
+    let fetched = match fetch_playlists(providers).await {
+        Ok(fetched) => fetched,
+        Err(e) => {
+            tracing::debug!("Skipping playlist refresh: {}", e);
+            return;
+        }
+    };
+
+    // Without a cache the frontend hasn't loaded playlists yet and will
+    // fetch them itself
+    let Some(cached) = cached_playlists() else {
+        return;
+    };
+    if !playlists_changed(&cached, &fetched) {
+        return;
+    }
+
+    tracing::info!("Provider playlists changed, notifying frontend");
+    if let Err(err) = app.emit("playlists-updated", fetched) {
+        tracing::error!(?err, "Failed to emit 'playlists-updated' event to frontend");
+    }
+}
+
+/// Refresh provider playlists every `interval` in the background
+pub fn start(app: AppHandle, providers: Arc<Mutex<ProviderRegistry>>, interval: Duration) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes immediately; startup loads playlists anyway
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            refresh(&app, &providers).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist(
+        source: &str,
+        id: &str,
+        revision: Option<&str>,
+        track_count: usize,
+    ) -> PlaylistInfo {
+        PlaylistInfo {
+            id: id.to_string(),
+            name: format!("Playlist {}", id),
+            description: None,
+            track_count,
+            owner: "Owner".to_string(),
+            source: source.to_string(),
+            revision: revision.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_playlists_changed_compares_ids_and_revisions() {
+        let cached = vec![
+            playlist("spotify", "a", Some("snap1"), 10),
+            playlist("jellyfin", "b", Some("2024-01-01T00:00:00Z"), 5),
+        ];
+
+        // Same set in another order with a renamed playlist
+        let mut fetched = vec![cached[1].clone(), cached[0].clone()];
+        fetched[0].name = "Renamed".to_string();
+        assert!(!playlists_changed(&cached, &fetched));
+
+        // New snapshot
+        let fetched = vec![
+            playlist("spotify", "a", Some("snap2"), 10),
+            cached[1].clone(),
+        ];
+        assert!(playlists_changed(&cached, &fetched));
+
+        // Playlist removed or added
+        assert!(playlists_changed(&cached, &cached[..1]));
+        let mut fetched = cached.clone();
+        fetched.push(playlist("spotify", "c", Some("snap1"), 0));
+        assert!(playlists_changed(&cached, &fetched));
+
+        // Same ID on another provider is a different playlist
+        let fetched = vec![
+            cached[0].clone(),
+            playlist("spotify", "b", Some("2024-01-01T00:00:00Z"), 5),
+        ];
+        assert!(playlists_changed(&cached, &fetched));
+    }
+
+    #[test]
+    fn test_track_count_detects_changes_without_revision() {
+        let cached = vec![playlist("jellyfin", "b", None, 5)];
+        assert!(!playlists_changed(
+            &cached,
+            &[playlist("jellyfin", "b", None, 5)]
+        ));
+        assert!(playlists_changed(
+            &cached,
+            &[playlist("jellyfin", "b", None, 6)]
+        ));
+    }
+
+    #[test]
+    fn test_reads_frontend_cache_format() {
+        let json = r#"{"version":1,"timestamp":1700000000000,"playlists":[
+            {"id":"a","name":"Mix","owner":"me","track_count":3,"source":"spotify","revision":"snap1"},
+            {"id":"b","name":"Old","owner":"me","track_count":1,"source":"jellyfin","image_url":"x"}
+        ]}"#;
+        let cached: CachedPlaylists = serde_json::from_str(json).unwrap();
+        assert_eq!(cached.playlists.len(), 2);
+        assert_eq!(cached.playlists[0].revision.as_deref(), Some("snap1"));
+        assert!(cached.playlists[1].revision.is_none());
+    }
+}
@@ -1,10 +1,14 @@
-use super::{MusicProvider, ProviderError};
+use super::rate_limit::RateLimiter;
+use super::retry::{self, RetryPolicy};
+use super::{sort_album_tracks, MusicProvider, ProviderCapabilities, ProviderError};
 /// Jellyfin provider implementation
-use crate::models::{Playlist, Source, Track};
+use crate::models::{Page, Playlist, Source, Track};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// Jellyfin provider state
 pub struct JellyfinProvider {
@@ -13,6 +17,7 @@ pub struct JellyfinProvider {
     authenticated: bool,
     user_id: Option<String>,
     client: Client,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 /// Jellyfin API response types
@@ -39,6 +44,10 @@ struct JellyfinItem {
     album_id: Option<String>,
     #[serde(rename = "Artists")]
     artists: Option<Vec<String>>,
+    #[serde(rename = "AlbumArtist")]
+    album_artist: Option<String>,
+    #[serde(rename = "AlbumArtists")]
+    album_artists: Option<Vec<JellyfinNameRef>>,
     #[serde(rename = "RunTimeTicks")]
     runtime_ticks: Option<u64>,
     #[serde(rename = "ImageTags")]
@@ -50,6 +59,23 @@ struct JellyfinItem {
     user_data: Option<Value>,
     #[serde(rename = "ChildCount")]
     child_count: Option<u32>,
+    #[serde(rename = "Genres")]
+    genres: Option<Vec<String>>,
+    #[serde(rename = "ProductionYear")]
+    production_year: Option<u32>,
+    #[serde(rename = "IndexNumber")]
+    index_number: Option<u32>,
+    #[serde(rename = "ParentIndexNumber")]
+    parent_index_number: Option<u32>,
+    #[serde(rename = "DateModified")]
+    date_modified: Option<String>,
+}
+
+/// Name/id pair Jellyfin uses for linked items such as `AlbumArtists`
+#[derive(Debug, Deserialize)]
+struct JellyfinNameRef {
+    #[serde(rename = "Name")]
+    name: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -57,10 +83,46 @@ struct JellyfinItemsResponse {
     #[serde(rename = "Items")]
     items: Vec<JellyfinItem>,
     #[serde(rename = "TotalRecordCount")]
-    #[allow(dead_code)]
     total_record_count: u32,
 }
 
+/// Tracks returned for an artist's top tracks, matching Spotify's list
+const ARTIST_TOP_TRACKS_LIMIT: usize = 10;
+
+/// Favorites fetched per request when listing them
+const FAVORITES_PAGE_SIZE: usize = 300;
+
+/// Restrict an items query URL to `limit` items starting at `offset`
+fn paged_url(url: &str, offset: usize, limit: usize) -> String {
+    format!("{}&StartIndex={}&Limit={}", url, offset, limit)
+}
+
+/// Join artist names for display, skipping blanks and repeats
+fn join_names<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    let mut joined: Vec<&str> = Vec::new();
+    for name in names.into_iter().map(str::trim) {
+        if !name.is_empty() && !joined.contains(&name) {
+            joined.push(name);
+        }
+    }
+    joined.join(", ")
+}
+
+/// Artist the item's album is credited to: `AlbumArtist` when set,
+/// otherwise the names in `AlbumArtists`
+fn item_album_artist(item: &JellyfinItem) -> Option<String> {
+    item.album_artist
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            let names = item.album_artists.as_ref()?;
+            Some(join_names(names.iter().map(|artist| artist.name.as_str())))
+                .filter(|joined| !joined.is_empty())
+        })
+}
+
 #[derive(Debug, Serialize)]
 #[allow(dead_code)]
 struct CreatePlaylistRequest {
@@ -70,15 +132,75 @@ struct CreatePlaylistRequest {
     ids: Vec<String>,
 }
 
+/// Clean up a user-entered server URL so request paths can be appended to
+/// it: defaults to `http://` when no scheme is given and drops trailing
+/// slashes. Fails for input that isn't an HTTP(S) URL with a host.
+pub fn normalize_base_url(url: &str) -> Result<String, ProviderError> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(ProviderError::Other(
+            "Jellyfin server URL is empty".to_string(),
+        ));
+    }
+
+    let with_scheme = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{}", url)
+    };
+    let invalid = |reason: String| {
+        ProviderError::Other(format!("Invalid Jellyfin server URL '{}': {}", url, reason))
+    };
+
+    let parsed = url::Url::parse(&with_scheme).map_err(|e| invalid(e.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid(format!(
+            "unsupported scheme '{}', use http or https",
+            parsed.scheme()
+        )));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(invalid("missing host".to_string()));
+    }
+
+    Ok(with_scheme.trim_end_matches('/').to_string())
+}
+
 impl JellyfinProvider {
-    pub fn new(base_url: String, api_key: String) -> Self {
-        Self {
-            base_url,
+    pub fn new(base_url: String, api_key: String) -> Result<Self, ProviderError> {
+        Self::with_client(base_url, api_key, crate::http_client::shared_client())
+    }
+
+    /// Create a provider that issues requests through the given HTTP client.
+    /// The base URL is normalized with [`normalize_base_url`].
+    pub fn with_client(
+        base_url: String,
+        api_key: String,
+        client: Client,
+    ) -> Result<Self, ProviderError> {
+        Ok(Self {
+            base_url: normalize_base_url(&base_url)?,
             api_key,
             authenticated: false,
             user_id: None,
-            client: Client::new(),
-        }
+            client,
+            rate_limiter: Arc::new(RateLimiter::default()),
+        })
+    }
+
+    /// URL of the server this provider talks to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Share a rate limiter across every request this provider makes
+    pub fn set_rate_limiter(&mut self, rate_limiter: Arc<RateLimiter>) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// `X-Emby-Authorization` value identifying this install to the server
+    fn authorization(&self) -> String {
+        crate::config::client_identity().jellyfin_authorization(&self.api_key)
     }
 
     /// Get authentication headers for streaming requests
@@ -86,10 +208,7 @@ impl JellyfinProvider {
     pub fn get_auth_headers(&self) -> Vec<(String, String)> {
         vec![
             ("X-Emby-Token".to_string(), self.api_key.clone()),
-            ("X-Emby-Authorization".to_string(), format!(
-                "MediaBrowser Token=\"{}\", Client=\"AnyPlayer\", Device=\"AnyPlayer\", DeviceId=\"AnyPlayer\", Version=\"1.0.0\"",
-                self.api_key
-            )),
+            ("X-Emby-Authorization".to_string(), self.authorization()),
         ]
     }
 
@@ -102,14 +221,188 @@ impl JellyfinProvider {
         );
         headers.insert(
             "X-Emby-Authorization",
-            reqwest::header::HeaderValue::from_str(&format!(
-                "MediaBrowser Token=\"{}\", Client=\"AnyPlayer\", Device=\"AnyPlayer\", DeviceId=\"AnyPlayer\", Version=\"1.0.0\"",
-                self.api_key
-            )).unwrap(),
+            reqwest::header::HeaderValue::from_str(&self.authorization()).unwrap(),
         );
         headers
     }
 
+    /// Send an idempotent GET, retrying transient failures with backoff
+    async fn get_with_retry(&self, url: &str) -> Result<reqwest::Response, reqwest::Error> {
+        retry::retry_with(
+            &RetryPolicy::default(),
+            || self.send(self.client.get(url).headers(self.build_headers())),
+            retry::decide_response,
+        )
+        .await
+    }
+
+    /// Send a request through the rate limiter, slowing down if the server returns 429
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.rate_limiter.acquire().await;
+        let response = request.send().await?;
+        self.rate_limiter
+            .record_status(
+                response.status(),
+                retry::parse_retry_after(response.headers()),
+            )
+            .await;
+        Ok(response)
+    }
+
+    /// ID of the signed-in user, or an error if not authenticated
+    fn authenticated_user_id(&self) -> Result<&str, ProviderError> {
+        if !self.authenticated {
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
+        }
+        self.user_id
+            .as_deref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))
+    }
+
+    /// Items query URL for the user's playlists
+    fn playlists_url(&self, user_id: &str) -> String {
+        format!(
+            "{}/Users/{}/Items?Filters=IsFolder&Recursive=true&IncludeItemTypes=Playlist",
+            self.base_url, user_id
+        )
+    }
+
+    /// Items query URL for the entries of a playlist
+    fn playlist_items_url(&self, user_id: &str, playlist_id: &str) -> String {
+        format!(
+            "{}/Users/{}/Items?ParentId={}&Fields=AudioInfo,ParentId,Genres",
+            self.base_url, user_id, playlist_id
+        )
+    }
+
+    /// Items query URL for the tracks of an album, in disc and track order
+    fn album_tracks_url(&self, user_id: &str, album_id: &str) -> String {
+        format!(
+            "{}/Users/{}/Items?ParentId={}&IncludeItemTypes=Audio&Recursive=true&SortBy=ParentIndexNumber,IndexNumber&Fields=AudioInfo,ParentId,Genres",
+            self.base_url, user_id, album_id
+        )
+    }
+
+    /// URL for a single item as seen by a user
+    fn item_url(&self, user_id: &str, item_id: &str) -> String {
+        format!("{}/Users/{}/Items/{}", self.base_url, user_id, item_id)
+    }
+
+    /// Items query URL for the albums credited to an album artist
+    fn artist_albums_url(&self, user_id: &str, artist_id: &str) -> String {
+        format!(
+            "{}/Users/{}/Items?AlbumArtistIds={}&IncludeItemTypes=MusicAlbum&Recursive=true&SortBy=ProductionYear,SortName",
+            self.base_url, user_id, artist_id
+        )
+    }
+
+    /// Items query URL for an artist's most played tracks
+    fn artist_top_tracks_url(&self, user_id: &str, artist_id: &str) -> String {
+        format!(
+            "{}/Users/{}/Items?ArtistIds={}&IncludeItemTypes=Audio&Recursive=true&SortBy=PlayCount,SortName&SortOrder=Descending&Limit={}&Fields=Genres",
+            self.base_url, user_id, artist_id, ARTIST_TOP_TRACKS_LIMIT
+        )
+    }
+
+    /// Items query URL for tracks matching a search
+    fn search_tracks_url(&self, user_id: &str, query: &str) -> String {
+        format!(
+            "{}/Users/{}/Items?searchTerm={}&IncludeItemTypes=Audio&Recursive=true&Fields=Genres",
+            self.base_url, user_id, query
+        )
+    }
+
+    /// URL that marks (POST) or unmarks (DELETE) an item as a favorite
+    fn favorite_item_url(&self, user_id: &str, item_id: &str) -> String {
+        format!(
+            "{}/Users/{}/FavoriteItems/{}",
+            self.base_url, user_id, item_id
+        )
+    }
+
+    /// Items query URL for the user's favorite tracks
+    fn favorites_url(&self, user_id: &str) -> String {
+        format!(
+            "{}/Users/{}/Items?Filters=IsFavorite&IncludeItemTypes=Audio&Recursive=true&SortBy=SortName&Fields=AudioInfo,ParentId,Genres",
+            self.base_url, user_id
+        )
+    }
+
+    /// Mark or unmark a track (or any item) as a favorite
+    pub async fn set_favorite(&self, item_id: &str, favorite: bool) -> Result<(), ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+        let url = self.favorite_item_url(user_id, item_id);
+        let request = if favorite {
+            self.client.post(&url)
+        } else {
+            self.client.delete(&url)
+        };
+
+        let response = self
+            .send(request.headers(self.build_headers()))
+            .await
+            .map_err(|e| ProviderError::with_context("Failed to update favorite", e))?;
+
+        if !response.status().is_success() {
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to update favorite",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// All tracks the user has marked as favorites, ready to play
+    pub async fn get_favorites(&self) -> Result<Vec<Track>, ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+        let url = self.favorites_url(user_id);
+
+        let mut tracks = Vec::new();
+        loop {
+            let data = self
+                .fetch_items(
+                    &paged_url(&url, tracks.len(), FAVORITES_PAGE_SIZE),
+                    "Failed to fetch favorites",
+                )
+                .await?;
+            let fetched = data.items.len();
+            tracks.extend(data.items.iter().map(|item| self.item_to_track(item)));
+
+            if fetched < FAVORITES_PAGE_SIZE || tracks.len() >= data.total_record_count as usize {
+                break;
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// Run an items query, returning the items and the server's total count
+    async fn fetch_items(
+        &self,
+        url: &str,
+        context: &str,
+    ) -> Result<JellyfinItemsResponse, ProviderError> {
+        let response = self
+            .get_with_retry(url)
+            .await
+            .map_err(|e| ProviderError::with_context(context, e))?;
+
+        if !response.status().is_success() {
+            return Err(ProviderError::from_status(response.status(), context));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ProviderError::Other(format!("{}: {}", context, e)))
+    }
+
     /// Helper method to get image URL if available
     /// For tracks, tries to use album artwork first, then falls back to item's own image
     fn get_image_url(&self, item: &JellyfinItem) -> Option<String> {
@@ -137,6 +430,94 @@ impl JellyfinProvider {
         None
     }
 
+    /// Tracks for the playable audio entries of a playlist, along with how
+    /// many entries of each other type (music videos, ...) were left out
+    fn playable_tracks(&self, items: &[JellyfinItem]) -> (Vec<Track>, BTreeMap<String, usize>) {
+        let mut tracks = Vec::new();
+        let mut skipped = BTreeMap::new();
+        for item in items {
+            if item.item_type == "Audio" {
+                tracks.push(self.item_to_track(item));
+            } else {
+                *skipped.entry(item.item_type.clone()).or_insert(0) += 1;
+            }
+        }
+        (tracks, skipped)
+    }
+
+    /// Fetch every entry of a playlist, keeping the playable audio
+    async fn fetch_playlist_tracks(
+        &self,
+        user_id: &str,
+        id: &str,
+    ) -> Result<Vec<Track>, ProviderError> {
+        let mut all_tracks = Vec::new();
+        let mut skipped: BTreeMap<String, usize> = BTreeMap::new();
+        let limit = 300; // Jellyfin default limit
+        let mut start_index = 0;
+        // Safety limit to prevent infinite loops. With limit=300, this allows for
+        // playlists with up to 300,000 items (1000 * 300), which should be sufficient
+        // for any realistic use case while protecting against API issues.
+        const MAX_ITERATIONS: usize = 1000;
+        let mut iteration_count = 0;
+
+        loop {
+            iteration_count += 1;
+            if iteration_count > MAX_ITERATIONS {
+                tracing::warn!(
+                    "Reached maximum iteration count ({}) while fetching Jellyfin playlist items",
+                    MAX_ITERATIONS
+                );
+                break;
+            }
+
+            let items_url = paged_url(&self.playlist_items_url(user_id, id), start_index, limit);
+            let items_response = self
+                .get_with_retry(&items_url)
+                .await
+                .map_err(|e| ProviderError::with_context("Failed to fetch playlist items", e))?;
+
+            if !items_response.status().is_success() {
+                return Err(ProviderError::from_status(
+                    items_response.status(),
+                    "Failed to fetch playlist items",
+                ));
+            }
+
+            let items_data: JellyfinItemsResponse = items_response.json().await.map_err(|e| {
+                ProviderError::Other(format!("Failed to parse playlist items: {}", e))
+            })?;
+
+            let (tracks, page_skipped) = self.playable_tracks(&items_data.items);
+            all_tracks.extend(tracks);
+            for (item_type, count) in page_skipped {
+                *skipped.entry(item_type).or_insert(0) += count;
+            }
+
+            // Check if we've fetched all items, counting the entries that
+            // were skipped as well as the tracks
+            // Break if: no items returned, fewer items than requested, or we've reached the total
+            let fetched_count = items_data.items.len();
+            start_index += fetched_count;
+            if fetched_count == 0
+                || fetched_count < limit
+                || start_index >= items_data.total_record_count as usize
+            {
+                break;
+            }
+        }
+
+        if !skipped.is_empty() {
+            tracing::info!(
+                "Skipped non-audio items in Jellyfin playlist {}: {:?}",
+                id,
+                skipped
+            );
+        }
+
+        Ok(all_tracks)
+    }
+
     /// Create a fallback playlist with basic metadata when detailed metadata is unavailable
     fn create_fallback_playlist(&self, id: &str, tracks: Vec<Track>) -> Playlist {
         let track_count = tracks.len();
@@ -149,17 +530,20 @@ impl JellyfinProvider {
             track_count,
             tracks,
             source: Source::Jellyfin,
+            revision: None,
         }
     }
 
     /// Convert Jellyfin item to Track
     fn item_to_track(&self, item: &JellyfinItem) -> Track {
         let duration_ms = item.runtime_ticks.map(|ticks| ticks / 10_000).unwrap_or(0);
+        let album_artist = item_album_artist(item);
         let artist = item
             .artists
             .as_ref()
-            .and_then(|artists| artists.first())
-            .cloned()
+            .map(|artists| join_names(artists.iter().map(String::as_str)))
+            .filter(|artists| !artists.is_empty())
+            .or_else(|| album_artist.clone())
             .unwrap_or_else(|| "Unknown Artist".to_string());
         let album = item
             .album
@@ -178,28 +562,82 @@ impl JellyfinProvider {
         );
 
         // Prepare authentication headers for streaming requests
-        let auth_headers = vec![
-            ("X-Emby-Token".to_string(), self.api_key.clone()),
-            ("X-Emby-Authorization".to_string(), format!(
-                "MediaBrowser Token=\"{}\", Client=\"AnyPlayer\", Device=\"AnyPlayer\", DeviceId=\"AnyPlayer\", Version=\"1.0.0\"",
-                self.api_key
-            )),
-        ];
+        let auth_headers = self.get_auth_headers();
 
         Track {
             id: item.id.clone(),
             title: item.name.clone(),
             artist,
             album,
+            album_artist,
             duration_ms,
             image_url,
             source: Source::Jellyfin,
             url: Some(stream_url),
             auth_headers: Some(auth_headers),
+            genre: item
+                .genres
+                .as_ref()
+                .and_then(|genres| genres.first())
+                .cloned(),
+            year: item.production_year,
+            track_number: item.index_number,
+            disc_number: item.parent_index_number,
+            enriched_at: None,
         }
     }
 
     /// Convert Jellyfin item to Playlist
+    /// Build an album from its metadata item (if it could be fetched) and its tracks
+    fn album_from_items(
+        &self,
+        id: &str,
+        album: Option<&JellyfinItem>,
+        items: &[JellyfinItem],
+    ) -> Playlist {
+        let mut tracks: Vec<Track> = items
+            .iter()
+            .filter(|item| item.item_type == "Audio")
+            .map(|item| self.item_to_track(item))
+            .collect();
+        sort_album_tracks(&mut tracks);
+
+        let name = album
+            .map(|album| album.name.clone())
+            .or_else(|| tracks.first().map(|t| t.album.clone()))
+            .unwrap_or_else(|| format!("Album {}", id));
+        let owner = album
+            .and_then(|album| {
+                item_album_artist(album).or_else(|| album.artists.as_ref()?.first().cloned())
+            })
+            .or_else(|| tracks.first().map(|t| t.artist.clone()))
+            .unwrap_or_else(|| "Unknown Artist".to_string());
+
+        Playlist {
+            id: id.to_string(),
+            name,
+            description: None,
+            owner,
+            image_url: album
+                .and_then(|album| self.get_image_url(album))
+                .or_else(|| tracks.first().and_then(|t| t.image_url.clone())),
+            track_count: tracks.len(),
+            tracks,
+            source: Source::Jellyfin,
+            revision: None,
+        }
+    }
+
+    /// Convert a MusicAlbum item to a track-less album summary
+    fn item_to_album(&self, item: &JellyfinItem) -> Playlist {
+        Playlist {
+            owner: item_album_artist(item)
+                .or_else(|| item.artists.as_ref()?.first().cloned())
+                .unwrap_or_else(|| "Unknown Artist".to_string()),
+            ..self.item_to_playlist(item)
+        }
+    }
+
     fn item_to_playlist(&self, item: &JellyfinItem) -> Playlist {
         let image_url = self.get_image_url(item);
 
@@ -212,6 +650,7 @@ impl JellyfinProvider {
             track_count: item.child_count.unwrap_or(0) as usize,
             tracks: Vec::new(),
             source: Source::Jellyfin,
+            revision: item.date_modified.clone(),
         }
     }
 }
@@ -227,45 +666,39 @@ impl MusicProvider for JellyfinProvider {
         // GET /System/Info with api_key header
         let url = format!("{}/System/Info", self.base_url);
         let response = self
-            .client
-            .get(&url)
-            .headers(self.build_headers())
-            .send()
+            .get_with_retry(&url)
             .await
-            .map_err(|e| ProviderError(format!("Failed to connect to Jellyfin: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to connect to Jellyfin", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Jellyfin authentication failed: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Jellyfin authentication failed",
+            ));
         }
 
         // Get list of users from the /Users endpoint and pick the first one
         // (since API keys don't have a "current user"; typically this is the admin/main user)
         let users_url = format!("{}/Users", self.base_url);
         let users_response = self
-            .client
-            .get(&users_url)
-            .headers(self.build_headers())
-            .send()
+            .get_with_retry(&users_url)
             .await
-            .map_err(|e| ProviderError(format!("Failed to get users: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to get users", e))?;
 
         if !users_response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to get user list: HTTP {}",
-                users_response.status()
-            )));
+            return Err(ProviderError::from_status(
+                users_response.status(),
+                "Failed to get user list",
+            ));
         }
 
         let users: Vec<JellyfinUser> = users_response
             .json()
             .await
-            .map_err(|e| ProviderError(format!("Failed to parse users: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to parse users: {}", e)))?;
 
         if users.is_empty() {
-            return Err(ProviderError(
+            return Err(ProviderError::NotFound(
                 "No users found on Jellyfin server".to_string(),
             ));
         }
@@ -297,41 +730,48 @@ impl MusicProvider for JellyfinProvider {
         self.authenticated
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            search_tracks: true,
+            search_playlists: true,
+            create_playlist: true,
+            edit_playlists: true,
+            streaming: true,
+            recently_played: true,
+        }
+    }
+
     async fn get_playlists(&self) -> Result<Vec<Playlist>, ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         let user_id = self
             .user_id
             .as_ref()
-            .ok_or_else(|| ProviderError("User ID not available".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))?;
 
         // GET /Users/{userId}/Items with Filters=IsFolder
-        let url = format!(
-            "{}/Users/{}/Items?Filters=IsFolder&Recursive=true&IncludeItemTypes=Playlist",
-            self.base_url, user_id
-        );
+        let url = self.playlists_url(user_id);
 
         let response = self
-            .client
-            .get(&url)
-            .headers(self.build_headers())
-            .send()
+            .get_with_retry(&url)
             .await
-            .map_err(|e| ProviderError(format!("Failed to fetch playlists: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to fetch playlists", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to fetch playlists: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to fetch playlists",
+            ));
         }
 
         let data: JellyfinItemsResponse = response
             .json()
             .await
-            .map_err(|e| ProviderError(format!("Failed to parse playlists: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to parse playlists: {}", e)))?;
 
         let playlists: Vec<Playlist> = data
             .items
@@ -344,94 +784,38 @@ impl MusicProvider for JellyfinProvider {
 
     async fn get_playlist(&self, id: &str) -> Result<Playlist, ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         let user_id = self
             .user_id
             .as_ref()
-            .ok_or_else(|| ProviderError("User ID not available".to_string()))?;
-
-        // Fetch all playlist items with pagination
-        let mut all_tracks = Vec::new();
-        let limit = 300; // Jellyfin default limit
-        let mut start_index = 0;
-        // Safety limit to prevent infinite loops. With limit=300, this allows for
-        // playlists with up to 300,000 items (1000 * 300), which should be sufficient
-        // for any realistic use case while protecting against API issues.
-        const MAX_ITERATIONS: usize = 1000;
-        let mut iteration_count = 0;
-
-        loop {
-            iteration_count += 1;
-            if iteration_count > MAX_ITERATIONS {
-                tracing::warn!(
-                    "Reached maximum iteration count ({}) while fetching Jellyfin playlist items",
-                    MAX_ITERATIONS
-                );
-                break;
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))?;
+
+        // A playlist whose entries can't be listed is still returned, just
+        // without tracks, unless the server rejected our credentials
+        let all_tracks = match self.fetch_playlist_tracks(user_id, id).await {
+            Ok(tracks) => tracks,
+            Err(e @ ProviderError::NotAuthenticated(_)) => return Err(e),
+            Err(e) => {
+                tracing::warn!("Failed to fetch items of Jellyfin playlist {}: {}", id, e);
+                Vec::new()
             }
-
-            let items_url = format!(
-                "{}/Users/{}/Items?ParentId={}&Fields=AudioInfo,ParentId&Limit={}&StartIndex={}",
-                self.base_url, user_id, id, limit, start_index
-            );
-            let items_response = self
-                .client
-                .get(&items_url)
-                .headers(self.build_headers())
-                .send()
-                .await
-                .map_err(|e| ProviderError(format!("Failed to fetch playlist items: {}", e)))?;
-
-            if !items_response.status().is_success() {
-                return Err(ProviderError(format!(
-                    "Failed to fetch playlist items: HTTP {}",
-                    items_response.status()
-                )));
-            }
-
-            let items_data: JellyfinItemsResponse = items_response
-                .json()
-                .await
-                .map_err(|e| ProviderError(format!("Failed to parse playlist items: {}", e)))?;
-
-            let tracks: Vec<Track> = items_data
-                .items
-                .into_iter()
-                .filter(|item| item.item_type == "Audio")
-                .map(|item| self.item_to_track(&item))
-                .collect();
-
-            let fetched_count = tracks.len();
-            all_tracks.extend(tracks);
-
-            // Check if we've fetched all items
-            // Break if: no items returned, fewer items than requested, or we've reached the total
-            if fetched_count == 0
-                || fetched_count < limit
-                || all_tracks.len() >= items_data.total_record_count as usize
-            {
-                break;
-            }
-
-            start_index += limit;
-        }
+        };
 
         // Try to get playlist metadata using the direct Playlists endpoint first
         let metadata_url = format!("{}/Playlists/{}", self.base_url, id);
-        let metadata_response = self
-            .client
-            .get(&metadata_url)
-            .headers(self.build_headers())
-            .send()
-            .await;
+        let metadata_response = self.get_with_retry(&metadata_url).await;
 
         // If direct endpoint works, use it; otherwise fall back to basic metadata
         let playlist = if let Ok(response) = metadata_response {
             if response.status().is_success() {
                 if let Ok(item) = response.json::<JellyfinItem>().await {
                     let mut playlist = self.item_to_playlist(&item);
+                    // ChildCount includes entries that aren't audio
+                    playlist.track_count = all_tracks.len();
                     playlist.tracks = all_tracks;
                     return Ok(playlist);
                 }
@@ -448,75 +832,122 @@ impl MusicProvider for JellyfinProvider {
 
     async fn get_track(&self, id: &str) -> Result<Track, ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         let user_id = self
             .user_id
             .as_ref()
-            .ok_or_else(|| ProviderError("User ID not available".to_string()))?;
-
-        // GET /Users/{userId}/Items/{id}
-        let url = format!("{}/Users/{}/Items/{}", self.base_url, user_id, id);
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))?;
 
         let response = self
-            .client
-            .get(&url)
-            .headers(self.build_headers())
-            .send()
+            .get_with_retry(&self.item_url(user_id, id))
             .await
-            .map_err(|e| ProviderError(format!("Failed to fetch track: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to fetch track", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to fetch track: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to fetch track",
+            ));
         }
 
         let item: JellyfinItem = response
             .json()
             .await
-            .map_err(|e| ProviderError(format!("Failed to parse track: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to parse track: {}", e)))?;
 
         Ok(self.item_to_track(&item))
     }
 
+    async fn get_album(&self, id: &str) -> Result<Playlist, ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+
+        let data = self
+            .fetch_items(
+                &self.album_tracks_url(user_id, id),
+                "Failed to fetch album tracks",
+            )
+            .await?;
+
+        // The album's own item carries its artist and artwork; the tracks are
+        // enough to build it if that lookup fails
+        let metadata_url = format!("{}/Users/{}/Items/{}", self.base_url, user_id, id);
+        let album = match self.get_with_retry(&metadata_url).await {
+            Ok(response) if response.status().is_success() => {
+                response.json::<JellyfinItem>().await.ok()
+            }
+            _ => None,
+        };
+
+        Ok(self.album_from_items(id, album.as_ref(), &data.items))
+    }
+
+    async fn get_artist_albums(&self, artist_id: &str) -> Result<Vec<Playlist>, ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+        let data = self
+            .fetch_items(
+                &self.artist_albums_url(user_id, artist_id),
+                "Failed to fetch artist albums",
+            )
+            .await?;
+
+        Ok(data
+            .items
+            .iter()
+            .map(|item| self.item_to_album(item))
+            .collect())
+    }
+
+    async fn get_artist_top_tracks(&self, artist_id: &str) -> Result<Vec<Track>, ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+        let data = self
+            .fetch_items(
+                &self.artist_top_tracks_url(user_id, artist_id),
+                "Failed to fetch artist top tracks",
+            )
+            .await?;
+
+        Ok(data
+            .items
+            .iter()
+            .map(|item| self.item_to_track(item))
+            .collect())
+    }
+
     async fn search_tracks(&self, query: &str) -> Result<Vec<Track>, ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         let user_id = self
             .user_id
             .as_ref()
-            .ok_or_else(|| ProviderError("User ID not available".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))?;
 
         // GET /Items with search query
-        let url = format!(
-            "{}/Users/{}/Items?searchTerm={}&IncludeItemTypes=Audio&Recursive=true",
-            self.base_url, user_id, query
-        );
+        let url = self.search_tracks_url(user_id, query);
 
         let response = self
-            .client
-            .get(&url)
-            .headers(self.build_headers())
-            .send()
+            .get_with_retry(&url)
             .await
-            .map_err(|e| ProviderError(format!("Failed to search tracks: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to search tracks", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to search tracks: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to search tracks",
+            ));
         }
 
         let data: JellyfinItemsResponse = response
             .json()
             .await
-            .map_err(|e| ProviderError(format!("Failed to parse search results: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to parse search results: {}", e)))?;
 
         let tracks: Vec<Track> = data
             .items
@@ -529,13 +960,15 @@ impl MusicProvider for JellyfinProvider {
 
     async fn search_playlists(&self, query: &str) -> Result<Vec<Playlist>, ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         let user_id = self
             .user_id
             .as_ref()
-            .ok_or_else(|| ProviderError("User ID not available".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))?;
 
         // GET /Items with search query for playlists
         let url = format!(
@@ -544,24 +977,21 @@ impl MusicProvider for JellyfinProvider {
         );
 
         let response = self
-            .client
-            .get(&url)
-            .headers(self.build_headers())
-            .send()
+            .get_with_retry(&url)
             .await
-            .map_err(|e| ProviderError(format!("Failed to search playlists: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to search playlists", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to search playlists: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to search playlists",
+            ));
         }
 
         let data: JellyfinItemsResponse = response
             .json()
             .await
-            .map_err(|e| ProviderError(format!("Failed to parse search results: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to parse search results: {}", e)))?;
 
         let playlists: Vec<Playlist> = data
             .items
@@ -572,6 +1002,63 @@ impl MusicProvider for JellyfinProvider {
         Ok(playlists)
     }
 
+    async fn get_playlists_page(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Playlist>, ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+        let url = paged_url(&self.playlists_url(user_id), offset, limit);
+        let data = self.fetch_items(&url, "Failed to fetch playlists").await?;
+
+        Ok(Page {
+            items: data
+                .items
+                .iter()
+                .map(|item| self.item_to_playlist(item))
+                .collect(),
+            total: data.total_record_count as usize,
+        })
+    }
+
+    async fn get_playlist_tracks_page(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Track>, ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+        let url = paged_url(&self.playlist_items_url(user_id, id), offset, limit);
+        let data = self
+            .fetch_items(&url, "Failed to fetch playlist items")
+            .await?;
+
+        Ok(Page {
+            items: self.playable_tracks(&data.items).0,
+            total: data.total_record_count as usize,
+        })
+    }
+
+    async fn search_tracks_page(
+        &self,
+        query: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Track>, ProviderError> {
+        let user_id = self.authenticated_user_id()?;
+        let url = paged_url(&self.search_tracks_url(user_id, query), offset, limit);
+        let data = self.fetch_items(&url, "Failed to search tracks").await?;
+
+        Ok(Page {
+            items: data
+                .items
+                .iter()
+                .map(|item| self.item_to_track(item))
+                .collect(),
+            total: data.total_record_count as usize,
+        })
+    }
+
     async fn get_stream_url(&self, track_id: &str) -> Result<String, ProviderError> {
         // Get direct stream URL from Jellyfin
         // Format: {base_url}/Audio/{track_id}/universal?api_key={api_key}
@@ -587,13 +1074,15 @@ impl MusicProvider for JellyfinProvider {
         description: Option<&str>,
     ) -> Result<Playlist, ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         let user_id = self
             .user_id
             .as_ref()
-            .ok_or_else(|| ProviderError("User ID not available".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))?;
 
         // POST /Playlists with playlist data
         let url = format!(
@@ -602,24 +1091,20 @@ impl MusicProvider for JellyfinProvider {
         );
 
         let response = self
-            .client
-            .post(&url)
-            .headers(self.build_headers())
-            .send()
+            .send(self.client.post(&url).headers(self.build_headers()))
             .await
-            .map_err(|e| ProviderError(format!("Failed to create playlist: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to create playlist", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to create playlist: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to create playlist",
+            ));
         }
 
-        let item: JellyfinItem = response
-            .json()
-            .await
-            .map_err(|e| ProviderError(format!("Failed to parse created playlist: {}", e)))?;
+        let item: JellyfinItem = response.json().await.map_err(|e| {
+            ProviderError::Other(format!("Failed to parse created playlist: {}", e))
+        })?;
 
         let mut playlist = self.item_to_playlist(&item);
         if let Some(desc) = description {
@@ -634,7 +1119,9 @@ impl MusicProvider for JellyfinProvider {
         track: &Track,
     ) -> Result<(), ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         // POST /Playlists/{playlistId}/Items?ids={trackId}
@@ -644,18 +1131,15 @@ impl MusicProvider for JellyfinProvider {
         );
 
         let response = self
-            .client
-            .post(&url)
-            .headers(self.build_headers())
-            .send()
+            .send(self.client.post(&url).headers(self.build_headers()))
             .await
-            .map_err(|e| ProviderError(format!("Failed to add track to playlist: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to add track to playlist", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to add track to playlist: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to add track to playlist",
+            ));
         }
 
         Ok(())
@@ -667,7 +1151,9 @@ impl MusicProvider for JellyfinProvider {
         track_id: &str,
     ) -> Result<(), ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         // DELETE /Playlists/{playlistId}/Items?ids={trackId}
@@ -677,18 +1163,15 @@ impl MusicProvider for JellyfinProvider {
         );
 
         let response = self
-            .client
-            .delete(&url)
-            .headers(self.build_headers())
-            .send()
+            .send(self.client.delete(&url).headers(self.build_headers()))
             .await
-            .map_err(|e| ProviderError(format!("Failed to remove track from playlist: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to remove track from playlist", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to remove track from playlist: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to remove track from playlist",
+            ));
         }
 
         Ok(())
@@ -696,39 +1179,38 @@ impl MusicProvider for JellyfinProvider {
 
     async fn get_recently_played(&self, limit: usize) -> Result<Vec<Track>, ProviderError> {
         if !self.authenticated {
-            return Err(ProviderError("Not authenticated".to_string()));
+            return Err(ProviderError::NotAuthenticated(
+                "Not authenticated".to_string(),
+            ));
         }
 
         let user_id = self
             .user_id
             .as_ref()
-            .ok_or_else(|| ProviderError("User ID not available".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("User ID not available".to_string()))?;
 
         // Get recently played items
         let url = format!(
-            "{}/Users/{}/Items?SortBy=DatePlayed&SortOrder=Descending&Limit={}&Filters=IsPlayed&IncludeItemTypes=Audio&Recursive=true",
+            "{}/Users/{}/Items?SortBy=DatePlayed&SortOrder=Descending&Limit={}&Filters=IsPlayed&IncludeItemTypes=Audio&Recursive=true&Fields=Genres",
             self.base_url, user_id, limit
         );
 
         let response = self
-            .client
-            .get(&url)
-            .headers(self.build_headers())
-            .send()
+            .get_with_retry(&url)
             .await
-            .map_err(|e| ProviderError(format!("Failed to fetch recently played: {}", e)))?;
+            .map_err(|e| ProviderError::with_context("Failed to fetch recently played", e))?;
 
         if !response.status().is_success() {
-            return Err(ProviderError(format!(
-                "Failed to fetch recently played: HTTP {}",
-                response.status()
-            )));
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Failed to fetch recently played",
+            ));
         }
 
         let data: JellyfinItemsResponse = response
             .json()
             .await
-            .map_err(|e| ProviderError(format!("Failed to parse recently played: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to parse recently played: {}", e)))?;
 
         let tracks: Vec<Track> = data
             .items
@@ -738,4 +1220,504 @@ impl MusicProvider for JellyfinProvider {
 
         Ok(tracks)
     }
+
+    async fn health_check(&self) -> Result<(), ProviderError> {
+        // /System/Info is cheap and requires a valid API key
+        let url = format!("{}/System/Info", self.base_url);
+        let response = self
+            .send(self.client.get(&url).headers(self.build_headers()))
+            .await
+            .map_err(|e| ProviderError::with_context("Failed to connect to Jellyfin", e))?;
+
+        if !response.status().is_success() {
+            return Err(ProviderError::from_status(
+                response.status(),
+                "Jellyfin health check failed",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Provider pointed at a mock server with its own client
+    fn mock_provider(server: &MockServer) -> JellyfinProvider {
+        JellyfinProvider::with_client(server.uri(), "test_key".to_string(), Client::new()).unwrap()
+    }
+
+    /// Answer the requests `authenticate` makes with a single user
+    async fn mount_auth(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/System/Info"))
+            .and(header("X-Emby-Token", "test_key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ServerName": "Test"
+            })))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Users"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "Id": "user1", "Name": "admin" }
+            ])))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_item_to_track_maps_metadata() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        let item: JellyfinItem = serde_json::from_value(serde_json::json!({
+            "Id": "item1",
+            "Name": "Song",
+            "Type": "Audio",
+            "Album": "Album",
+            "Artists": ["Artist"],
+            "RunTimeTicks": 1_800_000_000u64,
+            "Genres": ["Jazz", "Blues"],
+            "ProductionYear": 1959,
+            "IndexNumber": 3,
+            "ParentIndexNumber": 2
+        }))
+        .unwrap();
+
+        let track = provider.item_to_track(&item);
+        assert_eq!(track.title, "Song");
+        assert_eq!(track.duration_ms, 180_000);
+        assert_eq!(track.genre.as_deref(), Some("Jazz"));
+        assert_eq!(track.year, Some(1959));
+        assert_eq!(track.track_number, Some(3));
+        assert_eq!(track.disc_number, Some(2));
+    }
+
+    #[test]
+    fn test_item_to_track_without_metadata() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        let item: JellyfinItem = serde_json::from_value(serde_json::json!({
+            "Id": "item2",
+            "Name": "Untagged",
+            "Type": "Audio"
+        }))
+        .unwrap();
+
+        let track = provider.item_to_track(&item);
+        assert!(track.genre.is_none());
+        assert!(track.year.is_none());
+        assert!(track.track_number.is_none());
+        assert!(track.disc_number.is_none());
+    }
+
+    #[test]
+    fn test_item_to_track_joins_artists_and_maps_album_artist() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        let item: JellyfinItem = serde_json::from_value(serde_json::json!({
+            "Id": "item3",
+            "Name": "Duet",
+            "Type": "Audio",
+            "Album": "Summer Hits",
+            "Artists": ["Singer", "Rapper", "Singer"],
+            "AlbumArtist": "Various Artists",
+            "AlbumArtists": [{ "Name": "Various Artists", "Id": "va" }]
+        }))
+        .unwrap();
+
+        let track = provider.item_to_track(&item);
+        assert_eq!(track.artist, "Singer, Rapper");
+        assert_eq!(track.album_artist.as_deref(), Some("Various Artists"));
+
+        let item: JellyfinItem = serde_json::from_value(serde_json::json!({
+            "Id": "item4",
+            "Name": "Instrumental",
+            "Type": "Audio",
+            "AlbumArtists": [{ "Name": "Duo A", "Id": "a" }, { "Name": "Duo B", "Id": "b" }]
+        }))
+        .unwrap();
+
+        let track = provider.item_to_track(&item);
+        assert_eq!(track.album_artist.as_deref(), Some("Duo A, Duo B"));
+        assert_eq!(track.artist, "Duo A, Duo B");
+    }
+
+    #[test]
+    fn test_album_tracks_ordered_by_disc_then_track() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        let album: JellyfinItem = serde_json::from_value(serde_json::json!({
+            "Id": "album1",
+            "Name": "Double Album",
+            "Type": "MusicAlbum",
+            "AlbumArtist": "Band"
+        }))
+        .unwrap();
+        let items: Vec<JellyfinItem> = [(2, 1), (1, 2), (1, 1), (2, 2)]
+            .iter()
+            .map(|(disc, track)| {
+                serde_json::from_value(serde_json::json!({
+                    "Id": format!("d{}t{}", disc, track),
+                    "Name": "Song",
+                    "Type": "Audio",
+                    "Album": "Double Album",
+                    "IndexNumber": track,
+                    "ParentIndexNumber": disc
+                }))
+                .unwrap()
+            })
+            .collect();
+
+        let playlist = provider.album_from_items("album1", Some(&album), &items);
+
+        let ids: Vec<&str> = playlist.tracks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["d1t1", "d1t2", "d2t1", "d2t2"]);
+        assert_eq!(playlist.name, "Double Album");
+        assert_eq!(playlist.owner, "Band");
+        assert_eq!(playlist.track_count, 4);
+    }
+
+    #[test]
+    fn test_item_to_album_maps_artist_and_track_count() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        let item: JellyfinItem = serde_json::from_value(serde_json::json!({
+            "Id": "album1",
+            "Name": "Kind of Blue",
+            "Type": "MusicAlbum",
+            "AlbumArtist": "Miles Davis",
+            "Artists": ["Miles Davis", "John Coltrane"],
+            "ChildCount": 5,
+            "ProductionYear": 1959,
+            "ImageTags": { "Primary": "tag1" }
+        }))
+        .unwrap();
+
+        let album = provider.item_to_album(&item);
+        assert_eq!(album.id, "album1");
+        assert_eq!(album.name, "Kind of Blue");
+        assert_eq!(album.owner, "Miles Davis");
+        assert_eq!(album.track_count, 5);
+        assert!(album.tracks.is_empty());
+        assert!(album.image_url.is_some());
+
+        let untagged: JellyfinItem = serde_json::from_value(serde_json::json!({
+            "Id": "album2",
+            "Name": "Untagged",
+            "Type": "MusicAlbum",
+            "Artists": ["Someone"]
+        }))
+        .unwrap();
+        assert_eq!(provider.item_to_album(&untagged).owner, "Someone");
+    }
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        for (input, expected) in [
+            ("http://localhost:8096/", "http://localhost:8096"),
+            ("https://media.example.com//", "https://media.example.com"),
+            (
+                "https://example.com/jellyfin/",
+                "https://example.com/jellyfin",
+            ),
+            ("localhost:8096", "http://localhost:8096"),
+            ("jellyfin", "http://jellyfin"),
+            ("  jellyfin.local/  ", "http://jellyfin.local"),
+        ] {
+            assert_eq!(normalize_base_url(input).unwrap(), expected, "{}", input);
+        }
+
+        let provider =
+            JellyfinProvider::new("jellyfin:8096/".to_string(), "key".to_string()).unwrap();
+        assert_eq!(provider.base_url(), "http://jellyfin:8096");
+        assert_eq!(
+            provider.item_url("user1", "track1"),
+            "http://jellyfin:8096/Users/user1/Items/track1"
+        );
+    }
+
+    #[test]
+    fn test_invalid_base_url_is_rejected() {
+        for input in ["", "   ", "http://", "ftp://example.com", "http://bad host"] {
+            let error = normalize_base_url(input).unwrap_err();
+            assert!(
+                error.to_string().contains("Jellyfin server URL"),
+                "{}: {}",
+                input,
+                error
+            );
+        }
+        assert!(JellyfinProvider::new("http://".to_string(), "key".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_item_url() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        assert_eq!(
+            provider.item_url("user1", "track1"),
+            "http://localhost:8096/Users/user1/Items/track1"
+        );
+    }
+
+    #[test]
+    fn test_favorite_urls() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        assert_eq!(
+            provider.favorite_item_url("user1", "track1"),
+            "http://localhost:8096/Users/user1/FavoriteItems/track1"
+        );
+        assert_eq!(
+            paged_url(&provider.favorites_url("user1"), 0, 300),
+            "http://localhost:8096/Users/user1/Items?Filters=IsFavorite&IncludeItemTypes=Audio&Recursive=true&SortBy=SortName&Fields=AudioInfo,ParentId,Genres&StartIndex=0&Limit=300"
+        );
+    }
+
+    #[test]
+    fn test_playlist_entries_keep_only_audio() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        let data: JellyfinItemsResponse = serde_json::from_value(serde_json::json!({
+            "Items": [
+                { "Id": "a1", "Name": "Song", "Type": "Audio" },
+                { "Id": "v1", "Name": "Live Video", "Type": "MusicVideo" },
+                { "Id": "a2", "Name": "Another Song", "Type": "Audio" },
+                { "Id": "v2", "Name": "Clip", "Type": "MusicVideo" },
+                { "Id": "m1", "Name": "Film", "Type": "Movie" }
+            ],
+            "TotalRecordCount": 5
+        }))
+        .unwrap();
+
+        let (tracks, skipped) = provider.playable_tracks(&data.items);
+
+        let ids: Vec<&str> = tracks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "a2"]);
+        assert_eq!(skipped.get("MusicVideo"), Some(&2));
+        assert_eq!(skipped.get("Movie"), Some(&1));
+        assert_eq!(skipped.len(), 2);
+    }
+
+    #[test]
+    fn test_favorites_map_to_playable_tracks() {
+        let mut provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        provider.user_id = Some("user1".to_string());
+        let data: JellyfinItemsResponse = serde_json::from_value(serde_json::json!({
+            "Items": [{
+                "Id": "fav1",
+                "Name": "Loved Song",
+                "Type": "Audio",
+                "Album": "Album",
+                "AlbumId": "album1",
+                "AlbumPrimaryImageTag": "tag1",
+                "Artists": ["Artist"],
+                "RunTimeTicks": 2_400_000_000u64,
+                "UserData": { "IsFavorite": true }
+            }],
+            "TotalRecordCount": 1
+        }))
+        .unwrap();
+
+        let tracks: Vec<Track> = data
+            .items
+            .iter()
+            .map(|item| provider.item_to_track(item))
+            .collect();
+
+        assert_eq!(tracks.len(), 1);
+        let track = &tracks[0];
+        assert_eq!(track.id, "fav1");
+        assert_eq!(track.artist, "Artist");
+        assert_eq!(track.duration_ms, 240_000);
+        assert_eq!(track.source, Source::Jellyfin);
+        assert!(track
+            .url
+            .as_deref()
+            .unwrap()
+            .starts_with("http://localhost:8096/Audio/fav1/universal?UserId=user1"));
+        assert!(track.auth_headers.is_some());
+        assert!(track
+            .image_url
+            .as_deref()
+            .unwrap()
+            .contains("/Items/album1/Images/Primary?tag=tag1"));
+    }
+
+    #[test]
+    fn test_artist_urls_filter_by_artist() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        assert!(provider
+            .artist_albums_url("user1", "artist1")
+            .contains("AlbumArtistIds=artist1&IncludeItemTypes=MusicAlbum"));
+        assert!(provider
+            .artist_top_tracks_url("user1", "artist1")
+            .contains("ArtistIds=artist1&IncludeItemTypes=Audio"));
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+        assert_eq!(
+            provider.capabilities(),
+            ProviderCapabilities {
+                search_tracks: true,
+                search_playlists: true,
+                create_playlist: true,
+                edit_playlists: true,
+                streaming: true,
+                recently_played: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_paged_urls_include_offset_and_limit() {
+        let provider =
+            JellyfinProvider::new("http://localhost:8096".to_string(), "test_key".to_string())
+                .unwrap();
+
+        assert_eq!(
+            paged_url(&provider.search_tracks_url("user1", "jazz"), 20, 10),
+            "http://localhost:8096/Users/user1/Items?searchTerm=jazz&IncludeItemTypes=Audio&Recursive=true&Fields=Genres&StartIndex=20&Limit=10"
+        );
+        assert!(paged_url(&provider.playlists_url("user1"), 0, 50)
+            .ends_with("IncludeItemTypes=Playlist&StartIndex=0&Limit=50"));
+        assert!(
+            paged_url(&provider.playlist_items_url("user1", "list1"), 300, 100).contains(
+                "ParentId=list1&Fields=AudioInfo,ParentId,Genres&StartIndex=300&Limit=100"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_against_mock_server() {
+        let server = MockServer::start().await;
+        mount_auth(&server).await;
+
+        let mut provider = mock_provider(&server);
+        provider.authenticate().await.unwrap();
+
+        assert!(provider.is_authenticated());
+        assert_eq!(provider.user_id.as_deref(), Some("user1"));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejected_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/System/Info"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let mut provider = mock_provider(&server);
+        let error = provider.authenticate().await.unwrap_err();
+
+        assert!(matches!(error, ProviderError::NotAuthenticated(_)));
+        assert!(!provider.is_authenticated());
+    }
+
+    #[tokio::test]
+    async fn test_get_playlists_from_mock_server() {
+        let server = MockServer::start().await;
+        mount_auth(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/Users/user1/Items"))
+            .and(query_param("IncludeItemTypes", "Playlist"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Items": [
+                    { "Id": "list1", "Name": "Road Trip", "Type": "Playlist", "ChildCount": 12 },
+                    { "Id": "list2", "Name": "Focus", "Type": "Playlist" }
+                ],
+                "TotalRecordCount": 2
+            })))
+            .mount(&server)
+            .await;
+
+        let mut provider = mock_provider(&server);
+        provider.authenticate().await.unwrap();
+        let playlists = provider.get_playlists().await.unwrap();
+
+        assert_eq!(playlists.len(), 2);
+        assert_eq!(playlists[0].id, "list1");
+        assert_eq!(playlists[0].name, "Road Trip");
+        assert_eq!(playlists[0].track_count, 12);
+        assert_eq!(playlists[1].track_count, 0);
+        assert_eq!(playlists[1].source, Source::Jellyfin);
+    }
+
+    #[tokio::test]
+    async fn test_error_statuses_map_to_provider_errors() {
+        let server = MockServer::start().await;
+        mount_auth(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/Users/user1/Items"))
+            .and(query_param("IncludeItemTypes", "Playlist"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Users/user1/Items/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let mut provider = mock_provider(&server);
+        provider.authenticate().await.unwrap();
+
+        let error = provider.get_playlists().await.unwrap_err();
+        assert!(matches!(error, ProviderError::NotAuthenticated(_)));
+        let error = provider.get_track("missing").await.unwrap_err();
+        assert!(matches!(error, ProviderError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_playlist_with_failing_items_has_no_tracks() {
+        let server = MockServer::start().await;
+        mount_auth(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/Users/user1/Items"))
+            .and(query_param("ParentId", "list1"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/Playlists/list1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "Id": "list1",
+                "Name": "Broken",
+                "Type": "Playlist",
+                "ChildCount": 3
+            })))
+            .mount(&server)
+            .await;
+
+        let mut provider = mock_provider(&server);
+        provider.authenticate().await.unwrap();
+        let playlist = provider.get_playlist("list1").await.unwrap();
+
+        assert_eq!(playlist.name, "Broken");
+        assert!(playlist.tracks.is_empty());
+        assert_eq!(playlist.track_count, 0);
+    }
 }
@@ -1,22 +1,105 @@
 pub mod jellyfin;
+pub mod rate_limit;
+pub mod retry;
 /// Provider trait and implementations
 pub mod spotify;
 
-use crate::models::{Playlist, Source, Track};
+use crate::models::{Page, Playlist, Source, Track};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// Error type for provider operations
-#[derive(Debug)]
-pub struct ProviderError(pub String);
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    /// The provider isn't connected or rejected our credentials
+    #[error("{0}")]
+    NotAuthenticated(String),
+    /// The request never got a response (connection refused, DNS, timeout)
+    #[error("{0}")]
+    Network(String),
+    /// The requested item doesn't exist
+    #[error("{0}")]
+    NotFound(String),
+    /// The provider asked us to slow down (HTTP 429)
+    #[error("{0}")]
+    RateLimited(String),
+    /// Any other unsuccessful HTTP response
+    #[error("{message}")]
+    Api { status: u16, message: String },
+    /// Anything else (parse failures, unsupported operations, storage errors)
+    #[error("{0}")]
+    Other(String),
+}
+
+impl ProviderError {
+    /// Classify an unsuccessful HTTP status, prefixing `context` to the message
+    pub fn from_status(status: reqwest::StatusCode, context: &str) -> Self {
+        Self::for_status(status, format!("{}: HTTP {}", context, status))
+    }
+
+    /// Classify a reqwest error, prefixing `context` to the message
+    pub fn with_context(context: &str, error: reqwest::Error) -> Self {
+        Self::classify(&error, format!("{}: {}", context, error))
+    }
 
-impl std::fmt::Display for ProviderError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+    /// Whether the failure is transient and the request may succeed if retried
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(_) | Self::RateLimited(_) => true,
+            Self::Api { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    fn for_status(status: reqwest::StatusCode, message: String) -> Self {
+        match status {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                Self::NotAuthenticated(message)
+            }
+            reqwest::StatusCode::NOT_FOUND => Self::NotFound(message),
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Self::RateLimited(message),
+            _ => Self::Api {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+
+    fn classify(error: &reqwest::Error, message: String) -> Self {
+        match error.status() {
+            Some(status) => Self::for_status(status, message),
+            None if error.is_connect() || error.is_timeout() || error.is_request() => {
+                Self::Network(message)
+            }
+            None => Self::Other(message),
+        }
     }
 }
 
-impl std::error::Error for ProviderError {}
+impl From<reqwest::Error> for ProviderError {
+    fn from(error: reqwest::Error) -> Self {
+        let message = error.to_string();
+        Self::classify(&error, message)
+    }
+}
+
+/// Operations a provider supports, so the UI can hide the ones it doesn't
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderCapabilities {
+    /// Searching the provider's tracks
+    pub search_tracks: bool,
+    /// Searching the provider's playlists
+    pub search_playlists: bool,
+    /// Creating playlists on the provider
+    pub create_playlist: bool,
+    /// Adding tracks to and removing tracks from provider playlists
+    pub edit_playlists: bool,
+    /// Playing full tracks from the provider
+    pub streaming: bool,
+    /// Listing recently played tracks
+    pub recently_played: bool,
+}
 
 /// Core trait that all music providers must implement
 #[async_trait]
@@ -30,6 +113,9 @@ pub trait MusicProvider: Send + Sync {
     /// Check if provider is authenticated
     fn is_authenticated(&self) -> bool;
 
+    /// Describe which operations this provider supports
+    fn capabilities(&self) -> ProviderCapabilities;
+
     /// Get user's playlists
     async fn get_playlists(&self) -> Result<Vec<Playlist>, ProviderError>;
 
@@ -39,6 +125,15 @@ pub trait MusicProvider: Send + Sync {
     /// Get a specific track by ID
     async fn get_track(&self, id: &str) -> Result<Track, ProviderError>;
 
+    /// Get an album with its tracks in disc and track order
+    async fn get_album(&self, id: &str) -> Result<Playlist, ProviderError>;
+
+    /// Get an artist's albums as track-less playlist summaries
+    async fn get_artist_albums(&self, artist_id: &str) -> Result<Vec<Playlist>, ProviderError>;
+
+    /// Get an artist's most popular tracks
+    async fn get_artist_top_tracks(&self, artist_id: &str) -> Result<Vec<Track>, ProviderError>;
+
     /// Search for tracks by query
     async fn search_tracks(&self, query: &str) -> Result<Vec<Track>, ProviderError>;
 
@@ -72,6 +167,167 @@ pub trait MusicProvider: Send + Sync {
 
     /// Get recently played tracks
     async fn get_recently_played(&self, limit: usize) -> Result<Vec<Track>, ProviderError>;
+
+    /// Get up to `limit` of the user's playlists, starting at `offset`
+    ///
+    /// The default implementation fetches every playlist and slices the
+    /// result; providers with server-side paging should override it.
+    async fn get_playlists_page(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Playlist>, ProviderError> {
+        Ok(Page::from_all(self.get_playlists().await?, offset, limit))
+    }
+
+    /// Get up to `limit` tracks of a playlist, starting at `offset`
+    async fn get_playlist_tracks_page(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Track>, ProviderError> {
+        Ok(Page::from_all(
+            self.get_playlist(id).await?.tracks,
+            offset,
+            limit,
+        ))
+    }
+
+    /// Get up to `limit` tracks matching a search, starting at `offset`
+    async fn search_tracks_page(
+        &self,
+        query: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Track>, ProviderError> {
+        Ok(Page::from_all(
+            self.search_tracks(query).await?,
+            offset,
+            limit,
+        ))
+    }
+
+    /// Check that the provider is reachable and the credentials are still valid
+    ///
+    /// The default implementation only checks local authentication state;
+    /// providers should override it to hit a lightweight endpoint.
+    async fn health_check(&self) -> Result<(), ProviderError> {
+        if self.is_authenticated() {
+            Ok(())
+        } else {
+            Err(ProviderError::NotAuthenticated(format!(
+                "{} provider not authenticated",
+                self.source()
+            )))
+        }
+    }
+}
+
+/// Sort album tracks by disc, then track number. Tracks without a track
+/// number go last on their disc, keeping the order they came in.
+pub fn sort_album_tracks(tracks: &mut [Track]) {
+    tracks.sort_by_key(|t| {
+        (
+            t.disc_number.unwrap_or(1),
+            t.track_number.unwrap_or(u32::MAX),
+        )
+    });
+}
+
+/// Result of copying tracks into a new provider playlist
+#[derive(Debug, Clone)]
+pub struct PushOutcome {
+    /// The playlist created on the provider
+    pub playlist: Playlist,
+    /// Tracks that were added, as the provider knows them
+    pub pushed: Vec<Track>,
+    /// Tracks the provider couldn't match or refused to add
+    pub skipped: Vec<Track>,
+}
+
+/// Find the provider's copy of a track. Tracks from the provider itself are
+/// used as-is; others are matched by searching for an exact title and artist.
+async fn resolve_track(provider: &dyn MusicProvider, track: &Track) -> Option<Track> {
+    if track.source == provider.source() {
+        return Some(track.clone());
+    }
+
+    let query = format!("{} {}", track.title, track.artist);
+    match provider.search_tracks(&query).await {
+        Ok(results) => results.into_iter().find(|candidate| {
+            candidate.title.eq_ignore_ascii_case(&track.title)
+                && candidate.artist.eq_ignore_ascii_case(&track.artist)
+        }),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to resolve \"{}\" on {}: {}",
+                track.title,
+                provider.source(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Create a playlist on a provider and add every track it can resolve, in order
+pub async fn push_tracks_to_provider(
+    provider: &dyn MusicProvider,
+    name: &str,
+    description: Option<&str>,
+    tracks: &[Track],
+) -> Result<PushOutcome, ProviderError> {
+    let playlist = provider.create_playlist(name, description).await?;
+    let mut pushed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for track in tracks {
+        let Some(resolved) = resolve_track(provider, track).await else {
+            skipped.push(track.clone());
+            continue;
+        };
+        match provider
+            .add_track_to_playlist(&playlist.id, &resolved)
+            .await
+        {
+            Ok(()) => pushed.push(resolved),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to push \"{}\" to {}: {}",
+                    track.title,
+                    provider.source(),
+                    e
+                );
+                skipped.push(track.clone());
+            }
+        }
+    }
+
+    Ok(PushOutcome {
+        playlist,
+        pushed,
+        skipped,
+    })
+}
+
+/// Whether a track from `source` plays in full rather than as a preview.
+/// Spotify needs a premium account (`spotify_premium`, `None` when not
+/// connected) and a ready librespot session.
+pub fn plays_in_full(source: Source, spotify_premium: Option<bool>, session_ready: bool) -> bool {
+    match source {
+        Source::Spotify => spotify_premium == Some(true) && session_ready,
+        Source::Jellyfin | Source::Custom => true,
+    }
+}
+
+/// Run a provider's health check and measure how long it took
+pub async fn timed_health_check(
+    provider: &dyn MusicProvider,
+) -> Result<std::time::Duration, ProviderError> {
+    let start = std::time::Instant::now();
+    provider.health_check().await?;
+    Ok(start.elapsed())
 }
 
 /// Provider registry for managing multiple providers
@@ -79,6 +335,13 @@ pub struct ProviderRegistry {
     providers: std::collections::HashMap<Source, Arc<dyn MusicProvider>>,
     spotify_provider: Option<Arc<tokio::sync::Mutex<spotify::SpotifyProvider>>>,
     jellyfin_provider: Option<Arc<tokio::sync::Mutex<jellyfin::JellyfinProvider>>>,
+    /// Redirect URI matching the port the OAuth callback server is bound to
+    spotify_redirect_uri: String,
+    /// HTTP client shared by providers so they reuse one connection pool
+    http_client: reqwest::Client,
+    /// Request rate limits, shared by all requests to each provider
+    spotify_rate_limiter: Arc<rate_limit::RateLimiter>,
+    jellyfin_rate_limiter: Arc<rate_limit::RateLimiter>,
 }
 
 impl ProviderRegistry {
@@ -87,9 +350,37 @@ impl ProviderRegistry {
             providers: std::collections::HashMap::new(),
             spotify_provider: None,
             jellyfin_provider: None,
+            spotify_redirect_uri: spotify::redirect_uri_for_port(spotify::DEFAULT_OAUTH_PORT),
+            http_client: crate::http_client::shared_client(),
+            spotify_rate_limiter: Arc::new(rate_limit::RateLimiter::default()),
+            jellyfin_rate_limiter: Arc::new(rate_limit::RateLimiter::default()),
         }
     }
 
+    /// Set the per-provider request rate; applies to providers connected afterwards
+    pub fn set_requests_per_second(&mut self, requests_per_second: f64) {
+        self.spotify_rate_limiter = Arc::new(rate_limit::RateLimiter::new(requests_per_second));
+        self.jellyfin_rate_limiter = Arc::new(rate_limit::RateLimiter::new(requests_per_second));
+    }
+
+    /// The rate limiters providers are given when they connect
+    pub fn rate_limiters(&self) -> rate_limit::ProviderRateLimiters {
+        rate_limit::ProviderRateLimiters {
+            spotify: self.spotify_rate_limiter.clone(),
+            jellyfin: self.jellyfin_rate_limiter.clone(),
+        }
+    }
+
+    /// Update the Spotify redirect URI after the OAuth callback server binds
+    pub fn set_spotify_redirect_uri(&mut self, redirect_uri: String) {
+        self.spotify_redirect_uri = redirect_uri;
+    }
+
+    /// Get the Spotify redirect URI currently in use
+    pub fn spotify_redirect_uri(&self) -> &str {
+        &self.spotify_redirect_uri
+    }
+
     pub fn register(&mut self, provider: Arc<dyn MusicProvider>) {
         self.providers.insert(provider.source(), provider);
     }
@@ -105,7 +396,9 @@ impl ProviderRegistry {
     /// Initialize Spotify provider with default OAuth configuration (PKCE - no secrets needed)
     pub fn get_spotify_auth_url_default(&mut self) -> Result<String, ProviderError> {
         // Use default OAuth without cache - keyring is our source of truth for token persistence
-        let mut spotify_provider = spotify::SpotifyProvider::with_default_oauth();
+        let mut spotify_provider =
+            spotify::SpotifyProvider::with_default_oauth_and_redirect(&self.spotify_redirect_uri);
+        spotify_provider.set_rate_limiter(self.spotify_rate_limiter.clone());
 
         // PKCE requires mutable reference to generate verifier
         let auth_url = spotify_provider.get_auth_url()?;
@@ -114,6 +407,14 @@ impl ProviderRegistry {
         Ok(auth_url)
     }
 
+    /// Get the OAuth `state` value for the pending Spotify authorization
+    pub async fn get_spotify_oauth_state(&self) -> Option<String> {
+        match &self.spotify_provider {
+            Some(provider) => provider.lock().await.get_oauth_state(),
+            None => None,
+        }
+    }
+
     /// Initialize Spotify provider with OAuth configuration
     pub fn get_spotify_auth_url(
         &mut self,
@@ -126,6 +427,7 @@ impl ProviderRegistry {
             client_secret.to_string(),
             redirect_uri.to_string(),
         );
+        spotify_provider.set_rate_limiter(self.spotify_rate_limiter.clone());
 
         // PKCE requires mutable reference to generate verifier
         let auth_url = spotify_provider.get_auth_url()?;
@@ -153,16 +455,16 @@ impl ProviderRegistry {
             if let Some(token) = spotify.get_token().await {
                 tracing::info!("Retrieved token from provider, saving to keyring");
                 let mut tokens = crate::config::Config::load_tokens()
-                    .map_err(|e| ProviderError(format!("Failed to load tokens: {}", e)))?;
+                    .map_err(|e| ProviderError::Other(format!("Failed to load tokens: {}", e)))?;
                 tokens.spotify_token = Some(token);
                 crate::config::Config::save_tokens(&tokens)
-                    .map_err(|e| ProviderError(format!("Failed to save tokens: {}", e)))?;
+                    .map_err(|e| ProviderError::Other(format!("Failed to save tokens: {}", e)))?;
                 tracing::info!("Token saved to keyring successfully");
             } else {
                 tracing::warn!("Authentication succeeded but no token was retrieved");
             }
         } else {
-            return Err(ProviderError(
+            return Err(ProviderError::NotAuthenticated(
                 "Spotify provider not initialized".to_string(),
             ));
         }
@@ -179,13 +481,25 @@ impl ProviderRegistry {
         }
     }
 
+    /// Check the Spotify connection, returning the round-trip latency
+    pub async fn check_spotify_connection(&self) -> Result<std::time::Duration, ProviderError> {
+        if let Some(provider) = &self.spotify_provider {
+            let spotify = provider.lock().await;
+            timed_health_check(&*spotify).await
+        } else {
+            Err(ProviderError::NotAuthenticated(
+                "Spotify provider not authenticated".to_string(),
+            ))
+        }
+    }
+
     /// Get Spotify playlists
     pub async fn get_spotify_playlists(&self) -> Result<Vec<Playlist>, ProviderError> {
         if let Some(provider) = &self.spotify_provider {
             let spotify = provider.lock().await;
             spotify.get_playlists().await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Spotify provider not authenticated".to_string(),
             ))
         }
@@ -193,41 +507,82 @@ impl ProviderRegistry {
 
     /// Get a specific Spotify track by ID
     pub async fn get_spotify_track(&self, id: &str) -> Result<Track, ProviderError> {
+        self.get_track(Source::Spotify, id).await
+    }
+
+    /// Get a specific Spotify playlist by ID
+    pub async fn get_spotify_playlist(&self, id: &str) -> Result<Playlist, ProviderError> {
         if let Some(provider) = &self.spotify_provider {
             let spotify = provider.lock().await;
-            spotify.get_track(id).await
+            spotify.get_playlist(id).await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Spotify provider not authenticated".to_string(),
             ))
         }
     }
 
-    /// Get a specific Spotify playlist by ID
-    pub async fn get_spotify_playlist(&self, id: &str) -> Result<Playlist, ProviderError> {
+    /// Get the user's saved Spotify tracks as the "Liked Songs" playlist
+    pub async fn get_spotify_liked_songs(&self) -> Result<Playlist, ProviderError> {
+        self.get_spotify_playlist(spotify::LIKED_SONGS_ID).await
+    }
+
+    /// Add a track to or remove it from the user's Spotify library
+    pub async fn set_spotify_track_saved(
+        &self,
+        track_id: &str,
+        saved: bool,
+    ) -> Result<(), ProviderError> {
         if let Some(provider) = &self.spotify_provider {
             let spotify = provider.lock().await;
-            spotify.get_playlist(id).await
+            if saved {
+                spotify.save_track(track_id).await
+            } else {
+                spotify.remove_saved_track(track_id).await
+            }
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Spotify provider not authenticated".to_string(),
             ))
         }
     }
 
-    /// Authenticate with Jellyfin
+    /// Build an unauthenticated provider for a Jellyfin server using the shared
+    /// HTTP client and rate limiter
+    pub fn build_jellyfin_provider(
+        &self,
+        url: &str,
+        api_key: &str,
+    ) -> Result<jellyfin::JellyfinProvider, ProviderError> {
+        let mut jellyfin_provider = jellyfin::JellyfinProvider::with_client(
+            url.to_string(),
+            api_key.to_string(),
+            self.http_client.clone(),
+        )?;
+        jellyfin_provider.set_rate_limiter(self.jellyfin_rate_limiter.clone());
+        Ok(jellyfin_provider)
+    }
+
+    /// Authenticate with Jellyfin, replacing any previously active server
     pub async fn authenticate_jellyfin(
         &mut self,
         url: &str,
         api_key: &str,
     ) -> Result<(), ProviderError> {
-        let mut jellyfin_provider =
-            jellyfin::JellyfinProvider::new(url.to_string(), api_key.to_string());
+        let mut jellyfin_provider = self.build_jellyfin_provider(url, api_key)?;
         jellyfin_provider.authenticate().await?;
         self.jellyfin_provider = Some(Arc::new(tokio::sync::Mutex::new(jellyfin_provider)));
         Ok(())
     }
 
+    /// URL of the active Jellyfin server, if one is connected
+    pub async fn active_jellyfin_server(&self) -> Option<String> {
+        match &self.jellyfin_provider {
+            Some(provider) => Some(provider.lock().await.base_url().to_string()),
+            None => None,
+        }
+    }
+
     /// Check if Jellyfin is authenticated
     pub async fn is_jellyfin_authenticated(&self) -> bool {
         if let Some(provider) = &self.jellyfin_provider {
@@ -238,37 +593,42 @@ impl ProviderRegistry {
         }
     }
 
-    /// Get Jellyfin playlists
-    pub async fn get_jellyfin_playlists(&self) -> Result<Vec<Playlist>, ProviderError> {
+    /// Check the Jellyfin connection, returning the round-trip latency
+    pub async fn check_jellyfin_connection(&self) -> Result<std::time::Duration, ProviderError> {
         if let Some(provider) = &self.jellyfin_provider {
             let jellyfin = provider.lock().await;
-            jellyfin.get_playlists().await
+            timed_health_check(&*jellyfin).await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Jellyfin provider not authenticated".to_string(),
             ))
         }
     }
 
-    /// Get a specific Jellyfin track by ID
-    pub async fn get_jellyfin_track(&self, id: &str) -> Result<Track, ProviderError> {
+    /// Get Jellyfin playlists
+    pub async fn get_jellyfin_playlists(&self) -> Result<Vec<Playlist>, ProviderError> {
         if let Some(provider) = &self.jellyfin_provider {
             let jellyfin = provider.lock().await;
-            jellyfin.get_track(id).await
+            jellyfin.get_playlists().await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Jellyfin provider not authenticated".to_string(),
             ))
         }
     }
 
+    /// Get a specific Jellyfin track by ID
+    pub async fn get_jellyfin_track(&self, id: &str) -> Result<Track, ProviderError> {
+        self.get_track(Source::Jellyfin, id).await
+    }
+
     /// Get a specific Jellyfin playlist
     pub async fn get_jellyfin_playlist(&self, id: &str) -> Result<Playlist, ProviderError> {
         if let Some(provider) = &self.jellyfin_provider {
             let jellyfin = provider.lock().await;
             jellyfin.get_playlist(id).await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Jellyfin provider not authenticated".to_string(),
             ))
         }
@@ -280,7 +640,7 @@ impl ProviderRegistry {
             let jellyfin = provider.lock().await;
             jellyfin.search_tracks(query).await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Jellyfin provider not authenticated".to_string(),
             ))
         }
@@ -292,7 +652,7 @@ impl ProviderRegistry {
             let spotify = provider.lock().await;
             spotify.search_tracks(query).await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Spotify provider not authenticated".to_string(),
             ))
         }
@@ -307,12 +667,262 @@ impl ProviderRegistry {
             let jellyfin = provider.lock().await;
             jellyfin.search_playlists(query).await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Jellyfin provider not authenticated".to_string(),
             ))
         }
     }
 
+    /// Get the capabilities of a provider, or none if it isn't connected
+    pub async fn capabilities(
+        &self,
+        source: Source,
+    ) -> Result<ProviderCapabilities, ProviderError> {
+        match source {
+            Source::Spotify => Ok(match &self.spotify_provider {
+                Some(provider) => provider.lock().await.capabilities(),
+                None => ProviderCapabilities::default(),
+            }),
+            Source::Jellyfin => Ok(match &self.jellyfin_provider {
+                Some(provider) => provider.lock().await.capabilities(),
+                None => ProviderCapabilities::default(),
+            }),
+            Source::Custom => Err(ProviderError::Other(
+                "Custom playlists are not served by a provider".to_string(),
+            )),
+        }
+    }
+
+    /// Create a playlist on a provider from a list of tracks
+    pub async fn push_tracks(
+        &self,
+        source: Source,
+        name: &str,
+        description: Option<&str>,
+        tracks: &[Track],
+    ) -> Result<PushOutcome, ProviderError> {
+        match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => {
+                    push_tracks_to_provider(&*provider.lock().await, name, description, tracks)
+                        .await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => {
+                    push_tracks_to_provider(&*provider.lock().await, name, description, tracks)
+                        .await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom playlists are not served by a provider".to_string(),
+            )),
+        }
+    }
+
+    /// Get a track with full metadata from a provider
+    pub async fn get_track(&self, source: Source, id: &str) -> Result<Track, ProviderError> {
+        let track = match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => provider.lock().await.get_track(id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => provider.lock().await.get_track(id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom tracks are not served by a provider".to_string(),
+            )),
+        };
+        track.map(Track::mark_enriched)
+    }
+
+    /// Get an album and its tracks from a provider
+    pub async fn get_album(&self, source: Source, id: &str) -> Result<Playlist, ProviderError> {
+        match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => provider.lock().await.get_album(id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => provider.lock().await.get_album(id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom tracks don't belong to albums".to_string(),
+            )),
+        }
+    }
+
+    /// Get an artist's albums from a provider
+    pub async fn get_artist_albums(
+        &self,
+        source: Source,
+        artist_id: &str,
+    ) -> Result<Vec<Playlist>, ProviderError> {
+        match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => provider.lock().await.get_artist_albums(artist_id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => provider.lock().await.get_artist_albums(artist_id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom tracks don't have artist pages".to_string(),
+            )),
+        }
+    }
+
+    /// Get an artist's most popular tracks from a provider
+    pub async fn get_artist_top_tracks(
+        &self,
+        source: Source,
+        artist_id: &str,
+    ) -> Result<Vec<Track>, ProviderError> {
+        match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => provider.lock().await.get_artist_top_tracks(artist_id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => provider.lock().await.get_artist_top_tracks(artist_id).await,
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom tracks don't have artist pages".to_string(),
+            )),
+        }
+    }
+
+    /// Get one page of a provider's playlists
+    pub async fn get_playlists_page(
+        &self,
+        source: Source,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Playlist>, ProviderError> {
+        match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => {
+                    provider
+                        .lock()
+                        .await
+                        .get_playlists_page(offset, limit)
+                        .await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => {
+                    provider
+                        .lock()
+                        .await
+                        .get_playlists_page(offset, limit)
+                        .await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom playlists are not served by a provider".to_string(),
+            )),
+        }
+    }
+
+    /// Get one page of a provider playlist's tracks
+    pub async fn get_playlist_tracks_page(
+        &self,
+        source: Source,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Track>, ProviderError> {
+        match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => {
+                    let spotify = provider.lock().await;
+                    spotify.get_playlist_tracks_page(id, offset, limit).await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => {
+                    let jellyfin = provider.lock().await;
+                    jellyfin.get_playlist_tracks_page(id, offset, limit).await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom playlists are not served by a provider".to_string(),
+            )),
+        }
+    }
+
+    /// Get one page of a provider's track search results
+    pub async fn search_tracks_page(
+        &self,
+        source: Source,
+        query: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Page<Track>, ProviderError> {
+        match source {
+            Source::Spotify => match &self.spotify_provider {
+                Some(provider) => {
+                    let spotify = provider.lock().await;
+                    spotify.search_tracks_page(query, offset, limit).await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Spotify provider not authenticated".to_string(),
+                )),
+            },
+            Source::Jellyfin => match &self.jellyfin_provider {
+                Some(provider) => {
+                    let jellyfin = provider.lock().await;
+                    jellyfin.search_tracks_page(query, offset, limit).await
+                }
+                None => Err(ProviderError::NotAuthenticated(
+                    "Jellyfin provider not authenticated".to_string(),
+                )),
+            },
+            Source::Custom => Err(ProviderError::Other(
+                "Custom tracks cannot be searched through a provider".to_string(),
+            )),
+        }
+    }
+
     /// Get recently played tracks from Jellyfin
     pub async fn get_jellyfin_recently_played(
         &self,
@@ -322,7 +932,35 @@ impl ProviderRegistry {
             let jellyfin = provider.lock().await;
             jellyfin.get_recently_played(limit).await
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
+                "Jellyfin provider not authenticated".to_string(),
+            ))
+        }
+    }
+
+    /// Mark or unmark a Jellyfin track as a favorite
+    pub async fn set_jellyfin_favorite(
+        &self,
+        item_id: &str,
+        favorite: bool,
+    ) -> Result<(), ProviderError> {
+        if let Some(provider) = &self.jellyfin_provider {
+            let jellyfin = provider.lock().await;
+            jellyfin.set_favorite(item_id, favorite).await
+        } else {
+            Err(ProviderError::NotAuthenticated(
+                "Jellyfin provider not authenticated".to_string(),
+            ))
+        }
+    }
+
+    /// Get the user's favorite Jellyfin tracks
+    pub async fn get_jellyfin_favorites(&self) -> Result<Vec<Track>, ProviderError> {
+        if let Some(provider) = &self.jellyfin_provider {
+            let jellyfin = provider.lock().await;
+            jellyfin.get_favorites().await
+        } else {
+            Err(ProviderError::NotAuthenticated(
                 "Jellyfin provider not authenticated".to_string(),
             ))
         }
@@ -331,8 +969,7 @@ impl ProviderRegistry {
     /// Disconnect Spotify
     pub async fn disconnect_spotify(&mut self) -> Result<(), ProviderError> {
         // Clear the cache file when disconnecting
-        if let Ok(config_dir) = crate::config::Config::config_dir() {
-            let cache_path = config_dir.join("spotify_cache.json");
+        if let Ok(cache_path) = crate::config::Config::spotify_cache_path() {
             if cache_path.exists() {
                 if let Err(e) = std::fs::remove_file(&cache_path) {
                     tracing::warn!(
@@ -346,7 +983,7 @@ impl ProviderRegistry {
 
         // Clear stored tokens
         crate::config::Config::clear_tokens()
-            .map_err(|e| ProviderError(format!("Failed to clear tokens: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to clear tokens: {}", e)))?;
 
         self.spotify_provider = None;
         Ok(())
@@ -365,6 +1002,15 @@ impl ProviderRegistry {
         }
     }
 
+    /// Check whether a track will play in full rather than as a 30 second preview
+    pub async fn can_play_full(&self, track: &Track, spotify_session_ready: bool) -> bool {
+        let spotify_premium = match track.source {
+            Source::Spotify => self.is_spotify_premium().await,
+            Source::Jellyfin | Source::Custom => None,
+        };
+        plays_in_full(track.source, spotify_premium, spotify_session_ready)
+    }
+
     /// Get Spotify access token for session initialization
     ///
     /// Returns the OAuth access token if authenticated, None otherwise.
@@ -388,7 +1034,7 @@ impl ProviderRegistry {
             spotify.refresh_token().await?;
             Ok(())
         } else {
-            Err(ProviderError(
+            Err(ProviderError::NotAuthenticated(
                 "Spotify provider not authenticated".to_string(),
             ))
         }
@@ -408,17 +1054,29 @@ impl ProviderRegistry {
 
         // Load credentials from keyring
         let tokens = Config::load_tokens()
-            .map_err(|e| ProviderError(format!("Failed to load tokens: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to load tokens: {}", e)))?;
 
-        if tokens.jellyfin_api_key.is_none() || tokens.jellyfin_url.is_none() {
-            tracing::info!("No Jellyfin credentials found in keyring");
-            return Ok(false);
-        }
-
-        let api_key = tokens.jellyfin_api_key.unwrap();
-        let url = tokens.jellyfin_url.unwrap();
-
-        tracing::info!("Found Jellyfin credentials in keyring, authenticating");
+        let (url, api_key) = match (tokens.jellyfin_url, tokens.jellyfin_api_key) {
+            (Some(url), Some(api_key)) => {
+                tracing::info!("Found Jellyfin credentials in keyring, authenticating");
+                (url, api_key)
+            }
+            // Fall back to a server configured in config.toml or ANYPLAYER_* env vars
+            _ => match Config::load()
+                .ok()
+                .and_then(|config| config.active_jellyfin_server().cloned())
+                .filter(|server| !server.api_key.is_empty())
+            {
+                Some(server) => {
+                    tracing::info!("Found Jellyfin credentials in config, authenticating");
+                    (server.server_url, server.api_key)
+                }
+                None => {
+                    tracing::info!("No Jellyfin credentials found in keyring");
+                    return Ok(false);
+                }
+            },
+        };
 
         // Authenticate with stored credentials
         self.authenticate_jellyfin(&url, &api_key).await?;
@@ -470,7 +1128,7 @@ impl ProviderRegistry {
 
         // Load tokens from keyring
         let tokens = Config::load_tokens()
-            .map_err(|e| ProviderError(format!("Failed to load tokens: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Failed to load tokens: {}", e)))?;
 
         if tokens.spotify_token.is_none() {
             tracing::info!("No Spotify token found in keyring");
@@ -480,7 +1138,9 @@ impl ProviderRegistry {
         tracing::info!("Found Spotify token in keyring, creating provider");
 
         // Create provider without file-based cache - keyring is our only source of truth
-        let mut spotify_provider = spotify::SpotifyProvider::with_default_oauth();
+        let mut spotify_provider =
+            spotify::SpotifyProvider::with_default_oauth_and_redirect(&self.spotify_redirect_uri);
+        spotify_provider.set_rate_limiter(self.spotify_rate_limiter.clone());
 
         if let Some(token) = tokens.spotify_token {
             tracing::info!("Setting token on provider");
@@ -516,6 +1176,302 @@ mod tests {
     use rspotify::Token;
     use serial_test::serial;
 
+    #[tokio::test]
+    async fn test_auth_headers_per_source() {
+        let mut registry = ProviderRegistry::new();
+        assert!(registry.get_auth_headers(Source::Jellyfin).await.is_none());
+
+        let provider = registry
+            .build_jellyfin_provider("http://localhost:8096", "secret")
+            .unwrap();
+        registry.jellyfin_provider = Some(Arc::new(tokio::sync::Mutex::new(provider)));
+
+        let headers = registry.get_auth_headers(Source::Jellyfin).await.unwrap();
+        assert!(headers.contains(&("X-Emby-Token".to_string(), "secret".to_string())));
+        assert!(headers
+            .iter()
+            .any(|(name, value)| name == "X-Emby-Authorization" && value.contains("secret")));
+
+        // Spotify streams through librespot and custom tracks need no auth
+        assert!(registry.get_auth_headers(Source::Spotify).await.is_none());
+        assert!(registry.get_auth_headers(Source::Custom).await.is_none());
+    }
+
+    /// Minimal in-memory provider for exercising trait default methods
+    struct MockProvider {
+        authenticated: bool,
+        source: Source,
+        /// Ids of tracks added to playlists, in order
+        added: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl MockProvider {
+        fn new(authenticated: bool) -> Self {
+            Self {
+                authenticated,
+                source: Source::Custom,
+                added: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MusicProvider for MockProvider {
+        fn source(&self) -> Source {
+            self.source
+        }
+
+        async fn authenticate(&mut self) -> Result<(), ProviderError> {
+            self.authenticated = true;
+            Ok(())
+        }
+
+        fn is_authenticated(&self) -> bool {
+            self.authenticated
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities::default()
+        }
+
+        async fn get_playlists(&self) -> Result<Vec<Playlist>, ProviderError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_playlist(&self, id: &str) -> Result<Playlist, ProviderError> {
+            Err(ProviderError::NotFound(format!(
+                "Playlist {} not found",
+                id
+            )))
+        }
+
+        async fn get_track(&self, id: &str) -> Result<Track, ProviderError> {
+            Err(ProviderError::NotFound(format!("Track {} not found", id)))
+        }
+
+        async fn get_album(&self, id: &str) -> Result<Playlist, ProviderError> {
+            Err(ProviderError::NotFound(format!("Album {} not found", id)))
+        }
+
+        async fn get_artist_albums(
+            &self,
+            _artist_id: &str,
+        ) -> Result<Vec<Playlist>, ProviderError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_artist_top_tracks(
+            &self,
+            _artist_id: &str,
+        ) -> Result<Vec<Track>, ProviderError> {
+            Ok(Vec::new())
+        }
+
+        async fn search_tracks(&self, _query: &str) -> Result<Vec<Track>, ProviderError> {
+            Ok(Vec::new())
+        }
+
+        async fn search_playlists(&self, _query: &str) -> Result<Vec<Playlist>, ProviderError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_stream_url(&self, track_id: &str) -> Result<String, ProviderError> {
+            Ok(format!("http://localhost/{}", track_id))
+        }
+
+        async fn create_playlist(
+            &self,
+            name: &str,
+            description: Option<&str>,
+        ) -> Result<Playlist, ProviderError> {
+            Ok(Playlist {
+                id: "mock-playlist".to_string(),
+                name: name.to_string(),
+                description: description.map(str::to_string),
+                owner: "mock".to_string(),
+                image_url: None,
+                track_count: 0,
+                tracks: Vec::new(),
+                source: self.source,
+                revision: None,
+            })
+        }
+
+        async fn add_track_to_playlist(
+            &self,
+            _playlist_id: &str,
+            track: &Track,
+        ) -> Result<(), ProviderError> {
+            self.added.lock().unwrap().push(track.id.clone());
+            Ok(())
+        }
+
+        async fn remove_track_from_playlist(
+            &self,
+            _playlist_id: &str,
+            _track_id: &str,
+        ) -> Result<(), ProviderError> {
+            Err(ProviderError::Other("Not supported".to_string()))
+        }
+
+        async fn get_recently_played(&self, _limit: usize) -> Result<Vec<Track>, ProviderError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_check_ok() {
+        let provider = MockProvider::new(true);
+        let latency = timed_health_check(&provider).await;
+        assert!(latency.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_err() {
+        let provider = MockProvider::new(false);
+        let result = timed_health_check(&provider).await;
+        assert!(result.is_err());
+        let err = result.unwrap_err();
+        assert!(matches!(err, ProviderError::NotAuthenticated(_)));
+        assert!(err.to_string().contains("not authenticated"));
+    }
+
+    #[tokio::test]
+    async fn test_push_only_sends_tracks_the_provider_can_serve() {
+        let provider = MockProvider {
+            source: Source::Spotify,
+            ..MockProvider::new(true)
+        };
+        let track_from = |id: &str, source: Source| Track {
+            source,
+            ..album_track(id, None, None)
+        };
+        let tracks = vec![
+            track_from("sp1", Source::Spotify),
+            track_from("jf1", Source::Jellyfin),
+            track_from("sp2", Source::Spotify),
+            track_from("local1", Source::Custom),
+        ];
+
+        let outcome = push_tracks_to_provider(&provider, "Mix", None, &tracks)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.playlist.name, "Mix");
+        assert_eq!(*provider.added.lock().unwrap(), vec!["sp1", "sp2"]);
+        assert_eq!(outcome.pushed.len(), 2);
+        let skipped: Vec<_> = outcome.skipped.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(skipped, vec!["jf1", "local1"]);
+    }
+
+    #[test]
+    fn test_plays_in_full() {
+        for session_ready in [false, true] {
+            assert!(plays_in_full(Source::Jellyfin, None, session_ready));
+            assert!(plays_in_full(Source::Custom, None, session_ready));
+            assert!(!plays_in_full(Source::Spotify, None, session_ready));
+            assert!(!plays_in_full(Source::Spotify, Some(false), session_ready));
+        }
+        assert!(!plays_in_full(Source::Spotify, Some(true), false));
+        assert!(plays_in_full(Source::Spotify, Some(true), true));
+    }
+
+    #[tokio::test]
+    async fn test_can_play_full_without_spotify() {
+        let registry = ProviderRegistry::new();
+        let track = album_track("t1", None, None);
+        assert!(registry.can_play_full(&track, false).await);
+
+        let spotify_track = Track {
+            source: Source::Spotify,
+            ..track
+        };
+        assert!(!registry.can_play_full(&spotify_track, true).await);
+    }
+
+    #[test]
+    fn test_error_from_status() {
+        assert!(matches!(
+            ProviderError::from_status(reqwest::StatusCode::UNAUTHORIZED, "Failed"),
+            ProviderError::NotAuthenticated(_)
+        ));
+        assert!(matches!(
+            ProviderError::from_status(reqwest::StatusCode::NOT_FOUND, "Failed"),
+            ProviderError::NotFound(_)
+        ));
+        assert!(matches!(
+            ProviderError::from_status(reqwest::StatusCode::TOO_MANY_REQUESTS, "Failed"),
+            ProviderError::RateLimited(_)
+        ));
+
+        let err = ProviderError::from_status(
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to fetch playlists",
+        );
+        assert!(matches!(err, ProviderError::Api { status: 500, .. }));
+        assert!(err.is_transient());
+        assert!(!ProviderError::NotFound("gone".to_string()).is_transient());
+        assert_eq!(
+            err.to_string(),
+            "Failed to fetch playlists: HTTP 500 Internal Server Error"
+        );
+    }
+
+    fn album_track(id: &str, disc_number: Option<u32>, track_number: Option<u32>) -> Track {
+        Track {
+            id: id.to_string(),
+            title: format!("Track {}", id),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            album_artist: None,
+            duration_ms: 1000,
+            image_url: None,
+            source: Source::Jellyfin,
+            url: None,
+            auth_headers: None,
+            genre: None,
+            year: None,
+            track_number,
+            disc_number,
+            enriched_at: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_album_tracks_by_disc_then_track() {
+        let mut tracks = vec![
+            album_track("d2t1", Some(2), Some(1)),
+            album_track("untagged", None, None),
+            album_track("d1t2", Some(1), Some(2)),
+            album_track("d1t1", Some(1), Some(1)),
+            album_track("no-disc", None, Some(3)),
+        ];
+
+        sort_album_tracks(&mut tracks);
+
+        let ids: Vec<&str> = tracks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["d1t1", "d1t2", "no-disc", "untagged", "d2t1"]);
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_without_providers() {
+        let registry = ProviderRegistry::new();
+        for source in [Source::Spotify, Source::Jellyfin] {
+            assert_eq!(
+                registry.capabilities(source).await.unwrap(),
+                ProviderCapabilities::default()
+            );
+        }
+        assert!(registry.capabilities(Source::Custom).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_connection_without_providers() {
+        let registry = ProviderRegistry::new();
+        assert!(registry.check_spotify_connection().await.is_err());
+        assert!(registry.check_jellyfin_connection().await.is_err());
+    }
+
     /// Helper to create a test token that's not expired
     fn create_valid_token() -> Token {
         use chrono::{Duration as ChronoDuration, Utc};
@@ -562,6 +1518,7 @@ mod tests {
             spotify_token: Some(create_valid_token()),
             jellyfin_api_key: None,
             jellyfin_url: None,
+            jellyfin_server_keys: Default::default(),
         };
 
         // Save tokens to the system keyring
@@ -590,6 +1547,7 @@ mod tests {
             spotify_token: Some(create_expired_token()),
             jellyfin_api_key: None,
             jellyfin_url: None,
+            jellyfin_server_keys: Default::default(),
         };
 
         // Save tokens
@@ -642,6 +1600,7 @@ mod tests {
             spotify_token: Some(create_valid_token()),
             jellyfin_api_key: Some("test_key".to_string()),
             jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: Default::default(),
         };
         Config::save_tokens(&tokens).expect("Failed to save tokens");
 
@@ -679,6 +1638,7 @@ mod tests {
             spotify_token: None,
             jellyfin_api_key: Some("test_api_key".to_string()),
             jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: Default::default(),
         };
 
         // Save tokens to the system keyring
@@ -703,6 +1663,7 @@ mod tests {
             spotify_token: None,
             jellyfin_api_key: None,
             jellyfin_url: Some("http://localhost:8096".to_string()),
+            jellyfin_server_keys: Default::default(),
         };
 
         Config::save_tokens(&tokens).expect("Failed to save test tokens");
@@ -726,6 +1687,7 @@ mod tests {
             spotify_token: None,
             jellyfin_api_key: Some("test_api_key".to_string()),
             jellyfin_url: None,
+            jellyfin_server_keys: Default::default(),
         };
 
         Config::save_tokens(&tokens).expect("Failed to save test tokens");
@@ -755,4 +1717,43 @@ mod tests {
         assert!(result.is_ok());
         assert!(!result.unwrap());
     }
+
+    #[test]
+    fn test_select_active_jellyfin_server() {
+        let mut config = Config::default();
+        assert!(config.active_jellyfin_server().is_none());
+
+        config.add_jellyfin_server("http://home:8096", Some("Home".to_string()));
+        config.add_jellyfin_server("http://friend:8096", Some("Friend".to_string()));
+        // Re-adding a known server doesn't duplicate it
+        config.add_jellyfin_server("http://home:8096", None);
+        assert_eq!(config.jellyfin.len(), 2);
+
+        // Without a selection the first server is used
+        assert_eq!(
+            config.active_jellyfin_server().unwrap().server_url,
+            "http://home:8096"
+        );
+
+        config
+            .set_active_jellyfin_server("http://friend:8096")
+            .expect("Failed to select server");
+        let active = config.active_jellyfin_server().unwrap();
+        assert_eq!(active.name.as_deref(), Some("Friend"));
+
+        let registry = ProviderRegistry::new();
+        let provider = registry
+            .build_jellyfin_provider(&active.server_url, "friend_key")
+            .unwrap();
+        assert_eq!(provider.base_url(), "http://friend:8096");
+        assert!(!provider.is_authenticated());
+
+        assert!(config
+            .set_active_jellyfin_server("http://unknown:8096")
+            .is_err());
+        assert_eq!(
+            config.active_jellyfin_server().unwrap().server_url,
+            "http://friend:8096"
+        );
+    }
 }
@@ -0,0 +1,212 @@
+/// Token-bucket rate limiting for outgoing provider requests
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+/// Default request rate per provider
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 10.0;
+
+/// Slowest we'll throttle to after repeated 429s, as a fraction of the configured rate
+const MIN_RATE_FRACTION: f64 = 0.125;
+
+/// How much of the configured rate each successful request wins back after a 429
+const RECOVERY_FRACTION: f64 = 0.05;
+
+#[derive(Debug)]
+struct Bucket {
+    /// Configured requests per second
+    configured_rate: f64,
+    /// Current requests per second (lowered after 429s)
+    rate: f64,
+    /// Maximum burst size
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+    /// No requests until then, set from a 429's `Retry-After`
+    paused_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Token bucket shared by every request to one provider
+#[derive(Debug)]
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_second` sustained, with bursts of up to one second's worth
+    pub fn new(requests_per_second: f64) -> Self {
+        let rate = requests_per_second.max(0.1);
+        let capacity = rate.max(1.0);
+        Self {
+            bucket: Mutex::new(Bucket {
+                configured_rate: rate,
+                rate,
+                capacity,
+                tokens: capacity,
+                last_refill: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+                if let Some(until) = bucket.paused_until.filter(|until| *until > now) {
+                    until - now
+                } else {
+                    bucket.refill(now);
+                    if bucket.tokens >= 1.0 {
+                        bucket.tokens -= 1.0;
+                        return;
+                    }
+                    Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.rate)
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// The provider returned 429 - halve the rate and drop any saved-up burst
+    pub async fn on_rate_limited(&self) {
+        let mut bucket = self.bucket.lock().await;
+        bucket.rate = (bucket.rate / 2.0).max(bucket.configured_rate * MIN_RATE_FRACTION);
+        bucket.tokens = bucket.tokens.min(0.0);
+        tracing::warn!(
+            "Provider rate limit hit, slowing down to {:.2} requests/s",
+            bucket.rate
+        );
+    }
+
+    /// The provider asked us to wait `delay` before sending anything else
+    pub async fn pause_for(&self, delay: Duration) {
+        let mut bucket = self.bucket.lock().await;
+        let until = Instant::now() + delay;
+        bucket.paused_until = Some(
+            bucket
+                .paused_until
+                .map_or(until, |current| current.max(until)),
+        );
+    }
+
+    /// A request succeeded - creep back toward the configured rate
+    pub async fn on_success(&self) {
+        let mut bucket = self.bucket.lock().await;
+        if bucket.rate < bucket.configured_rate {
+            bucket.rate = (bucket.rate + bucket.configured_rate * RECOVERY_FRACTION)
+                .min(bucket.configured_rate);
+        }
+    }
+
+    /// Current allowed requests per second
+    pub async fn current_rate(&self) -> f64 {
+        self.bucket.lock().await.rate
+    }
+
+    /// Whether requests are currently being slowed down or held back
+    /// because the provider rate limited us
+    pub async fn is_throttled(&self) -> bool {
+        let bucket = self.bucket.lock().await;
+        bucket.rate < bucket.configured_rate
+            || bucket
+                .paused_until
+                .is_some_and(|until| until > Instant::now())
+    }
+
+    /// Record the outcome of a request, adjusting the rate on 429 and
+    /// holding further requests back for the `Retry-After` it came with
+    pub async fn record_status(&self, status: reqwest::StatusCode, retry_after: Option<Duration>) {
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            self.on_rate_limited().await;
+            if let Some(delay) = retry_after {
+                self.pause_for(delay).await;
+            }
+        } else if status.is_success() {
+            self.on_success().await;
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_REQUESTS_PER_SECOND)
+    }
+}
+
+/// The limiter of each provider, for reporting on them without going
+/// through the provider registry
+#[derive(Debug, Clone)]
+pub struct ProviderRateLimiters {
+    pub spotify: Arc<RateLimiter>,
+    pub jellyfin: Arc<RateLimiter>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_limiter_enforces_rate_over_burst() {
+        let limiter = RateLimiter::new(10.0);
+        let start = Instant::now();
+
+        // The first 10 fit in the burst, the remaining 20 are paced at 10/s
+        for _ in 0..30 {
+            limiter.acquire().await;
+        }
+
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(1950), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(2100), "{:?}", elapsed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limited_slows_down_and_recovers() {
+        let limiter = RateLimiter::new(10.0);
+
+        limiter.on_rate_limited().await;
+        assert_eq!(limiter.current_rate().await, 5.0);
+
+        // Never drops below the floor
+        for _ in 0..10 {
+            limiter.on_rate_limited().await;
+        }
+        assert_eq!(limiter.current_rate().await, 1.25);
+
+        for _ in 0..100 {
+            limiter.on_success().await;
+        }
+        assert_eq!(limiter.current_rate().await, 10.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_after_holds_requests_back() {
+        let limiter = RateLimiter::new(10.0);
+        assert!(!limiter.is_throttled().await);
+
+        limiter
+            .record_status(
+                reqwest::StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(2)),
+            )
+            .await;
+        assert!(limiter.is_throttled().await);
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(2), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(2500), "{:?}", elapsed);
+    }
+}
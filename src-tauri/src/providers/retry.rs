@@ -0,0 +1,324 @@
+/// Retry with exponential backoff for transient provider errors
+use reqwest::{header::HeaderMap, RequestBuilder, Response, StatusCode};
+use std::future::Future;
+use std::time::Duration;
+
+/// Upper bound on a server-provided `Retry-After`, so a misbehaving server
+/// can't stall a request indefinitely
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// How many times and how quickly to retry a failed request
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each subsequent retry
+    pub base_delay: Duration,
+    /// Cap on the computed backoff delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before retry number `attempt` (0-based).
+    ///
+    /// `jitter` in `[0, 1)` scales the delay into `[50%, 100%)` of the
+    /// exponential value so concurrent clients don't retry in lockstep.
+    pub fn backoff_delay(&self, attempt: u32, jitter: f64) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        exponential.mul_f64(0.5 + jitter.clamp(0.0, 1.0) / 2.0)
+    }
+}
+
+/// Whether a failed attempt should be retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Retry, optionally after a server-specified delay
+    Retry { after: Option<Duration> },
+    /// Return the result as-is
+    Stop,
+}
+
+/// Decide whether a response status is worth retrying.
+///
+/// Rate limiting (429) and server errors (5xx) are transient; other 4xx
+/// responses won't change on retry and fail fast.
+pub fn decide_status(status: StatusCode, retry_after: Option<Duration>) -> RetryDecision {
+    if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        RetryDecision::Retry { after: retry_after }
+    } else {
+        RetryDecision::Stop
+    }
+}
+
+/// Decide whether a transport error is worth retrying
+pub fn decide_error(error: &reqwest::Error) -> RetryDecision {
+    if error.is_connect() || error.is_timeout() {
+        RetryDecision::Retry { after: None }
+    } else if let Some(status) = error.status() {
+        decide_status(status, None)
+    } else {
+        RetryDecision::Stop
+    }
+}
+
+/// Decide whether the outcome of a reqwest request should be retried
+pub fn decide_response(result: &Result<Response, reqwest::Error>) -> RetryDecision {
+    match result {
+        Ok(response) if response.status().is_success() => RetryDecision::Stop,
+        Ok(response) => decide_status(response.status(), parse_retry_after(response.headers())),
+        Err(e) => decide_error(e),
+    }
+}
+
+/// Parse a `Retry-After` header, given either in delay-seconds or as an
+/// HTTP date
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after_value(value, chrono::Utc::now())
+}
+
+fn parse_retry_after_value(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let delay = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+            // A date in the past means "retry now"
+            (date.with_timezone(&chrono::Utc) - now)
+                .to_std()
+                .unwrap_or(Duration::ZERO)
+        }
+    };
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+/// Run `op` until it succeeds, `decide` says stop, or attempts run out
+pub async fn retry_with<T, E, F, Fut, D>(policy: &RetryPolicy, mut op: F, decide: D) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    D: Fn(&Result<T, E>) -> RetryDecision,
+{
+    let mut attempt = 0;
+    loop {
+        let result = op().await;
+        match decide(&result) {
+            RetryDecision::Retry { after } if attempt + 1 < policy.max_attempts => {
+                let delay =
+                    after.unwrap_or_else(|| policy.backoff_delay(attempt, rand::random::<f64>()));
+                tracing::debug!(
+                    "Transient provider error, retrying in {}ms (attempt {}/{})",
+                    delay.as_millis(),
+                    attempt + 2,
+                    policy.max_attempts
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            _ => return result,
+        }
+    }
+}
+
+/// Send an idempotent request, rebuilding it for each attempt
+pub async fn send_with_retry<F>(policy: &RetryPolicy, build: F) -> Result<Response, reqwest::Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    retry_with(policy, || build().send(), decide_response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_grows_exponentially_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+
+        // Full jitter yields the exponential value
+        assert_eq!(policy.backoff_delay(0, 1.0), Duration::from_millis(100));
+        assert_eq!(policy.backoff_delay(1, 1.0), Duration::from_millis(200));
+        assert_eq!(policy.backoff_delay(2, 1.0), Duration::from_millis(400));
+        assert_eq!(policy.backoff_delay(3, 1.0), Duration::from_millis(500));
+        assert_eq!(policy.backoff_delay(30, 1.0), Duration::from_millis(500));
+
+        // No jitter halves the delay
+        assert_eq!(policy.backoff_delay(1, 0.0), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_decide_status() {
+        assert_eq!(
+            decide_status(StatusCode::TOO_MANY_REQUESTS, None),
+            RetryDecision::Retry { after: None }
+        );
+        assert_eq!(
+            decide_status(StatusCode::INTERNAL_SERVER_ERROR, None),
+            RetryDecision::Retry { after: None }
+        );
+        assert_eq!(
+            decide_status(
+                StatusCode::SERVICE_UNAVAILABLE,
+                Some(Duration::from_secs(3))
+            ),
+            RetryDecision::Retry {
+                after: Some(Duration::from_secs(3))
+            }
+        );
+        assert_eq!(
+            decide_status(StatusCode::NOT_FOUND, None),
+            RetryDecision::Stop
+        );
+        assert_eq!(
+            decide_status(StatusCode::UNAUTHORIZED, None),
+            RetryDecision::Stop
+        );
+        assert_eq!(
+            decide_status(StatusCode::BAD_REQUEST, None),
+            RetryDecision::Stop
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(parse_retry_after(&headers), Some(MAX_RETRY_AFTER));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_parse_retry_after_http_date() {
+        let now = chrono::DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        assert_eq!(
+            parse_retry_after_value("Wed, 21 Oct 2015 07:28:05 GMT", now),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            parse_retry_after_value("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            parse_retry_after_value("Thu, 22 Oct 2015 07:28:00 GMT", now),
+            Some(MAX_RETRY_AFTER)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_after_delays_next_attempt() {
+        use std::sync::Arc;
+
+        // A 429 asking for 2 seconds, then success
+        let calls = Arc::new(AtomicU32::new(0));
+        let task = tokio::spawn({
+            let calls = calls.clone();
+            async move {
+                retry_with(
+                    &RetryPolicy::default(),
+                    || {
+                        let call = calls.fetch_add(1, Ordering::SeqCst);
+                        let mut headers = HeaderMap::new();
+                        let status = if call == 0 {
+                            headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+                            StatusCode::TOO_MANY_REQUESTS
+                        } else {
+                            StatusCode::OK
+                        };
+                        async move { Ok::<_, ()>((status, headers)) }
+                    },
+                    |result| match result {
+                        Ok((status, _)) if status.is_success() => RetryDecision::Stop,
+                        Ok((status, headers)) => decide_status(*status, parse_retry_after(headers)),
+                        Err(()) => RetryDecision::Stop,
+                    },
+                )
+                .await
+            }
+        });
+        while calls.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        // The server's delay replaces the much shorter default backoff
+        tokio::time::advance(Duration::from_millis(1999)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!task.is_finished());
+
+        tokio::time::advance(Duration::from_millis(1)).await;
+        let (status, _) = task.await.unwrap().unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_stops_after_max_attempts() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+        };
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), &str> = retry_with(
+            &policy,
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err("unavailable") }
+            },
+            |_| RetryDecision::Retry { after: None },
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_fails_fast_on_stop() {
+        let policy = RetryPolicy::default();
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), &str> = retry_with(
+            &policy,
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err("not found") }
+            },
+            |_| RetryDecision::Stop,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
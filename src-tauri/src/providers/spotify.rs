@@ -1,26 +1,263 @@
-use super::{MusicProvider, ProviderError};
+use super::rate_limit::RateLimiter;
+use super::retry::{
+    decide_error, decide_status, parse_retry_after, retry_with, RetryDecision, RetryPolicy,
+};
+use super::{sort_album_tracks, MusicProvider, ProviderCapabilities, ProviderError};
 use crate::models::{Playlist, Source, Track};
 use async_trait::async_trait;
 use futures::stream::StreamExt;
+use rspotify::model::SubscriptionLevel;
 use rspotify::{prelude::*, scopes, AuthCodePkceSpotify, Credentials, OAuth, Token};
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Public Spotify Client ID - used across the application
 pub const SPOTIFY_CLIENT_ID: &str = "243bb6667db04143b6586d8598aed48b";
 
-/// Default OAuth redirect URI - must be localhost with specific port for Spotify
-const DEFAULT_REDIRECT_URI: &str = "http://127.0.0.1:8989/callback";
+/// Default port for the local OAuth callback server
+pub const DEFAULT_OAUTH_PORT: u16 = 8989;
+
+/// Build the OAuth redirect URI for a local callback server port.
+/// Spotify requires a loopback address for desktop redirect URIs.
+pub fn redirect_uri_for_port(port: u16) -> String {
+    format!("http://127.0.0.1:{}/callback", port)
+}
 
 /// Maximum consecutive errors allowed when streaming playlist items
 /// before giving up. Allows for transient network issues.
 const MAX_CONSECUTIVE_ERRORS: u32 = 3;
 
+/// Convert an rspotify error into a `ProviderError`, keeping the HTTP status
+fn spotify_error(context: &str, error: rspotify::ClientError) -> ProviderError {
+    match error {
+        rspotify::ClientError::Http(err) => match *err {
+            rspotify::http::HttpError::StatusCode(response) => {
+                ProviderError::from_status(response.status(), context)
+            }
+            rspotify::http::HttpError::Client(e) => ProviderError::with_context(context, e),
+        },
+        other => ProviderError::Other(format!("{}: {}", context, other)),
+    }
+}
+
+/// HTTP status of a failed rspotify call, if the server responded, with the
+/// `Retry-After` delay it asked for
+fn spotify_status<T>(
+    result: &rspotify::ClientResult<T>,
+) -> Option<(reqwest::StatusCode, Option<Duration>)> {
+    match result {
+        Err(rspotify::ClientError::Http(err)) => match err.as_ref() {
+            rspotify::http::HttpError::StatusCode(response) => {
+                Some((response.status(), parse_retry_after(response.headers())))
+            }
+            rspotify::http::HttpError::Client(e) => e.status().map(|status| (status, None)),
+        },
+        _ => None,
+    }
+}
+
+/// Retry decision for rspotify results: transport failures, 429 and 5xx
+/// are retried, other API errors fail fast
+fn decide<T>(result: &rspotify::ClientResult<T>) -> RetryDecision {
+    match result {
+        Err(rspotify::ClientError::Http(err)) => match err.as_ref() {
+            rspotify::http::HttpError::StatusCode(response) => {
+                decide_status(response.status(), parse_retry_after(response.headers()))
+            }
+            rspotify::http::HttpError::Client(e) => decide_error(e),
+        },
+        _ => RetryDecision::Stop,
+    }
+}
+
+/// ID of the "Liked Songs" pseudo-playlist holding the user's saved tracks
+pub const LIKED_SONGS_ID: &str = "liked-songs";
+
+/// Saved tracks fetched per request; the most the library endpoints allow
+const SAVED_TRACKS_PAGE_SIZE: u32 = 50;
+
+/// Collect every item of an offset-paginated endpoint, calling `fetch_page`
+/// with the offset of each page until the reported total is reached
+async fn fetch_all_pages<T, F, Fut>(mut fetch_page: F) -> Result<Vec<T>, ProviderError>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<rspotify::model::Page<T>, ProviderError>>,
+{
+    let mut items = Vec::new();
+    loop {
+        let page = fetch_page(items.len() as u32).await?;
+        let fetched = page.items.len();
+        items.extend(page.items);
+
+        if fetched == 0 || page.next.is_none() || items.len() >= page.total as usize {
+            return Ok(items);
+        }
+    }
+}
+
+/// Wrap the user's saved tracks as the "Liked Songs" pseudo-playlist
+fn liked_songs_playlist(tracks: Vec<Track>) -> Playlist {
+    Playlist {
+        id: LIKED_SONGS_ID.to_string(),
+        name: "Liked Songs".to_string(),
+        description: None,
+        owner: "Spotify".to_string(),
+        image_url: None,
+        track_count: tracks.len(),
+        tracks,
+        source: Source::Spotify,
+        revision: None,
+    }
+}
+
+/// Extract the ID part of an artist ID that may be a full URI or URL
+fn clean_artist_id(id: &str) -> &str {
+    if id.contains("spotify:artist:") {
+        id.split(':').next_back().unwrap_or(id)
+    } else if id.contains("/artist/") {
+        id.split('/').next_back().unwrap_or(id)
+    } else {
+        id
+    }
+}
+
+/// Extract the ID part of a track ID that may be a full URI or URL
+fn clean_track_id(id: &str) -> &str {
+    if id.contains("spotify:track:") {
+        id.split(':').next_back().unwrap_or(id)
+    } else if id.contains("/track/") {
+        id.split('/').next_back().unwrap_or(id)
+    } else {
+        id
+    }
+}
+
+/// Audio Spotify can serve for a track
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpotifyStream {
+    /// The full track, streamed through librespot from a `spotify:track:` URI
+    Full(String),
+    /// A 30 second preview clip served over HTTP
+    Preview(String),
+}
+
+/// Decide how to play a Spotify track.
+///
+/// Premium users with a ready librespot session always get the full track.
+/// While their session is still connecting a known preview is used instead.
+/// Free accounts get an error rather than a 30 second clip.
+pub fn select_stream(
+    track_id: &str,
+    preview_url: Option<&str>,
+    is_premium: bool,
+    session_ready: bool,
+) -> Result<SpotifyStream, ProviderError> {
+    if !is_premium {
+        return Err(ProviderError::Other(
+            "Spotify Premium is required to play full tracks".to_string(),
+        ));
+    }
+
+    if session_ready {
+        let clean_id = track_id.trim_start_matches("spotify:track:");
+        return Ok(SpotifyStream::Full(format!("spotify:track:{}", clean_id)));
+    }
+
+    match preview_url {
+        Some(url) => Ok(SpotifyStream::Preview(url.to_string())),
+        None => Err(ProviderError::Other(
+            "Spotify session is not initialized".to_string(),
+        )),
+    }
+}
+
+/// Year from a Spotify release date (`YYYY`, `YYYY-MM` or `YYYY-MM-DD`)
+fn release_year(release_date: Option<&str>) -> Option<u32> {
+    release_date?.get(..4)?.parse().ok()
+}
+
+/// Convert a track fetched by ID, keeping the bare ID it was requested with
+fn looked_up_track(track: &rspotify::model::FullTrack, clean_id: &str) -> Track {
+    Track {
+        id: clean_id.to_string(),
+        // Return full track URI for premium streaming via librespot
+        url: Some(format!("spotify:track:{}", clean_id)),
+        ..full_track_to_track(track)
+    }
+}
+
+/// Convert a full Spotify track to a playable track
+fn full_track_to_track(track: &rspotify::model::FullTrack) -> Track {
+    Track {
+        id: track
+            .id
+            .as_ref()
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        title: track.name.clone(),
+        artist: track
+            .artists
+            .iter()
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>()
+            .join(", "),
+        album: track.album.name.clone(),
+        album_artist: None,
+        duration_ms: track.duration.num_milliseconds() as u64,
+        image_url: track.album.images.first().map(|img| img.url.clone()),
+        source: Source::Spotify,
+        // Premium playback only - return spotify:track: URI for librespot
+        url: track.id.as_ref().map(|id| format!("spotify:track:{}", id)),
+        auth_headers: None,
+        genre: None,
+        year: release_year(track.album.release_date.as_deref()),
+        track_number: Some(track.track_number),
+        disc_number: u32::try_from(track.disc_number).ok(),
+        enriched_at: None,
+    }
+}
+
+/// Convert an album listing entry to a track-less album summary, with the
+/// release year as its description
+fn simplified_album_to_playlist(album: &rspotify::model::SimplifiedAlbum) -> Playlist {
+    Playlist {
+        id: album
+            .id
+            .as_ref()
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        name: album.name.clone(),
+        description: release_year(album.release_date.as_deref()).map(|year| year.to_string()),
+        owner: album
+            .artists
+            .iter()
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>()
+            .join(", "),
+        image_url: album.images.first().map(|img| img.url.clone()),
+        track_count: 0,
+        tracks: Vec::new(),
+        source: Source::Spotify,
+        revision: None,
+    }
+}
+
+/// Whether the account can play full tracks. Free accounts report a
+/// product too, so having one doesn't mean Premium.
+fn is_premium_subscription(product: Option<SubscriptionLevel>) -> bool {
+    matches!(product, Some(SubscriptionLevel::Premium))
+}
+
 /// Spotify provider state
 pub struct SpotifyProvider {
     client: Option<AuthCodePkceSpotify>,
     is_authenticated: bool,
     is_premium: bool,
     access_token: Option<String>,
+    /// Shared limiter every Web API request passes through
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl Default for SpotifyProvider {
@@ -37,15 +274,50 @@ impl SpotifyProvider {
             is_authenticated: false,
             is_premium: false,
             access_token: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
         }
     }
 
+    /// Share a rate limiter across every request this provider makes
+    pub fn set_rate_limiter(&mut self, rate_limiter: Arc<RateLimiter>) {
+        self.rate_limiter = rate_limiter;
+    }
+
+    /// Run a Web API call through the rate limiter, retrying transient failures
+    async fn call<T, F, Fut>(&self, mut op: F) -> rspotify::ClientResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = rspotify::ClientResult<T>>,
+    {
+        let limiter = &self.rate_limiter;
+        retry_with(
+            &RetryPolicy::default(),
+            || {
+                let request = op();
+                async move {
+                    limiter.acquire().await;
+                    let result = request.await;
+                    match spotify_status(&result) {
+                        Some((status, retry_after)) => {
+                            limiter.record_status(status, retry_after).await
+                        }
+                        None if result.is_ok() => limiter.on_success().await,
+                        None => {}
+                    }
+                    result
+                }
+            },
+            decide,
+        )
+        .await
+    }
+
     /// Helper method to create default OAuth configuration with PKCE
-    fn default_oauth_config() -> (Credentials, OAuth) {
+    fn default_oauth_config(redirect_uri: &str) -> (Credentials, OAuth) {
         // Use PKCE for public clients (desktop apps) that don't have/store a secret
         let credentials = Credentials::new_pkce(SPOTIFY_CLIENT_ID);
         let oauth = OAuth {
-            redirect_uri: DEFAULT_REDIRECT_URI.to_string(),
+            redirect_uri: redirect_uri.to_string(),
             scopes: scopes!(
                 "playlist-read-private",
                 "playlist-read-collaborative",
@@ -66,7 +338,13 @@ impl SpotifyProvider {
 
     /// Create a new Spotify provider with default OAuth configuration (PKCE - no secrets needed)
     pub fn with_default_oauth() -> Self {
-        let (credentials, oauth) = Self::default_oauth_config();
+        Self::with_default_oauth_and_redirect(&redirect_uri_for_port(DEFAULT_OAUTH_PORT))
+    }
+
+    /// Create a new Spotify provider with default OAuth configuration and a
+    /// specific redirect URI (used when the callback server is on a non-default port)
+    pub fn with_default_oauth_and_redirect(redirect_uri: &str) -> Self {
+        let (credentials, oauth) = Self::default_oauth_config(redirect_uri);
         let client = AuthCodePkceSpotify::new(credentials, oauth);
 
         Self {
@@ -74,12 +352,14 @@ impl SpotifyProvider {
             is_authenticated: false,
             is_premium: false,
             access_token: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
         }
     }
 
     /// Create a new Spotify provider with default OAuth and configured cache path
     pub fn with_default_oauth_and_cache(cache_path: PathBuf) -> Self {
-        let (credentials, oauth) = Self::default_oauth_config();
+        let (credentials, oauth) =
+            Self::default_oauth_config(&redirect_uri_for_port(DEFAULT_OAUTH_PORT));
         let mut client = AuthCodePkceSpotify::new(credentials, oauth);
 
         // Configure token cache
@@ -91,6 +371,7 @@ impl SpotifyProvider {
             is_authenticated: false,
             is_premium: false,
             access_token: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
         }
     }
 
@@ -122,6 +403,7 @@ impl SpotifyProvider {
             is_authenticated: false,
             is_premium: false,
             access_token: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
         }
     }
 
@@ -132,9 +414,17 @@ impl SpotifyProvider {
             .map(|c| {
                 // PKCE requires mutable reference to generate verifier
                 c.get_authorize_url(None)
-                    .map_err(|e| ProviderError(e.to_string()))
+                    .map_err(|e| ProviderError::Other(e.to_string()))
             })
-            .ok_or_else(|| ProviderError("Client not configured".to_string()))?
+            .ok_or_else(|| ProviderError::NotAuthenticated("Client not configured".to_string()))?
+    }
+
+    /// Get the OAuth `state` value included in the authorization URL
+    ///
+    /// rspotify generates a random state for each OAuth configuration; the
+    /// callback must echo it back for the code to be accepted.
+    pub fn get_oauth_state(&self) -> Option<String> {
+        self.client.as_ref().map(|c| c.oauth.state.clone())
     }
 
     /// Fetch current user profile and check premium status
@@ -142,14 +432,14 @@ impl SpotifyProvider {
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| ProviderError("Client not configured".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Client not configured".to_string()))?;
 
-        let user = client
-            .current_user()
+        let user = self
+            .call(|| client.current_user())
             .await
-            .map_err(|e| ProviderError(format!("Failed to fetch user profile: {}", e)))?;
+            .map_err(|e| spotify_error("Failed to fetch user profile", e))?;
 
-        let is_premium = user.product.is_some();
+        let is_premium = is_premium_subscription(user.product);
 
         tracing::info!("Spotify user subscription type: {:?}", user.product);
 
@@ -184,13 +474,13 @@ impl SpotifyProvider {
         let client = self
             .client
             .as_mut()
-            .ok_or_else(|| ProviderError("Client not configured".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Client not configured".to_string()))?;
 
         // Request access token
         client
             .request_token(code)
             .await
-            .map_err(|e| ProviderError(format!("Failed to request access token: {}", e)))?;
+            .map_err(|e| spotify_error("Failed to request access token", e))?;
 
         // Mark as authenticated after successful token request
         self.is_authenticated = true;
@@ -261,7 +551,7 @@ impl SpotifyProvider {
         let client = self
             .client
             .as_mut()
-            .ok_or_else(|| ProviderError("Client not configured".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Client not configured".to_string()))?;
 
         // If the provided token is already expired, attempt to refresh it using its refresh token
         if token.is_expired() {
@@ -271,11 +561,10 @@ impl SpotifyProvider {
                 // Temporarily set the expired token (containing the refresh token) on the client
                 // so that rspotify can perform the refresh operation
                 {
-                    let mut token_guard = client
-                        .token
-                        .lock()
-                        .await
-                        .map_err(|_| ProviderError("Failed to lock token".to_string()))?;
+                    let mut token_guard =
+                        client.token.lock().await.map_err(|_| {
+                            ProviderError::Other("Failed to lock token".to_string())
+                        })?;
                     *token_guard = Some(token.clone());
                 }
 
@@ -284,11 +573,9 @@ impl SpotifyProvider {
                     Ok(_) => {
                         tracing::info!("Token refreshed successfully");
                         // Read back the refreshed token from the client
-                        let token_guard = client
-                            .token
-                            .lock()
-                            .await
-                            .map_err(|_| ProviderError("Failed to lock token".to_string()))?;
+                        let token_guard = client.token.lock().await.map_err(|_| {
+                            ProviderError::Other("Failed to lock token".to_string())
+                        })?;
 
                         if let Some(refreshed_token) = token_guard.as_ref() {
                             // Keep internal metadata in sync with the newly refreshed token
@@ -306,21 +593,21 @@ impl SpotifyProvider {
 
                             return Ok(());
                         } else {
-                            return Err(ProviderError(
+                            return Err(ProviderError::Other(
                                 "Token refresh succeeded but no token found in client".to_string(),
                             ));
                         }
                     }
                     Err(e) => {
                         tracing::warn!("Failed to refresh expired token: {}", e);
-                        return Err(ProviderError(format!(
+                        return Err(ProviderError::NotAuthenticated(format!(
                             "Provided Spotify token is expired and refresh failed: {}",
                             e
                         )));
                     }
                 }
             } else {
-                return Err(ProviderError(
+                return Err(ProviderError::NotAuthenticated(
                     "Provided Spotify token is expired and has no refresh token".to_string(),
                 ));
             }
@@ -335,7 +622,7 @@ impl SpotifyProvider {
             .token
             .lock()
             .await
-            .map_err(|_| ProviderError("Failed to lock token".to_string()))?;
+            .map_err(|_| ProviderError::Other("Failed to lock token".to_string()))?;
         *token_guard = Some(token);
         drop(token_guard);
 
@@ -356,6 +643,60 @@ impl SpotifyProvider {
         Ok(())
     }
 
+    /// Every track in the user's library ("Liked Songs"), newest first
+    pub async fn get_saved_tracks(&self) -> Result<Vec<Track>, ProviderError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+
+        let saved = fetch_all_pages(|offset| async move {
+            self.call(|| {
+                client.current_user_saved_tracks_manual(
+                    None,
+                    Some(SAVED_TRACKS_PAGE_SIZE),
+                    Some(offset),
+                )
+            })
+            .await
+            .map_err(|e| spotify_error("Failed to fetch saved tracks", e))
+        })
+        .await?;
+
+        Ok(saved
+            .iter()
+            .map(|saved| full_track_to_track(&saved.track))
+            .collect())
+    }
+
+    /// Add a track to the user's library
+    pub async fn save_track(&self, track_id: &str) -> Result<(), ProviderError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+        let track_id = rspotify::model::TrackId::from_id(clean_track_id(track_id))
+            .map_err(|e| ProviderError::Other(format!("Invalid track ID: {}", e)))?;
+
+        self.call(|| client.current_user_saved_tracks_add([track_id.clone()]))
+            .await
+            .map_err(|e| spotify_error("Failed to save track", e))
+    }
+
+    /// Remove a track from the user's library
+    pub async fn remove_saved_track(&self, track_id: &str) -> Result<(), ProviderError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+        let track_id = rspotify::model::TrackId::from_id(clean_track_id(track_id))
+            .map_err(|e| ProviderError::Other(format!("Invalid track ID: {}", e)))?;
+
+        self.call(|| client.current_user_saved_tracks_delete([track_id.clone()]))
+            .await
+            .map_err(|e| spotify_error("Failed to remove saved track", e))
+    }
+
     /// Get the cache path if configured
     pub fn get_cache_path(&self) -> Option<PathBuf> {
         self.client.as_ref().map(|c| c.config.cache_path.clone())
@@ -456,7 +797,7 @@ impl MusicProvider for SpotifyProvider {
     async fn authenticate(&mut self) -> Result<(), ProviderError> {
         // OAuth flow is handled via get_auth_url() and authenticate_with_code()
         self.client.is_some().then_some(()).ok_or_else(|| {
-            ProviderError(
+            ProviderError::NotAuthenticated(
                 "Not authenticated. Use get_auth_url() and authenticate_with_code()".to_string(),
             )
         })
@@ -466,19 +807,34 @@ impl MusicProvider for SpotifyProvider {
         self.is_authenticated && self.client.is_some()
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            search_tracks: true,
+            create_playlist: true,
+            // Full tracks only stream through librespot with a premium account
+            streaming: self.is_premium,
+            ..ProviderCapabilities::default()
+        }
+    }
+
     async fn get_playlists(&self) -> Result<Vec<Playlist>, ProviderError> {
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| ProviderError("Not authenticated".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
 
         // Use stream API for pagination
         let mut playlists_stream = client.current_user_playlists();
         let mut result = Vec::new();
+        let page_size = client.config.pagination_chunks.max(1) as usize;
 
+        // The stream fetches a page at a time; take a token before each page
+        self.rate_limiter.acquire().await;
         while let Some(playlist_item) = playlists_stream.next().await {
-            let item = playlist_item
-                .map_err(|e| ProviderError(format!("Failed to fetch playlist: {}", e)))?;
+            if !result.is_empty() && result.len() % page_size == 0 {
+                self.rate_limiter.acquire().await;
+            }
+            let item = playlist_item.map_err(|e| spotify_error("Failed to fetch playlist", e))?;
             result.push(Playlist {
                 id: item.id.to_string(),
                 name: item.name,
@@ -491,6 +847,7 @@ impl MusicProvider for SpotifyProvider {
                 track_count: item.tracks.total as usize,
                 tracks: Vec::new(),
                 source: Source::Spotify,
+                revision: Some(item.snapshot_id),
             });
         }
 
@@ -498,10 +855,14 @@ impl MusicProvider for SpotifyProvider {
     }
 
     async fn get_playlist(&self, id: &str) -> Result<Playlist, ProviderError> {
+        if id == LIKED_SONGS_ID {
+            return Ok(liked_songs_playlist(self.get_saved_tracks().await?));
+        }
+
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| ProviderError("Not authenticated".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
 
         // Extract the ID part - it could be a full URI or just the ID
         let clean_id = if id.contains("spotify:playlist:") {
@@ -513,12 +874,12 @@ impl MusicProvider for SpotifyProvider {
         };
 
         let playlist_id = rspotify::model::PlaylistId::from_id(clean_id)
-            .map_err(|e| ProviderError(format!("Invalid playlist ID: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Invalid playlist ID: {}", e)))?;
 
-        let playlist = client
-            .playlist(playlist_id.clone(), None, None)
+        let playlist = self
+            .call(|| client.playlist(playlist_id.clone(), None, None))
             .await
-            .map_err(|e| ProviderError(format!("Failed to fetch playlist: {}", e)))?;
+            .map_err(|e| spotify_error("Failed to fetch playlist", e))?;
 
         let mut tracks = Vec::new();
 
@@ -526,8 +887,16 @@ impl MusicProvider for SpotifyProvider {
         // Retry transient network errors to handle large playlists more robustly
         let mut tracks_stream = client.playlist_items(playlist_id.clone(), None, None);
         let mut consecutive_errors = 0;
+        let page_size = client.config.pagination_chunks.max(1) as usize;
+        let mut items_seen = 0usize;
 
+        // The stream fetches a page at a time; take a token before each page
+        self.rate_limiter.acquire().await;
         while let Some(track_result) = tracks_stream.next().await {
+            items_seen += 1;
+            if items_seen > 1 && (items_seen - 1) % page_size == 0 {
+                self.rate_limiter.acquire().await;
+            }
             match track_result {
                 Ok(item) => {
                     consecutive_errors = 0; // Reset error counter on success
@@ -536,6 +905,7 @@ impl MusicProvider for SpotifyProvider {
                         let duration_ms = t.duration.num_milliseconds() as u64;
                         // Premium playback only - return spotify:track: URI for librespot
                         let url = t.id.as_ref().map(|id| format!("spotify:track:{}", id));
+                        let year = release_year(t.album.release_date.as_deref());
                         tracks.push(Track {
                             id: t.id.map(|id| id.to_string()).unwrap_or_default(),
                             title: t.name,
@@ -546,11 +916,17 @@ impl MusicProvider for SpotifyProvider {
                                 .collect::<Vec<_>>()
                                 .join(", "),
                             album: t.album.name,
+                            album_artist: None,
                             duration_ms,
                             image_url: t.album.images.first().map(|img| img.url.clone()),
                             source: Source::Spotify,
                             url,
                             auth_headers: None,
+                            genre: None,
+                            year,
+                            track_number: Some(t.track_number),
+                            disc_number: u32::try_from(t.disc_number).ok(),
+                            enriched_at: None,
                         });
                     }
                 }
@@ -564,7 +940,7 @@ impl MusicProvider for SpotifyProvider {
                     );
 
                     if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
-                        return Err(ProviderError(format!(
+                        return Err(ProviderError::Other(format!(
                             "Failed to fetch playlist tracks after {} consecutive errors: {}",
                             MAX_CONSECUTIVE_ERRORS, e
                         )));
@@ -588,20 +964,140 @@ impl MusicProvider for SpotifyProvider {
             track_count: tracks.len(),
             tracks,
             source: Source::Spotify,
+            revision: Some(playlist.snapshot_id),
+        })
+    }
+    async fn get_album(&self, id: &str) -> Result<Playlist, ProviderError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+
+        // Extract the ID part - it could be a full URI or just the ID
+        let clean_id = if id.contains("spotify:album:") {
+            id.split(':').next_back().unwrap_or(id)
+        } else if id.contains("/album/") {
+            id.split('/').next_back().unwrap_or(id)
+        } else {
+            id
+        };
+
+        let album_id = rspotify::model::AlbumId::from_id(clean_id)
+            .map_err(|e| ProviderError::Other(format!("Invalid album ID: {}", e)))?;
+
+        let album = self
+            .call(|| client.album(album_id.clone(), None))
+            .await
+            .map_err(|e| spotify_error("Failed to fetch album", e))?;
+
+        let album_artist = album
+            .artists
+            .iter()
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let image_url = album.images.first().map(|img| img.url.clone());
+        let year = release_year(Some(&album.release_date));
+
+        // Album track listings are simplified tracks; the album fills in the rest
+        let mut tracks = Vec::new();
+        let mut tracks_stream = client.album_track(album_id, None);
+        self.rate_limiter.acquire().await;
+        while let Some(track_result) = tracks_stream.next().await {
+            let t = track_result.map_err(|e| spotify_error("Failed to fetch album tracks", e))?;
+            let url = t.id.as_ref().map(|id| format!("spotify:track:{}", id));
+            tracks.push(Track {
+                id: t.id.map(|id| id.to_string()).unwrap_or_default(),
+                title: t.name,
+                artist: t
+                    .artists
+                    .iter()
+                    .map(|a| a.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                album: album.name.clone(),
+                album_artist: None,
+                duration_ms: t.duration.num_milliseconds() as u64,
+                image_url: image_url.clone(),
+                source: Source::Spotify,
+                url,
+                auth_headers: None,
+                genre: album.genres.first().cloned(),
+                year,
+                track_number: Some(t.track_number),
+                disc_number: u32::try_from(t.disc_number).ok(),
+                enriched_at: None,
+            });
+        }
+        sort_album_tracks(&mut tracks);
+
+        Ok(Playlist {
+            id: album.id.to_string(),
+            name: album.name,
+            description: None,
+            owner: album_artist,
+            image_url,
+            track_count: tracks.len(),
+            tracks,
+            source: Source::Spotify,
+            revision: None,
         })
     }
+
+    async fn get_artist_albums(&self, artist_id: &str) -> Result<Vec<Playlist>, ProviderError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+
+        let artist_id = rspotify::model::ArtistId::from_id(clean_artist_id(artist_id))
+            .map_err(|e| ProviderError::Other(format!("Invalid artist ID: {}", e)))?;
+
+        let mut albums = Vec::new();
+        let mut albums_stream =
+            client.artist_albums(artist_id, Some(rspotify::model::AlbumType::Album), None);
+        self.rate_limiter.acquire().await;
+        while let Some(album_result) = albums_stream.next().await {
+            let album =
+                album_result.map_err(|e| spotify_error("Failed to fetch artist albums", e))?;
+            albums.push(simplified_album_to_playlist(&album));
+        }
+
+        Ok(albums)
+    }
+
+    async fn get_artist_top_tracks(&self, artist_id: &str) -> Result<Vec<Track>, ProviderError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+
+        let artist_id = rspotify::model::ArtistId::from_id(clean_artist_id(artist_id))
+            .map_err(|e| ProviderError::Other(format!("Invalid artist ID: {}", e)))?;
+
+        let tracks = self
+            .call(|| {
+                client
+                    .artist_top_tracks(artist_id.clone(), Some(rspotify::model::Market::FromToken))
+            })
+            .await
+            .map_err(|e| spotify_error("Failed to fetch artist top tracks", e))?;
+
+        Ok(tracks.iter().map(full_track_to_track).collect())
+    }
+
     async fn search_tracks(&self, query: &str) -> Result<Vec<Track>, ProviderError> {
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| ProviderError("Not authenticated".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
 
         use rspotify::model::SearchType;
 
-        let search_result = client
-            .search(query, SearchType::Track, None, None, Some(20), None)
+        let search_result = self
+            .call(|| client.search(query, SearchType::Track, None, None, Some(20), None))
             .await
-            .map_err(|e| ProviderError(format!("Failed to search Spotify tracks: {}", e)))?;
+            .map_err(|e| spotify_error("Failed to search Spotify tracks", e))?;
 
         let tracks = if let rspotify::model::SearchResult::Tracks(page) = search_result {
             page.items
@@ -627,11 +1123,17 @@ impl MusicProvider for SpotifyProvider {
                         title: track.name.clone(),
                         artist: artists,
                         album: album_name,
+                        album_artist: None,
                         duration_ms,
                         image_url,
                         source: Source::Spotify,
                         url: track.external_urls.get("spotify").cloned(),
                         auth_headers: None,
+                        genre: None,
+                        year: release_year(track.album.release_date.as_deref()),
+                        track_number: Some(track.track_number),
+                        disc_number: u32::try_from(track.disc_number).ok(),
+                        enriched_at: None,
                     }
                 })
                 .collect()
@@ -646,10 +1148,10 @@ impl MusicProvider for SpotifyProvider {
         let _client = self
             .client
             .as_ref()
-            .ok_or_else(|| ProviderError("Not authenticated".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
 
         // TODO: Implement playlist search using rspotify search API
-        Err(ProviderError(format!(
+        Err(ProviderError::Other(format!(
             "Playlist search not yet implemented for query: {}",
             query
         )))
@@ -668,7 +1170,7 @@ impl MusicProvider for SpotifyProvider {
         // Verify user is premium
         if !self.is_premium {
             tracing::warn!("Premium required for track playback. User has free tier account.");
-            return Err(ProviderError(
+            return Err(ProviderError::Other(
                 "Premium required for full Spotify playback".to_string(),
             ));
         }
@@ -685,7 +1187,7 @@ impl MusicProvider for SpotifyProvider {
         let client = self
             .client
             .as_ref()
-            .ok_or_else(|| ProviderError("Not authenticated".to_string()))?;
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
 
         // Extract the ID part - it could be a full URI or just the ID
         let clean_id = if track_id.contains("spotify:track:") {
@@ -697,53 +1199,91 @@ impl MusicProvider for SpotifyProvider {
         };
 
         let track_id_obj = rspotify::model::TrackId::from_id(clean_id)
-            .map_err(|e| ProviderError(format!("Invalid track ID: {}", e)))?;
+            .map_err(|e| ProviderError::Other(format!("Invalid track ID: {}", e)))?;
 
-        let track = client
-            .track(track_id_obj, None)
+        let track = self
+            .call(|| client.track(track_id_obj.clone(), None))
             .await
-            .map_err(|e| ProviderError(format!("Failed to fetch track: {}", e)))?;
-
-        let duration_ms = track.duration.num_milliseconds() as u64;
-        // Return full track URI for premium streaming via librespot
-        let url = Some(format!("spotify:track:{}", clean_id));
+            .map_err(|e| spotify_error("Failed to fetch track", e))?;
 
-        Ok(Track {
-            id: clean_id.to_string(),
-            title: track.name,
-            artist: track
-                .artists
-                .iter()
-                .map(|a| a.name.clone())
-                .collect::<Vec<_>>()
-                .join(", "),
-            album: track.album.name,
-            duration_ms,
-            image_url: track.album.images.first().map(|img| img.url.clone()),
-            source: Source::Spotify,
-            url,
-            auth_headers: None,
-        })
+        Ok(looked_up_track(&track, clean_id))
     }
 
     async fn create_playlist(
         &self,
-        _name: &str,
-        _description: Option<&str>,
+        name: &str,
+        description: Option<&str>,
     ) -> Result<Playlist, ProviderError> {
-        Err(ProviderError(
-            "Playlist creation not yet implemented".to_string(),
-        ))
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+
+        let user = self
+            .call(|| client.current_user())
+            .await
+            .map_err(|e| spotify_error("Failed to fetch user profile", e))?;
+
+        let playlist = self
+            .call(|| {
+                client.user_playlist_create(user.id.clone(), name, Some(false), None, description)
+            })
+            .await
+            .map_err(|e| spotify_error("Failed to create playlist", e))?;
+
+        Ok(Playlist {
+            id: playlist.id.to_string(),
+            name: playlist.name,
+            description: playlist.description,
+            owner: playlist
+                .owner
+                .display_name
+                .unwrap_or_else(|| playlist.owner.id.to_string()),
+            image_url: playlist.images.first().map(|img| img.url.clone()),
+            track_count: 0,
+            tracks: Vec::new(),
+            source: Source::Spotify,
+            revision: Some(playlist.snapshot_id),
+        })
     }
 
     async fn add_track_to_playlist(
         &self,
-        _playlist_id: &str,
-        _track: &Track,
+        playlist_id: &str,
+        track: &Track,
     ) -> Result<(), ProviderError> {
-        Err(ProviderError(
-            "Add track to playlist not yet implemented".to_string(),
-        ))
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Not authenticated".to_string()))?;
+
+        let clean_playlist_id = if playlist_id.contains("spotify:playlist:") {
+            playlist_id.split(':').next_back().unwrap_or(playlist_id)
+        } else {
+            playlist_id
+        };
+        let clean_track_id = if track.id.contains("spotify:track:") {
+            track.id.split(':').next_back().unwrap_or(&track.id)
+        } else {
+            &track.id
+        };
+
+        let playlist_id = rspotify::model::PlaylistId::from_id(clean_playlist_id)
+            .map_err(|e| ProviderError::Other(format!("Invalid playlist ID: {}", e)))?;
+        let track_id = rspotify::model::TrackId::from_id(clean_track_id)
+            .map_err(|e| ProviderError::Other(format!("Invalid track ID: {}", e)))?;
+
+        self.call(|| {
+            client.playlist_add_items(
+                playlist_id.clone(),
+                [rspotify::model::PlayableId::Track(track_id.clone())],
+                None,
+            )
+        })
+        .await
+        .map_err(|e| spotify_error("Failed to add track to playlist", e))?;
+
+        Ok(())
     }
 
     async fn remove_track_from_playlist(
@@ -751,14 +1291,255 @@ impl MusicProvider for SpotifyProvider {
         _playlist_id: &str,
         _track_id: &str,
     ) -> Result<(), ProviderError> {
-        Err(ProviderError(
+        Err(ProviderError::Other(
             "Remove track from playlist not yet implemented".to_string(),
         ))
     }
 
     async fn get_recently_played(&self, _limit: usize) -> Result<Vec<Track>, ProviderError> {
-        Err(ProviderError(
+        Err(ProviderError::Other(
             "Get recently played not yet implemented".to_string(),
         ))
     }
+
+    async fn health_check(&self) -> Result<(), ProviderError> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ProviderError::NotAuthenticated("Client not configured".to_string()))?;
+
+        // The current user profile (/me) is the lightest authenticated endpoint
+        self.rate_limiter.acquire().await;
+        client
+            .current_user()
+            .await
+            .map_err(|e| spotify_error("Spotify health check failed", e))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_premium_subscription_counts_as_premium() {
+        assert!(is_premium_subscription(Some(SubscriptionLevel::Premium)));
+        assert!(!is_premium_subscription(Some(SubscriptionLevel::Free)));
+        // Without the user-read-private scope the product is left out
+        assert!(!is_premium_subscription(None));
+
+        let user: rspotify::model::PrivateUser =
+            serde_json::from_str(r#"{"id":"u","product":"free","external_urls":{},"href":"h"}"#)
+                .unwrap();
+        assert!(!is_premium_subscription(user.product));
+    }
+
+    fn sample_artist() -> serde_json::Value {
+        serde_json::json!({
+            "external_urls": { "spotify": "https://open.spotify.com/artist/0OdUWJ0sBjDrqHygGUXeCF" },
+            "href": "https://api.spotify.com/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+            "id": "0OdUWJ0sBjDrqHygGUXeCF",
+            "name": "Band of Horses",
+            "type": "artist",
+            "uri": "spotify:artist:0OdUWJ0sBjDrqHygGUXeCF"
+        })
+    }
+
+    fn sample_album() -> serde_json::Value {
+        serde_json::json!({
+            "album_type": "album",
+            "artists": [sample_artist()],
+            "available_markets": ["US"],
+            "external_urls": { "spotify": "https://open.spotify.com/album/4sb0eMpDn3upAFfyi4q2rw" },
+            "href": "https://api.spotify.com/v1/albums/4sb0eMpDn3upAFfyi4q2rw",
+            "id": "4sb0eMpDn3upAFfyi4q2rw",
+            "images": [{ "height": 640, "url": "https://i.scdn.co/image/cover", "width": 640 }],
+            "name": "Everything All the Time",
+            "release_date": "2006-03-21",
+            "release_date_precision": "day",
+            "total_tracks": 10,
+            "type": "album",
+            "uri": "spotify:album:4sb0eMpDn3upAFfyi4q2rw"
+        })
+    }
+
+    fn sample_full_track() -> rspotify::model::FullTrack {
+        serde_json::from_value(serde_json::json!({
+            "album": sample_album(),
+            "artists": [sample_artist()],
+            "available_markets": ["US"],
+            "disc_number": 1,
+            "duration_ms": 322_000,
+            "explicit": false,
+            "external_ids": { "isrc": "USSUB0670202" },
+            "external_urls": { "spotify": "https://open.spotify.com/track/0eGsygTp906u18L0Oimnem" },
+            "href": "https://api.spotify.com/v1/tracks/0eGsygTp906u18L0Oimnem",
+            "id": "0eGsygTp906u18L0Oimnem",
+            "is_local": false,
+            "name": "The Funeral",
+            "popularity": 70,
+            "preview_url": null,
+            "track_number": 2,
+            "type": "track",
+            "uri": "spotify:track:0eGsygTp906u18L0Oimnem"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_top_track_mapping() {
+        let track = sample_full_track();
+
+        let track = full_track_to_track(&track);
+        assert_eq!(track.title, "The Funeral");
+        assert_eq!(track.artist, "Band of Horses");
+        assert_eq!(track.album, "Everything All the Time");
+        assert_eq!(track.duration_ms, 322_000);
+        assert_eq!(track.year, Some(2006));
+        assert_eq!(track.track_number, Some(2));
+        assert_eq!(track.disc_number, Some(1));
+        assert_eq!(
+            track.image_url.as_deref(),
+            Some("https://i.scdn.co/image/cover")
+        );
+        assert!(track.url.unwrap().starts_with("spotify:track:"));
+    }
+
+    #[test]
+    fn test_get_track_mapping_keeps_requested_id() {
+        let track = sample_full_track();
+
+        let track = looked_up_track(&track, "0eGsygTp906u18L0Oimnem");
+        assert_eq!(track.id, "0eGsygTp906u18L0Oimnem");
+        assert_eq!(
+            track.url.as_deref(),
+            Some("spotify:track:0eGsygTp906u18L0Oimnem")
+        );
+        assert_eq!(track.title, "The Funeral");
+        assert_eq!(track.album, "Everything All the Time");
+        assert_eq!(track.source, Source::Spotify);
+    }
+
+    #[test]
+    fn test_artist_album_mapping() {
+        let album: rspotify::model::SimplifiedAlbum =
+            serde_json::from_value(sample_album()).unwrap();
+
+        let playlist = simplified_album_to_playlist(&album);
+        assert_eq!(playlist.name, "Everything All the Time");
+        assert_eq!(playlist.owner, "Band of Horses");
+        assert_eq!(playlist.description.as_deref(), Some("2006"));
+        assert_eq!(playlist.source, Source::Spotify);
+        assert!(playlist.tracks.is_empty());
+    }
+
+    #[test]
+    fn test_liked_songs_mapping() {
+        let saved = rspotify::model::SavedTrack {
+            added_at: chrono::Utc::now(),
+            track: sample_full_track(),
+        };
+
+        let playlist = liked_songs_playlist(vec![full_track_to_track(&saved.track)]);
+        assert_eq!(playlist.id, LIKED_SONGS_ID);
+        assert_eq!(playlist.name, "Liked Songs");
+        assert_eq!(playlist.track_count, 1);
+        assert_eq!(playlist.source, Source::Spotify);
+
+        let track = &playlist.tracks[0];
+        assert_eq!(track.title, "The Funeral");
+        assert_eq!(track.artist, "Band of Horses");
+        assert!(track.url.as_deref().unwrap().starts_with("spotify:track:"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_follows_offsets() {
+        let all: Vec<u32> = (0..120).collect();
+        let requested = std::sync::Mutex::new(Vec::new());
+
+        let items = fetch_all_pages(|offset| {
+            requested.lock().unwrap().push(offset);
+            let end = (offset as usize + 50).min(all.len());
+            let page = rspotify::model::Page {
+                href: String::new(),
+                items: all[offset as usize..end].to_vec(),
+                limit: 50,
+                next: (end < all.len()).then(|| "next".to_string()),
+                offset,
+                previous: None,
+                total: all.len() as u32,
+            };
+            async move { Ok(page) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(items, all);
+        assert_eq!(*requested.lock().unwrap(), vec![0, 50, 100]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_stops_on_error() {
+        let result: Result<Vec<u32>, _> = fetch_all_pages(|_| async {
+            Err(ProviderError::Network("connection reset".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(ProviderError::Network(_))));
+    }
+
+    #[test]
+    fn test_clean_track_id() {
+        assert_eq!(clean_track_id("spotify:track:abc"), "abc");
+        assert_eq!(clean_track_id("https://open.spotify.com/track/abc"), "abc");
+        assert_eq!(clean_track_id("abc"), "abc");
+    }
+
+    #[test]
+    fn test_clean_artist_id() {
+        assert_eq!(clean_artist_id("spotify:artist:abc"), "abc");
+        assert_eq!(
+            clean_artist_id("https://open.spotify.com/artist/abc"),
+            "abc"
+        );
+        assert_eq!(clean_artist_id("abc"), "abc");
+    }
+
+    #[test]
+    fn test_select_stream() {
+        let preview = Some("https://p.scdn.co/mp3-preview/abc");
+
+        assert_eq!(
+            select_stream("spotify:track:abc", preview, true, true).unwrap(),
+            SpotifyStream::Full("spotify:track:abc".to_string())
+        );
+        assert_eq!(
+            select_stream("abc", None, true, true).unwrap(),
+            SpotifyStream::Full("spotify:track:abc".to_string())
+        );
+        assert_eq!(
+            select_stream("abc", preview, true, false).unwrap(),
+            SpotifyStream::Preview(preview.unwrap().to_string())
+        );
+        assert!(select_stream("abc", None, true, false).is_err());
+
+        let err = select_stream("abc", preview, false, true).unwrap_err();
+        assert!(err.to_string().contains("Premium"));
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let mut provider = SpotifyProvider::new();
+        let capabilities = provider.capabilities();
+        assert!(capabilities.search_tracks);
+        assert!(!capabilities.search_playlists);
+        assert!(capabilities.create_playlist);
+        assert!(!capabilities.edit_playlists);
+        assert!(!capabilities.recently_played);
+        assert!(!capabilities.streaming);
+
+        provider.is_premium = true;
+        assert!(provider.capabilities().streaming);
+    }
 }
@@ -1,5 +1,6 @@
 /// Persistent state management for playback session
-use crate::models::{PlaybackState, RepeatMode, Track};
+use crate::models::{PlaybackInfo, PlaybackState, RepeatMode, Track, Volume};
+use crate::playback::PlaybackQueue;
 use serde::{
     de::Error as DeError, ser::Error as SerError, Deserialize, Deserializer, Serialize, Serializer,
 };
@@ -7,6 +8,10 @@ use serde_json::Value;
 use std::path::PathBuf;
 use tokio::fs;
 
+/// Serializes writes to the state file so the autosave task and explicit
+/// saves never interleave
+static SAVE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
 #[cfg(test)]
 use std::sync::OnceLock;
 #[cfg(test)]
@@ -83,9 +88,16 @@ where
     serde_json::from_value(Value::Array(values)).map_err(DeError::custom)
 }
 
+/// Current layout of the saved state file; bump when fields change and
+/// add a step to `PersistentPlaybackState::migrate`
+pub const STATE_VERSION: u32 = 1;
+
 /// Persistent playback state that gets saved to disk
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PersistentPlaybackState {
+    /// Layout version of the saved file (0 for files written before versioning)
+    #[serde(default)]
+    pub version: u32,
     /// Current playing track
     #[serde(
         serialize_with = "serialize_option_track_sanitized",
@@ -107,7 +119,7 @@ pub struct PersistentPlaybackState {
     /// Repeat mode
     pub repeat_mode: RepeatMode,
     /// Volume (0-100)
-    pub volume: u32,
+    pub volume: Volume,
     /// Shuffle order
     pub shuffle_order: Vec<usize>,
     /// Playback state (playing/paused/stopped)
@@ -117,13 +129,14 @@ pub struct PersistentPlaybackState {
 impl Default for PersistentPlaybackState {
     fn default() -> Self {
         Self {
+            version: STATE_VERSION,
             current_track: None,
             queue: Vec::new(),
             current_index: 0,
             position_ms: 0,
             shuffle: false,
             repeat_mode: RepeatMode::Off,
-            volume: 50,
+            volume: Volume::default(),
             shuffle_order: Vec::new(),
             state: PlaybackState::Stopped,
         }
@@ -131,13 +144,31 @@ impl Default for PersistentPlaybackState {
 }
 
 impl PersistentPlaybackState {
+    /// Snapshot the live playback info and queue, including the shuffle order
+    pub fn capture(info: &PlaybackInfo, queue: &PlaybackQueue) -> Self {
+        Self {
+            version: STATE_VERSION,
+            current_track: info.current_track.clone(),
+            queue: queue.tracks.clone(),
+            current_index: queue.current_index,
+            position_ms: info.position_ms,
+            shuffle: info.shuffle,
+            repeat_mode: info.repeat_mode,
+            volume: info.volume,
+            shuffle_order: queue.shuffle_order.clone(),
+            state: info.state,
+        }
+    }
+
     /// Get the path to the state file
     #[cfg(not(test))]
     async fn get_state_file_path() -> Result<PathBuf, String> {
-        let data_dir =
-            dirs::data_dir().ok_or_else(|| "Failed to get data directory".to_string())?;
-
-        let state_dir = data_dir.join("any-player");
+        let state_dir = match crate::config::configured_data_dir() {
+            Some(dir) => dir,
+            None => dirs::data_dir()
+                .ok_or_else(|| "Failed to get data directory".to_string())?
+                .join("any-player"),
+        };
 
         // Ensure directory exists
         fs::create_dir_all(&state_dir)
@@ -147,16 +178,21 @@ impl PersistentPlaybackState {
         Ok(state_dir.join("playback_state.json"))
     }
 
-    /// Get the path to the state file (test version with unique paths per thread)
+    /// Get the path to the state file (test version with unique paths per
+    /// thread, unless the test set a data directory)
     #[cfg(test)]
     async fn get_state_file_path() -> Result<PathBuf, String> {
-        let temp_dir = std::env::temp_dir();
-        let thread_id = std::thread::current().id();
-
-        // Use a combination of process ID, test run UUID, and thread ID for uniqueness
-        let test_id =
-            TEST_UUID.get_or_init(|| format!("{}-{}", std::process::id(), uuid::Uuid::new_v4()));
-        let state_dir = temp_dir.join(format!("any-player-test-{}-{:?}", test_id, thread_id));
+        let state_dir = match crate::config::configured_data_dir() {
+            Some(dir) => dir,
+            None => {
+                let thread_id = std::thread::current().id();
+
+                // Use a combination of process ID, test run UUID, and thread ID for uniqueness
+                let test_id = TEST_UUID
+                    .get_or_init(|| format!("{}-{}", std::process::id(), uuid::Uuid::new_v4()));
+                std::env::temp_dir().join(format!("any-player-test-{}-{:?}", test_id, thread_id))
+            }
+        };
 
         // Ensure directory exists
         fs::create_dir_all(&state_dir)
@@ -180,6 +216,7 @@ impl PersistentPlaybackState {
         .map_err(|e| format!("Failed to spawn blocking task: {}", e))??;
 
         // Write to disk using async I/O
+        let _guard = SAVE_LOCK.lock().await;
         fs::write(&path, json)
             .await
             .map_err(|e| format!("Failed to write state file: {}", e))?;
@@ -188,8 +225,54 @@ impl PersistentPlaybackState {
         Ok(())
     }
 
+    /// Bring a saved state from an older layout up to `STATE_VERSION`
+    ///
+    /// Returns `None` when the version is newer than this build understands.
+    fn migrate(mut value: Value) -> Option<Value> {
+        let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+        if version > STATE_VERSION as u64 {
+            return None;
+        }
+
+        // Version 0 (unversioned) has the same fields as version 1
+        if let Value::Object(ref mut map) = value {
+            map.insert("version".to_string(), Value::from(STATE_VERSION));
+        }
+        Some(value)
+    }
+
+    /// Parse a saved state, migrating older layouts
+    ///
+    /// Unknown versions and unreadable data are discarded with a warning so an
+    /// update never leaves the player stuck on a state it can't load.
+    fn parse(json: &str) -> Option<Self> {
+        let value: Value = match serde_json::from_str(json) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Discarding unreadable playback state: {}", e);
+                return None;
+            }
+        };
+
+        let Some(value) = Self::migrate(value) else {
+            tracing::warn!(
+                "Discarding playback state saved by a newer version (supported: {})",
+                STATE_VERSION
+            );
+            return None;
+        };
+
+        match serde_json::from_value(value) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                tracing::warn!("Discarding playback state that failed to load: {}", e);
+                None
+            }
+        }
+    }
+
     /// Load state from disk (async, non-blocking)
-    /// Returns None if no saved state exists
+    /// Returns None if no saved state exists or it can't be migrated
     pub async fn load() -> Result<Option<Self>, String> {
         let path = Self::get_state_file_path().await?;
 
@@ -205,14 +288,14 @@ impl PersistentPlaybackState {
             .await
             .map_err(|e| format!("Failed to read state file: {}", e))?;
 
-        let state = tokio::task::spawn_blocking(move || {
-            serde_json::from_str(&json).map_err(|e| format!("Failed to deserialize state: {}", e))
-        })
-        .await
-        .map_err(|e| format!("Failed to spawn blocking task: {}", e))??;
+        let state = tokio::task::spawn_blocking(move || Self::parse(&json))
+            .await
+            .map_err(|e| format!("Failed to spawn blocking task: {}", e))?;
 
-        tracing::info!("Loaded playback state from {:?}", path);
-        Ok(Some(state))
+        if state.is_some() {
+            tracing::info!("Loaded playback state from {:?}", path);
+        }
+        Ok(state)
     }
 
     /// Delete the saved state file (async, non-blocking)
@@ -245,6 +328,7 @@ mod tests {
             title: format!("Test Track {}", id),
             artist: "Test Artist".to_string(),
             album: "Test Album".to_string(),
+            album_artist: None,
             duration_ms: 180000,
             image_url: Some("https://example.com/image.jpg".to_string()),
             source: Source::Jellyfin,
@@ -257,19 +341,25 @@ mod tests {
             } else {
                 None
             },
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            enriched_at: None,
         }
     }
 
     #[test]
     fn test_default_persistent_state() {
         let state = PersistentPlaybackState::default();
+        assert_eq!(state.version, STATE_VERSION);
         assert_eq!(state.current_track, None);
         assert_eq!(state.queue.len(), 0);
         assert_eq!(state.current_index, 0);
         assert_eq!(state.position_ms, 0);
         assert!(!state.shuffle);
         assert_eq!(state.repeat_mode, RepeatMode::Off);
-        assert_eq!(state.volume, 50);
+        assert_eq!(state.volume.percent(), 50);
         assert_eq!(state.shuffle_order.len(), 0);
         assert_eq!(state.state, PlaybackState::Stopped);
     }
@@ -278,13 +368,14 @@ mod tests {
     fn test_serialize_sanitizes_auth_headers() {
         let track_with_auth = create_test_track("1", true);
         let state = PersistentPlaybackState {
+            version: STATE_VERSION,
             current_track: Some(track_with_auth.clone()),
             queue: vec![track_with_auth.clone()],
             current_index: 0,
             position_ms: 5000,
             shuffle: false,
             repeat_mode: RepeatMode::Off,
-            volume: 75,
+            volume: Volume::new(75),
             shuffle_order: vec![],
             state: PlaybackState::Playing,
         };
@@ -362,13 +453,14 @@ mod tests {
         let track2 = create_test_track("2", false);
 
         let original_state = PersistentPlaybackState {
+            version: STATE_VERSION,
             current_track: Some(track1.clone()),
             queue: vec![track1.clone(), track2.clone()],
             current_index: 1,
             position_ms: 45000,
             shuffle: true,
             repeat_mode: RepeatMode::All,
-            volume: 80,
+            volume: Volume::new(80),
             shuffle_order: vec![1, 0],
             state: PlaybackState::Paused,
         };
@@ -404,7 +496,7 @@ mod tests {
         assert_eq!(restored_state.position_ms, 45000);
         assert!(restored_state.shuffle);
         assert_eq!(restored_state.repeat_mode, RepeatMode::All);
-        assert_eq!(restored_state.volume, 80);
+        assert_eq!(restored_state.volume.percent(), 80);
         assert_eq!(restored_state.shuffle_order, vec![1, 0]);
         assert_eq!(restored_state.state, PlaybackState::Paused);
     }
@@ -416,13 +508,14 @@ mod tests {
 
         let track = create_test_track("1", true);
         let state = PersistentPlaybackState {
+            version: STATE_VERSION,
             current_track: Some(track.clone()),
             queue: vec![track.clone()],
             current_index: 0,
             position_ms: 10000,
             shuffle: false,
             repeat_mode: RepeatMode::One,
-            volume: 60,
+            volume: Volume::new(60),
             shuffle_order: vec![],
             state: PlaybackState::Playing,
         };
@@ -438,7 +531,7 @@ mod tests {
 
         // Verify
         assert_eq!(loaded_state.position_ms, 10000);
-        assert_eq!(loaded_state.volume, 60);
+        assert_eq!(loaded_state.volume.percent(), 60);
         assert_eq!(loaded_state.state, PlaybackState::Playing);
         assert!(loaded_state.current_track.is_some());
         assert_eq!(
@@ -494,4 +587,63 @@ mod tests {
             "State should not exist after delete"
         );
     }
+
+    #[tokio::test]
+    async fn test_load_unversioned_state_migrates() {
+        let path = PersistentPlaybackState::get_state_file_path()
+            .await
+            .unwrap();
+        let json = r#"{
+            "current_track": null,
+            "queue": [],
+            "current_index": 0,
+            "position_ms": 1234,
+            "shuffle": true,
+            "repeat_mode": "All",
+            "volume": 40,
+            "shuffle_order": [],
+            "state": "Paused"
+        }"#;
+        fs::write(&path, json).await.unwrap();
+
+        let state = PersistentPlaybackState::load()
+            .await
+            .expect("Load should not error")
+            .expect("Unversioned state should be migrated");
+        assert_eq!(state.version, STATE_VERSION);
+        assert_eq!(state.position_ms, 1234);
+        assert_eq!(state.volume.percent(), 40);
+        assert_eq!(state.repeat_mode, RepeatMode::All);
+
+        PersistentPlaybackState::delete().await.unwrap();
+    }
+
+    #[test]
+    fn test_out_of_range_saved_volume_is_clamped() {
+        let mut saved = serde_json::to_value(PersistentPlaybackState::default()).unwrap();
+        saved["volume"] = Value::from(250);
+
+        let state: PersistentPlaybackState = serde_json::from_value(saved).unwrap();
+        assert_eq!(state.volume, Volume::new(100));
+    }
+
+    #[tokio::test]
+    async fn test_load_unknown_or_corrupt_state_returns_none() {
+        let path = PersistentPlaybackState::get_state_file_path()
+            .await
+            .unwrap();
+
+        let mut future = serde_json::to_value(PersistentPlaybackState::default()).unwrap();
+        future["version"] = Value::from(STATE_VERSION + 1);
+        fs::write(&path, future.to_string()).await.unwrap();
+        assert_eq!(PersistentPlaybackState::load().await.unwrap(), None);
+
+        fs::write(&path, r#"{"volume": "loud"}"#).await.unwrap();
+        assert_eq!(PersistentPlaybackState::load().await.unwrap(), None);
+
+        fs::write(&path, "not json").await.unwrap();
+        assert_eq!(PersistentPlaybackState::load().await.unwrap(), None);
+
+        PersistentPlaybackState::delete().await.unwrap();
+    }
 }
@@ -0,0 +1,250 @@
+/// System tray icon showing the current track, with a menu of basic
+/// transport controls
+use crate::models::{PlaybackInfo, PlaybackState};
+use crate::PlaybackManager;
+use std::sync::Arc;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+/// Tooltip shown when nothing is playing
+const IDLE_TOOLTIP: &str = "Any Player";
+
+/// What a tray menu entry does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    PlayPause,
+    Next,
+    Previous,
+    Show,
+    Quit,
+}
+
+impl TrayAction {
+    /// Every action, in menu order
+    pub const ALL: [TrayAction; 5] = [
+        TrayAction::PlayPause,
+        TrayAction::Next,
+        TrayAction::Previous,
+        TrayAction::Show,
+        TrayAction::Quit,
+    ];
+
+    /// Menu item ID the action is registered under
+    pub fn id(self) -> &'static str {
+        match self {
+            TrayAction::PlayPause => "play_pause",
+            TrayAction::Next => "next",
+            TrayAction::Previous => "previous",
+            TrayAction::Show => "show",
+            TrayAction::Quit => "quit",
+        }
+    }
+
+    /// Action for a clicked menu item, `None` for items that do nothing
+    pub fn from_menu_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TrayAction::PlayPause => "Play",
+            TrayAction::Next => "Next",
+            TrayAction::Previous => "Previous",
+            TrayAction::Show => "Show Any Player",
+            TrayAction::Quit => "Quit",
+        }
+    }
+}
+
+/// "Title — Artist" for the current track, or `None` when nothing is loaded
+pub fn now_playing_text(info: &PlaybackInfo) -> Option<String> {
+    info.current_track
+        .as_ref()
+        .map(|track| format!("{} — {}", track.title, track.artist))
+}
+
+/// Label for the play/pause item given the current state
+pub fn play_pause_label(state: PlaybackState) -> &'static str {
+    match state {
+        PlaybackState::Playing | PlaybackState::Loading => "Pause",
+        PlaybackState::Paused | PlaybackState::Stopped => TrayAction::PlayPause.label(),
+    }
+}
+
+/// Run a tray action against the app
+fn perform(app: &AppHandle, playback: &Arc<Mutex<PlaybackManager>>, action: TrayAction) {
+    match action {
+        TrayAction::Show => crate::focus_main_window(app),
+        TrayAction::Quit => app.exit(0),
+        TrayAction::PlayPause | TrayAction::Next | TrayAction::Previous => {
+            let playback = playback.clone();
+            tauri::async_runtime::spawn(async move {
+                let playback = playback.lock().await;
+                match action {
+                    TrayAction::PlayPause => playback.toggle_play_pause().await,
+                    TrayAction::Next => {
+                        let _ = playback.next_track().await;
+                    }
+                    TrayAction::Previous => {
+                        let _ = playback.previous_track().await;
+                    }
+                    TrayAction::Show | TrayAction::Quit => {}
+                }
+            });
+        }
+    }
+}
+
+/// Hide the main window if it's showing, otherwise bring it to the front
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false) {
+        if let Err(e) = window.hide() {
+            tracing::warn!("Failed to hide the main window: {}", e);
+        }
+    } else {
+        crate::focus_main_window(app);
+    }
+}
+
+/// Handle to the tray icon for reflecting playback changes
+pub struct TrayHandle {
+    tray: TrayIcon,
+    now_playing: MenuItem<tauri::Wry>,
+    play_pause: MenuItem<tauri::Wry>,
+}
+
+impl TrayHandle {
+    /// Show the current track in the tooltip, title and menu
+    pub fn update(&self, info: &PlaybackInfo) {
+        let text = now_playing_text(info);
+        let result = self
+            .tray
+            .set_tooltip(Some(text.as_deref().unwrap_or(IDLE_TOOLTIP)))
+            // Only macOS shows a title next to the icon
+            .and_then(|_| self.tray.set_title(text.as_deref()))
+            .and_then(|_| {
+                self.now_playing
+                    .set_text(text.as_deref().unwrap_or("Nothing playing"))
+            })
+            .and_then(|_| self.play_pause.set_text(play_pause_label(info.state)));
+        if let Err(e) = result {
+            tracing::warn!("Failed to update the tray icon: {}", e);
+        }
+    }
+}
+
+/// Create the tray icon and its menu
+pub fn start(app: &AppHandle, playback: Arc<Mutex<PlaybackManager>>) -> tauri::Result<TrayHandle> {
+    let now_playing =
+        MenuItem::with_id(app, "now_playing", "Nothing playing", false, None::<&str>)?;
+    let item = |action: TrayAction| {
+        MenuItem::with_id(app, action.id(), action.label(), true, None::<&str>)
+    };
+    let play_pause = item(TrayAction::PlayPause)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &now_playing,
+            &PredefinedMenuItem::separator(app)?,
+            &play_pause,
+            &item(TrayAction::Next)?,
+            &item(TrayAction::Previous)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(TrayAction::Show)?,
+            &item(TrayAction::Quit)?,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id("main")
+        .tooltip(IDLE_TOOLTIP)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(move |app, event| {
+            if let Some(action) = TrayAction::from_menu_id(event.id().as_ref()) {
+                perform(app, &playback, action);
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                toggle_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    let tray = builder.build(app)?;
+
+    Ok(TrayHandle {
+        tray,
+        now_playing,
+        play_pause,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Source, Track};
+
+    #[test]
+    fn test_menu_ids_map_to_actions() {
+        assert_eq!(
+            TrayAction::from_menu_id("play_pause"),
+            Some(TrayAction::PlayPause)
+        );
+        assert_eq!(TrayAction::from_menu_id("next"), Some(TrayAction::Next));
+        assert_eq!(
+            TrayAction::from_menu_id("previous"),
+            Some(TrayAction::Previous)
+        );
+        assert_eq!(TrayAction::from_menu_id("show"), Some(TrayAction::Show));
+        assert_eq!(TrayAction::from_menu_id("quit"), Some(TrayAction::Quit));
+        // The now-playing label isn't clickable
+        assert_eq!(TrayAction::from_menu_id("now_playing"), None);
+
+        for action in TrayAction::ALL {
+            assert_eq!(TrayAction::from_menu_id(action.id()), Some(action));
+        }
+    }
+
+    #[test]
+    fn test_labels_follow_playback() {
+        assert_eq!(play_pause_label(PlaybackState::Playing), "Pause");
+        assert_eq!(play_pause_label(PlaybackState::Loading), "Pause");
+        assert_eq!(play_pause_label(PlaybackState::Paused), "Play");
+        assert_eq!(play_pause_label(PlaybackState::Stopped), "Play");
+
+        assert_eq!(now_playing_text(&PlaybackInfo::default()), None);
+        let info = PlaybackInfo {
+            current_track: Some(Track {
+                id: "1".to_string(),
+                title: "Song".to_string(),
+                artist: "Artist".to_string(),
+                album: "Album".to_string(),
+                album_artist: None,
+                duration_ms: 200_000,
+                image_url: None,
+                source: Source::Jellyfin,
+                url: None,
+                auth_headers: None,
+                genre: None,
+                year: None,
+                track_number: None,
+                disc_number: None,
+                enriched_at: None,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(now_playing_text(&info).as_deref(), Some("Song — Artist"));
+    }
+}
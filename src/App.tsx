@@ -1,5 +1,5 @@
 import { useState, useMemo, useEffect } from "react";
-import { Toaster } from "react-hot-toast";
+import toast, { Toaster } from "react-hot-toast";
 import "./App.css";
 import {
   Sidebar,
@@ -33,6 +33,17 @@ export default function App() {
     };
   }, []);
 
+  // Let the user know when unplayable tracks were skipped
+  useEffect(() => {
+    const unlisten = listen<string[]>("tracks-skipped", (event) => {
+      toast.error(`Skipped unplayable tracks: ${event.payload.join(", ")}`);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Auto-load playlists on startup after validating connections
   useEffect(() => {
     const initializePlaylists = async () => {
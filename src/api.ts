@@ -5,12 +5,24 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   PlaybackStatus,
+  QueueInfo,
+  QueueStats,
+  NowPlaying,
   Playlist,
   Track,
   CustomPlaylist,
   PlaylistTrack,
   ColumnPreferences,
+  DownloadedTrack,
+  DownloadSizeReport,
+  JellyfinServerInfo,
   UnionPlaylistSource,
+  PushPlaylistReport,
+  LibraryStats,
+  ConnectionStatus,
+  ItemPage,
+  ProviderCapabilities,
+  RateLimitStatus,
 } from "./types";
 
 declare global {
@@ -33,6 +45,18 @@ export class TauriAPI {
     return invoke<PlaybackStatus>("get_playback_status");
   }
 
+  async getQueue(): Promise<QueueInfo> {
+    return invoke<QueueInfo>("get_queue");
+  }
+
+  async getQueueStats(): Promise<QueueStats> {
+    return invoke<QueueStats>("get_queue_stats");
+  }
+
+  async getNowPlaying(): Promise<NowPlaying | null> {
+    return invoke<NowPlaying | null>("get_now_playing");
+  }
+
   async play(): Promise<void> {
     return invoke<void>("play");
   }
@@ -61,17 +85,116 @@ export class TauriAPI {
     return invoke<void>("set_volume", { volume });
   }
 
+  async setTrackGain(
+    source: string,
+    trackId: string,
+    gainDb: number | null,
+  ): Promise<void> {
+    return invoke<void>("set_track_gain", { source, trackId, gainDb });
+  }
+
+  async getTrackGain(source: string, trackId: string): Promise<number | null> {
+    return invoke<number | null>("get_track_gain", { source, trackId });
+  }
+
   async toggleShuffle(): Promise<void> {
     return invoke<void>("toggle_shuffle");
   }
 
+  async reshuffleQueue(): Promise<void> {
+    return invoke<void>("reshuffle_queue");
+  }
+
+  async setShuffleSeed(seed: number | null): Promise<void> {
+    return invoke<void>("set_shuffle_seed", { seed });
+  }
+
   async setRepeatMode(mode: "off" | "one" | "all"): Promise<void> {
     return invoke<void>("set_repeat_mode", { mode });
   }
 
+  async cycleRepeatMode(): Promise<"off" | "one" | "all"> {
+    return invoke<"off" | "one" | "all">("cycle_repeat_mode");
+  }
+
   // Playlist commands
-  async getPlaylists(source: string): Promise<Playlist[]> {
-    return invoke<Playlist[]>("get_playlists", { source });
+  async getPlaylists(
+    source: string,
+    offset = 0,
+    limit = 100,
+  ): Promise<ItemPage<Playlist>> {
+    return invoke<ItemPage<Playlist>>("get_playlists", {
+      source,
+      offset,
+      limit,
+    });
+  }
+
+  async getPlaylistTracks(
+    source: string,
+    id: string,
+    offset = 0,
+    limit = 100,
+  ): Promise<ItemPage<Track>> {
+    return invoke<ItemPage<Track>>("get_playlist_tracks", {
+      source,
+      id,
+      offset,
+      limit,
+    });
+  }
+
+  async getAlbum(source: string, id: string): Promise<Playlist> {
+    return invoke<Playlist>("get_album", { source, id });
+  }
+
+  async getArtistAlbums(source: string, artistId: string): Promise<Playlist[]> {
+    return invoke<Playlist[]>("get_artist_albums", { source, artistId });
+  }
+
+  async getArtistTopTracks(source: string, artistId: string): Promise<Track[]> {
+    return invoke<Track[]>("get_artist_top_tracks", { source, artistId });
+  }
+
+  async searchTracks(
+    source: string,
+    query: string,
+    offset = 0,
+    limit = 100,
+  ): Promise<ItemPage<Track>> {
+    return invoke<ItemPage<Track>>("search_tracks", {
+      source,
+      query,
+      offset,
+      limit,
+    });
+  }
+
+  /** Resolves to null when a newer query for the same source replaced it */
+  async searchTracksDebounced(
+    source: string,
+    query: string,
+    limit = 100,
+  ): Promise<ItemPage<Track> | null> {
+    return invoke<ItemPage<Track> | null>("search_tracks_debounced", {
+      source,
+      query,
+      limit,
+    });
+  }
+
+  async getProviderCapabilities(source: string): Promise<ProviderCapabilities> {
+    return invoke<ProviderCapabilities>("get_provider_capabilities", {
+      source,
+    });
+  }
+
+  async getRateLimitStatus(): Promise<RateLimitStatus> {
+    return invoke<RateLimitStatus>("get_rate_limit_status");
+  }
+
+  async canPlayFull(track: Track): Promise<boolean> {
+    return invoke<boolean>("can_play_full", { track });
   }
 
   async playTrack(trackId: string, source: string): Promise<void> {
@@ -86,8 +209,12 @@ export class TauriAPI {
     return invoke<void>("clear_queue");
   }
 
-  async playPlaylist(playlistId: string, source: string): Promise<void> {
-    return invoke<void>("play_playlist", { playlistId, source });
+  async playPlaylist(
+    playlistId: string,
+    source: string,
+    append = false,
+  ): Promise<void> {
+    return invoke<void>("play_playlist", { playlistId, source, append });
   }
 
   async playTracksImmediate(tracks: Track[]): Promise<void> {
@@ -155,6 +282,10 @@ export class TauriAPI {
     return invoke<boolean>("check_spotify_premium");
   }
 
+  async checkSpotifyConnection(): Promise<ConnectionStatus> {
+    return invoke<ConnectionStatus>("check_spotify_connection");
+  }
+
   async initializeSpotifySession(accessToken: string): Promise<void> {
     return invoke<void>("initialize_spotify_session", { accessToken });
   }
@@ -179,12 +310,24 @@ export class TauriAPI {
     return invoke<Playlist>("get_spotify_playlist", { id });
   }
 
+  async getSpotifyLikedSongs(): Promise<Playlist> {
+    return invoke<Playlist>("get_spotify_liked_songs");
+  }
+
+  async saveSpotifyTrack(trackId: string): Promise<void> {
+    return invoke<void>("save_spotify_track", { trackId });
+  }
+
+  async removeSavedSpotifyTrack(trackId: string): Promise<void> {
+    return invoke<void>("remove_saved_spotify_track", { trackId });
+  }
+
   async checkOAuthCode(): Promise<boolean> {
     return invoke<boolean>("check_oauth_code");
   }
 
-  async disconnectSpotify(): Promise<void> {
-    return invoke<void>("disconnect_spotify");
+  async disconnectSpotify(purge = false): Promise<void> {
+    return invoke<void>("disconnect_spotify", { purge });
   }
 
   async restoreSpotifySession(): Promise<boolean> {
@@ -196,10 +339,15 @@ export class TauriAPI {
   }
 
   // Jellyfin commands
-  async authenticateJellyfin(url: string, apiKey: string): Promise<void> {
+  async authenticateJellyfin(
+    url: string,
+    apiKey: string,
+    name?: string,
+  ): Promise<void> {
     return invoke<void>("authenticate_jellyfin", {
       url,
       apiKey: apiKey,
+      name,
     });
   }
 
@@ -207,6 +355,10 @@ export class TauriAPI {
     return invoke<boolean>("is_jellyfin_authenticated");
   }
 
+  async checkJellyfinConnection(): Promise<ConnectionStatus> {
+    return invoke<ConnectionStatus>("check_jellyfin_connection");
+  }
+
   async getJellyfinPlaylists(): Promise<Playlist[]> {
     return invoke<Playlist[]>("get_jellyfin_playlists");
   }
@@ -215,24 +367,35 @@ export class TauriAPI {
     return invoke<Playlist>("get_jellyfin_playlist", { id });
   }
 
-  async searchJellyfinTracks(query: string): Promise<Track[]> {
-    return invoke<Track[]>("search_jellyfin_tracks", { query });
+  async searchJellyfinTracks(query: string, refresh = false): Promise<Track[]> {
+    return invoke<Track[]>("search_jellyfin_tracks", { query, refresh });
   }
 
-  async searchSpotifyTracks(query: string): Promise<Track[]> {
-    return invoke<Track[]>("search_spotify_tracks", { query });
+  async searchSpotifyTracks(query: string, refresh = false): Promise<Track[]> {
+    return invoke<Track[]>("search_spotify_tracks", { query, refresh });
   }
 
-  async searchJellyfinPlaylists(query: string): Promise<Playlist[]> {
-    return invoke<Playlist[]>("search_jellyfin_playlists", { query });
+  async searchJellyfinPlaylists(
+    query: string,
+    refresh = false,
+  ): Promise<Playlist[]> {
+    return invoke<Playlist[]>("search_jellyfin_playlists", { query, refresh });
   }
 
   async getJellyfinRecentlyPlayed(limit: number): Promise<Track[]> {
     return invoke<Track[]>("get_jellyfin_recently_played", { limit });
   }
 
-  async disconnectJellyfin(): Promise<void> {
-    return invoke<void>("disconnect_jellyfin");
+  async setJellyfinFavorite(trackId: string, favorite: boolean): Promise<void> {
+    return invoke<void>("set_jellyfin_favorite", { trackId, favorite });
+  }
+
+  async getJellyfinFavorites(): Promise<Track[]> {
+    return invoke<Track[]>("get_jellyfin_favorites");
+  }
+
+  async disconnectJellyfin(purge = false): Promise<void> {
+    return invoke<void>("disconnect_jellyfin", { purge });
   }
 
   async getJellyfinCredentials(): Promise<[string, string] | null> {
@@ -243,10 +406,48 @@ export class TauriAPI {
     return invoke<boolean>("restore_jellyfin_session");
   }
 
+  async listJellyfinServers(): Promise<JellyfinServerInfo[]> {
+    return invoke<JellyfinServerInfo[]>("list_jellyfin_servers");
+  }
+
+  async setActiveJellyfinServer(url: string): Promise<void> {
+    return invoke("set_active_jellyfin_server", { url });
+  }
+
   async getAudioFile(url: string): Promise<string> {
     return invoke<string>("get_audio_file", { url });
   }
 
+  // Diagnostics commands
+  async getLogDirectory(): Promise<string> {
+    return invoke<string>("get_log_directory");
+  }
+
+  async openLogDirectory(): Promise<void> {
+    return invoke("open_log_directory");
+  }
+
+  // Offline download commands
+  async downloadTrack(track: Track): Promise<DownloadedTrack> {
+    return invoke<DownloadedTrack>("download_track", { track });
+  }
+
+  async isDownloaded(source: string, trackId: string): Promise<boolean> {
+    return invoke<boolean>("is_downloaded", { source, trackId });
+  }
+
+  async removeDownload(source: string, trackId: string): Promise<void> {
+    return invoke("remove_download", { source, trackId });
+  }
+
+  async getDownloads(): Promise<DownloadedTrack[]> {
+    return invoke<DownloadedTrack[]>("get_downloads");
+  }
+
+  async getDownloadSize(): Promise<DownloadSizeReport> {
+    return invoke<DownloadSizeReport>("get_download_size");
+  }
+
   // Custom playlist commands
   async createCustomPlaylist(
     name: string,
@@ -260,6 +461,21 @@ export class TauriAPI {
     });
   }
 
+  async snapshotProviderPlaylist(
+    source: string,
+    playlistId: string,
+    name: string,
+  ): Promise<CustomPlaylist> {
+    return invoke("snapshot_provider_playlist", { source, playlistId, name });
+  }
+
+  async pushCustomPlaylistToProvider(
+    playlistId: string,
+    source: string,
+  ): Promise<PushPlaylistReport> {
+    return invoke("push_custom_playlist_to_provider", { playlistId, source });
+  }
+
   async getCustomPlaylists(): Promise<CustomPlaylist[]> {
     return invoke("get_custom_playlists");
   }
@@ -297,6 +513,12 @@ export class TauriAPI {
     return invoke("get_custom_playlist_tracks", { playlistId });
   }
 
+  async getCustomPlaylistTracksSorted(
+    playlistId: string,
+  ): Promise<PlaylistTrack[]> {
+    return invoke("get_custom_playlist_tracks_sorted", { playlistId });
+  }
+
   async removeTrackFromCustomPlaylist(trackId: number): Promise<void> {
     return invoke("remove_track_from_custom_playlist", { trackId });
   }
@@ -313,6 +535,26 @@ export class TauriAPI {
     });
   }
 
+  async moveCustomPlaylistTrack(
+    trackId: number,
+    fromPlaylistId: string,
+    toPlaylistId: string,
+  ): Promise<void> {
+    return invoke("move_custom_playlist_track", {
+      trackId,
+      fromPlaylistId,
+      toPlaylistId,
+    });
+  }
+
+  async generatePlaylistCover(playlistId: string): Promise<string | null> {
+    return invoke<string | null>("generate_playlist_cover", { playlistId });
+  }
+
+  async getLibraryStats(): Promise<LibraryStats> {
+    return invoke<LibraryStats>("get_library_stats");
+  }
+
   async getColumnPreferences(): Promise<ColumnPreferences> {
     return invoke("get_column_preferences");
   }
@@ -321,6 +563,10 @@ export class TauriAPI {
     return invoke("save_column_preferences", { preferences });
   }
 
+  async getAvailableColumns(): Promise<string[]> {
+    return invoke<string[]>("get_available_columns");
+  }
+
   // Union playlist commands
   async createUnionPlaylist(
     name: string,
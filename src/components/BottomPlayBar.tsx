@@ -72,6 +72,14 @@ export function BottomPlayBar() {
           <div className="bottom-bar-text">
             <div className="bottom-bar-title">{currentTrack.title}</div>
             <div className="bottom-bar-artist">{currentTrack.artist}</div>
+            {playback.playbackStatus?.error && (
+              <div
+                className="bottom-bar-error"
+                title={playback.playbackStatus.error}
+              >
+                {playback.playbackStatus.error}
+              </div>
+            )}
           </div>
         </div>
 
@@ -9,8 +9,11 @@ interface TrackTableProps {
   onReorderTrack?: (trackId: number, newPosition: number) => void;
   onPlayTrack?: (track: PlaylistTrack | Track) => void;
   onPlayFromTrack?: (index: number) => void;
+  onSortChange?: () => void;
 }
 
+const SORTABLE_COLUMNS = ["title", "artist", "album", "duration"];
+
 const DEFAULT_COLUMNS: ColumnPreferences = {
   columns: ["title", "artist", "album", "duration", "source"],
   column_order: [0, 1, 2, 3, 4],
@@ -29,6 +32,7 @@ export function TrackTable({
   onReorderTrack,
   onPlayTrack,
   onPlayFromTrack,
+  onSortChange,
 }: TrackTableProps) {
   const [columnPrefs, setColumnPrefs] =
     useState<ColumnPreferences>(DEFAULT_COLUMNS);
@@ -54,6 +58,37 @@ export function TrackTable({
     return `${minutes}:${seconds.toString().padStart(2, "0")}`;
   };
 
+  const handleSort = async (column: string) => {
+    const prefs: ColumnPreferences = {
+      ...columnPrefs,
+      sort_by: column,
+      sort_desc:
+        columnPrefs.sort_by === column ? !columnPrefs.sort_desc : false,
+    };
+    setColumnPrefs(prefs);
+    try {
+      await tauriAPI.saveColumnPreferences(prefs);
+      onSortChange?.();
+    } catch (err) {
+      console.error("Failed to save sort preference:", err);
+    }
+  };
+
+  const isSortable = (column: string) =>
+    !!onSortChange && SORTABLE_COLUMNS.includes(column);
+
+  const getSortIndicator = (column: string) => {
+    if (!onSortChange || columnPrefs.sort_by !== column) return "";
+    return columnPrefs.sort_desc ? " ▼" : " ▲";
+  };
+
+  // Drag reordering only makes sense when tracks are shown in playlist order
+  const canReorder =
+    !!onReorderTrack &&
+    (!onSortChange ||
+      !columnPrefs.sort_by ||
+      columnPrefs.sort_by === "position");
+
   const handleDragStart = (trackId: number | string) => {
     setDraggedTrack(Number(trackId));
   };
@@ -94,6 +129,14 @@ export function TrackTable({
         return isPlaylistTrack
           ? (track as PlaylistTrack).track_source
           : (track as Track).source;
+      case "genre":
+        return isPlaylistTrack ? "--" : (track as Track).genre || "--";
+      case "year":
+        return isPlaylistTrack ? "--" : (track as Track).year || "--";
+      case "track_number":
+        return isPlaylistTrack ? "--" : (track as Track).track_number || "--";
+      case "disc_number":
+        return isPlaylistTrack ? "--" : (track as Track).disc_number || "--";
       default:
         return "";
     }
@@ -111,6 +154,14 @@ export function TrackTable({
         return "Duration";
       case "source":
         return "Source";
+      case "genre":
+        return "Genre";
+      case "year":
+        return "Year";
+      case "track_number":
+        return "Track";
+      case "disc_number":
+        return "Disc";
       default:
         return column;
     }
@@ -128,9 +179,14 @@ export function TrackTable({
                 key={column}
                 style={{
                   width: columnPrefs.column_widths[column] || "auto",
+                  cursor: isSortable(column) ? "pointer" : undefined,
                 }}
+                onClick={
+                  isSortable(column) ? () => handleSort(column) : undefined
+                }
               >
                 {getColumnLabel(column)}
+                {getSortIndicator(column)}
               </th>
             ))}
             {onRemoveTrack && <th className="actions-column">Actions</th>}
@@ -140,7 +196,7 @@ export function TrackTable({
           {tracks.map((track, index) => (
             <tr
               key={track.id}
-              draggable={!!onReorderTrack}
+              draggable={canReorder}
               onDragStart={() => handleDragStart(track.id)}
               onDragOver={handleDragOver}
               onDrop={() => handleDrop(index)}
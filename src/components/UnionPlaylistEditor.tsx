@@ -329,6 +329,14 @@ export function UnionPlaylistEditor({
                   <span className="source-type-badge">
                     {source.source_type}
                   </span>
+                  {!source.available && (
+                    <span
+                      className="source-unavailable-badge"
+                      title={`Connect ${source.source_type} to include this playlist`}
+                    >
+                      Unavailable
+                    </span>
+                  )}
                 </div>
                 <button
                   className="remove-btn"
@@ -6,6 +6,7 @@ import type {
   Track,
   UnionPlaylistSource,
 } from "../types";
+import { commandErrorMessage } from "../utils/commandError";
 
 const CACHE_VERSION = 1;
 
@@ -118,7 +119,7 @@ export function useCustomPlaylists() {
         console.error("Failed to save custom disk cache:", err);
       });
     } catch (err) {
-      setError(err instanceof Error ? err.message : "Failed to load playlists");
+      setError(commandErrorMessage(err, "Failed to load playlists"));
       console.error("Error loading custom playlists:", err);
     } finally {
       setLoading(false);
@@ -237,7 +238,7 @@ export function useCustomPlaylistTracks(playlistId: string | null) {
               setLoading(false);
               // Still fetch fresh data in the background and update
               tauriAPI
-                .getCustomPlaylistTracks(playlistId)
+                .getCustomPlaylistTracksSorted(playlistId)
                 .then((data) => {
                   console.log(
                     `Fetched ${data.length} tracks from backend for playlist ${playlistId}`,
@@ -260,9 +261,7 @@ export function useCustomPlaylistTracks(playlistId: string | null) {
                 .catch((err) => {
                   console.error("Failed to refresh tracks:", err);
                   setError(
-                    err instanceof Error
-                      ? err.message
-                      : "Failed to refresh tracks",
+                    commandErrorMessage(err, "Failed to refresh tracks"),
                   );
                 });
               return;
@@ -277,7 +276,7 @@ export function useCustomPlaylistTracks(playlistId: string | null) {
       try {
         setLoading(true);
         setError(null);
-        const data = await tauriAPI.getCustomPlaylistTracks(playlistId);
+        const data = await tauriAPI.getCustomPlaylistTracksSorted(playlistId);
         console.log(`Loaded ${data.length} tracks for playlist ${playlistId}`);
         setTracks(data);
 
@@ -293,7 +292,7 @@ export function useCustomPlaylistTracks(playlistId: string | null) {
             console.error("Failed to save tracks to disk cache:", err);
           });
       } catch (err) {
-        setError(err instanceof Error ? err.message : "Failed to load tracks");
+        setError(commandErrorMessage(err, "Failed to load tracks"));
         console.error("Error loading playlist tracks:", err);
       } finally {
         setLoading(false);
@@ -391,7 +390,7 @@ export function useUnionPlaylistSources(unionPlaylistId: string | null) {
       const data = await tauriAPI.getUnionPlaylistSources(unionPlaylistId);
       setSources(data);
     } catch (err) {
-      setError(err instanceof Error ? err.message : "Failed to load sources");
+      setError(commandErrorMessage(err, "Failed to load sources"));
       console.error("Error loading union playlist sources:", err);
     } finally {
       setLoading(false);
@@ -518,9 +517,7 @@ export function useUnionPlaylistTracks(unionPlaylistId: string | null) {
                 .catch((err) => {
                   console.error("Failed to refresh union tracks:", err);
                   setError(
-                    err instanceof Error
-                      ? err.message
-                      : "Failed to refresh tracks",
+                    commandErrorMessage(err, "Failed to refresh tracks"),
                   );
                 });
               return;
@@ -556,7 +553,7 @@ export function useUnionPlaylistTracks(unionPlaylistId: string | null) {
             console.error("Failed to save union tracks to disk cache:", err);
           });
       } catch (err) {
-        setError(err instanceof Error ? err.message : "Failed to load tracks");
+        setError(commandErrorMessage(err, "Failed to load tracks"));
         console.error("Error loading union playlist tracks:", err);
       } finally {
         setLoading(false);
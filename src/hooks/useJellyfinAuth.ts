@@ -1,6 +1,7 @@
 import { useState, useCallback, useEffect } from "react";
 import { tauriAPI } from "../api";
 import { retryWithDelay } from "../utils/retryHelper";
+import { commandErrorMessage } from "../utils/commandError";
 
 export function useJellyfinAuth() {
   const [isConnected, setIsConnected] = useState(false);
@@ -48,8 +49,7 @@ export function useJellyfinAuth() {
           setError("Authentication failed");
         }
       } catch (err) {
-        const message =
-          err instanceof Error ? err.message : "Connection failed";
+        const message = commandErrorMessage(err, "Connection failed");
         setError(message);
       } finally {
         setIsLoading(false);
@@ -58,14 +58,13 @@ export function useJellyfinAuth() {
     [checkAuthStatus],
   );
 
-  const disconnect = useCallback(async () => {
+  const disconnect = useCallback(async (purge = false) => {
     try {
-      await tauriAPI.disconnectJellyfin();
+      await tauriAPI.disconnectJellyfin(purge);
       setIsConnected(false);
       setError(null);
     } catch (err) {
-      const message =
-        err instanceof Error ? err.message : "Failed to disconnect";
+      const message = commandErrorMessage(err, "Failed to disconnect");
       setError(message);
     }
   }, []);
@@ -1,6 +1,8 @@
 import { useState, useCallback, useRef, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { tauriAPI } from "../api";
 import type { Playlist, TauriSource } from "../types";
+import { commandErrorMessage } from "../utils/commandError";
 
 const CACHE_VERSION = 1;
 
@@ -102,6 +104,22 @@ export function usePlaylists() {
     }
   }, []);
 
+  // Pick up provider changes found by the backend's background refresh
+  useEffect(() => {
+    const unlisten = listen<Playlist[]>("playlists-updated", (event) => {
+      playlistCache = event.payload;
+      cacheInitialized = true;
+      setPlaylists(event.payload);
+      saveToDiskCache(event.payload).catch((err) => {
+        console.error("Failed to save disk cache:", err);
+      });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const loadPlaylists = useCallback(
     async (source: TauriSource, forceReload = false) => {
       // Prevent concurrent loads
@@ -177,8 +195,7 @@ export function usePlaylists() {
           console.error("Failed to save disk cache:", err);
         });
       } catch (err) {
-        const message =
-          err instanceof Error ? err.message : "Failed to load playlists";
+        const message = commandErrorMessage(err, "Failed to load playlists");
         setError(message);
         setPlaylists([]);
         playlistCache = [];
@@ -208,8 +225,7 @@ export function usePlaylists() {
         if (source === "all") return;
         await tauriAPI.playPlaylist(playlistId, source);
       } catch (err) {
-        const message =
-          err instanceof Error ? err.message : "Failed to play playlist";
+        const message = commandErrorMessage(err, "Failed to play playlist");
         setError(message);
         console.error("Error playing playlist:", err);
       }
@@ -1,6 +1,7 @@
 import { useState, useCallback } from "react";
 import { tauriAPI } from "../api";
 import type { SearchResult, TauriSource, SearchType } from "../types";
+import { commandErrorMessage } from "../utils/commandError";
 
 export function useSearch() {
   const [results, setResults] = useState<SearchResult[]>([]);
@@ -8,7 +9,12 @@ export function useSearch() {
   const [error, setError] = useState<string | null>(null);
 
   const search = useCallback(
-    async (query: string, searchType: SearchType, source: TauriSource) => {
+    async (
+      query: string,
+      searchType: SearchType,
+      source: TauriSource,
+      refresh = false,
+    ) => {
       if (!query.trim()) {
         setResults([]);
         return;
@@ -22,7 +28,10 @@ export function useSearch() {
         if (searchType === "tracks") {
           if (source === "spotify" || source === "all") {
             try {
-              const spotifyTracks = await tauriAPI.searchSpotifyTracks(query);
+              const spotifyTracks = await tauriAPI.searchSpotifyTracks(
+                query,
+                refresh,
+              );
               searchResults.push(
                 ...spotifyTracks.map((track) => ({
                   id: track.id,
@@ -39,7 +48,10 @@ export function useSearch() {
 
           if (source === "jellyfin" || source === "all") {
             try {
-              const jellyfinTracks = await tauriAPI.searchJellyfinTracks(query);
+              const jellyfinTracks = await tauriAPI.searchJellyfinTracks(
+                query,
+                refresh,
+              );
               searchResults.push(
                 ...jellyfinTracks.map((track) => ({
                   id: track.id,
@@ -58,7 +70,7 @@ export function useSearch() {
           if (source === "jellyfin" || source === "all") {
             try {
               const jellyfinPlaylists =
-                await tauriAPI.searchJellyfinPlaylists(query);
+                await tauriAPI.searchJellyfinPlaylists(query, refresh);
               searchResults.push(
                 ...jellyfinPlaylists.map((playlist) => ({
                   id: playlist.id,
@@ -80,7 +92,7 @@ export function useSearch() {
 
         setResults(searchResults);
       } catch (err) {
-        const message = err instanceof Error ? err.message : "Search failed";
+        const message = commandErrorMessage(err, "Search failed");
         setError(message);
         setResults([]);
       } finally {
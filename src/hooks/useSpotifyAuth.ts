@@ -1,6 +1,7 @@
 import { useState, useCallback, useEffect } from "react";
 import { tauriAPI } from "../api";
 import { retryWithDelay } from "../utils/retryHelper";
+import { commandErrorMessage } from "../utils/commandError";
 
 // Time to wait for backend to finish processing OAuth authentication (in milliseconds).
 // NOTE: 2000ms was chosen based on observed worst-case latency for the backend to
@@ -72,8 +73,7 @@ export function useSpotifyAuth() {
     try {
       return await tauriAPI.getSpotifyAuthUrl();
     } catch (err) {
-      const message =
-        err instanceof Error ? err.message : "Failed to get auth URL";
+      const message = commandErrorMessage(err, "Failed to get auth URL");
       setError(message);
       throw err;
     }
@@ -138,23 +138,22 @@ export function useSpotifyAuth() {
       console.log("Starting polling for OAuth callback completion");
       await pollForAuth();
     } catch (err) {
-      const message = err instanceof Error ? err.message : "Connection failed";
+      const message = commandErrorMessage(err, "Connection failed");
       setError(message);
       setIsLoading(false);
     }
   }, [getAuthUrl, pollForAuth]);
 
-  const disconnect = useCallback(async () => {
+  const disconnect = useCallback(async (purge = false) => {
     try {
-      await tauriAPI.disconnectSpotify();
+      await tauriAPI.disconnectSpotify(purge);
       setIsConnected(false);
       setError(null);
       setAuthUrl(null);
       setIsPremium(null);
       setSessionReady(false);
     } catch (err) {
-      const message =
-        err instanceof Error ? err.message : "Failed to disconnect";
+      const message = commandErrorMessage(err, "Failed to disconnect");
       setError(message);
     }
   }, []);
@@ -186,8 +185,7 @@ export function useSpotifyAuth() {
         setError("Session initialization completed but session not ready");
       }
     } catch (err) {
-      const message =
-        err instanceof Error ? err.message : "Failed to initialize session";
+      const message = commandErrorMessage(err, "Failed to initialize session");
       setError(message);
       console.error("Session initialization error:", err);
     } finally {
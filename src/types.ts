@@ -5,7 +5,7 @@
 export type Page = "now-playing" | "playlists" | "search" | "settings";
 
 export interface PlaybackStatus {
-  state: "playing" | "paused" | "stopped";
+  state: "loading" | "playing" | "paused" | "stopped";
   shuffle: boolean;
   repeat_mode: "off" | "one" | "all";
   volume: number;
@@ -13,6 +13,28 @@ export interface PlaybackStatus {
   position: number;
   duration: number;
   queue: Track[];
+  error?: string | null;
+}
+
+export interface QueueInfo {
+  tracks: Track[];
+  current_index: number;
+  play_order: number[];
+}
+
+export interface QueueStats {
+  track_count: number;
+  total_duration_ms: number;
+  remaining_duration_ms: number;
+  unknown_duration_count: number;
+}
+
+export interface NowPlaying {
+  track: Track;
+  state: "loading" | "playing" | "paused";
+  art_path: string | null;
+  elapsed_ms: number;
+  duration_ms: number;
 }
 
 export interface Track {
@@ -20,10 +42,21 @@ export interface Track {
   title: string;
   artist: string;
   album?: string;
+  album_artist?: string;
   duration_ms?: number;
   source: "spotify" | "jellyfin" | "custom";
   url?: string;
   image_url?: string;
+  genre?: string;
+  year?: number;
+  track_number?: number;
+  disc_number?: number;
+  enriched_at?: number;
+}
+
+export interface ItemPage<T> {
+  items: T[];
+  total: number;
 }
 
 export interface Playlist {
@@ -35,6 +68,7 @@ export interface Playlist {
   image_url?: string;
   tracks?: Track[];
   description?: string;
+  revision?: string;
 }
 
 export interface CustomPlaylist {
@@ -46,6 +80,21 @@ export interface CustomPlaylist {
   updated_at: number;
   track_count: number;
   playlist_type: "standard" | "union";
+  origin_source?: string | null;
+  origin_playlist_id?: string | null;
+}
+
+export interface LibraryStats {
+  playlist_count: number;
+  union_playlist_count: number;
+  track_count: number;
+  artist_count: number;
+}
+
+export interface PushPlaylistReport {
+  playlist_id: string;
+  pushed_count: number;
+  skipped: Track[];
 }
 
 export interface UnionPlaylistSource {
@@ -55,6 +104,8 @@ export interface UnionPlaylistSource {
   source_playlist_id: string;
   position: number;
   added_at: number;
+  /** False while the source's provider is disconnected */
+  available: boolean;
 }
 
 export interface PlaylistTrack {
@@ -72,10 +123,65 @@ export interface PlaylistTrack {
   url?: string;
 }
 
+export interface DownloadedTrack {
+  track_source: string;
+  track_id: string;
+  local_path: string;
+  size_bytes: number;
+  downloaded_at: number;
+}
+
+export interface DownloadSizeReport {
+  track_count: number;
+  total_bytes: number;
+}
+
+export interface JellyfinServerInfo {
+  name: string;
+  url: string;
+  active: boolean;
+}
+
+export interface ProviderCapabilities {
+  search_tracks: boolean;
+  search_playlists: boolean;
+  create_playlist: boolean;
+  edit_playlists: boolean;
+  streaming: boolean;
+  recently_played: boolean;
+}
+
+/** Providers currently slowed down by their rate limits */
+export interface RateLimitStatus {
+  spotify: boolean;
+  jellyfin: boolean;
+}
+
+export type CommandErrorKind =
+  | "auth"
+  | "network"
+  | "not_found"
+  | "invalid_input"
+  | "internal";
+
+/** Error value Tauri commands reject with */
+export interface CommandError {
+  kind: CommandErrorKind;
+  message: string;
+}
+
+export interface ConnectionStatus {
+  connected: boolean;
+  latency_ms: number;
+  status: string;
+}
+
 export interface ColumnPreferences {
   columns: string[];
   column_order: number[];
   column_widths: Record<string, number>;
+  sort_by?: string | null;
+  sort_desc?: boolean;
 }
 
 export interface SearchResult {
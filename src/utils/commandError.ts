@@ -0,0 +1,24 @@
+import type { CommandError } from "../types";
+
+/**
+ * Whether a rejected command value is a structured CommandError.
+ */
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    "kind" in err &&
+    "message" in err &&
+    typeof (err as CommandError).message === "string"
+  );
+}
+
+/**
+ * Human-readable message for a rejected command, falling back when the
+ * value isn't a CommandError or Error.
+ */
+export function commandErrorMessage(err: unknown, fallback: string): string {
+  if (isCommandError(err)) return err.message;
+  if (err instanceof Error) return err.message;
+  return fallback;
+}